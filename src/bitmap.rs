@@ -31,6 +31,31 @@ pub fn clear_nth_bit(bitmap: &mut u64, n: u32) -> Result<(), BitmapErr> {
     Ok(())
 }
 
+/// Return the n-th bit in a bitmap stored as a byte array. Bit n is located at bit (n % 8) of
+/// byte (n / 8).
+pub fn get_nth_bit_in_bytes(bitmap: &[u8], n: u32) -> Result<u8, BitmapErr> {
+    let byte = bitmap.get((n / 8) as usize).ok_or(BitmapErr::OutOfBounds)?;
+    Ok((*byte >> (n % 8)) & 1u8)
+}
+
+/// Set the n-th bit in a bitmap stored as a byte array to 1.
+pub fn set_nth_bit_in_bytes(bitmap: &mut [u8], n: u32) -> Result<(), BitmapErr> {
+    let byte = bitmap
+        .get_mut((n / 8) as usize)
+        .ok_or(BitmapErr::OutOfBounds)?;
+    *byte |= 1u8 << (n % 8);
+    Ok(())
+}
+
+/// Set the n-th bit in a bitmap stored as a byte array to 0.
+pub fn clear_nth_bit_in_bytes(bitmap: &mut [u8], n: u32) -> Result<(), BitmapErr> {
+    let byte = bitmap
+        .get_mut((n / 8) as usize)
+        .ok_or(BitmapErr::OutOfBounds)?;
+    *byte &= !(1u8 << (n % 8));
+    Ok(())
+}
+
 /// Custom error for bitmap operations.
 #[derive(Debug)]
 pub enum BitmapErr {
@@ -53,4 +78,19 @@ mod tests {
 
         assert_eq!(bitmap, 5); // 0b00000101
     }
+
+    #[test]
+    fn test_byte_bitmap_operations() {
+        let mut bitmap = [0u8, 12u8]; // 0b00001100_00000000
+        assert_eq!(get_nth_bit_in_bytes(&bitmap, 3).unwrap(), 0);
+        assert_eq!(get_nth_bit_in_bytes(&bitmap, 11).unwrap(), 1);
+        assert_eq!(get_nth_bit_in_bytes(&bitmap, 12).unwrap(), 0);
+        assert!(get_nth_bit_in_bytes(&bitmap, 16).is_err());
+
+        set_nth_bit_in_bytes(&mut bitmap, 0).unwrap();
+        clear_nth_bit_in_bytes(&mut bitmap, 11).unwrap();
+        assert!(set_nth_bit_in_bytes(&mut bitmap, 16).is_err());
+
+        assert_eq!(bitmap, [1u8, 4u8]);
+    }
 }
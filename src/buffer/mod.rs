@@ -7,12 +7,14 @@ use crate::buffer::replacement::clock::ClockReplacer;
 use crate::buffer::replacement::lru::LRUReplacer;
 use crate::buffer::replacement::slow::SlowReplacer;
 use crate::buffer::replacement::{PageReplacer, ReplacerAlgorithm};
-use crate::constants::{BufferFrameIdT, PageIdT, BUFFER_SIZE};
-use crate::disk::DiskManager;
-use crate::page::{PageBytes, RawPage};
+use crate::constants::{BufferFrameIdT, LsnT, PageIdT, BUFFER_SIZE, CATALOG_ROOT_ID, INVALID_LSN};
+use crate::disk::{DiskError, DiskManager};
+use crate::log::LogManager;
+use crate::page::{PageBytes, RawPage, RelationPage};
 
 use std::collections::HashMap;
 use std::fmt::{self, Formatter};
+use std::ops::{Deref, DerefMut};
 use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 pub mod replacement;
@@ -64,6 +66,10 @@ pub struct BufferFrame {
 
     /// Number of times the contained page has been accessed since being read from disk.
     usage_count: Arc<Mutex<u32>>,
+
+    /// LSN of the latest log record for the contained page, or `INVALID_LSN` if the page hasn't
+    /// been logged since being read from disk.
+    lsn: LsnT,
 }
 
 impl BufferFrame {
@@ -75,6 +81,7 @@ impl BufferFrame {
             dirty_flag: false,
             pin_count: Arc::new(Mutex::new(0)),
             usage_count: Arc::new(Mutex::new(0)),
+            lsn: INVALID_LSN,
         }
     }
 
@@ -93,6 +100,23 @@ impl BufferFrame {
         self.page.as_mut()
     }
 
+    /// Return an immutable reference to the contained page as a relation page.
+    /// Pages are stored as raw bytes without a type tag, so this is a best-effort check: the page
+    /// is only considered a relation page if it isn't the catalog root page and its header is
+    /// consistent (see `RelationPage::validate`).
+    pub fn as_relation_page(&self) -> Result<&PageBytes, BufferFrameError> {
+        let page = self.get_page().ok_or(BufferFrameError::EmptyBuffer)?;
+        check_relation_page(page)?;
+        Ok(page)
+    }
+
+    /// Return a mutable reference to the contained page as a relation page.
+    pub fn as_relation_page_mut(&mut self) -> Result<&mut PageBytes, BufferFrameError> {
+        let page = self.get_mut_page().ok_or(BufferFrameError::EmptyBuffer)?;
+        check_relation_page(page)?;
+        Ok(page)
+    }
+
     /// Return the dirty flag of this buffer frame.
     fn is_dirty(&self) -> bool {
         self.dirty_flag
@@ -103,6 +127,17 @@ impl BufferFrame {
         self.dirty_flag = flag;
     }
 
+    /// Return the LSN of the latest log record for the contained page.
+    fn get_lsn(&self) -> LsnT {
+        self.lsn
+    }
+
+    /// Set the LSN of the latest log record for the contained page. The log is flushed up to this
+    /// LSN before the page is written to disk.
+    pub fn set_lsn(&mut self, lsn: LsnT) {
+        self.lsn = lsn;
+    }
+
     /// Return the pin count of this buffer frame.
     fn get_pin_count(&self) -> u32 {
         let pins = self.pin_count.lock().unwrap();
@@ -131,6 +166,7 @@ impl BufferFrame {
         self.dirty_flag = false;
         self.pin_count = Arc::new(Mutex::new(0));
         self.usage_count = Arc::new(Mutex::new(0));
+        self.lsn = INVALID_LSN;
     }
 
     /// Panic if the buffer frame has a pin count greater than 0.
@@ -159,6 +195,14 @@ impl fmt::Debug for BufferFrame {
     }
 }
 
+/// Return an error if the specified page can't be interpreted as a relation page.
+fn check_relation_page(page: &PageBytes) -> Result<(), BufferFrameError> {
+    if RawPage::get_id(page) == CATALOG_ROOT_ID || RelationPage::validate(page).is_err() {
+        return Err(BufferFrameError::InvalidDowncast);
+    }
+    Ok(())
+}
+
 /// Custom buffer errors.
 #[derive(Debug)]
 pub enum BufferFrameError {
@@ -174,6 +218,86 @@ pub type FrameArc = Arc<RwLock<BufferFrame>>;
 pub type FrameRLatch<'a> = RwLockReadGuard<'a, BufferFrame>;
 pub type FrameWLatch<'a> = RwLockWriteGuard<'a, BufferFrame>;
 
+/// A reference to a pinned buffer frame, returned by the buffer manager when a page is created or
+/// fetched. The frame's latches are acquired through the `RwLock` that it dereferences to. The
+/// page is unpinned when the reference is dropped, so that an early return can't leak a pin.
+pub struct PinnedFrame<'a> {
+    buffer_manager: &'a BufferManager,
+
+    /// The pinned frame, or None if ownership of the pin has been released by `into_arc()`.
+    frame_arc: Option<FrameArc>,
+}
+
+impl<'a> PinnedFrame<'a> {
+    fn new(buffer_manager: &'a BufferManager, frame_arc: FrameArc) -> Self {
+        Self {
+            buffer_manager,
+            frame_arc: Some(frame_arc),
+        }
+    }
+
+    /// Return the frame without unpinning it when dropped. The caller is then responsible for
+    /// unpinning the page with `BufferManager::unpin_r()` or `BufferManager::unpin_w()`.
+    pub fn into_arc(mut self) -> FrameArc {
+        // .unwrap() ok since the frame is only taken here or when dropped.
+        self.frame_arc.take().unwrap()
+    }
+}
+
+impl Deref for PinnedFrame<'_> {
+    type Target = RwLock<BufferFrame>;
+
+    fn deref(&self) -> &RwLock<BufferFrame> {
+        self.frame_arc.as_ref().unwrap()
+    }
+}
+
+impl Drop for PinnedFrame<'_> {
+    fn drop(&mut self) {
+        if let Some(frame_arc) = self.frame_arc.take() {
+            self.buffer_manager.release_pin(&frame_arc.read().unwrap());
+        }
+    }
+}
+
+/// A pinned and write-latched relation page, returned by
+/// `BufferManager::fetch_relation_page_write()`. The guard dereferences to the page bytes, and
+/// mutably dereferencing it marks the page as dirty. The page is unpinned and the latch is
+/// released when the guard is dropped, so that the page can't be leaked by an early return.
+pub struct PageGuard<'a> {
+    buffer_manager: &'a BufferManager,
+    frame: FrameWLatch<'a>,
+}
+
+impl PageGuard<'_> {
+    /// Set the LSN of the latest log record for the guarded page.
+    pub fn set_lsn(&mut self, lsn: LsnT) {
+        self.frame.set_lsn(lsn);
+    }
+}
+
+impl Deref for PageGuard<'_> {
+    type Target = PageBytes;
+
+    fn deref(&self) -> &PageBytes {
+        // .unwrap() ok since the guard is only created for a frame containing a relation page.
+        self.frame.get_page().unwrap()
+    }
+}
+
+impl DerefMut for PageGuard<'_> {
+    fn deref_mut(&mut self) -> &mut PageBytes {
+        self.frame.set_dirty_flag(true);
+        self.frame.get_mut_page().unwrap()
+    }
+}
+
+impl Drop for PageGuard<'_> {
+    fn drop(&mut self) {
+        self.buffer_manager.release_pin(&self.frame);
+    }
+}
+
 /// Type alias for page table used internally by buffer manager.
 type PageTable = HashMap<PageIdT, BufferFrameIdT>;
 
@@ -194,6 +318,9 @@ pub struct BufferManager {
 
     /// Mapping of pages to buffer frames that they occupy.
     page_table: Arc<Mutex<PageTable>>,
+
+    /// Log manager for recording modifications of relation pages, if logging is enabled.
+    log_manager: Option<Arc<LogManager>>,
 }
 
 impl BufferManager {
@@ -215,12 +342,26 @@ impl BufferManager {
             disk_manager,
             replacer,
             page_table: Arc::new(Mutex::new(HashMap::with_capacity(BUFFER_SIZE as usize))),
+            log_manager: None,
         }
     }
 
-    /// Initialize a new page, pin it, and return a reference to its frame.
+    /// Enable logging with the specified log manager. Modifications of relation pages made
+    /// through the heap are then appended to the log.
+    pub fn with_log_manager(mut self, log_manager: Arc<LogManager>) -> Self {
+        self.log_manager = Some(log_manager);
+        self
+    }
+
+    /// Return the log manager, or None if logging isn't enabled.
+    pub fn get_log_manager(&self) -> Option<Arc<LogManager>> {
+        self.log_manager.clone()
+    }
+
+    /// Initialize a new page, pin it, and return a reference to its frame. The page is unpinned
+    /// when the reference is dropped.
     /// If there are no open buffer frames and all existing pages are pinned, then return an error.
-    pub fn create_page(&self) -> Result<FrameArc, BufferError> {
+    pub fn create_page(&self) -> Result<PinnedFrame<'_>, BufferError> {
         // Acquire latch for page table.
         let mut page_table = self.page_table.lock().unwrap();
 
@@ -234,8 +375,16 @@ impl BufferManager {
                 // TODO: handle pin assertions in page replacer
                 frame.assert_unpinned();
 
-                // Allocate space on disk and initialize the new page.
-                let new_page_id = self.disk_manager.allocate_page();
+                // Allocate space on disk and initialize the new page. Allocation only happens once
+                // a frame has been secured, so that a full buffer never leaks a disk page. If
+                // allocation fails, the frame is handed back to the replacer untouched.
+                let new_page_id = match self.disk_manager.allocate_page() {
+                    Ok(page_id) => page_id,
+                    Err(e) => {
+                        self.replacer.unpin(frame_id);
+                        return Err(e.into());
+                    }
+                };
                 let new_page = RawPage::new(new_page_id);
 
                 // Update the page table.
@@ -243,7 +392,11 @@ impl BufferManager {
                 if let Some(victim) = frame.get_page() {
                     let victim_id = RawPage::get_id(victim);
                     if frame.is_dirty() {
-                        self.disk_manager.write_page(victim_id, victim);
+                        if let Err(e) = self.write_back(&frame) {
+                            self.disk_manager.deallocate_page(new_page_id);
+                            self.replacer.unpin(frame_id);
+                            return Err(e);
+                        }
                     }
 
                     // .unwrap() ok since victim page must have an page table entry.
@@ -258,16 +411,17 @@ impl BufferManager {
                 self.replacer.pin(frame_id);
 
                 // Return a reference to the frame.
-                Ok(frame_arc.clone())
+                Ok(PinnedFrame::new(self, frame_arc.clone()))
             }
             None => Err(BufferError::NoBufFrame),
         }
     }
 
-    /// Fetch the specified page, pin it, and return a reference to its frame.
+    /// Fetch the specified page, pin it, and return a reference to its frame. The page is unpinned
+    /// when the reference is dropped.
     /// If the page does not exist in the buffer, then fetch the page from disk.
     /// If the page does not exist on disk, then return an error.
-    pub fn fetch_page(&self, page_id: PageIdT) -> Result<FrameArc, BufferError> {
+    pub fn fetch_page(&self, page_id: PageIdT) -> Result<PinnedFrame<'_>, BufferError> {
         // Assert that the page exists on disk.
         if !self.disk_manager.is_allocated(page_id) {
             return Err(BufferError::PageDiskDNE);
@@ -284,7 +438,7 @@ impl BufferManager {
                 frame.pin();
                 self.replacer.pin(frame.get_id());
 
-                Ok(frame_arc.clone())
+                Ok(PinnedFrame::new(self, frame_arc.clone()))
             }
             // Otherwise, retrieve the page from disk and (possibly) replace a page in the buffer.
             // If all frames are occupied and pinned, give up and return an error.
@@ -300,15 +454,22 @@ impl BufferManager {
                         frame.assert_unpinned();
 
                         // Fetch the requested page into memory from disk.
+                        // If the read fails, the frame is handed back to the replacer untouched.
                         let mut page = RawPage::new(page_id);
-                        self.disk_manager.read_page(page_id, &mut page);
+                        if let Err(e) = self.disk_manager.read_page(page_id, &mut page) {
+                            self.replacer.unpin(frame_id);
+                            return Err(e.into());
+                        }
 
                         // Update the page table.
                         // If the frame contains a modified victim page, flush its data out to disk.
                         if let Some(victim) = frame.get_page() {
                             let victim_id = RawPage::get_id(victim);
                             if frame.is_dirty() {
-                                self.disk_manager.write_page(victim_id, &victim)
+                                if let Err(e) = self.write_back(&frame) {
+                                    self.replacer.unpin(frame_id);
+                                    return Err(e);
+                                }
                             }
 
                             // .unwrap() ok since victim page must have an page table entry.
@@ -322,7 +483,7 @@ impl BufferManager {
                         self.replacer.pin(frame_id);
 
                         // Return the write latch.
-                        Ok(frame_arc.clone())
+                        Ok(PinnedFrame::new(self, frame_arc.clone()))
                     }
                     None => Err(BufferError::NoBufFrame),
                 }
@@ -330,8 +491,37 @@ impl BufferManager {
         }
     }
 
+    /// Fetch the specified relation page, pin it, and return a guard holding its write latch.
+    /// The page is unpinned when the guard is dropped. If the page can't be interpreted as a
+    /// relation page, then unpin it and return an error.
+    pub fn fetch_relation_page_write(
+        &self,
+        page_id: PageIdT,
+    ) -> Result<PageGuard<'_>, BufferError> {
+        // The frame can't be evicted while it's pinned, so it's safe to latch it through the
+        // buffer pool rather than the returned reference.
+        let frame_id = self
+            .fetch_page(page_id)?
+            .into_arc()
+            .read()
+            .unwrap()
+            .get_id();
+        let frame = self.buffer.pool[frame_id as usize].write().unwrap();
+
+        let guard = PageGuard {
+            buffer_manager: self,
+            frame,
+        };
+        if let Err(e) = guard.frame.as_relation_page() {
+            return Err(BufferError::InvalidPage(e));
+        }
+        Ok(guard)
+    }
+
     /// Delete the specified page. If the page is pinned, then return an error.
-    pub fn delete_page(&self, page_id: PageIdT) -> Result<(), BufferError> {
+    /// The delete policy determines whether a modified page in the buffer is written to disk
+    /// before the page is deallocated.
+    pub fn delete_page(&self, page_id: PageIdT, policy: DeletePolicy) -> Result<(), BufferError> {
         // Assert that the page exists on disk.
         if !self.disk_manager.is_allocated(page_id) {
             return Err(BufferError::PageDiskDNE);
@@ -345,6 +535,9 @@ impl BufferManager {
                 let mut frame = frame_arc.write().unwrap();
                 match frame.get_pin_count() {
                     0 => {
+                        if policy == DeletePolicy::FlushThenDelete && frame.is_dirty() {
+                            self.write_back(&frame)?;
+                        }
                         frame.overwrite(None);
 
                         // .unwrap() ok since page exists in buffer.
@@ -372,9 +565,7 @@ impl BufferManager {
             Some(frame_arc) => {
                 let frame = frame_arc.read().unwrap();
                 if frame.is_dirty() {
-                    // .unwrap() ok since dirty frame implies frame contains a page.
-                    let page = frame.get_page().unwrap();
-                    self.disk_manager.write_page(RawPage::get_id(page), page);
+                    self.write_back(&frame)?;
                 }
                 Ok(())
             }
@@ -388,29 +579,45 @@ impl BufferManager {
             let frame_arc = self.buffer.get(frame_id);
             let frame = frame_arc.read().unwrap();
             if frame.is_dirty() {
-                // .unwrap() ok since dirty frame implies frame contains a page.
-                let page = frame.get_page().unwrap();
-                self.disk_manager.write_page(RawPage::get_id(page), page);
+                self.write_back(&frame)?;
             }
         }
         Ok(())
     }
 
-    /// Unpin the page contained in the specified frame and release the read latch.
-    pub fn unpin_r(&self, frame: FrameRLatch) {
-        match frame.get_page() {
-            Some(_) => {
-                frame.unpin();
-                if frame.get_pin_count() == 0 {
-                    self.replacer.unpin(frame.get_id());
+    /// Return the IDs of the pages in the buffer that were modified by an operation logged with an
+    /// LSN greater than or equal to the specified LSN.
+    pub fn get_pages_modified_since(&self, lsn: LsnT) -> Vec<PageIdT> {
+        let mut page_ids = Vec::new();
+        for frame_id in 0..self.buffer.size() {
+            let frame_arc = self.buffer.get(frame_id);
+            let frame = frame_arc.read().unwrap();
+            if let Some(page) = frame.get_page() {
+                if frame.get_lsn() != INVALID_LSN && frame.get_lsn() >= lsn {
+                    page_ids.push(RawPage::get_id(page));
                 }
             }
-            None => panic!("Attempted to unpin an empty buffer frame"),
         }
+        page_ids
+    }
+
+    /// Unpin the page contained in the specified frame and release the read latch.
+    /// Only frames obtained with `PinnedFrame::into_arc()` should be unpinned explicitly.
+    #[deprecated(note = "pins are released when the `PinnedFrame` is dropped")]
+    pub fn unpin_r(&self, frame: FrameRLatch) {
+        self.release_pin(&frame);
     }
 
     /// Unpin the page contained in the specified frame and release the write latch.
+    /// Only frames obtained with `PinnedFrame::into_arc()` should be unpinned explicitly.
+    #[deprecated(note = "pins are released when the `PinnedFrame` is dropped")]
     pub fn unpin_w(&self, frame: FrameWLatch) {
+        self.release_pin(&frame);
+    }
+
+    /// Unpin the page contained in the specified frame, and hand the frame back to the replacer
+    /// once it's no longer pinned.
+    fn release_pin(&self, frame: &BufferFrame) {
         match frame.get_page() {
             Some(_) => {
                 frame.unpin();
@@ -422,6 +629,21 @@ impl BufferManager {
         }
     }
 
+    /// Write the page contained in the specified frame to disk. If logging is enabled, the log is
+    /// flushed up to the LSN of the page first, so that the page never reaches disk before its
+    /// log records (write-ahead logging).
+    fn write_back(&self, frame: &BufferFrame) -> Result<(), BufferError> {
+        // .unwrap() ok since the caller only writes back frames that contain a page.
+        let page = frame.get_page().unwrap();
+        if let Some(log_manager) = &self.log_manager {
+            if frame.get_lsn() != INVALID_LSN {
+                log_manager.flush_to_lsn(frame.get_lsn());
+            }
+        }
+        self.disk_manager.write_page(RawPage::get_id(page), page)?;
+        Ok(())
+    }
+
     /// Find the specified page in the page table, and return a reference to its frame.
     fn lookup(&self, page_table: &MutexGuard<PageTable>, page_id: PageIdT) -> Option<FrameArc> {
         match page_table.get(&page_id) {
@@ -431,6 +653,17 @@ impl BufferManager {
     }
 }
 
+/// Policies for handling the contents of a page that is deleted by the buffer manager.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// Drop the page from the buffer without writing it to disk.
+    #[default]
+    Discard,
+
+    /// Write the page to disk if it has been modified, and then delete it.
+    FlushThenDelete,
+}
+
 /// Custom error types to be used by the buffer manager.
 #[derive(Debug)]
 pub enum BufferError {
@@ -447,4 +680,157 @@ pub enum BufferError {
 
     /// Error to be thrown when the specified foo does not exist on disk.
     PageDiskDNE,
+
+    /// Error to be thrown when the disk manager fails to read, write, or allocate a page.
+    Disk(DiskError),
+
+    /// Error to be thrown when a fetched page can't be interpreted as the requested page type.
+    InvalidPage(BufferFrameError),
+}
+
+impl From<DiskError> for BufferError {
+    fn from(e: DiskError) -> Self {
+        Self::Disk(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::PAGE_SIZE;
+    use crate::page::{DictionaryEntryKind, DictionaryPage};
+
+    #[test]
+    fn test_downcast_relation_page() {
+        let mut frame = BufferFrame::new(0);
+        assert!(matches!(
+            frame.as_relation_page(),
+            Err(BufferFrameError::EmptyBuffer)
+        ));
+
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+        frame.overwrite(Some(page));
+
+        let page = frame.as_relation_page_mut().unwrap();
+        RelationPage::set_next_page_id(page, 6);
+        let page = frame.as_relation_page().unwrap();
+        assert_eq!(RelationPage::get_id(page), 5);
+        assert_eq!(RelationPage::get_next_page_id(page), Some(6));
+    }
+
+    #[test]
+    fn test_downcast_dictionary_page() {
+        let mut page = RawPage::new(CATALOG_ROOT_ID);
+        DictionaryPage::set(&mut page, "users", 3, DictionaryEntryKind::Relation).unwrap();
+
+        let mut frame = BufferFrame::new(0);
+        frame.overwrite(Some(page));
+        assert!(matches!(
+            frame.as_relation_page(),
+            Err(BufferFrameError::InvalidDowncast)
+        ));
+        assert!(matches!(
+            frame.as_relation_page_mut(),
+            Err(BufferFrameError::InvalidDowncast)
+        ));
+    }
+
+    #[test]
+    fn test_fetch_relation_page_write() {
+        let manager = BufferManager::new(4, DiskManager::in_memory(), ReplacerAlgorithm::Slow);
+        let page_id = {
+            let frame_arc = manager.create_page().unwrap();
+            let mut frame = frame_arc.write().unwrap();
+            let page = frame.get_mut_page().unwrap();
+            RelationPage::init(page);
+            RawPage::get_id(page)
+        };
+
+        // Mutate the page through the guard.
+        {
+            let mut guard = manager.fetch_relation_page_write(page_id).unwrap();
+            RelationPage::set_next_page_id(&mut guard, 42);
+        }
+
+        // Assert that dropping the guard unpinned the page and marked it as dirty.
+        let frame_arc = manager.fetch_page(page_id).unwrap();
+        let frame = frame_arc.read().unwrap();
+        assert_eq!(frame.get_pin_count(), 1);
+        assert!(frame.is_dirty());
+        assert_eq!(
+            RelationPage::get_next_page_id(frame.get_page().unwrap()),
+            Some(42)
+        );
+        drop(frame);
+        drop(frame_arc);
+        assert_eq!(count_pinned(&manager), 0);
+    }
+
+    #[test]
+    fn test_fetch_relation_page_write_invalid_page() {
+        let manager = BufferManager::new(4, DiskManager::in_memory(), ReplacerAlgorithm::Slow);
+        let page_id = {
+            let frame_arc = manager.create_page().unwrap();
+            let mut frame = frame_arc.write().unwrap();
+            let page = frame.get_mut_page().unwrap();
+            RelationPage::set_free_pointer(page, PAGE_SIZE);
+            RawPage::get_id(page)
+        };
+
+        // Assert that a page that isn't a relation page is rejected and left unpinned.
+        assert!(matches!(
+            manager.fetch_relation_page_write(page_id),
+            Err(BufferError::InvalidPage(BufferFrameError::InvalidDowncast))
+        ));
+        assert_eq!(count_pinned(&manager), 0);
+    }
+
+    #[test]
+    fn test_pinned_frame_unpins_on_error() {
+        let manager = BufferManager::new(4, DiskManager::in_memory(), ReplacerAlgorithm::Slow);
+        let page_id = RawPage::get_id(
+            manager
+                .create_page()
+                .unwrap()
+                .read()
+                .unwrap()
+                .get_page()
+                .unwrap(),
+        );
+
+        // Simulate an operation that fails while holding a pinned and latched page.
+        let operation = || -> Result<(), BufferError> {
+            let frame_arc = manager.fetch_page(page_id)?;
+            let mut frame = frame_arc.write().unwrap();
+            frame.set_dirty_flag(true);
+            manager.fetch_page(page_id + 1)?;
+            Ok(())
+        };
+        assert!(matches!(operation(), Err(BufferError::PageDiskDNE)));
+
+        // Assert that the early return left no frame pinned.
+        assert_eq!(count_pinned(&manager), 0);
+        manager.delete_page(page_id, DeletePolicy::Discard).unwrap();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_pinned_frame_into_arc() {
+        let manager = BufferManager::new(4, DiskManager::in_memory(), ReplacerAlgorithm::Slow);
+
+        // Assert that a frame released from its pinned reference stays pinned until it is
+        // unpinned explicitly.
+        let frame_arc = manager.create_page().unwrap().into_arc();
+        assert_eq!(count_pinned(&manager), 1);
+        manager.unpin_w(frame_arc.write().unwrap());
+        assert_eq!(count_pinned(&manager), 0);
+    }
+
+    /// Return the number of frames in the buffer that are pinned.
+    fn count_pinned(manager: &BufferManager) -> usize {
+        (0..manager.buffer.size())
+            .filter(|&id| manager.buffer.get(id).read().unwrap().get_pin_count() > 0)
+            .count()
+    }
 }
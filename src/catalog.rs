@@ -3,16 +3,25 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
-use crate::buffer::{BufferError, BufferManager};
-use crate::constants::RelationIdT;
-use crate::relation::heap::Heap;
+use crate::buffer::{BufferError, BufferManager, DeletePolicy};
+use crate::constants::{PageIdT, RelationIdT, CATALOG_ROOT_ID};
+use crate::index::btree_index::BTreeIndex;
+use crate::index::{Index, IndexError, IndexMeta};
+use crate::page::{DictionaryEntryKind, DictionaryPage, PageError, RawPage, SchemaPage};
+use crate::relation::heap::{Heap, HeapError};
 use crate::relation::Relation;
 use crate::relation::Schema;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
 
-/// The system catalog maintains metadata about relations in the database.
+/// The system catalog maintains metadata about relations and indexes in the database.
+///
+/// Every relation is recorded in the dictionary, a chain of dictionary pages starting at
+/// CATALOG_ROOT_ID, which maps the name of the relation to the ID of its schema page. The schema
+/// page contains the relation ID, the root page ID of its heap, and its attributes, so that the
+/// catalog can be reconstructed from disk when the database is reopened. Every index is recorded
+/// in the dictionary with the ID of its root page.
 pub struct SystemCatalog {
     /// Mapping of relation IDs to relations
     relations: Arc<RwLock<HashMap<RelationIdT, Arc<Relation>>>>,
@@ -20,6 +29,9 @@ pub struct SystemCatalog {
     /// Mapping of relation names to relation IDs
     relation_ids: Arc<RwLock<HashMap<String, RelationIdT>>>,
 
+    /// Mapping of index names to indexes
+    indexes: Arc<RwLock<HashMap<String, Arc<dyn Index>>>>,
+
     /// Next relation ID to be used
     next_relation_id: AtomicU32,
 
@@ -28,32 +40,103 @@ pub struct SystemCatalog {
 }
 
 impl SystemCatalog {
-    /// Create a new system catalog.
+    /// Create a new system catalog, loading every relation recorded in the dictionary page.
+    /// Panics if the dictionary page or a schema page can't be read.
     pub fn new(buffer_manager: Arc<BufferManager>) -> Self {
-        Self {
+        let catalog = Self {
             relations: Arc::new(RwLock::new(HashMap::new())),
             relation_ids: Arc::new(RwLock::new(HashMap::new())),
+            indexes: Arc::new(RwLock::new(HashMap::new())),
             next_relation_id: AtomicU32::new(0),
             buffer_manager,
+        };
+        catalog.load().unwrap();
+        catalog
+    }
+
+    /// Reconstruct every relation recorded in the dictionary page from its schema page.
+    ///
+    /// Serial counters are not persisted. They are re-derived from the records still stored, so
+    /// serial values can be reused after the highest rows are deleted.
+    /// TODO: The key columns of indexes are not persisted, so indexes are not loaded from disk.
+    fn load(&self) -> Result<(), CatalogError> {
+        let entries: Vec<(String, PageIdT)> = self
+            .get_dictionary_entries()?
+            .into_iter()
+            .filter(|(_, _, kind)| *kind == DictionaryEntryKind::Relation)
+            .map(|(name, root_id, _)| (name, root_id))
+            .collect();
+
+        let mut relation_ids = self.relation_ids.write().unwrap();
+        let mut relations = self.relations.write().unwrap();
+        for (name, schema_page_id) in entries {
+            let frame_arc = self.buffer_manager.fetch_page(schema_page_id)?;
+            let frame = frame_arc.read().unwrap();
+            let page = frame.get_page().unwrap();
+            let relation_id = SchemaPage::get_relation_id(page);
+            let heap_root_id = SchemaPage::get_heap_root_id(page);
+            let attrs = SchemaPage::get_attributes(page);
+
+            // RELEASE read latch to schema page before the heap is read.
+            drop(frame);
+            drop(frame_arc);
+
+            let schema = Arc::new(Schema::new(attrs?));
+            let heap = Arc::new(Heap::open(self.buffer_manager.clone(), heap_root_id));
+            let relation = Arc::new(Relation::open(relation_id, name.clone(), schema, heap)?);
+            relation_ids.insert(name, relation_id);
+            relations.insert(relation_id, relation);
+
+            // Relation IDs are never reused, so continue counting from the largest loaded ID.
+            self.next_relation_id
+                .fetch_max(relation_id + 1, Ordering::SeqCst);
         }
+        Ok(())
     }
 
-    /// Initialize a new relation and return a protected reference.
+    /// Initialize a new relation, record it in the dictionary page and a new schema page, and
+    /// return a protected reference. Return an error if a relation or index already exists with
+    /// the name, or if the name or schema can't be recorded, in which case the pages allocated
+    /// for the relation are deleted.
     pub fn create_relation(
         &self,
         name: &str,
         schema: Arc<Schema>,
-    ) -> Result<Arc<Relation>, BufferError> {
-        // Initialize a new database heap.
+    ) -> Result<Arc<Relation>, CatalogError> {
+        // Lock the relation_ids and relations table, so that relations are recorded in the
+        // dictionary page in the same order as they are created.
+        let mut relation_ids = self.relation_ids.write().unwrap();
+        let mut relations = self.relations.write().unwrap();
+        if relation_ids.contains_key(name) {
+            return Err(CatalogError::RelationExists);
+        }
+        if self.name_exists(name)? {
+            return Err(CatalogError::NameExists);
+        }
+
+        // Initialize a new database heap, and record it in a new schema page and the dictionary
+        // page.
         let heap = Arc::new(Heap::new(self.buffer_manager.clone())?);
+        let relation_id = self.get_next_relation_id();
+        let schema_page_id = match self.create_schema_page(relation_id, heap.get_root_id(), &schema)
+        {
+            Ok(page_id) => page_id,
+            Err(e) => {
+                self.delete_pages(&[heap.get_root_id()]);
+                return Err(e);
+            }
+        };
+        if let Err(e) =
+            self.set_dictionary_entry(name, schema_page_id, DictionaryEntryKind::Relation)
+        {
+            self.delete_pages(&[heap.get_root_id(), schema_page_id]);
+            return Err(e);
+        }
 
         // Create a new relation with the given name, schema, and newly initialized heap.
-        let relation_id = self.get_next_relation_id();
         let relation = Arc::new(Relation::new(relation_id, name.to_string(), schema, heap));
 
-        // Lock and update the relation_ids and relations table.
-        let mut relation_ids = self.relation_ids.write().unwrap();
-        let mut relations = self.relations.write().unwrap();
+        // Update the relation_ids and relations table.
         relation_ids.insert(name.to_string(), relation_id);
         relations.insert(relation_id, relation.clone());
 
@@ -61,6 +144,37 @@ impl SystemCatalog {
         Ok(relation)
     }
 
+    /// Return the relation with the specified name, or create it if it doesn't exist. Return an
+    /// error if an existing relation has a different schema, or if the relation can't be created
+    /// (see `create_relation`).
+    ///
+    /// Checks of a relation loaded from disk reject every value until their validators are
+    /// registered, which is done by passing the original schema of the relation to this method.
+    pub fn get_or_create_relation(
+        &self,
+        name: &str,
+        schema: Arc<Schema>,
+    ) -> Result<Arc<Relation>, CatalogError> {
+        loop {
+            if let Some(relation) = self.get_relation(name) {
+                let current = relation.get_schema();
+                if *current != *schema {
+                    return Err(CatalogError::SchemaMismatch);
+                }
+                let attrs = current.get_attributes();
+                if attrs.iter().any(|attr| attr.has_unregistered_checks()) {
+                    relation.register_checks(schema);
+                }
+                return Ok(relation);
+            }
+            match self.create_relation(name, schema.clone()) {
+                // The relation may have been created by another thread in the meantime.
+                Err(CatalogError::RelationExists) => continue,
+                result => return result,
+            }
+        }
+    }
+
     /// Lookup a relation by its name and return a protected reference.
     /// Return None if a relation does exist in the database with the given name.
     pub fn get_relation(&self, name: &str) -> Option<Arc<Relation>> {
@@ -81,9 +195,338 @@ impl SystemCatalog {
         }
     }
 
+    /// Return the ID and name of every relation in the database, in the order that they were
+    /// recorded in the dictionary. Panics if the dictionary can't be read.
+    pub fn list_relations(&self) -> Vec<(RelationIdT, String)> {
+        let relation_ids = self.relation_ids.read().unwrap();
+
+        self.get_dictionary_entries()
+            .unwrap()
+            .into_iter()
+            .filter(|(_, _, kind)| *kind == DictionaryEntryKind::Relation)
+            .filter_map(|(name, _, _)| Some((*relation_ids.get(&name)?, name)))
+            .collect()
+    }
+
+    /// Create a B+ tree index on the specified columns of a relation, back-fill it with the
+    /// existing records of the relation, and record it in the dictionary page. The index is
+    /// updated by subsequent insertions into the relation.
+    ///
+    /// Return an error if the relation doesn't exist, a relation or index already exists with
+    /// the index name, or the key columns can't be indexed.
+    pub fn create_index(
+        &self,
+        index_name: &str,
+        relation_name: &str,
+        key_columns: &[u32],
+    ) -> Result<Arc<dyn Index>, CatalogError> {
+        // Lock the relation_ids table, so that a relation can't be created with the index name
+        // concurrently.
+        let relation_ids = self.relation_ids.read().unwrap();
+        let mut indexes = self.indexes.write().unwrap();
+        if self.name_exists(index_name)? {
+            return Err(CatalogError::NameExists);
+        }
+
+        let relation = match relation_ids.get(relation_name) {
+            Some(&id) => self.get_relation_by_id(id).unwrap(),
+            None => return Err(CatalogError::RelationDNE),
+        };
+        let schema = relation.get_schema();
+        let mut key_attrs = Vec::with_capacity(key_columns.len());
+        for &col in key_columns {
+            match schema.get_attributes().get(col as usize) {
+                Some(attr) => key_attrs.push(attr.clone()),
+                None => return Err(CatalogError::InvalidKeyColumn),
+            }
+        }
+
+        let meta = IndexMeta::new(
+            index_name,
+            relation_name,
+            Arc::new(Schema::new(key_attrs)),
+            key_columns.to_vec(),
+            self.buffer_manager.clone(),
+        );
+        let index = Arc::new(BTreeIndex::new(meta)?);
+        let root_id = index.get_root_id();
+
+        if let Err(e) = self.set_dictionary_entry(index_name, root_id, DictionaryEntryKind::Index) {
+            self.delete_pages(&[root_id]);
+            return Err(e);
+        }
+        if let Err(e) = relation.add_index(index.clone()) {
+            // Pages created by splits during the back-fill are leaked, since they can't be
+            // reached without the root page.
+            let _ = self.remove_dictionary_entry(index_name);
+            self.delete_pages(&[root_id]);
+            return Err(e.into());
+        }
+
+        indexes.insert(index_name.to_string(), index.clone());
+        Ok(index)
+    }
+
+    /// Lookup an index by its name and return a protected reference.
+    /// Return None if an index does not exist in the database with the given name.
+    pub fn get_index(&self, name: &str) -> Option<Arc<dyn Index>> {
+        let indexes = self.indexes.read().unwrap();
+        indexes.get(name).cloned()
+    }
+
+    /// Return whether a relation or index is recorded in the dictionary with the specified name.
+    fn name_exists(&self, name: &str) -> Result<bool, CatalogError> {
+        Ok(self.find_dictionary_page(name)?.is_some())
+    }
+
+    /// Write the metadata of a relation into a newly created schema page and return its page ID.
+    fn create_schema_page(
+        &self,
+        relation_id: RelationIdT,
+        heap_root_id: PageIdT,
+        schema: &Schema,
+    ) -> Result<PageIdT, CatalogError> {
+        let frame_arc = self.buffer_manager.create_page()?;
+        let mut frame = frame_arc.write().unwrap();
+
+        let page = frame.get_mut_page().unwrap();
+        let page_id = RawPage::get_id(page);
+        let result = SchemaPage::write(page, relation_id, heap_root_id, schema.get_attributes());
+        frame.set_dirty_flag(true);
+
+        drop(frame);
+        drop(frame_arc);
+        if let Err(e) = result {
+            self.delete_pages(&[page_id]);
+            return Err(e.into());
+        }
+        Ok(page_id)
+    }
+
+    /// Delete pages that were allocated for a relation or index which couldn't be created. Errors
+    /// are ignored, since the pages are unreachable either way.
+    fn delete_pages(&self, page_ids: &[PageIdT]) {
+        for page_id in page_ids {
+            let _ = self
+                .buffer_manager
+                .delete_page(*page_id, DeletePolicy::Discard);
+        }
+    }
+
+    /// Return the IDs of the dictionary pages, from the first page to the last page.
+    fn get_dictionary_page_ids(&self) -> Result<Vec<PageIdT>, CatalogError> {
+        let mut page_ids = vec![CATALOG_ROOT_ID];
+        loop {
+            let frame_arc = self.buffer_manager.fetch_page(*page_ids.last().unwrap())?;
+            let frame = frame_arc.read().unwrap();
+            match DictionaryPage::get_next_page_id(frame.get_page().unwrap()) {
+                // A chain that loops back on itself can only be the result of corruption.
+                Some(page_id) if page_ids.contains(&page_id) => {
+                    return Err(CatalogError::PageCorrupted)
+                }
+                Some(page_id) => page_ids.push(page_id),
+                None => return Ok(page_ids),
+            }
+        }
+    }
+
+    /// Return the name, root page ID, and kind of every entry in the dictionary. Entries are
+    /// ordered by when they were first recorded.
+    fn get_dictionary_entries(
+        &self,
+    ) -> Result<Vec<(String, PageIdT, DictionaryEntryKind)>, CatalogError> {
+        let mut entries = Vec::new();
+        for page_id in self.get_dictionary_page_ids()? {
+            let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+            let frame = frame_arc.read().unwrap();
+            let page = frame.get_page().unwrap();
+            entries.extend(
+                (0..DictionaryPage::get_num_entries(page))
+                    .filter_map(|idx| DictionaryPage::get_entry(page, idx)),
+            );
+        }
+        Ok(entries)
+    }
+
+    /// Return the ID of the dictionary page containing the entry with the specified name, or None
+    /// if no such entry exists.
+    fn find_dictionary_page(&self, name: &str) -> Result<Option<PageIdT>, CatalogError> {
+        for page_id in self.get_dictionary_page_ids()? {
+            let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+            let frame = frame_arc.read().unwrap();
+            if DictionaryPage::get(frame.get_page().unwrap(), name).is_some() {
+                return Ok(Some(page_id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Record an entry in the dictionary. An existing entry with the same name is overwritten in
+    /// place. Otherwise, the entry is appended to the last dictionary page, and a new dictionary
+    /// page is chained to the end of the dictionary if the last page is full.
+    ///
+    /// The dictionary must only be modified while holding the write latch of either the
+    /// relation_ids or indexes table, so that it's modified by one thread at a time.
+    fn set_dictionary_entry(
+        &self,
+        name: &str,
+        root_id: PageIdT,
+        kind: DictionaryEntryKind,
+    ) -> Result<(), CatalogError> {
+        // Only appending a new entry to the last page can overflow.
+        let page_id = match self.find_dictionary_page(name)? {
+            Some(page_id) => page_id,
+            None => *self.get_dictionary_page_ids()?.last().unwrap(),
+        };
+
+        let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+        let mut frame = frame_arc.write().unwrap();
+        match DictionaryPage::set(frame.get_mut_page().unwrap(), name, root_id, kind) {
+            Ok(()) => {
+                frame.set_dirty_flag(true);
+                return Ok(());
+            }
+            Err(PageError::PageOverflow) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        // RELEASE write latch to the last page BEFORE calling buffer manager to prevent
+        // deadlocks.
+        drop(frame);
+        drop(frame_arc);
+
+        // Record the entry in a new dictionary page, and link it to the end of the dictionary.
+        let new_page_id = {
+            let frame_arc = self.buffer_manager.create_page()?;
+            let mut frame = frame_arc.write().unwrap();
+            let page = frame.get_mut_page().unwrap();
+
+            // .unwrap() ok since the name fit in the last page, and the new page is empty.
+            DictionaryPage::set(page, name, root_id, kind).unwrap();
+            let page_id = RawPage::get_id(page);
+            frame.set_dirty_flag(true);
+            page_id
+        };
+        let frame_arc = match self.buffer_manager.fetch_page(page_id) {
+            Ok(frame_arc) => frame_arc,
+            Err(e) => {
+                self.delete_pages(&[new_page_id]);
+                return Err(e.into());
+            }
+        };
+        let mut frame = frame_arc.write().unwrap();
+        DictionaryPage::set_next_page_id(frame.get_mut_page().unwrap(), new_page_id);
+        frame.set_dirty_flag(true);
+
+        Ok(())
+    }
+
+    /// Remove the entry with the specified name from the dictionary, and return whether an entry
+    /// was removed. Dictionary pages that are emptied remain in the chain.
+    fn remove_dictionary_entry(&self, name: &str) -> Result<bool, CatalogError> {
+        let page_id = match self.find_dictionary_page(name)? {
+            Some(page_id) => page_id,
+            None => return Ok(false),
+        };
+
+        let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+        let mut frame = frame_arc.write().unwrap();
+        DictionaryPage::remove(frame.get_mut_page().unwrap(), name);
+        frame.set_dirty_flag(true);
+        Ok(true)
+    }
+
     /// Return the next relation ID and atomically increment the counter.
     fn get_next_relation_id(&self) -> u32 {
         // Note: .fetch_add() increments the value and returns the PREVIOUS value
         self.next_relation_id.fetch_add(1, Ordering::SeqCst)
     }
 }
+
+/// Custom errors to be used by the system catalog.
+#[derive(Debug, Eq, PartialEq)]
+pub enum CatalogError {
+    /// Error to be thrown when a relation or index name is too long to be recorded in the
+    /// dictionary.
+    NameTooLong,
+
+    /// Error to be thrown when a relation is created with the name of an existing relation.
+    RelationExists,
+
+    /// Error to be thrown when a relation or index is created with the name of an existing index,
+    /// or an index is created with the name of an existing relation.
+    NameExists,
+
+    /// Error to be thrown when an existing relation doesn't have the expected schema.
+    SchemaMismatch,
+
+    /// Error to be thrown when an index is created on a relation that doesn't exist.
+    RelationDNE,
+
+    /// Error to be thrown when an index is created on a column that doesn't exist in the
+    /// relation.
+    InvalidKeyColumn,
+
+    /// Error to be thrown when an index can't be created or back-filled.
+    IndexFailure(IndexError),
+
+    /// Error to be thrown when a schema has too many attributes to be recorded.
+    CatalogFull,
+
+    /// Error to be thrown when a catalog page has an inconsistent header and can't be read.
+    PageCorrupted,
+
+    /// Errors to be thrown when the buffer manager encounters a recoverable error.
+    BufMgrNoBufFrame,
+    BufMgrPagePinned,
+    BufMgrPageBufDNE,
+    BufMgrPageDiskDNE,
+    BufMgrDiskFailure,
+}
+
+impl From<BufferError> for CatalogError {
+    fn from(e: BufferError) -> Self {
+        match e {
+            BufferError::NoBufFrame => CatalogError::BufMgrNoBufFrame,
+            BufferError::PagePinned => CatalogError::BufMgrPagePinned,
+            BufferError::PageBufDNE => CatalogError::BufMgrPageBufDNE,
+            BufferError::PageDiskDNE => CatalogError::BufMgrPageDiskDNE,
+            BufferError::Disk(_) => CatalogError::BufMgrDiskFailure,
+            BufferError::InvalidPage(_) => CatalogError::PageCorrupted,
+        }
+    }
+}
+
+impl From<IndexError> for CatalogError {
+    fn from(e: IndexError) -> Self {
+        CatalogError::IndexFailure(e)
+    }
+}
+
+impl From<HeapError> for CatalogError {
+    fn from(e: HeapError) -> Self {
+        match e {
+            HeapError::IndexFailure(e) => CatalogError::IndexFailure(e),
+            HeapError::BufMgrNoBufFrame => CatalogError::BufMgrNoBufFrame,
+            HeapError::BufMgrPagePinned => CatalogError::BufMgrPagePinned,
+            HeapError::BufMgrPageBufDNE => CatalogError::BufMgrPageBufDNE,
+            HeapError::BufMgrPageDiskDNE => CatalogError::BufMgrPageDiskDNE,
+            HeapError::BufMgrDiskFailure => CatalogError::BufMgrDiskFailure,
+            // Other than the errors above, the heap of a relation only fails to be read when its
+            // pages are corrupted.
+            _ => CatalogError::PageCorrupted,
+        }
+    }
+}
+
+impl From<PageError> for CatalogError {
+    fn from(e: PageError) -> Self {
+        match e {
+            PageError::NameTooLong => CatalogError::NameTooLong,
+            PageError::InvalidHeader => CatalogError::PageCorrupted,
+            // Other than the errors above, catalog pages only fail to be written when they are
+            // out of space.
+            _ => CatalogError::CatalogFull,
+        }
+    }
+}
@@ -0,0 +1,337 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::concurrency::transaction::{Transaction, TransactionState};
+use crate::constants::TransactionIdT;
+use crate::relation::record::RecordId;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// The lock manager grants shared and exclusive locks on records to transactions, following
+/// two-phase locking. A transaction may acquire locks while it is growing, and moves to the
+/// shrinking phase once it releases a lock, after which it can't acquire any more locks.
+///
+/// Requests for a lock that conflicts with a lock held by another transaction block until the
+/// conflicting lock is released. A transaction holding the only shared lock on a record may
+/// upgrade it to an exclusive lock.
+///
+/// Deadlocks are detected with a wait-for graph, which has an edge from each blocked transaction
+/// to every transaction holding a conflicting lock. Whenever a request would block, the graph is
+/// checked for a cycle through the requesting transaction. The youngest transaction in the cycle
+/// (which has the largest ID) is aborted, and its pending request fails with
+/// `LockError::Deadlock`. Alternatively, requests can be made with a timeout, which fail with
+/// `LockError::Timeout` if the lock isn't granted in time.
+pub struct LockManager {
+    lock_table: Mutex<LockTable>,
+}
+
+impl LockManager {
+    pub fn new() -> Self {
+        Self {
+            lock_table: Mutex::new(LockTable {
+                locks: HashMap::new(),
+                waits_for: HashMap::new(),
+                victims: HashSet::new(),
+            }),
+        }
+    }
+
+    /// Acquire a shared lock on a record, blocking until no other transaction holds an exclusive
+    /// lock on it. Acquiring a lock that the transaction already holds has no effect.
+    pub fn lock_shared(&self, txn: &Transaction, rid: RecordId) -> Result<(), LockError> {
+        self.lock(txn, rid, LockMode::Shared, None)
+    }
+
+    /// Acquire a shared lock on a record like `lock_shared()`, but return an error and abort the
+    /// transaction if the lock can't be acquired within the specified duration.
+    pub fn lock_shared_timeout(
+        &self,
+        txn: &Transaction,
+        rid: RecordId,
+        timeout: Duration,
+    ) -> Result<(), LockError> {
+        self.lock(txn, rid, LockMode::Shared, Some(Instant::now() + timeout))
+    }
+
+    /// Acquire an exclusive lock on a record, blocking until no other transaction holds a lock on
+    /// it. A shared lock held by the transaction is upgraded.
+    pub fn lock_exclusive(&self, txn: &Transaction, rid: RecordId) -> Result<(), LockError> {
+        self.lock(txn, rid, LockMode::Exclusive, None)
+    }
+
+    /// Acquire an exclusive lock on a record like `lock_exclusive()`, but return an error and
+    /// abort the transaction if the lock can't be acquired within the specified duration.
+    pub fn lock_exclusive_timeout(
+        &self,
+        txn: &Transaction,
+        rid: RecordId,
+        timeout: Duration,
+    ) -> Result<(), LockError> {
+        self.lock(
+            txn,
+            rid,
+            LockMode::Exclusive,
+            Some(Instant::now() + timeout),
+        )
+    }
+
+    /// Release the lock held by a transaction on a record, and move the transaction to the
+    /// shrinking phase. Return whether the transaction held a lock on the record.
+    pub fn unlock(&self, txn: &Transaction, rid: RecordId) -> bool {
+        if txn.get_state() == TransactionState::Growing {
+            txn.set_state(TransactionState::Shrinking);
+        }
+        if !txn.remove_lock(rid) {
+            return false;
+        }
+
+        let mut lock_table = self.lock_table.lock().unwrap();
+        self.release(&mut lock_table, txn.get_id(), rid);
+        true
+    }
+
+    /// Release every lock held by a transaction. This is called when the transaction commits or
+    /// aborts, so the state of the transaction is left as-is.
+    pub fn unlock_all(&self, txn: &Transaction) {
+        let rids = txn.take_lock_set();
+
+        let mut lock_table = self.lock_table.lock().unwrap();
+        for rid in rids {
+            self.release(&mut lock_table, txn.get_id(), rid);
+        }
+    }
+
+    /// Acquire a lock on a record in the specified mode, waiting until the deadline (if any).
+    /// Return an error if the transaction can't acquire locks, is chosen as the victim of a
+    /// deadlock, or reaches the deadline, in which case the transaction is aborted (unless it has
+    /// already committed or aborted).
+    fn lock(
+        &self,
+        txn: &Transaction,
+        rid: RecordId,
+        mode: LockMode,
+        deadline: Option<Instant>,
+    ) -> Result<(), LockError> {
+        match txn.get_state() {
+            TransactionState::Growing => {}
+            TransactionState::Shrinking => {
+                txn.set_state(TransactionState::Aborted);
+                return Err(LockError::LockOnShrinking);
+            }
+            TransactionState::Committed | TransactionState::Aborted => {
+                return Err(LockError::TransactionFinished);
+            }
+        }
+
+        let id = txn.get_id();
+        let mut lock_table = self.lock_table.lock().unwrap();
+        loop {
+            if lock_table.victims.remove(&id) {
+                txn.set_state(TransactionState::Aborted);
+                return Err(LockError::Deadlock);
+            }
+
+            let state = lock_table.locks.entry(rid).or_insert_with(LockState::new);
+            let blockers = state.get_blockers(id, mode);
+            if blockers.is_empty() {
+                state.grant(id, mode);
+                break;
+            }
+            let released = state.released.clone();
+
+            // The request would block, so check whether waiting would cause a deadlock.
+            lock_table.waits_for.insert(id, (rid, blockers));
+            if let Some(cycle) = lock_table.find_cycle(id) {
+                let victim = *cycle.iter().max().unwrap();
+                let (victim_rid, _) = lock_table.waits_for.remove(&victim).unwrap();
+                if victim == id {
+                    txn.set_state(TransactionState::Aborted);
+                    return Err(LockError::Deadlock);
+                }
+
+                // Wake up the victim, so that its request fails.
+                lock_table.victims.insert(victim);
+                lock_table.locks[&victim_rid].released.notify_all();
+            }
+
+            lock_table = match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    let (mut lock_table, result) =
+                        released.wait_timeout(lock_table, timeout).unwrap();
+                    if result.timed_out() {
+                        lock_table.waits_for.remove(&id);
+                        lock_table.victims.remove(&id);
+                        txn.set_state(TransactionState::Aborted);
+                        return Err(LockError::Timeout);
+                    }
+                    lock_table
+                }
+                None => released.wait(lock_table).unwrap(),
+            };
+            lock_table.waits_for.remove(&id);
+        }
+
+        txn.add_lock(rid);
+        Ok(())
+    }
+
+    /// Release the lock held by a transaction on a record, and wake up the transactions waiting
+    /// for it.
+    fn release(&self, lock_table: &mut LockTable, id: TransactionIdT, rid: RecordId) {
+        let state = match lock_table.locks.get_mut(&rid) {
+            Some(state) => state,
+            None => return,
+        };
+        state.shared.remove(&id);
+        if state.exclusive == Some(id) {
+            state.exclusive = None;
+        }
+
+        state.released.notify_all();
+        if state.shared.is_empty() && state.exclusive.is_none() {
+            lock_table.locks.remove(&rid);
+        }
+    }
+}
+
+impl Default for LockManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The state of every lock, and the wait-for graph of blocked transactions.
+struct LockTable {
+    /// Mapping of record IDs to the state of their locks. Records without any lock holders are
+    /// removed from the table.
+    locks: HashMap<RecordId, LockState>,
+
+    /// Mapping of the IDs of blocked transactions to the record they are waiting to lock and the
+    /// IDs of the transactions holding a conflicting lock on it
+    waits_for: HashMap<TransactionIdT, (RecordId, HashSet<TransactionIdT>)>,
+
+    /// IDs of blocked transactions that were chosen as the victim of a deadlock, but haven't
+    /// woken up yet
+    victims: HashSet<TransactionIdT>,
+}
+
+impl LockTable {
+    /// Return the IDs of the transactions in a cycle of the wait-for graph through the specified
+    /// transaction, or None if there is no such cycle.
+    fn find_cycle(&self, start: TransactionIdT) -> Option<Vec<TransactionIdT>> {
+        let mut path = vec![start];
+        let mut visited = HashSet::new();
+        match self.find_path(start, start, &mut path, &mut visited) {
+            true => Some(path),
+            false => None,
+        }
+    }
+
+    /// Search the wait-for graph depth-first for a path from a transaction to the target
+    /// transaction. Return whether a path was found, in which case the transactions on the path
+    /// (excluding the target) are appended to `path`.
+    fn find_path(
+        &self,
+        from: TransactionIdT,
+        target: TransactionIdT,
+        path: &mut Vec<TransactionIdT>,
+        visited: &mut HashSet<TransactionIdT>,
+    ) -> bool {
+        let blockers = match self.waits_for.get(&from) {
+            Some((_, blockers)) => blockers,
+            None => return false,
+        };
+        for &next in blockers {
+            if next == target {
+                return true;
+            }
+            if !visited.insert(next) {
+                continue;
+            }
+            path.push(next);
+            if self.find_path(next, target, path, visited) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+/// The mode of a lock on a record.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+/// The holders of the lock on a single record.
+struct LockState {
+    /// IDs of the transactions holding a shared lock
+    shared: HashSet<TransactionIdT>,
+
+    /// ID of the transaction holding an exclusive lock, if any
+    exclusive: Option<TransactionIdT>,
+
+    /// Condition variable notified whenever a lock on the record is released
+    released: Arc<Condvar>,
+}
+
+impl LockState {
+    fn new() -> Self {
+        Self {
+            shared: HashSet::new(),
+            exclusive: None,
+            released: Arc::new(Condvar::new()),
+        }
+    }
+
+    /// Return the IDs of the other transactions holding a lock that conflicts with a lock in the
+    /// specified mode. The specified transaction can be granted the lock if there are none.
+    fn get_blockers(&self, id: TransactionIdT, mode: LockMode) -> HashSet<TransactionIdT> {
+        let mut blockers: HashSet<TransactionIdT> = self.exclusive.into_iter().collect();
+        if mode == LockMode::Exclusive {
+            blockers.extend(self.shared.iter());
+        }
+        blockers.remove(&id);
+        blockers
+    }
+
+    /// Grant a lock in the specified mode to a transaction. An exclusive lock replaces a shared
+    /// lock held by the transaction, and a shared lock is subsumed by an exclusive lock.
+    fn grant(&mut self, id: TransactionIdT, mode: LockMode) {
+        match mode {
+            LockMode::Shared if self.exclusive != Some(id) => {
+                self.shared.insert(id);
+            }
+            LockMode::Shared => {}
+            LockMode::Exclusive => {
+                self.shared.remove(&id);
+                self.exclusive = Some(id);
+            }
+        }
+    }
+}
+
+/// Custom errors to be used by the lock manager.
+#[derive(Debug, Eq, PartialEq)]
+pub enum LockError {
+    /// Error to be thrown when a transaction requests a lock after releasing a lock, which
+    /// violates two-phase locking. The transaction is aborted.
+    LockOnShrinking,
+
+    /// Error to be thrown when a transaction that has already committed or aborted requests a
+    /// lock.
+    TransactionFinished,
+
+    /// Error to be thrown when a transaction is aborted to break a deadlock.
+    Deadlock,
+
+    /// Error to be thrown when a lock requested with a timeout isn't granted in time. The
+    /// transaction is aborted.
+    Timeout,
+}
@@ -0,0 +1,8 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+pub mod lock_manager;
+pub mod transaction;
+pub mod transaction_manager;
@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::constants::TransactionIdT;
+use crate::relation::record::{Record, RecordId};
+use crate::relation::Relation;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// A database transaction. Operations performed on behalf of a transaction are passed a
+/// reference to it.
+///
+/// Modifications performed on behalf of the transaction are recorded in its write set, so that
+/// they can be finalized when the transaction commits or undone when it aborts. The records
+/// locked by the transaction are recorded in its lock set, so that they can be unlocked when the
+/// transaction commits or aborts.
+pub struct Transaction {
+    id: TransactionIdT,
+    state: Mutex<TransactionState>,
+    write_set: Mutex<Vec<WriteRecord>>,
+    lock_set: Mutex<HashSet<RecordId>>,
+}
+
+impl Transaction {
+    pub fn new(id: TransactionIdT) -> Self {
+        Self {
+            id,
+            state: Mutex::new(TransactionState::Growing),
+            write_set: Mutex::new(Vec::new()),
+            lock_set: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Return the ID of this transaction.
+    pub fn get_id(&self) -> TransactionIdT {
+        self.id
+    }
+
+    /// Return the current state of this transaction.
+    pub fn get_state(&self) -> TransactionState {
+        *self.state.lock().unwrap()
+    }
+
+    /// Set the state of this transaction.
+    pub(crate) fn set_state(&self, state: TransactionState) {
+        *self.state.lock().unwrap() = state;
+    }
+
+    /// Record a modification performed on behalf of this transaction.
+    pub fn append_write_record(&self, record: WriteRecord) {
+        self.write_set.lock().unwrap().push(record);
+    }
+
+    /// Remove and return every modification recorded in the write set, in the order they were
+    /// performed.
+    pub(crate) fn take_write_set(&self) -> Vec<WriteRecord> {
+        std::mem::take(&mut *self.write_set.lock().unwrap())
+    }
+
+    /// Return whether this transaction holds a lock (of either mode) on the specified record.
+    pub fn is_locked(&self, rid: RecordId) -> bool {
+        self.lock_set.lock().unwrap().contains(&rid)
+    }
+
+    /// Record that this transaction holds a lock on the specified record.
+    pub(crate) fn add_lock(&self, rid: RecordId) {
+        self.lock_set.lock().unwrap().insert(rid);
+    }
+
+    /// Record that this transaction no longer holds a lock on the specified record. Return
+    /// whether the lock was held.
+    pub(crate) fn remove_lock(&self, rid: RecordId) -> bool {
+        self.lock_set.lock().unwrap().remove(&rid)
+    }
+
+    /// Remove and return every record that this transaction holds a lock on.
+    pub(crate) fn take_lock_set(&self) -> HashSet<RecordId> {
+        std::mem::take(&mut *self.lock_set.lock().unwrap())
+    }
+}
+
+/// The state of a transaction. Under two-phase locking, a transaction is growing from when it
+/// begins until it releases its first lock, after which it is shrinking and can't acquire any
+/// more locks. A transaction is growing or shrinking until it either commits or aborts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransactionState {
+    Growing,
+    Shrinking,
+    Committed,
+    Aborted,
+}
+
+/// A modification of a record in a relation, performed on behalf of a transaction.
+pub struct WriteRecord {
+    /// Relation containing the modified record
+    pub relation: Arc<Relation>,
+
+    /// ID of the modified record after the modification
+    pub rid: RecordId,
+
+    /// Kind of the modification
+    pub write_type: WriteType,
+}
+
+/// The kind of a modification in a write set.
+pub enum WriteType {
+    /// The record was inserted.
+    Insert,
+
+    /// The record was flagged for deletion. The deletion is committed when the transaction
+    /// commits.
+    Delete,
+
+    /// The record was updated, with the contents of the record before the update.
+    Update(Record),
+}
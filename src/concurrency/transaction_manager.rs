@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::concurrency::lock_manager::LockManager;
+use crate::concurrency::transaction::{Transaction, TransactionState, WriteRecord, WriteType};
+use crate::constants::{TransactionIdT, SYSTEM_TXN_ID};
+use crate::log::{LogManager, LogOperation};
+use crate::relation::heap::HeapError;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// The transaction manager begins transactions and finalizes them when they commit or abort.
+/// Every lock held by a transaction is released once it commits or aborts.
+///
+/// If logging is enabled, a commit log record is appended and flushed before a transaction is
+/// considered committed, and an abort log record is appended once the modifications of an
+/// aborted transaction are undone. Transactions without either are rolled back by recovery.
+pub struct TransactionManager {
+    /// Next transaction ID to be used
+    next_txn_id: AtomicU32,
+
+    /// Lock manager which grants locks to the transactions of this manager
+    lock_manager: Arc<LockManager>,
+
+    /// Log manager for recording commits and aborts, if logging is enabled
+    log_manager: Option<Arc<LogManager>>,
+}
+
+impl TransactionManager {
+    /// Create a new transaction manager. Transaction IDs start after the ID reserved for
+    /// operations that aren't performed on behalf of a user transaction.
+    pub fn new(lock_manager: Arc<LockManager>) -> Self {
+        Self {
+            next_txn_id: AtomicU32::new(SYSTEM_TXN_ID + 1),
+            lock_manager,
+            log_manager: None,
+        }
+    }
+
+    /// Enable logging of commits and aborts with the specified log manager, which should be the
+    /// log manager of the buffer manager that modifications are made through. Transaction IDs
+    /// start after every ID in the existing log, so that recovery can't confuse transactions.
+    pub fn with_log_manager(mut self, log_manager: Arc<LogManager>) -> Self {
+        if let Some(max_id) = log_manager
+            .read_records()
+            .iter()
+            .map(|r| r.get_txn_id())
+            .max()
+        {
+            let next_id = self.next_txn_id.get_mut();
+            *next_id = (*next_id).max(max_id + 1);
+        }
+        self.log_manager = Some(log_manager);
+        self
+    }
+
+    /// Begin a new transaction with a unique ID, which is greater than the ID of every
+    /// transaction that began before it.
+    pub fn begin(&self) -> Transaction {
+        Transaction::new(self.get_next_txn_id())
+    }
+
+    /// Commit a transaction, committing the deletions in its write set and releasing its locks.
+    /// Return an error if the transaction has already committed or aborted, or if a deletion
+    /// can't be committed.
+    pub fn commit(&self, txn: &Transaction) -> Result<(), TransactionError> {
+        match txn.get_state() {
+            TransactionState::Growing | TransactionState::Shrinking => {}
+            _ => return Err(TransactionError::InvalidState),
+        }
+
+        for record in txn.take_write_set() {
+            if let WriteType::Delete = record.write_type {
+                record.relation.commit_delete(record.rid, txn)?;
+            }
+        }
+
+        if let Some(log_manager) = &self.log_manager {
+            let lsn = log_manager.append(txn.get_id(), LogOperation::Commit);
+            log_manager.flush_to_lsn(lsn);
+        }
+
+        txn.set_state(TransactionState::Committed);
+        self.lock_manager.unlock_all(txn);
+        Ok(())
+    }
+
+    /// Abort a transaction, undoing the modifications in its write set in reverse order and
+    /// releasing its locks. A transaction that was already aborted (such as by the lock manager)
+    /// can be aborted again to undo its modifications. Return an error if the transaction has
+    /// already committed, or if a modification can't be undone, in which case the transaction is
+    /// still aborted.
+    pub fn abort(&self, txn: &Transaction) -> Result<(), TransactionError> {
+        if txn.get_state() == TransactionState::Committed {
+            return Err(TransactionError::InvalidState);
+        }
+        txn.set_state(TransactionState::Aborted);
+
+        let result = self.rollback(txn);
+        if let (Ok(_), Some(log_manager)) = (&result, &self.log_manager) {
+            log_manager.append(txn.get_id(), LogOperation::Abort);
+        }
+        self.lock_manager.unlock_all(txn);
+        result
+    }
+
+    /// Undo the modifications in the write set of a transaction in reverse order. The undoing
+    /// modifications are themselves recorded in the write set, so they're discarded afterwards.
+    fn rollback(&self, txn: &Transaction) -> Result<(), TransactionError> {
+        let result = Self::undo(txn.take_write_set(), txn);
+        txn.take_write_set();
+        result
+    }
+
+    /// Undo each modification in a write set, stopping at the first that can't be undone.
+    fn undo(write_set: Vec<WriteRecord>, txn: &Transaction) -> Result<(), TransactionError> {
+        for record in write_set.into_iter().rev() {
+            let relation = record.relation;
+            let rid = record.rid;
+            match record.write_type {
+                WriteType::Insert => {
+                    relation.flag_delete(rid, txn)?;
+                    relation.commit_delete(rid, txn)?;
+                }
+                WriteType::Delete => relation.rollback_delete(rid, txn)?,
+                WriteType::Update(old) => {
+                    relation.update(old.to_unallocated(), rid, txn)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the next transaction ID and atomically increment the counter.
+    fn get_next_txn_id(&self) -> TransactionIdT {
+        self.next_txn_id.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+/// Custom errors to be used by the transaction manager.
+#[derive(Debug, Eq, PartialEq)]
+pub enum TransactionError {
+    /// Error to be thrown when a transaction that has already committed or aborted is committed,
+    /// or a committed transaction is aborted.
+    InvalidState,
+
+    /// Error to be thrown when a modification in the write set of a transaction can't be
+    /// committed or undone.
+    HeapFailure(HeapError),
+}
+
+impl From<HeapError> for TransactionError {
+    fn from(e: HeapError) -> Self {
+        TransactionError::HeapFailure(e)
+    }
+}
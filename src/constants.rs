@@ -22,3 +22,4 @@ pub const MAX_RECORD_SIZE: u32 = PAGE_SIZE - 4 * 8;
 pub const BUFFER_SIZE: BufferFrameIdT = 512; // safe to modify
 pub const CATALOG_ROOT_ID: PageIdT = 0;
 pub const INVALID_LSN: LsnT = 0;
+pub const SYSTEM_TXN_ID: TransactionIdT = 0;
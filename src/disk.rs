@@ -11,101 +11,357 @@ use std::io::prelude::*;
 use std::io::SeekFrom;
 use std::io::Write;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, RwLock};
 
-/// The disk manager is responsible for managing pages stored on disk.
+/// Custom errors to be used by the disk manager.
+#[derive(Debug)]
+pub enum DiskError {
+    /// Error to be thrown when the database file could not be opened, read, or written.
+    Io(std::io::Error),
+
+    /// Error to be thrown when a page that has not been allocated is read or written.
+    PageNotAllocated,
+
+    /// Error to be thrown when a page lies beyond the addressable range of the database file.
+    OutOfSpace,
+}
+
+impl From<std::io::Error> for DiskError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Interface for the backends of the disk manager, which store the pages of the database.
+/// Page IDs that were previously deallocated are recycled before new IDs are handed out, and
+/// the first dictionary page (ID = 0) is always allocated.
+pub trait Storage: Send + Sync {
+    /// Read a single page's data into the specified byte array.
+    fn read_page(&self, page_id: PageIdT, page_data: &mut PageBytes) -> Result<(), DiskError>;
+
+    /// Write the specified byte array out to the page.
+    fn write_page(&self, page_id: PageIdT, page_data: &PageBytes) -> Result<(), DiskError>;
+
+    /// Allocate a zeroed page and return the id of the allocated page.
+    fn allocate_page(&self) -> Result<PageIdT, DiskError>;
+
+    /// Deallocate the specified page. Deallocating an unallocated page is a no-op.
+    fn deallocate_page(&self, page_id: PageIdT);
 
+    /// Return whether the specified page is currently allocated.
+    fn is_allocated(&self, page_id: PageIdT) -> bool;
+
+    /// Return the IDs of all pages currently allocated, in ascending order.
+    fn allocated_page_ids(&self) -> Vec<PageIdT>;
+}
+
+/// The disk manager is responsible for managing pages stored on disk.
+/// Pages are stored in a database file by default. For tests, the pages can instead be stored in
+/// memory (see `DiskManager::in_memory`).
 pub struct DiskManager {
-    db_filename: String,
-    next_page_id: AtomicU32,
+    storage: Box<dyn Storage>,
 }
 
 impl DiskManager {
     /// Create a new disk manager.
     ///
     /// The first dictionary page (ID = 0) is allocated when the disk manager is initialized.
+    /// Panics if the database file cannot be created.
+    pub fn new(filename: &str) -> Self {
+        Self {
+            storage: Box::new(FileStorage::new(filename)),
+        }
+    }
+
+    /// Create a disk manager for an existing database file, such that every page in the file is
+    /// considered allocated. Pages that were deallocated before the file was closed are not
+    /// recycled. Panics if the database file cannot be opened.
+    pub fn open(filename: &str) -> Self {
+        Self {
+            storage: Box::new(FileStorage::open(filename)),
+        }
+    }
+
+    /// Create a disk manager which stores pages in memory instead of a file. The pages are lost
+    /// when the disk manager is dropped, so this is only useful for tests.
+    pub fn in_memory() -> Self {
+        Self {
+            storage: Box::new(MemoryStorage::new()),
+        }
+    }
+
+    /// Write the specified byte array out to disk.
+    pub fn write_page(&self, page_id: PageIdT, page_data: &PageBytes) -> Result<(), DiskError> {
+        self.storage.write_page(page_id, page_data)
+    }
+
+    /// Read a single page's data into the specified byte array.
+    pub fn read_page(&self, page_id: PageIdT, page_data: &mut PageBytes) -> Result<(), DiskError> {
+        self.storage.read_page(page_id, page_data)
+    }
+
+    /// Allocate a page on disk and return the id of the allocated page.
+    /// Page IDs that were previously deallocated are recycled before new IDs are handed out.
+    pub fn allocate_page(&self) -> Result<PageIdT, DiskError> {
+        self.storage.allocate_page()
+    }
+
+    /// Deallocate the specified page on disk. The page ID is added to the free list so that it
+    /// can be reused by a later allocation. Deallocating an unallocated page is a no-op.
+    pub fn deallocate_page(&self, page_id: PageIdT) {
+        self.storage.deallocate_page(page_id)
+    }
+
+    /// Return whether the specified page is currently allocated on disk.
+    pub fn is_allocated(&self, page_id: PageIdT) -> bool {
+        self.storage.is_allocated(page_id)
+    }
+
+    /// Return the IDs of all pages currently allocated on disk, in ascending order.
+    pub fn allocated_page_ids(&self) -> Vec<PageIdT> {
+        self.storage.allocated_page_ids()
+    }
+}
+
+/// Bookkeeping of allocated page IDs, which is shared by the storage backends.
+struct PageAllocator {
+    next_page_id: AtomicU32,
+
+    /// IDs of deallocated pages that can be recycled by subsequent allocations.
+    free_list: Mutex<Vec<PageIdT>>,
+}
+
+impl PageAllocator {
+    /// Create an allocator where the pages with IDs less than `num_pages` are allocated. The
+    /// first dictionary page is always allocated.
+    fn new(num_pages: u32) -> Self {
+        Self {
+            next_page_id: AtomicU32::new(num_pages.max(CATALOG_ROOT_ID + 1)),
+            free_list: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Return an ID for a new page, preferring a recycled ID. The second value is true if the
+    /// ID was recycled.
+    fn allocate(&self) -> (PageIdT, bool) {
+        match self.free_list.lock().unwrap().pop() {
+            Some(pid) => (pid, true),
+            // Note: .fetch_add() increments the value and returns the PREVIOUS value
+            None => (self.next_page_id.fetch_add(1, Ordering::SeqCst), false),
+        }
+    }
+
+    /// Return a recycled ID to the free list, after the page couldn't be allocated.
+    fn release(&self, page_id: PageIdT) {
+        self.free_list.lock().unwrap().push(page_id);
+    }
+
+    fn deallocate(&self, page_id: PageIdT) {
+        if page_id == CATALOG_ROOT_ID || page_id >= self.next_page_id.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut free_list = self.free_list.lock().unwrap();
+        if !free_list.contains(&page_id) {
+            free_list.push(page_id);
+        }
+    }
+
+    fn is_allocated(&self, page_id: PageIdT) -> bool {
+        page_id < self.next_page_id.load(Ordering::SeqCst)
+            && !self.free_list.lock().unwrap().contains(&page_id)
+    }
+
+    fn allocated_page_ids(&self) -> Vec<PageIdT> {
+        let free_list = self.free_list.lock().unwrap();
+        (0..self.next_page_id.load(Ordering::SeqCst))
+            .filter(|page_id| !free_list.contains(page_id))
+            .collect()
+    }
+}
+
+/// Storage backend which stores pages in a database file, where each page is located at the
+/// offset of its ID multiplied by the page size.
+pub struct FileStorage {
+    db_filename: String,
+    allocator: PageAllocator,
+}
+
+impl FileStorage {
+    /// Create a new database file. Panics if the database file cannot be created.
     pub fn new(filename: &str) -> Self {
         // Create database file.
-        let mut file = open_write_file(filename);
+        let mut file = open_write_file(filename).unwrap();
         let zeros = [0; (PAGE_SIZE * 2) as usize];
         file.write_all(&zeros).unwrap();
         file.flush().unwrap();
 
         Self {
             db_filename: filename.to_string(),
-            next_page_id: AtomicU32::new(CATALOG_ROOT_ID + 1),
+            allocator: PageAllocator::new(CATALOG_ROOT_ID + 1),
         }
     }
 
-    /// Write the specified byte array out to disk.
-    pub fn write_page(&self, page_id: PageIdT, page_data: &PageBytes) {
+    /// Open an existing database file, such that every page in the file is considered
+    /// allocated. Panics if the database file cannot be opened.
+    pub fn open(filename: &str) -> Self {
+        let len = File::open(filename).unwrap().metadata().unwrap().len();
+        let num_pages = (len / PAGE_SIZE as u64) as u32;
+
+        Self {
+            db_filename: filename.to_string(),
+            allocator: PageAllocator::new(num_pages),
+        }
+    }
+}
+
+impl Storage for FileStorage {
+    fn read_page(&self, page_id: PageIdT, page_data: &mut PageBytes) -> Result<(), DiskError> {
         if !self.is_allocated(page_id) {
-            panic!(
-                "Cannot write page (ID: {}) which has not been allocated",
-                page_id
-            );
+            return Err(DiskError::PageNotAllocated);
         }
 
-        let mut file = open_write_file(&self.db_filename);
-        let offset = page_id * PAGE_SIZE;
-        file.seek(SeekFrom::Start(offset as u64)).unwrap();
-        file.write_all(page_data).unwrap();
-        file.flush().unwrap();
+        let mut file = File::open(&self.db_filename)?;
+        file.seek(SeekFrom::Start(page_offset(page_id)?))?;
+        file.read_exact(&mut *page_data)?;
+        Ok(())
     }
 
-    /// Read a single page's data into the specified byte array.
-    pub fn read_page(&self, page_id: PageIdT, page_data: &mut PageBytes) {
+    fn write_page(&self, page_id: PageIdT, page_data: &PageBytes) -> Result<(), DiskError> {
         if !self.is_allocated(page_id) {
-            panic!(
-                "Cannot read page (ID: {}) which has not been allocated",
-                page_id
-            );
+            return Err(DiskError::PageNotAllocated);
         }
 
-        let mut file = File::open(&self.db_filename).unwrap();
-        let offset = page_id * PAGE_SIZE;
-        file.seek(SeekFrom::Start(offset as u64)).unwrap();
-        file.read_exact(&mut *page_data).unwrap();
+        let mut file = open_write_file(&self.db_filename)?;
+        file.seek(SeekFrom::Start(page_offset(page_id)?))?;
+        file.write_all(page_data)?;
+        file.flush()?;
+        Ok(())
     }
 
-    /// Allocate a page on disk and return the id of the allocated page.
-    pub fn allocate_page(&self) -> u32 {
+    fn allocate_page(&self) -> Result<PageIdT, DiskError> {
         // Open database file.
-        let mut file = open_write_file(&self.db_filename);
+        let mut file = open_write_file(&self.db_filename)?;
 
         // Obtain the descriptor for the newly allocated page.
-        let page_id = self.get_next_page_id();
+        let (page_id, recycled) = self.allocator.allocate();
 
-        // Zero-out newly allocated page on disk.
-        let data = [0; PAGE_SIZE as usize];
-        let offset = page_id * PAGE_SIZE;
-        file.seek(SeekFrom::Start(offset as u64)).unwrap();
-        file.write_all(&data).unwrap();
-        file.flush().unwrap();
+        // Zero-out newly allocated page on disk. A recycled ID is returned to the free list if
+        // the page could not be written.
+        let result = zero_page(&mut file, page_id);
+        if result.is_err() && recycled {
+            self.allocator.release(page_id);
+        }
+        result?;
 
         // Return new page descriptor.
-        page_id
+        Ok(page_id)
     }
 
-    /// Deallocate the specified page on disk. (Do nothing for now)
-    pub fn deallocate_page(&self, _page_id: PageIdT) {}
+    fn deallocate_page(&self, page_id: PageIdT) {
+        self.allocator.deallocate(page_id)
+    }
 
-    /// Return the next page ID and atomically increment the counter.
-    fn get_next_page_id(&self) -> u32 {
-        // Note: .fetch_add() increments the value and returns the PREVIOUS value
-        self.next_page_id.fetch_add(1, Ordering::SeqCst)
+    fn is_allocated(&self, page_id: PageIdT) -> bool {
+        self.allocator.is_allocated(page_id)
     }
 
-    /// Return whether the specified page is currently allocated on disk.
-    pub fn is_allocated(&self, page_id: PageIdT) -> bool {
-        page_id < self.next_page_id.load(Ordering::SeqCst)
+    fn allocated_page_ids(&self) -> Vec<PageIdT> {
+        self.allocator.allocated_page_ids()
+    }
+}
+
+/// Storage backend which stores pages in memory, indexed by page ID.
+pub struct MemoryStorage {
+    pages: RwLock<Vec<PageBytes>>,
+    allocator: PageAllocator,
+}
+
+impl MemoryStorage {
+    /// Create an empty storage, where only the first dictionary page is allocated.
+    pub fn new() -> Self {
+        Self {
+            pages: RwLock::new(vec![
+                [0; PAGE_SIZE as usize];
+                (CATALOG_ROOT_ID + 1) as usize
+            ]),
+            allocator: PageAllocator::new(CATALOG_ROOT_ID + 1),
+        }
+    }
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read_page(&self, page_id: PageIdT, page_data: &mut PageBytes) -> Result<(), DiskError> {
+        if !self.is_allocated(page_id) {
+            return Err(DiskError::PageNotAllocated);
+        }
+
+        let pages = self.pages.read().unwrap();
+        page_data.copy_from_slice(&pages[page_id as usize]);
+        Ok(())
+    }
+
+    fn write_page(&self, page_id: PageIdT, page_data: &PageBytes) -> Result<(), DiskError> {
+        if !self.is_allocated(page_id) {
+            return Err(DiskError::PageNotAllocated);
+        }
+
+        let mut pages = self.pages.write().unwrap();
+        pages[page_id as usize].copy_from_slice(page_data);
+        Ok(())
+    }
+
+    fn allocate_page(&self) -> Result<PageIdT, DiskError> {
+        // Hold the pages while allocating, so that a new page is never read before it exists.
+        let mut pages = self.pages.write().unwrap();
+        let (page_id, _) = self.allocator.allocate();
+
+        // Zero-out newly allocated page, growing the storage if the page is new.
+        let idx = page_id as usize;
+        if idx >= pages.len() {
+            pages.resize(idx + 1, [0; PAGE_SIZE as usize]);
+        }
+        pages[idx] = [0; PAGE_SIZE as usize];
+
+        Ok(page_id)
+    }
+
+    fn deallocate_page(&self, page_id: PageIdT) {
+        self.allocator.deallocate(page_id)
+    }
+
+    fn is_allocated(&self, page_id: PageIdT) -> bool {
+        self.allocator.is_allocated(page_id)
+    }
+
+    fn allocated_page_ids(&self) -> Vec<PageIdT> {
+        self.allocator.allocated_page_ids()
     }
 }
 
 /// Open a file in write-mode.
-pub fn open_write_file(filename: &str) -> File {
-    OpenOptions::new()
-        .create(true)
-        .write(true)
-        .open(filename)
-        .unwrap()
+pub fn open_write_file(filename: &str) -> std::io::Result<File> {
+    OpenOptions::new().create(true).write(true).open(filename)
+}
+
+/// Return the byte offset of the specified page in the database file.
+fn page_offset(page_id: PageIdT) -> Result<u64, DiskError> {
+    page_id
+        .checked_mul(PAGE_SIZE)
+        .map(|offset| offset as u64)
+        .ok_or(DiskError::OutOfSpace)
+}
+
+/// Write a page of zeros at the specified page's offset in the database file.
+fn zero_page(file: &mut File, page_id: PageIdT) -> Result<(), DiskError> {
+    let data = [0; PAGE_SIZE as usize];
+    file.seek(SeekFrom::Start(page_offset(page_id)?))?;
+    file.write_all(&data)?;
+    file.flush()?;
+    Ok(())
 }
@@ -0,0 +1,219 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{serialize_columns, BaseExecutor, ExecutorError};
+use crate::plan::aggr::{AggFn, AggregationPlanNode};
+use crate::plan::QueryPlanNode;
+use crate::relation::record::Record;
+use crate::relation::types::{InnerValue, Value, ValueError};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// An executor which groups the records of its child by the group-by columns and computes
+/// aggregates for each group.
+///
+/// Every record of the child is consumed before any output is produced. One record is outputted
+/// for each group, in the order that the groups first appeared. If there are no group-by
+/// columns, a single record with the aggregates over every record is outputted (even if the
+/// child produced no records).
+pub struct AggregationExecutor {
+    /// Aggregation plan node to be executed
+    node: AggregationPlanNode,
+
+    /// Executor producing the records to be aggregated
+    child: Box<dyn BaseExecutor>,
+
+    /// Aggregated records that have not been returned yet. None until the child is consumed.
+    output: Option<VecDeque<Record>>,
+
+    /// Error which stopped the aggregation, if any.
+    error: Option<ExecutorError>,
+}
+
+impl AggregationExecutor {
+    pub fn new(node: AggregationPlanNode, child: Box<dyn BaseExecutor>) -> Self {
+        Self {
+            node,
+            child,
+            output: None,
+            error: None,
+        }
+    }
+
+    /// Consume the child and return an aggregated record for each group. Return an error if the
+    /// child fails or an aggregate can't be computed, such as when a sum overflows.
+    fn aggregate(&mut self) -> Result<VecDeque<Record>, ExecutorError> {
+        let schema = self.node.get_input_schema();
+        let group_by = self.node.get_group_by();
+        let aggregates = self.node.get_aggregates();
+
+        // Groups are stored in the order that they first appeared, along with the group-by
+        // values and aggregate accumulators of each group.
+        let mut group_idxs: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut groups: Vec<(Vec<Option<InnerValue>>, Vec<Accumulator>)> = Vec::new();
+        let new_group = |values| {
+            let accs = aggregates.iter().map(|(agg, _)| Accumulator::new(*agg));
+            (values, accs.collect())
+        };
+        if group_by.is_empty() {
+            group_idxs.insert(Vec::new(), 0);
+            groups.push(new_group(Vec::new()));
+        }
+
+        while let Some(record) = self.child.next() {
+            let record = record.lock().unwrap();
+            let get_inner = |idx: u32| {
+                let value = record.get_value(idx, schema.clone()).unwrap();
+                value.map(|value| value.get_inner())
+            };
+
+            let key = serialize_columns(&record, group_by, schema.clone()).unwrap();
+            let group_idx = *group_idxs.entry(key).or_insert_with(|| {
+                groups.push(new_group(
+                    group_by.iter().map(|idx| get_inner(*idx)).collect(),
+                ));
+                groups.len() - 1
+            });
+            for (acc, (_, idx)) in groups[group_idx].1.iter_mut().zip(aggregates.iter()) {
+                acc.update(get_inner(*idx))?;
+            }
+        }
+        if let Some(e) = self.child.take_error() {
+            return Err(e);
+        }
+
+        let out_schema = self.node.get_output_schema();
+        groups
+            .into_iter()
+            .map(|(group_values, accs)| -> Result<Record, ExecutorError> {
+                let mut values: Vec<Option<Box<dyn Value>>> = group_values
+                    .into_iter()
+                    .map(|value| value.map(|value| value.into_value()))
+                    .collect();
+                for acc in accs {
+                    values.push(acc.finish()?.map(|value| value.into_value()));
+                }
+                Ok(Record::new(values, out_schema.clone()).unwrap())
+            })
+            .collect()
+    }
+}
+
+impl BaseExecutor for AggregationExecutor {
+    /// Return the next aggregated record. If the aggregation fails, the error is stored and no
+    /// records are returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.output.is_none() {
+            let output = match self.aggregate() {
+                Ok(output) => output,
+                Err(e) => {
+                    self.error = Some(e);
+                    VecDeque::new()
+                }
+            };
+            self.output = Some(output);
+        }
+        let record = self.output.as_mut().unwrap().pop_front()?;
+        Some(Arc::new(Mutex::new(record)))
+    }
+
+    /// Discard the output and consume the child again upon the following call to `next()`.
+    fn reset(&mut self) {
+        self.output = None;
+        self.error = None;
+        self.child.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take()
+    }
+}
+
+/// The running state of an aggregate over a single group.
+struct Accumulator {
+    agg: AggFn,
+
+    /// Number of rows (for COUNT(*)) or non-null values (for every other aggregate).
+    count: i64,
+
+    /// Running sum of the values, or None if no values have been added. Integer values are
+    /// summed as big integers.
+    sum: Option<InnerValue>,
+
+    /// Running minimum or maximum value.
+    extreme: Option<InnerValue>,
+}
+
+impl Accumulator {
+    fn new(agg: AggFn) -> Self {
+        Self {
+            agg,
+            count: 0,
+            sum: None,
+            extreme: None,
+        }
+    }
+
+    /// Add a value to the aggregate. Null values are ignored by every aggregate other than
+    /// COUNT(*). Return an error if a SUM or AVG aggregate is given a non-numeric value or its
+    /// sum overflows.
+    fn update(&mut self, value: Option<InnerValue>) -> Result<(), ValueError> {
+        let value = match (self.agg, value) {
+            (AggFn::CountStar, _) => {
+                self.count += 1;
+                return Ok(());
+            }
+            (_, None) => return Ok(()),
+            (_, Some(value)) => value,
+        };
+        self.count += 1;
+
+        match self.agg {
+            AggFn::Sum | AggFn::Avg => {
+                let value = match value {
+                    InnerValue::TinyInt(v) => InnerValue::BigInt(v as i64),
+                    InnerValue::SmallInt(v) => InnerValue::BigInt(v as i64),
+                    InnerValue::Int(v) => InnerValue::BigInt(v as i64),
+                    InnerValue::BigInt(_) | InnerValue::Real(_) | InnerValue::Decimal { .. } => {
+                        value
+                    }
+                    _ => return Err(ValueError::NonNumeric),
+                };
+                self.sum = Some(match &self.sum {
+                    Some(sum) => sum.checked_add(&value)?,
+                    None => value,
+                });
+            }
+            AggFn::Min => {
+                if self.extreme.as_ref().is_none_or(|min| value < *min) {
+                    self.extreme = Some(value);
+                }
+            }
+            AggFn::Max => {
+                if self.extreme.as_ref().is_none_or(|max| value > *max) {
+                    self.extreme = Some(value);
+                }
+            }
+            AggFn::CountStar | AggFn::Count => {}
+        }
+        Ok(())
+    }
+
+    /// Return the final value of the aggregate, or None if the aggregate is null (i.e. there
+    /// were no non-null values).
+    fn finish(self) -> Result<Option<InnerValue>, ValueError> {
+        match self.agg {
+            AggFn::CountStar | AggFn::Count => Ok(Some(InnerValue::BigInt(self.count))),
+            AggFn::Min | AggFn::Max => Ok(self.extreme),
+            AggFn::Sum => Ok(self.sum),
+            AggFn::Avg => match self.sum {
+                Some(sum) => Ok(Some(InnerValue::Real(
+                    (sum.to_f64()? / self.count as f64) as f32,
+                ))),
+                None => Ok(None),
+            },
+        }
+    }
+}
@@ -0,0 +1,108 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::constants::SYSTEM_TXN_ID;
+use crate::executor::{count_record, BaseExecutor, ExecutorContext, ExecutorError};
+use crate::plan::delete::DeletePlanNode;
+use crate::relation::heap::HeapError;
+use crate::relation::record::Record;
+use crate::relation::Relation;
+use std::sync::{Arc, Mutex};
+
+/// An executor for delete operations in the database.
+///
+/// Every record produced by the child executor (e.g. a filtered sequential scan) is deleted from
+/// the relation by its record ID. The executor outputs a single record containing the number of
+/// deleted rows, with the schema `count_schema()`.
+pub struct DeleteExecutor {
+    /// Relation to delete records from
+    relation: Arc<Relation>,
+
+    /// Executor producing the records to be deleted
+    child: Box<dyn BaseExecutor>,
+
+    /// Context of the query, whose transaction the modifications are performed on behalf of
+    ctx: Arc<ExecutorContext>,
+
+    /// Whether the delete has already been executed
+    done: bool,
+
+    /// Error which stopped the delete, if any
+    error: Option<ExecutorError>,
+}
+
+impl DeleteExecutor {
+    /// Create a new delete executor. Panics if the relation of the plan node doesn't exist in
+    /// the system catalog.
+    pub fn new(
+        ctx: Arc<ExecutorContext>,
+        node: DeletePlanNode,
+        child: Box<dyn BaseExecutor>,
+    ) -> Self {
+        let relation = ctx
+            .get_system_catalog()
+            .get_relation_by_id(node.get_relation_id())
+            .unwrap();
+
+        Self {
+            relation,
+            child,
+            ctx,
+            done: false,
+            error: None,
+        }
+    }
+
+    /// Delete every record produced by the child and return the number of deleted rows. Records
+    /// that have already been deleted or were never allocated are skipped. Return an error if
+    /// any other deletion or the child fails, in which case the records before it remain
+    /// deleted.
+    pub fn execute(&mut self) -> Result<i64, ExecutorError> {
+        let mut count = 0;
+        while let Some(record) = self.child.next() {
+            let rid = match record.lock().unwrap().get_id() {
+                Some(rid) => rid,
+                None => continue,
+            };
+            match self.relation.flag_delete(rid, self.ctx.get_transaction()) {
+                Ok(()) => {}
+                Err(HeapError::RecordDeleted) => continue,
+                Err(e) => return Err(e.into()),
+            }
+            // Deletions on behalf of a user transaction are committed when the transaction commits.
+            if self.ctx.get_transaction().get_id() == SYSTEM_TXN_ID {
+                self.relation
+                    .commit_delete(rid, self.ctx.get_transaction())?;
+            }
+            count += 1;
+        }
+        if let Some(e) = self.child.take_error() {
+            return Err(e);
+        }
+        Ok(count)
+    }
+}
+
+impl BaseExecutor for DeleteExecutor {
+    /// Execute the delete and return a record containing the number of deleted rows. If the
+    /// delete fails, the error is stored and None is returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        match self.execute() {
+            Ok(count) => Some(Arc::new(Mutex::new(count_record(count)))),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take()
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{serialize_columns, BaseExecutor, ExecutorError};
+use crate::plan::distinct::DistinctPlanNode;
+use crate::plan::QueryPlanNode;
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// An executor which outputs the records of its child, skipping records equal to a record that
+/// was already outputted.
+///
+/// Records are compared by their serialized values, so null values are equal to each other and
+/// different from every other value.
+pub struct DistinctExecutor {
+    /// Schema of the records produced by the child
+    schema: Arc<Schema>,
+
+    /// Executor producing the records to be deduplicated
+    child: Box<dyn BaseExecutor>,
+
+    /// Serialized values of every record outputted so far
+    seen: HashSet<Vec<u8>>,
+}
+
+impl DistinctExecutor {
+    pub fn new(node: DistinctPlanNode, child: Box<dyn BaseExecutor>) -> Self {
+        Self {
+            schema: node.get_output_schema(),
+            child,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl BaseExecutor for DistinctExecutor {
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        let indices: Vec<u32> = (0..self.schema.attr_len()).collect();
+        loop {
+            let record = self.child.next()?;
+            let values =
+                serialize_columns(&record.lock().unwrap(), &indices, self.schema.clone()).unwrap();
+            if self.seen.insert(values) {
+                return Some(record);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.seen.clear();
+        self.child.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.child.take_error()
+    }
+}
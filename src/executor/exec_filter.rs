@@ -0,0 +1,43 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{BaseExecutor, ExecutorError};
+use crate::plan::filter::FilterPlanNode;
+use crate::relation::record::Record;
+use std::sync::{Arc, Mutex};
+
+/// An executor which only outputs the records of its child that satisfy a predicate.
+pub struct FilterExecutor {
+    /// Filter plan node to be executed
+    node: FilterPlanNode,
+
+    /// Executor producing the records to be filtered
+    child: Box<dyn BaseExecutor>,
+}
+
+impl FilterExecutor {
+    pub fn new(node: FilterPlanNode, child: Box<dyn BaseExecutor>) -> Self {
+        Self { node, child }
+    }
+}
+
+impl BaseExecutor for FilterExecutor {
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        loop {
+            let record = self.child.next()?;
+            if self.node.evaluate(&record.lock().unwrap()) {
+                return Some(record);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.child.take_error()
+    }
+}
@@ -0,0 +1,149 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{join_records, serialize_columns, BaseExecutor, ExecutorError};
+use crate::plan::hash_join::HashJoinPlanNode;
+use crate::plan::QueryPlanNode;
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// An executor for equi-joins of two children using a hash table.
+///
+/// In the build phase, every record of the left child is stored in a hash table keyed by its
+/// serialized join columns, so the smaller input should be the left child. In the probe phase,
+/// the records of the right child are streamed and joined with every left record with an equal
+/// key. Records with a null join column never match.
+pub struct HashJoinExecutor {
+    /// Hash join plan node to be executed
+    node: HashJoinPlanNode,
+
+    /// Executor producing the records of the build side
+    left: Box<dyn BaseExecutor>,
+
+    /// Executor producing the records of the probe side
+    right: Box<dyn BaseExecutor>,
+
+    /// Records of the left child, keyed by their serialized join columns. None until the build
+    /// phase is executed.
+    table: Option<HashMap<Vec<u8>, Vec<Record>>>,
+
+    /// Joined records that have not been returned yet
+    output: VecDeque<Record>,
+
+    /// Error of the left child which stopped the build phase, if any
+    error: Option<ExecutorError>,
+}
+
+impl HashJoinExecutor {
+    pub fn new(
+        node: HashJoinPlanNode,
+        left: Box<dyn BaseExecutor>,
+        right: Box<dyn BaseExecutor>,
+    ) -> Self {
+        Self {
+            node,
+            left,
+            right,
+            table: None,
+            output: VecDeque::new(),
+            error: None,
+        }
+    }
+
+    /// Drain the left child into a hash table keyed by the join columns. Return an error if the
+    /// left child fails.
+    fn build(&mut self) -> Result<HashMap<Vec<u8>, Vec<Record>>, ExecutorError> {
+        let schema = self.node.get_left_schema();
+        let key_attrs = self.node.get_left_key_attrs();
+
+        let mut table: HashMap<Vec<u8>, Vec<Record>> = HashMap::new();
+        while let Some(record) = self.left.next() {
+            let record = record.lock().unwrap().clone();
+            if let Some(key) = join_key(&record, key_attrs, &schema) {
+                table.entry(key).or_default().push(record);
+            }
+        }
+        match self.left.take_error() {
+            Some(e) => Err(e),
+            None => Ok(table),
+        }
+    }
+}
+
+impl BaseExecutor for HashJoinExecutor {
+    /// Return the next joined record. If the left child fails, the error is stored and no
+    /// records are returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.table.is_none() {
+            match self.build() {
+                Ok(table) => self.table = Some(table),
+                Err(e) => {
+                    self.error = Some(e);
+                    self.table = Some(HashMap::new());
+                    return None;
+                }
+            }
+        }
+        if self.error.is_some() {
+            return None;
+        }
+        let left_schema = self.node.get_left_schema();
+        let right_schema = self.node.get_right_schema();
+        let out_schema = self.node.get_output_schema();
+
+        loop {
+            if let Some(record) = self.output.pop_front() {
+                return Some(Arc::new(Mutex::new(record)));
+            }
+
+            // Probe the hash table with the next record of the right child.
+            let right = self.right.next()?;
+            let right = right.lock().unwrap();
+            let key = match join_key(&right, self.node.get_right_key_attrs(), &right_schema) {
+                Some(key) => key,
+                None => continue,
+            };
+            if let Some(matches) = self.table.as_ref().unwrap().get(&key) {
+                for left in matches {
+                    let record = join_records(
+                        left,
+                        left_schema.clone(),
+                        &right,
+                        right_schema.clone(),
+                        out_schema.clone(),
+                    )
+                    .unwrap();
+                    self.output.push_back(record);
+                }
+            }
+        }
+    }
+
+    /// Discard the hash table and rebuild it from the left child upon the following call to
+    /// `next()`.
+    fn reset(&mut self) {
+        self.table = None;
+        self.output.clear();
+        self.error = None;
+        self.left.reset();
+        self.right.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take().or_else(|| self.right.take_error())
+    }
+}
+
+/// Return the serialized join columns of a record, or None if any join column is null.
+fn join_key(record: &Record, key_attrs: &[u32], schema: &Arc<Schema>) -> Option<Vec<u8>> {
+    for idx in key_attrs {
+        if record.is_null(*idx, schema.clone()).unwrap() {
+            return None;
+        }
+    }
+    Some(serialize_columns(record, key_attrs, schema.clone()).unwrap())
+}
@@ -0,0 +1,104 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{BaseExecutor, ExecutorContext, ExecutorError};
+use crate::index::btree_index::BTreeIndex;
+use crate::plan::index_scan::IndexScanPlanNode;
+use crate::relation::heap::HeapError;
+use crate::relation::record::{Record, RecordId};
+use crate::relation::Relation;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// An executor for scans over the records of a relation whose keys are in the range of the plan
+/// node, using a B+ tree index on the relation.
+///
+/// The matching record IDs are looked up in the index upon the first call to `next()`, and each
+/// record is then read from the heap of the relation in key order. Record IDs whose records have
+/// been deleted since they were indexed are skipped. If the index or the heap can't be read, the
+/// scan is stopped and the error is stored; see `take_error()`.
+pub struct IndexScanExecutor {
+    /// Index scan plan node to be executed
+    node: IndexScanPlanNode,
+
+    /// Relation being scanned
+    relation: Arc<Relation>,
+
+    /// Index over the relation
+    index: Arc<BTreeIndex>,
+
+    /// Context of the query that the scan belongs to
+    ctx: Arc<ExecutorContext>,
+
+    /// Record IDs that have not been read yet. None until the index is looked up.
+    rids: Option<VecDeque<RecordId>>,
+
+    /// Error which stopped the scan, if any
+    error: Option<ExecutorError>,
+}
+
+impl IndexScanExecutor {
+    /// Create a new index scan executor. Panics if the relation of the plan node doesn't exist in
+    /// the system catalog.
+    pub fn new(ctx: Arc<ExecutorContext>, node: IndexScanPlanNode, index: Arc<BTreeIndex>) -> Self {
+        let relation = ctx
+            .get_system_catalog()
+            .get_relation_by_id(node.get_relation_id())
+            .unwrap();
+
+        Self {
+            node,
+            relation,
+            index,
+            ctx,
+            rids: None,
+            error: None,
+        }
+    }
+}
+
+impl BaseExecutor for IndexScanExecutor {
+    /// Return the next record in the range. If the index or a record can't be read, such as when
+    /// every buffer frame is pinned, the error is stored and None is returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.rids.is_none() {
+            let rids = self.index.range_scan(
+                self.node.get_low(),
+                self.node.get_high(),
+                self.ctx.get_transaction(),
+            );
+            match rids {
+                Ok(rids) => self.rids = Some(rids.into()),
+                Err(e) => {
+                    self.error = Some(e.into());
+                    self.rids = Some(VecDeque::new());
+                    return None;
+                }
+            }
+        }
+        loop {
+            let rid = self.rids.as_mut().unwrap().pop_front()?;
+            match self.relation.read(rid) {
+                Ok(record) => return Some(Arc::new(Mutex::new(record))),
+                Err(HeapError::RecordDeleted) | Err(HeapError::RecordDNE) => continue,
+                Err(e) => {
+                    self.error = Some(e.into());
+                    self.rids.as_mut().unwrap().clear();
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Look up the record IDs in the index again upon the following call to `next()`.
+    fn reset(&mut self) {
+        self.rids = None;
+        self.error = None;
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take()
+    }
+}
@@ -3,28 +3,109 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
-use crate::executor::{BaseExecutor, QueryMeta};
+use crate::executor::{count_record, BaseExecutor, ExecutorContext, ExecutorError};
 use crate::plan::insert::InsertPlanNode;
 use crate::relation::record::Record;
+use crate::relation::Relation;
 use std::sync::{Arc, Mutex};
 
 /// An executor for insert operations in the database.
+///
+/// The literal records of the insert plan node are inserted first, followed by every record
+/// produced by the child executor (if any). The executor outputs a single record containing the
+/// number of inserted rows, with the schema `count_schema()`.
 pub struct InsertExecutor {
-    /// Metadata for this executor
-    meta: QueryMeta,
-
     /// Insert plan node to be executed
     node: InsertPlanNode,
+
+    /// Relation to insert records into
+    relation: Arc<Relation>,
+
+    /// Executor producing records to be inserted
+    child: Option<Box<dyn BaseExecutor>>,
+
+    /// Context of the query, whose transaction the modifications are performed on behalf of
+    ctx: Arc<ExecutorContext>,
+
+    /// Whether the insert has already been executed
+    done: bool,
+
+    /// Error which stopped the insert, if any
+    error: Option<ExecutorError>,
 }
 
 impl InsertExecutor {
-    pub fn new(meta: QueryMeta, node: InsertPlanNode) -> Self {
-        Self { meta, node }
+    /// Create a new insert executor. Panics if the relation of the plan node doesn't exist in
+    /// the system catalog.
+    pub fn new(
+        ctx: Arc<ExecutorContext>,
+        node: InsertPlanNode,
+        child: Option<Box<dyn BaseExecutor>>,
+    ) -> Self {
+        let relation = ctx
+            .get_system_catalog()
+            .get_relation_by_id(node.get_relation_id())
+            .unwrap();
+
+        Self {
+            node,
+            relation,
+            child,
+            ctx,
+            done: false,
+            error: None,
+        }
+    }
+
+    /// Insert every record into the relation and return the number of inserted rows. Return an
+    /// error if any insertion or the child fails, in which case the records before it remain
+    /// inserted.
+    ///
+    /// Every record is collected from the child before any record is inserted, since a scan of
+    /// the same relation would otherwise produce the inserted records again and never finish.
+    pub fn execute(&mut self) -> Result<i64, ExecutorError> {
+        let mut records: Vec<Record> = self
+            .node
+            .get_records()
+            .iter()
+            .map(|record| record.to_unallocated())
+            .collect();
+        if let Some(child) = self.child.as_mut() {
+            while let Some(record) = child.next() {
+                records.push(record.lock().unwrap().to_unallocated());
+            }
+            if let Some(e) = child.take_error() {
+                return Err(e);
+            }
+        }
+
+        let mut count = 0;
+        for record in records {
+            self.relation.insert(record, self.ctx.get_transaction())?;
+            count += 1;
+        }
+        Ok(count)
     }
 }
 
 impl BaseExecutor for InsertExecutor {
-    fn next() -> Option<Arc<Mutex<Record>>> {
-        todo!()
+    /// Execute the insert and return a record containing the number of inserted rows. If the
+    /// insert fails, the error is stored and None is returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        match self.execute() {
+            Ok(count) => Some(Arc::new(Mutex::new(count_record(count)))),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take()
     }
 }
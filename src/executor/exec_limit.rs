@@ -0,0 +1,60 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{BaseExecutor, ExecutorError};
+use crate::plan::limit::LimitPlanNode;
+use crate::relation::record::Record;
+use std::sync::{Arc, Mutex};
+
+/// An executor which skips the first `offset` records of its child, and then outputs at most
+/// `limit` records.
+pub struct LimitExecutor {
+    /// Maximum number of records to be outputted
+    limit: usize,
+
+    /// Number of records to be skipped before outputting records
+    offset: usize,
+
+    /// Number of records pulled from the child so far
+    count: usize,
+
+    /// Executor producing the records to be limited
+    child: Box<dyn BaseExecutor>,
+}
+
+impl LimitExecutor {
+    pub fn new(node: LimitPlanNode, child: Box<dyn BaseExecutor>) -> Self {
+        Self {
+            limit: node.get_limit(),
+            offset: node.get_offset(),
+            count: 0,
+            child,
+        }
+    }
+}
+
+impl BaseExecutor for LimitExecutor {
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        while self.count < self.offset {
+            self.child.next()?;
+            self.count += 1;
+        }
+        if self.count >= self.offset + self.limit {
+            return None;
+        }
+        let record = self.child.next()?;
+        self.count += 1;
+        Some(record)
+    }
+
+    fn reset(&mut self) {
+        self.count = 0;
+        self.child.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.child.take_error()
+    }
+}
@@ -0,0 +1,149 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{join_records, BaseExecutor, ExecutorContext, ExecutorError};
+use crate::index::Index;
+use crate::plan::nested_index_join::NestedIndexJoinPlanNode;
+use crate::plan::QueryPlanNode;
+use crate::relation::heap::HeapError;
+use crate::relation::record::Record;
+use crate::relation::Relation;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// An executor for equi-joins of an outer child with an inner relation, using an index on the
+/// inner relation.
+///
+/// The records of the outer child are streamed, and the index is probed with the join key
+/// columns of each outer record. Every matching inner record is read from the heap of the inner
+/// relation and joined with the outer record, so the inner relation is never scanned in full.
+/// Outer records with a null join column never match, and index entries whose records have been
+/// deleted are skipped. If the index or the inner relation can't be read, the join is stopped and
+/// the error is stored; see `take_error()`.
+pub struct NestedIndexJoinExecutor {
+    /// Nested index join plan node to be executed
+    node: NestedIndexJoinPlanNode,
+
+    /// Executor producing the records of the outer side
+    outer: Box<dyn BaseExecutor>,
+
+    /// Relation on the inner side
+    inner: Arc<Relation>,
+
+    /// Index over the inner relation, keyed by the inner join columns
+    index: Arc<dyn Index>,
+
+    /// Context of the query that the join belongs to
+    ctx: Arc<ExecutorContext>,
+
+    /// Joined records that have not been returned yet
+    output: VecDeque<Record>,
+
+    /// Error which stopped the join, if any
+    error: Option<ExecutorError>,
+}
+
+impl NestedIndexJoinExecutor {
+    /// Create a new nested index join executor. Panics if the inner relation of the plan node
+    /// doesn't exist in the system catalog.
+    pub fn new(
+        ctx: Arc<ExecutorContext>,
+        node: NestedIndexJoinPlanNode,
+        outer: Box<dyn BaseExecutor>,
+        index: Arc<dyn Index>,
+    ) -> Self {
+        let inner = ctx
+            .get_system_catalog()
+            .get_relation_by_id(node.get_inner_relation_id())
+            .unwrap();
+
+        Self {
+            node,
+            outer,
+            inner,
+            index,
+            ctx,
+            output: VecDeque::new(),
+            error: None,
+        }
+    }
+
+    /// Return every inner record matching the outer record, in the order of the index. Return an
+    /// error if the index or a matching record can't be read.
+    fn probe(&self, outer: &Record) -> Result<Vec<Record>, ExecutorError> {
+        let outer_schema = self.node.get_outer_schema();
+        let key_attrs = self.node.get_outer_key_attrs();
+        for idx in key_attrs {
+            if outer.is_null(*idx, outer_schema.clone()).unwrap() {
+                return Ok(Vec::new());
+            }
+        }
+
+        let key = outer
+            .project(key_attrs, outer_schema, self.node.get_key_schema())
+            .unwrap();
+        let rids = self.index.get(&key, self.ctx.get_transaction())?;
+
+        let mut records = Vec::with_capacity(rids.len());
+        for rid in rids {
+            match self.inner.read(rid) {
+                Ok(record) => records.push(record),
+                Err(HeapError::RecordDeleted) | Err(HeapError::RecordDNE) => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(records)
+    }
+}
+
+impl BaseExecutor for NestedIndexJoinExecutor {
+    /// Return the next joined record. If the index or the inner relation can't be read, the error
+    /// is stored and None is returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.error.is_some() {
+            return None;
+        }
+        let outer_schema = self.node.get_outer_schema();
+        let inner_schema = self.node.get_inner_schema();
+        let out_schema = self.node.get_output_schema();
+
+        loop {
+            if let Some(record) = self.output.pop_front() {
+                return Some(Arc::new(Mutex::new(record)));
+            }
+
+            let outer = self.outer.next()?;
+            let outer = outer.lock().unwrap();
+            let matches = match self.probe(&outer) {
+                Ok(matches) => matches,
+                Err(e) => {
+                    self.error = Some(e);
+                    return None;
+                }
+            };
+            for inner in matches {
+                let record = join_records(
+                    &outer,
+                    outer_schema.clone(),
+                    &inner,
+                    inner_schema.clone(),
+                    out_schema.clone(),
+                )
+                .unwrap();
+                self.output.push_back(record);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.output.clear();
+        self.error = None;
+        self.outer.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take().or_else(|| self.outer.take_error())
+    }
+}
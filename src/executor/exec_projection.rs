@@ -0,0 +1,49 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{BaseExecutor, ExecutorError};
+use crate::plan::projection::ProjectionPlanNode;
+use crate::plan::QueryPlanNode;
+use crate::relation::record::Record;
+use std::sync::{Arc, Mutex};
+
+/// An executor which projects each record of its child to the columns of the output schema.
+pub struct ProjectionExecutor {
+    /// Projection plan node to be executed
+    node: ProjectionPlanNode,
+
+    /// Executor producing the records to be projected
+    child: Box<dyn BaseExecutor>,
+}
+
+impl ProjectionExecutor {
+    pub fn new(node: ProjectionPlanNode, child: Box<dyn BaseExecutor>) -> Self {
+        Self { node, child }
+    }
+}
+
+impl BaseExecutor for ProjectionExecutor {
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        let record = self.child.next()?;
+        let record = record
+            .lock()
+            .unwrap()
+            .project(
+                self.node.get_indices(),
+                self.node.get_input_schema(),
+                self.node.get_output_schema(),
+            )
+            .unwrap();
+        Some(Arc::new(Mutex::new(record)))
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.child.take_error()
+    }
+}
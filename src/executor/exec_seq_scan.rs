@@ -0,0 +1,85 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::constants::PageIdT;
+use crate::executor::{BaseExecutor, ExecutorContext, ExecutorError};
+use crate::plan::seq_scan::SeqScanPlanNode;
+use crate::relation::heap::Heap;
+use crate::relation::record::Record;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// An executor for sequential scans over every live record of a relation.
+///
+/// The heap of the relation is scanned one page at a time, starting from its root page and
+/// following the next page IDs. Each page is only pinned while its records are copied out.
+/// If a page can't be read, the scan is stopped and the error is stored; see `take_error()`.
+pub struct SeqScanExecutor {
+    /// Heap of the relation being scanned
+    heap: Arc<Heap>,
+
+    /// Records of the most recently read page that have not been returned yet
+    records: VecDeque<Record>,
+
+    /// ID of the next page to be read, or None if every page has been read
+    next_page_id: Option<PageIdT>,
+
+    /// Error which stopped the scan, if any
+    error: Option<ExecutorError>,
+}
+
+impl SeqScanExecutor {
+    /// Create a new sequential scan executor. Panics if the relation of the plan node doesn't
+    /// exist in the system catalog.
+    pub fn new(ctx: Arc<ExecutorContext>, node: SeqScanPlanNode) -> Self {
+        let relation = ctx
+            .get_system_catalog()
+            .get_relation_by_id(node.get_relation_id())
+            .unwrap();
+        let heap = relation.get_heap();
+        let next_page_id = Some(heap.get_root_id());
+
+        Self {
+            heap,
+            records: VecDeque::new(),
+            next_page_id,
+            error: None,
+        }
+    }
+}
+
+impl BaseExecutor for SeqScanExecutor {
+    /// Return the next record of the relation. If a page can't be read, such as when every buffer
+    /// frame is pinned, the error is stored and None is returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        loop {
+            if let Some(record) = self.records.pop_front() {
+                return Some(Arc::new(Mutex::new(record)));
+            }
+            let page_id = self.next_page_id?;
+            let (records, next_page_id) = match self.heap.scan_page(page_id) {
+                Ok(page) => page,
+                Err(e) => {
+                    self.error = Some(e.into());
+                    self.next_page_id = None;
+                    return None;
+                }
+            };
+            self.records = records.into();
+            self.next_page_id = next_page_id;
+        }
+    }
+
+    /// Restart the scan from the root page of the heap.
+    fn reset(&mut self) {
+        self.records.clear();
+        self.next_page_id = Some(self.heap.get_root_id());
+        self.error = None;
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take()
+    }
+}
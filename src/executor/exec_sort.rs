@@ -0,0 +1,112 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{BaseExecutor, ExecutorError};
+use crate::plan::sort::SortPlanNode;
+use crate::plan::QueryPlanNode;
+use crate::relation::record::Record;
+use crate::relation::types::InnerValue;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// An executor which outputs the records of its child sorted by the sort keys.
+///
+/// Every record of the child is consumed and sorted in memory before any output is produced.
+/// Values are compared by the ordering of `InnerValue`, and null values are ordered before all
+/// other values. The sort is stable, so records with equal sort keys keep the order of the child.
+pub struct SortExecutor {
+    /// Sort plan node to be executed
+    node: SortPlanNode,
+
+    /// Executor producing the records to be sorted
+    child: Box<dyn BaseExecutor>,
+
+    /// Sorted records that have not been returned yet. None until the child is consumed.
+    output: Option<VecDeque<Record>>,
+
+    /// Error of the child which stopped the sort, if any
+    error: Option<ExecutorError>,
+}
+
+impl SortExecutor {
+    pub fn new(node: SortPlanNode, child: Box<dyn BaseExecutor>) -> Self {
+        Self {
+            node,
+            child,
+            output: None,
+            error: None,
+        }
+    }
+
+    /// Consume the child and return its records in sorted order. Return an error if the child
+    /// fails.
+    fn sort(&mut self) -> Result<VecDeque<Record>, ExecutorError> {
+        let schema = self.node.get_output_schema();
+        let sort_keys = self.node.get_sort_keys();
+
+        // Decode the sort key values of each record once, rather than on every comparison.
+        let mut rows: Vec<(Vec<Option<InnerValue>>, Record)> = Vec::new();
+        while let Some(record) = self.child.next() {
+            let record = record.lock().unwrap().clone();
+            let values = sort_keys
+                .iter()
+                .map(|(idx, _)| {
+                    let value = record.get_value(*idx, schema.clone()).unwrap();
+                    value.map(|value| value.get_inner())
+                })
+                .collect();
+            rows.push((values, record));
+        }
+        if let Some(e) = self.child.take_error() {
+            return Err(e);
+        }
+
+        rows.sort_by(|(a, _), (b, _)| {
+            for ((a, b), (_, ascending)) in a.iter().zip(b.iter()).zip(sort_keys.iter()) {
+                // Null values are ordered before all other values.
+                let ordering = match ascending {
+                    true => a.cmp(b),
+                    false => b.cmp(a),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
+        });
+        Ok(rows.into_iter().map(|(_, record)| record).collect())
+    }
+}
+
+impl BaseExecutor for SortExecutor {
+    /// Return the next sorted record. If the child fails, the error is stored and no records are
+    /// returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.output.is_none() {
+            let output = match self.sort() {
+                Ok(output) => output,
+                Err(e) => {
+                    self.error = Some(e);
+                    VecDeque::new()
+                }
+            };
+            self.output = Some(output);
+        }
+        let record = self.output.as_mut().unwrap().pop_front()?;
+        Some(Arc::new(Mutex::new(record)))
+    }
+
+    /// Discard the output and consume the child again upon the following call to `next()`.
+    fn reset(&mut self) {
+        self.output = None;
+        self.error = None;
+        self.child.reset();
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take()
+    }
+}
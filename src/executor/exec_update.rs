@@ -0,0 +1,117 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::executor::{count_record, BaseExecutor, ExecutorContext, ExecutorError};
+use crate::plan::update::UpdatePlanNode;
+use crate::relation::record::{Record, RecordId};
+use crate::relation::Relation;
+use std::sync::{Arc, Mutex};
+
+/// An executor for update operations in the database.
+///
+/// Every record produced by the child executor contains the new values of a record, and its
+/// record ID specifies the record to be updated. Updated records may be reallocated if they
+/// no longer fit in their page, so the (possibly changed) record IDs are collected. The executor
+/// outputs a single record containing the number of updated rows, with the schema
+/// `count_schema()`.
+pub struct UpdateExecutor {
+    /// Relation to update records in
+    relation: Arc<Relation>,
+
+    /// Executor producing the new records, along with the IDs of the records to be updated
+    child: Box<dyn BaseExecutor>,
+
+    /// Context of the query, whose transaction the modifications are performed on behalf of
+    ctx: Arc<ExecutorContext>,
+
+    /// IDs of the updated records, in the order that they were updated
+    updated_ids: Vec<RecordId>,
+
+    /// Whether the update has already been executed
+    done: bool,
+
+    /// Error which stopped the update, if any
+    error: Option<ExecutorError>,
+}
+
+impl UpdateExecutor {
+    /// Create a new update executor. Panics if the relation of the plan node doesn't exist in
+    /// the system catalog.
+    pub fn new(
+        ctx: Arc<ExecutorContext>,
+        node: UpdatePlanNode,
+        child: Box<dyn BaseExecutor>,
+    ) -> Self {
+        let relation = ctx
+            .get_system_catalog()
+            .get_relation_by_id(node.get_relation_id())
+            .unwrap();
+
+        Self {
+            relation,
+            child,
+            ctx,
+            updated_ids: Vec::new(),
+            done: false,
+            error: None,
+        }
+    }
+
+    /// Update every record produced by the child and return the number of updated rows. Records
+    /// without a record ID are skipped. Return an error if the child or any update fails, in
+    /// which case the records before it remain updated.
+    ///
+    /// Every record is collected from the child before any record is updated, since a record
+    /// that is moved by an update may otherwise be produced again by a scan of the same relation.
+    pub fn execute(&mut self) -> Result<i64, ExecutorError> {
+        let mut records = Vec::new();
+        while let Some(record) = self.child.next() {
+            let record = record.lock().unwrap();
+            if let Some(rid) = record.get_id() {
+                records.push((rid, record.to_unallocated()));
+            }
+        }
+        if let Some(e) = self.child.take_error() {
+            return Err(e);
+        }
+
+        let mut count = 0;
+        for (rid, record) in records {
+            let new_rid = self
+                .relation
+                .update(record, rid, self.ctx.get_transaction())?;
+            self.updated_ids.push(new_rid);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Return the IDs of the updated records, in the order that they were updated.
+    pub fn get_updated_ids(&self) -> &[RecordId] {
+        &self.updated_ids
+    }
+}
+
+impl BaseExecutor for UpdateExecutor {
+    /// Execute the update and return a record containing the number of updated rows. If the
+    /// update fails, the error is stored and None is returned.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        match self.execute() {
+            Ok(count) => Some(Arc::new(Mutex::new(count_record(count)))),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        self.error.take()
+    }
+}
@@ -5,30 +5,208 @@
 
 use crate::buffer::BufferManager;
 use crate::catalog::SystemCatalog;
-use crate::relation::record::Record;
+use crate::concurrency::transaction::Transaction;
+use crate::index::IndexError;
+use crate::relation::heap::HeapError;
+use crate::relation::record::{Record, RecordErr};
+use crate::relation::types::{DataType, InnerValue, ValueError};
+use crate::relation::{Attribute, Schema};
 use std::sync::{Arc, Mutex};
 
+pub mod exec_aggr;
+pub mod exec_delete;
+pub mod exec_distinct;
+pub mod exec_filter;
+pub mod exec_hash_join;
+pub mod exec_index_scan;
 pub mod exec_insert;
+pub mod exec_limit;
+pub mod exec_nested_index_join;
+pub mod exec_projection;
+pub mod exec_seq_scan;
+pub mod exec_sort;
+pub mod exec_update;
 
 /// The `executor` directory contains definitions for executor for a query plan tree.
 /// Each executor type executes a certain operation (such as hash join, sequential scan, etc.)
 /// for a corresponding plan node.
 pub trait BaseExecutor {
-    fn next() -> Option<Arc<Mutex<Record>>>;
+    /// Return the next record produced by this executor, or None if there are no more records.
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>>;
+
+    /// Restart iteration from the beginning, so that the following call to `next()` returns the
+    /// first record again. This allows a parent (such as the inner side of a nested loop join) to
+    /// iterate over an executor repeatedly.
+    ///
+    /// Panics by default, since executors with side effects (such as inserts) can't be rerun.
+    fn reset(&mut self) {
+        panic!("this executor can't be reset");
+    }
+
+    /// Take the error which stopped this executor or one of its children, if any.
+    ///
+    /// An executor that fails stores the error and returns None from `next()`, so None doesn't
+    /// necessarily mean that every record was produced. A parent must take the error of a child
+    /// once the child is exhausted and report it, rather than treat the child's output as
+    /// complete. Returns None by default, for executors which can't fail.
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        None
+    }
 }
 
-/// All of the metadata required to execute a given query.
-pub struct QueryMeta {
+/// All of the state required to execute a given query. Executors that access relations or
+/// indexes hold a shared reference to the context of the query.
+pub struct ExecutorContext {
     system_catalog: Arc<SystemCatalog>,
     buffer_manager: Arc<BufferManager>,
+    txn: Transaction,
     // TODO: Implement and add log and lock managers
 }
 
-impl QueryMeta {
-    pub fn new(system_catalog: Arc<SystemCatalog>, buffer_manager: Arc<BufferManager>) -> Self {
+impl ExecutorContext {
+    pub fn new(
+        system_catalog: Arc<SystemCatalog>,
+        buffer_manager: Arc<BufferManager>,
+        txn: Transaction,
+    ) -> Self {
         Self {
             system_catalog,
             buffer_manager,
+            txn,
         }
     }
+
+    pub fn get_system_catalog(&self) -> Arc<SystemCatalog> {
+        self.system_catalog.clone()
+    }
+
+    pub fn get_buffer_manager(&self) -> Arc<BufferManager> {
+        self.buffer_manager.clone()
+    }
+
+    /// Return the transaction that the query is executed on behalf of.
+    pub fn get_transaction(&self) -> &Transaction {
+        &self.txn
+    }
+}
+
+/// Return the schema of records reporting the number of rows affected by a modification, such
+/// as the number of rows inserted.
+pub fn count_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![Attribute::new(
+        "count",
+        DataType::BigInt,
+        false,
+        false,
+        false,
+    )]))
+}
+
+/// Return a record reporting the number of affected rows, with the schema `count_schema()`.
+pub(crate) fn count_record(count: i64) -> Record {
+    Record::new(vec![Some(Box::new(count))], count_schema()).unwrap()
+}
+
+/// Return the schema of records produced by joining records of the left schema with records of
+/// the right schema, which contains the attributes of the left schema followed by the
+/// attributes of the right schema.
+pub fn join_schema(left: &Schema, right: &Schema) -> Arc<Schema> {
+    let attrs = left
+        .get_attributes()
+        .iter()
+        .chain(right.get_attributes().iter())
+        .cloned()
+        .collect();
+    Arc::new(Schema::new(attrs))
+}
+
+/// Return the concatenation of a left and a right record, with the schema returned by
+/// `join_schema()` for their schemas.
+pub(crate) fn join_records(
+    left: &Record,
+    left_schema: Arc<Schema>,
+    right: &Record,
+    right_schema: Arc<Schema>,
+    out_schema: Arc<Schema>,
+) -> Result<Record, RecordErr> {
+    let mut values = Vec::with_capacity(out_schema.attr_len() as usize);
+    for idx in 0..left_schema.attr_len() {
+        values.push(left.get_value(idx, left_schema.clone())?);
+    }
+    for idx in 0..right_schema.attr_len() {
+        values.push(right.get_value(idx, right_schema.clone())?);
+    }
+    Record::new(values, out_schema)
+}
+
+/// Serialize the specified columns of a record into bytes, such that two records have equal
+/// serialized columns if and only if their values in those columns are equal. Null values are
+/// considered equal to each other, and different from every other value.
+pub(crate) fn serialize_columns(
+    record: &Record,
+    indices: &[u32],
+    schema: Arc<Schema>,
+) -> Result<Vec<u8>, RecordErr> {
+    let mut bytes = Vec::new();
+    for idx in indices {
+        let value = match record.get_value(*idx, schema.clone())? {
+            Some(value) => value,
+            None => {
+                bytes.push(0);
+                continue;
+            }
+        };
+        bytes.push(1);
+        match value.get_inner() {
+            InnerValue::Boolean(v) => bytes.push(v as u8),
+            InnerValue::TinyInt(v) => bytes.extend_from_slice(&v.to_be_bytes()),
+            InnerValue::SmallInt(v) => bytes.extend_from_slice(&v.to_be_bytes()),
+            InnerValue::Int(v) | InnerValue::Date(v) => bytes.extend_from_slice(&v.to_be_bytes()),
+            InnerValue::BigInt(v) | InnerValue::Timestamp(v) => {
+                bytes.extend_from_slice(&v.to_be_bytes())
+            }
+            InnerValue::Real(v) => bytes.extend_from_slice(&v.to_bits().to_be_bytes()),
+            InnerValue::Decimal { mantissa, scale } => {
+                bytes.extend_from_slice(&mantissa.to_be_bytes());
+                bytes.push(scale);
+            }
+            InnerValue::Varchar(v) => {
+                // Prefix the length so that adjacent varchar columns can't be confused.
+                bytes.extend_from_slice(&(v.len() as u32).to_be_bytes());
+                bytes.extend_from_slice(v.as_bytes());
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+/// Custom errors to be used by executors.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ExecutorError {
+    /// Error to be thrown when a relation can't be read or modified.
+    Heap(HeapError),
+
+    /// Error to be thrown when an index can't be read.
+    Index(IndexError),
+
+    /// Error to be thrown when a value can't be computed, such as a sum that overflows.
+    Value(ValueError),
+}
+
+impl From<HeapError> for ExecutorError {
+    fn from(e: HeapError) -> Self {
+        ExecutorError::Heap(e)
+    }
+}
+
+impl From<IndexError> for ExecutorError {
+    fn from(e: IndexError) -> Self {
+        ExecutorError::Index(e)
+    }
+}
+
+impl From<ValueError> for ExecutorError {
+    fn from(e: ValueError) -> Self {
+        ExecutorError::Value(e)
+    }
 }
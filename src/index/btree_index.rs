@@ -0,0 +1,344 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::buffer::BufferManager;
+use crate::concurrency::transaction::Transaction;
+use crate::constants::PageIdT;
+use crate::index::{
+    encode_key, get_entry_rid, get_key_size, make_entry, Index, IndexError, IndexMeta, RID_SIZE,
+};
+use crate::page::{IndexPage, PageBytes, RawPage};
+use crate::relation::record::{Record, RecordId};
+use std::sync::{Arc, RwLock};
+
+/// A B+ tree index, whose nodes are stored as index pages through the buffer manager.
+///
+/// Every entry in the tree is the encoded key followed by the encoded record ID, so multiple
+/// records with the same key are stored as separate entries that are adjacent in the leaves.
+/// Looking up a key scans every entry between (key, min RID) and (key, max RID).
+///
+/// The root page of the tree never changes. When the root node splits, its contents are moved to
+/// a new page and the root is rewritten as an internal node. Nodes are not merged upon deletion,
+/// so deleted entries leave behind partially filled (or empty) leaves.
+pub struct BTreeIndex {
+    /// Metadata for this index.
+    meta: IndexMeta,
+
+    /// ID of the root page of the tree.
+    root_id: PageIdT,
+
+    /// Size of each entry in the tree, which is the encoded key size plus the record ID size.
+    entry_size: u32,
+
+    /// Buffer manager to request necessary pages for index operations.
+    buffer_manager: Arc<BufferManager>,
+
+    /// Latch for the entire tree. Lookups acquire a read latch and modifications acquire a write
+    /// latch.
+    latch: RwLock<()>,
+}
+
+impl BTreeIndex {
+    /// Create a new, empty B+ tree index. Return an error if the key schema can't be indexed.
+    pub fn new(meta: IndexMeta) -> Result<Self, IndexError> {
+        let buffer_manager = meta.get_buffer_manager();
+        let entry_size = get_key_size(&meta)? + RID_SIZE;
+
+        let frame_arc = buffer_manager.create_page()?;
+        let mut frame = frame_arc.write().unwrap();
+
+        let page = frame.get_mut_page().unwrap();
+        let root_id = RawPage::get_id(page);
+        IndexPage::write_leaf(page, entry_size, &[], None).unwrap();
+
+        drop(frame);
+        drop(frame_arc);
+
+        Ok(Self {
+            meta,
+            root_id,
+            entry_size,
+            buffer_manager,
+            latch: RwLock::new(()),
+        })
+    }
+
+    /// Return the ID of the root page of the tree.
+    pub fn get_root_id(&self) -> PageIdT {
+        self.root_id
+    }
+
+    /// Return the IDs of every record whose key is between the specified bounds (inclusive), in
+    /// key order. A bound of `None` leaves that end of the range open.
+    pub fn range_scan(
+        &self,
+        low: Option<&Record>,
+        high: Option<&Record>,
+        _txn: &Transaction,
+    ) -> Result<Vec<RecordId>, IndexError> {
+        let _latch = self.latch.read().unwrap();
+
+        let low = match low {
+            Some(key) => min_entry(&encode_key(key, &self.meta)?),
+            None => Vec::new(),
+        };
+        let high = match high {
+            Some(key) => Some(max_entry(&encode_key(key, &self.meta)?)),
+            None => None,
+        };
+        self.scan(&low, high.as_deref())
+    }
+
+    /// Return the record IDs of every entry between the specified entries (inclusive), by
+    /// walking the leaves from the leaf containing the lower bound. An upper bound of `None`
+    /// scans to the last leaf.
+    fn scan(&self, low: &[u8], high: Option<&[u8]>) -> Result<Vec<RecordId>, IndexError> {
+        let mut rids = Vec::new();
+        let (_, mut node) = self.find_leaf(low)?;
+        loop {
+            let start = node.keys.partition_point(|entry| entry.as_slice() < low);
+            for entry in node.keys[start..].iter() {
+                if high.is_some_and(|high| entry.as_slice() > high) {
+                    return Ok(rids);
+                }
+                rids.push(get_entry_rid(entry));
+            }
+            match node.next_leaf_id {
+                Some(pid) => node = self.read_node(pid)?,
+                None => return Ok(rids),
+            }
+        }
+    }
+
+    /// Return the leaf node (and its page ID) whose key range contains the specified entry.
+    fn find_leaf(&self, entry: &[u8]) -> Result<(PageIdT, Node), IndexError> {
+        let mut page_id = self.root_id;
+        loop {
+            let node = self.read_node(page_id)?;
+            if node.is_leaf {
+                return Ok((page_id, node));
+            }
+            page_id = node.children[node.get_child_idx(entry)];
+        }
+    }
+
+    /// Insert an entry into the subtree rooted at the specified page. If the root of the subtree
+    /// was split, return the separator key and the page ID of the new right sibling.
+    fn insert_into(
+        &self,
+        page_id: PageIdT,
+        entry: Vec<u8>,
+    ) -> Result<Option<(Vec<u8>, PageIdT)>, IndexError> {
+        let mut node = self.read_node(page_id)?;
+
+        if node.is_leaf {
+            match node.keys.binary_search(&entry) {
+                Ok(_) => return Ok(None),
+                Err(idx) => node.keys.insert(idx, entry),
+            }
+        } else {
+            let idx = node.get_child_idx(&entry);
+            match self.insert_into(node.children[idx], entry)? {
+                Some((separator, right_id)) => {
+                    node.keys.insert(idx, separator);
+                    node.children.insert(idx + 1, right_id);
+                }
+                None => return Ok(None),
+            }
+        }
+
+        if node.keys.len() as u32 <= IndexPage::get_max_keys(self.entry_size, node.is_leaf) {
+            self.write_node(page_id, &node)?;
+            return Ok(None);
+        }
+
+        // The node overflowed, so split it in half.
+        let mid = node.keys.len() / 2;
+        let (separator, right) = match node.is_leaf {
+            true => {
+                let right = Node {
+                    is_leaf: true,
+                    keys: node.keys.split_off(mid),
+                    children: Vec::new(),
+                    next_leaf_id: node.next_leaf_id,
+                };
+                (right.keys[0].clone(), right)
+            }
+            false => {
+                let right = Node {
+                    is_leaf: false,
+                    keys: node.keys.split_off(mid + 1),
+                    children: node.children.split_off(mid + 1),
+                    next_leaf_id: None,
+                };
+                (node.keys.pop().unwrap(), right)
+            }
+        };
+        let right_id = self.create_node(&right)?;
+        if node.is_leaf {
+            node.next_leaf_id = Some(right_id);
+        }
+        self.write_node(page_id, &node)?;
+
+        Ok(Some((separator, right_id)))
+    }
+
+    /// Read the node stored in the specified page.
+    fn read_node(&self, page_id: PageIdT) -> Result<Node, IndexError> {
+        let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+        let frame = frame_arc.read().unwrap();
+
+        let page = frame.get_page().unwrap();
+        let is_leaf = IndexPage::is_leaf(page);
+        let num_keys = IndexPage::get_num_keys(page);
+
+        let keys = (0..num_keys)
+            .map(|i| IndexPage::get_key(page, i).to_vec())
+            .collect();
+        let (children, next_leaf_id) = match is_leaf {
+            true => (Vec::new(), IndexPage::get_next_leaf_id(page)),
+            false => (
+                (0..num_keys + 1)
+                    .map(|i| IndexPage::get_child_id(page, i))
+                    .collect(),
+                None,
+            ),
+        };
+
+        Ok(Node {
+            is_leaf,
+            keys,
+            children,
+            next_leaf_id,
+        })
+    }
+
+    /// Write a node into the specified page.
+    fn write_node(&self, page_id: PageIdT, node: &Node) -> Result<(), IndexError> {
+        let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+        let mut frame = frame_arc.write().unwrap();
+
+        node.write(frame.get_mut_page().unwrap(), self.entry_size);
+        frame.set_dirty_flag(true);
+
+        Ok(())
+    }
+
+    /// Write a node into a newly created page and return its page ID.
+    fn create_node(&self, node: &Node) -> Result<PageIdT, IndexError> {
+        let frame_arc = self.buffer_manager.create_page()?;
+        let mut frame = frame_arc.write().unwrap();
+
+        let page = frame.get_mut_page().unwrap();
+        let page_id = RawPage::get_id(page);
+        node.write(page, self.entry_size);
+        frame.set_dirty_flag(true);
+
+        Ok(page_id)
+    }
+}
+
+impl Index for BTreeIndex {
+    fn get_meta(&self) -> &IndexMeta {
+        &self.meta
+    }
+
+    /// Return the IDs of every record with the specified key, in record ID order.
+    fn get(&self, key: &Record, _txn: &Transaction) -> Result<Vec<RecordId>, IndexError> {
+        let _latch = self.latch.read().unwrap();
+
+        let key = encode_key(key, &self.meta)?;
+        self.scan(&min_entry(&key), Some(&max_entry(&key)))
+    }
+
+    /// Add an entry for the specified key and record ID. Adding an entry that already exists has
+    /// no effect.
+    fn set(&self, key: &Record, rid: RecordId, _txn: &Transaction) -> Result<(), IndexError> {
+        let _latch = self.latch.write().unwrap();
+
+        let key = encode_key(key, &self.meta)?;
+        let entry = make_entry(&key, rid);
+
+        if let Some((separator, right_id)) = self.insert_into(self.root_id, entry)? {
+            // The root node was split, so move its contents (the left half) to a new page and
+            // rewrite the root as an internal node with two children.
+            let left = self.read_node(self.root_id)?;
+            let left_id = self.create_node(&left)?;
+            let root = Node {
+                is_leaf: false,
+                keys: vec![separator],
+                children: vec![left_id, right_id],
+                next_leaf_id: None,
+            };
+            self.write_node(self.root_id, &root)?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the entry for the specified key and record ID. Return whether an entry was removed.
+    fn delete(&self, key: &Record, rid: RecordId, _txn: &Transaction) -> Result<bool, IndexError> {
+        let _latch = self.latch.write().unwrap();
+
+        let key = encode_key(key, &self.meta)?;
+        let entry = make_entry(&key, rid);
+
+        let (leaf_id, mut leaf) = self.find_leaf(&entry)?;
+        match leaf.keys.binary_search(&entry) {
+            Ok(idx) => {
+                leaf.keys.remove(idx);
+                self.write_node(leaf_id, &leaf)?;
+                Ok(true)
+            }
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+/// Return the smallest possible entry for the encoded key.
+fn min_entry(key: &[u8]) -> Vec<u8> {
+    make_entry(
+        key,
+        RecordId {
+            page_id: 0,
+            slot_index: 0,
+        },
+    )
+}
+
+/// Return the largest possible entry for the encoded key.
+fn max_entry(key: &[u8]) -> Vec<u8> {
+    make_entry(
+        key,
+        RecordId {
+            page_id: PageIdT::MAX,
+            slot_index: u32::MAX,
+        },
+    )
+}
+
+/// An in-memory copy of a B+ tree node.
+struct Node {
+    is_leaf: bool,
+    keys: Vec<Vec<u8>>,
+    children: Vec<PageIdT>,
+    next_leaf_id: Option<PageIdT>,
+}
+
+impl Node {
+    /// Return the index of the child whose subtree contains the specified entry.
+    fn get_child_idx(&self, entry: &[u8]) -> usize {
+        self.keys.partition_point(|key| key.as_slice() <= entry)
+    }
+
+    /// Write this node into a page.
+    fn write(&self, page: &mut PageBytes, entry_size: u32) {
+        match self.is_leaf {
+            true => IndexPage::write_leaf(page, entry_size, &self.keys, self.next_leaf_id),
+            false => IndexPage::write_internal(page, entry_size, &self.keys, &self.children),
+        }
+        .unwrap()
+    }
+}
@@ -0,0 +1,304 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::buffer::BufferManager;
+use crate::concurrency::transaction::Transaction;
+use crate::constants::PageIdT;
+use crate::index::{
+    encode_key, get_entry_rid, get_key_size, make_entry, Index, IndexError, IndexMeta, RID_SIZE,
+};
+use crate::page::{HashBucketPage, HashDirectoryPage, PageBytes, RawPage, HASH_MAX_GLOBAL_DEPTH};
+use crate::relation::record::{Record, RecordId};
+use std::sync::{Arc, RwLock};
+
+/// An extendible hash index for equality lookups, whose directory and buckets are stored as
+/// pages through the buffer manager.
+///
+/// Every entry in a bucket is the encoded key followed by the encoded record ID, so multiple
+/// records with the same key are stored as separate entries in the same bucket.
+///
+/// When a bucket overflows, it is split into two buckets by one more bit of the key hash. If the
+/// local depth of the bucket is equal to the global depth, the directory is doubled first.
+/// Buckets are not merged upon deletion.
+pub struct HashTableIndex {
+    /// Metadata for this index.
+    meta: IndexMeta,
+
+    /// ID of the directory page.
+    directory_id: PageIdT,
+
+    /// Size of each entry in a bucket, which is the encoded key size plus the record ID size.
+    entry_size: u32,
+
+    /// Buffer manager to request necessary pages for index operations.
+    buffer_manager: Arc<BufferManager>,
+
+    /// Latch for the entire index. Lookups acquire a read latch and modifications acquire a
+    /// write latch.
+    latch: RwLock<()>,
+}
+
+impl HashTableIndex {
+    /// Create a new, empty hash index with a single bucket. Return an error if the key schema
+    /// can't be indexed.
+    pub fn new(meta: IndexMeta) -> Result<Self, IndexError> {
+        let buffer_manager = meta.get_buffer_manager();
+        let entry_size = get_key_size(&meta)? + RID_SIZE;
+
+        let bucket_frame_arc = buffer_manager.create_page()?;
+        let mut bucket_frame = bucket_frame_arc.write().unwrap();
+        let bucket = bucket_frame.get_mut_page().unwrap();
+        let bucket_id = RawPage::get_id(bucket);
+        HashBucketPage::write(bucket, 0, entry_size, &[]).unwrap();
+        drop(bucket_frame);
+        drop(bucket_frame_arc);
+
+        let dir_frame_arc = buffer_manager.create_page()?;
+        let mut dir_frame = dir_frame_arc.write().unwrap();
+        let directory = dir_frame.get_mut_page().unwrap();
+        let directory_id = RawPage::get_id(directory);
+        HashDirectoryPage::write(directory, 0, &[bucket_id]).unwrap();
+        drop(dir_frame);
+        drop(dir_frame_arc);
+
+        Ok(Self {
+            meta,
+            directory_id,
+            entry_size,
+            buffer_manager,
+            latch: RwLock::new(()),
+        })
+    }
+
+    /// Return the ID of the directory page.
+    pub fn get_directory_id(&self) -> PageIdT {
+        self.directory_id
+    }
+
+    /// Split a full bucket into itself and a new bucket by the next bit of the key hash, and
+    /// point the corresponding directory slots to the new bucket. The directory must have a
+    /// global depth greater than the local depth of the bucket.
+    fn split_bucket(
+        &self,
+        directory: &mut Directory,
+        bucket_id: PageIdT,
+        bucket: Bucket,
+    ) -> Result<(), IndexError> {
+        let bit = 1 << bucket.local_depth;
+        let (high, low): (Vec<Vec<u8>>, Vec<Vec<u8>>) = bucket
+            .entries
+            .into_iter()
+            .partition(|entry| hash(&entry[..entry.len() - RID_SIZE as usize]) & bit != 0);
+
+        let local_depth = bucket.local_depth + 1;
+        let low = Bucket {
+            local_depth,
+            entries: low,
+        };
+        let high = Bucket {
+            local_depth,
+            entries: high,
+        };
+        let high_id = self.create_bucket(&high)?;
+        self.write_bucket(bucket_id, &low)?;
+
+        for (idx, id) in directory.bucket_ids.iter_mut().enumerate() {
+            if *id == bucket_id && idx as u64 & bit != 0 {
+                *id = high_id;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the directory page.
+    fn read_directory(&self) -> Result<Directory, IndexError> {
+        let frame_arc = self.buffer_manager.fetch_page(self.directory_id)?;
+        let frame = frame_arc.read().unwrap();
+
+        let page = frame.get_page().unwrap();
+        let global_depth = HashDirectoryPage::get_global_depth(page);
+        let bucket_ids = (0..1 << global_depth)
+            .map(|i| HashDirectoryPage::get_bucket_id(page, i))
+            .collect();
+
+        Ok(Directory {
+            global_depth,
+            bucket_ids,
+        })
+    }
+
+    /// Write the directory page.
+    fn write_directory(&self, directory: &Directory) -> Result<(), IndexError> {
+        let frame_arc = self.buffer_manager.fetch_page(self.directory_id)?;
+        let mut frame = frame_arc.write().unwrap();
+
+        HashDirectoryPage::write(
+            frame.get_mut_page().unwrap(),
+            directory.global_depth,
+            &directory.bucket_ids,
+        )
+        .unwrap();
+        frame.set_dirty_flag(true);
+
+        Ok(())
+    }
+
+    /// Read the bucket stored in the specified page.
+    fn read_bucket(&self, page_id: PageIdT) -> Result<Bucket, IndexError> {
+        let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+        let frame = frame_arc.read().unwrap();
+
+        let page = frame.get_page().unwrap();
+        let local_depth = HashBucketPage::get_local_depth(page);
+        let entries = (0..HashBucketPage::get_num_entries(page))
+            .map(|i| HashBucketPage::get_entry(page, i).to_vec())
+            .collect();
+
+        Ok(Bucket {
+            local_depth,
+            entries,
+        })
+    }
+
+    /// Write a bucket into the specified page.
+    fn write_bucket(&self, page_id: PageIdT, bucket: &Bucket) -> Result<(), IndexError> {
+        let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+        let mut frame = frame_arc.write().unwrap();
+
+        bucket.write(frame.get_mut_page().unwrap(), self.entry_size);
+        frame.set_dirty_flag(true);
+
+        Ok(())
+    }
+
+    /// Write a bucket into a newly created page and return its page ID.
+    fn create_bucket(&self, bucket: &Bucket) -> Result<PageIdT, IndexError> {
+        let frame_arc = self.buffer_manager.create_page()?;
+        let mut frame = frame_arc.write().unwrap();
+
+        let page = frame.get_mut_page().unwrap();
+        let page_id = RawPage::get_id(page);
+        bucket.write(page, self.entry_size);
+        frame.set_dirty_flag(true);
+
+        Ok(page_id)
+    }
+}
+
+impl Index for HashTableIndex {
+    fn get_meta(&self) -> &IndexMeta {
+        &self.meta
+    }
+
+    /// Return the IDs of every record with the specified key.
+    fn get(&self, key: &Record, _txn: &Transaction) -> Result<Vec<RecordId>, IndexError> {
+        let _latch = self.latch.read().unwrap();
+
+        let key = encode_key(key, &self.meta)?;
+        let directory = self.read_directory()?;
+        let bucket = self.read_bucket(directory.get_bucket_id(hash(&key)))?;
+
+        Ok(bucket
+            .entries
+            .iter()
+            .filter(|entry| &entry[..key.len()] == key.as_slice())
+            .map(|entry| get_entry_rid(entry))
+            .collect())
+    }
+
+    /// Add an entry for the specified key and record ID. Adding an entry that already exists has
+    /// no effect. Return an error if the bucket for the key is full and can't be split any
+    /// further.
+    fn set(&self, key: &Record, rid: RecordId, _txn: &Transaction) -> Result<(), IndexError> {
+        let _latch = self.latch.write().unwrap();
+
+        let key = encode_key(key, &self.meta)?;
+        let entry = make_entry(&key, rid);
+        let hash = hash(&key);
+
+        loop {
+            let mut directory = self.read_directory()?;
+            let bucket_id = directory.get_bucket_id(hash);
+            let mut bucket = self.read_bucket(bucket_id)?;
+
+            if bucket.entries.contains(&entry) {
+                return Ok(());
+            }
+            if (bucket.entries.len() as u32) < HashBucketPage::get_max_entries(self.entry_size) {
+                bucket.entries.push(entry);
+                return self.write_bucket(bucket_id, &bucket);
+            }
+
+            // The bucket is full, so split it and retry.
+            if bucket.local_depth == directory.global_depth {
+                if directory.global_depth == HASH_MAX_GLOBAL_DEPTH {
+                    return Err(IndexError::IndexFull);
+                }
+                directory.bucket_ids.extend_from_within(..);
+                directory.global_depth += 1;
+            }
+            self.split_bucket(&mut directory, bucket_id, bucket)?;
+            self.write_directory(&directory)?;
+        }
+    }
+
+    /// Remove the entry for the specified key and record ID. Return whether an entry was removed.
+    fn delete(&self, key: &Record, rid: RecordId, _txn: &Transaction) -> Result<bool, IndexError> {
+        let _latch = self.latch.write().unwrap();
+
+        let key = encode_key(key, &self.meta)?;
+        let entry = make_entry(&key, rid);
+
+        let directory = self.read_directory()?;
+        let bucket_id = directory.get_bucket_id(hash(&key));
+        let mut bucket = self.read_bucket(bucket_id)?;
+
+        match bucket.entries.iter().position(|e| e == &entry) {
+            Some(idx) => {
+                bucket.entries.swap_remove(idx);
+                self.write_bucket(bucket_id, &bucket)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Hash an encoded key with 64-bit FNV-1a. The hash must be stable across runs, since it
+/// determines where entries are stored on disk.
+fn hash(key: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// An in-memory copy of the hash directory.
+struct Directory {
+    global_depth: u32,
+    bucket_ids: Vec<PageIdT>,
+}
+
+impl Directory {
+    /// Return the ID of the bucket that a key with the specified hash belongs to.
+    fn get_bucket_id(&self, hash: u64) -> PageIdT {
+        self.bucket_ids[(hash & ((1 << self.global_depth) - 1)) as usize]
+    }
+}
+
+/// An in-memory copy of a hash bucket.
+struct Bucket {
+    local_depth: u32,
+    entries: Vec<Vec<u8>>,
+}
+
+impl Bucket {
+    /// Write this bucket into a page.
+    fn write(&self, page: &mut PageBytes, entry_size: u32) {
+        HashBucketPage::write(page, self.local_depth, entry_size, &self.entries).unwrap()
+    }
+}
@@ -0,0 +1,255 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+pub mod btree_index;
+pub mod hash_table_index;
+
+use crate::buffer::{BufferError, BufferManager};
+use crate::concurrency::transaction::Transaction;
+use crate::relation::record::{Record, RecordErr, RecordId};
+use crate::relation::types::{size_of, DataType, InnerValue};
+use crate::relation::Schema;
+use std::sync::Arc;
+
+/// An index maps keys to the IDs of the records with that key. Key records passed to an index
+/// are expected to be instances of the key schema in its metadata.
+pub trait Index: Send + Sync {
+    /// Return the metadata for this index.
+    fn get_meta(&self) -> &IndexMeta;
+
+    /// Return the IDs of every record with the specified key.
+    fn get(&self, key: &Record, txn: &Transaction) -> Result<Vec<RecordId>, IndexError>;
+
+    /// Add an entry for the specified key and record ID.
+    fn set(&self, key: &Record, rid: RecordId, txn: &Transaction) -> Result<(), IndexError>;
+
+    /// Remove the entry for the specified key and record ID. Return whether an entry was removed.
+    fn delete(&self, key: &Record, rid: RecordId, txn: &Transaction) -> Result<bool, IndexError>;
+}
+
+/// Metadata for an index. The schema of an index describes the columns of its keys, and key
+/// records passed to an index are expected to be instances of this schema. The key attributes
+/// are the indices of the key columns in the indexed table, in key order, so that a table
+/// record can be projected to a key record with `project_key`.
+pub struct IndexMeta {
+    name: String,
+    table_name: String,
+    schema: Arc<Schema>,
+    key_attrs: Vec<u32>,
+    varchar_key_size: u32,
+    buffer_manager: Arc<BufferManager>,
+}
+
+impl IndexMeta {
+    pub fn new(
+        name: &str,
+        table_name: &str,
+        schema: Arc<Schema>,
+        key_attrs: Vec<u32>,
+        buffer_manager: Arc<BufferManager>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            table_name: table_name.to_string(),
+            schema,
+            key_attrs,
+            varchar_key_size: DEFAULT_VARCHAR_KEY_SIZE,
+            buffer_manager,
+        }
+    }
+
+    /// Set the size in bytes that varchar key columns are truncated or padded to.
+    pub fn with_varchar_key_size(mut self, size: u32) -> Self {
+        self.varchar_key_size = size;
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn get_table_name(&self) -> &str {
+        self.table_name.as_str()
+    }
+
+    pub fn get_schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    pub fn get_key_attrs(&self) -> &[u32] {
+        &self.key_attrs
+    }
+
+    pub fn get_varchar_key_size(&self) -> u32 {
+        self.varchar_key_size
+    }
+
+    pub fn get_buffer_manager(&self) -> Arc<BufferManager> {
+        self.buffer_manager.clone()
+    }
+
+    /// Project a record of the indexed table to a key record for this index.
+    pub fn project_key(
+        &self,
+        record: &Record,
+        table_schema: Arc<Schema>,
+    ) -> Result<Record, IndexError> {
+        Ok(record.project(&self.key_attrs, table_schema, self.schema.clone())?)
+    }
+}
+
+/// Default size in bytes of an encoded varchar key column value.
+const DEFAULT_VARCHAR_KEY_SIZE: u32 = 32;
+
+/// Maximum size in bytes of an encoded key, so that index pages can hold a reasonable number
+/// of entries.
+const MAX_KEY_SIZE: u32 = 1024;
+
+/// Return the size in bytes of an encoded key column value, excluding its null flag.
+fn get_column_size(data_type: DataType, meta: &IndexMeta) -> u32 {
+    match data_type {
+        DataType::Varchar => meta.varchar_key_size,
+        _ => size_of(data_type),
+    }
+}
+
+/// Return the size in bytes of keys of the specified index after encoding. Each key column is
+/// encoded as a null flag followed by the value. Return an error if the key schema has no
+/// columns or its keys are too large to be indexed.
+pub(crate) fn get_key_size(meta: &IndexMeta) -> Result<u32, IndexError> {
+    let attrs = meta.schema.get_attributes();
+    let size = attrs
+        .iter()
+        .map(|attr| 1 + get_column_size(attr.get_data_type(), meta))
+        .sum();
+    if attrs.is_empty() || size > MAX_KEY_SIZE {
+        return Err(IndexError::UnsupportedKeyType);
+    }
+    Ok(size)
+}
+
+/// Encode a key record into a fixed-length byte string by concatenating its encoded columns in
+/// order, such that comparing two encoded keys byte-wise gives the same ordering as comparing
+/// the original key values column by column. Null values are ordered before all other values.
+///
+/// Varchar values are truncated or zero-padded to the configured varchar key size, so varchar
+/// values that share a prefix of that size can't be distinguished by the index.
+pub(crate) fn encode_key(key: &Record, meta: &IndexMeta) -> Result<Vec<u8>, IndexError> {
+    let mut bytes = Vec::with_capacity(get_key_size(meta)? as usize);
+
+    for (idx, attr) in meta.schema.get_attributes().iter().enumerate() {
+        let size = get_column_size(attr.get_data_type(), meta) as usize;
+        let value = match key.get_value(idx as u32, meta.schema.clone())? {
+            Some(value) => value,
+            None => {
+                bytes.resize(bytes.len() + 1 + size, 0);
+                continue;
+            }
+        };
+
+        // Flip the sign bit of signed values so that negative values are ordered before
+        // positive values.
+        bytes.push(1);
+        match value.get_inner() {
+            InnerValue::Boolean(v) => bytes.push(v as u8),
+            InnerValue::TinyInt(v) => bytes.push(v as u8 ^ (1 << 7)),
+            InnerValue::SmallInt(v) => {
+                bytes.extend_from_slice(&(v as u16 ^ (1 << 15)).to_be_bytes())
+            }
+            InnerValue::Int(v) | InnerValue::Date(v) => {
+                bytes.extend_from_slice(&(v as u32 ^ (1 << 31)).to_be_bytes())
+            }
+            InnerValue::BigInt(v)
+            | InnerValue::Timestamp(v)
+            | InnerValue::Decimal { mantissa: v, .. } => {
+                bytes.extend_from_slice(&(v as u64 ^ (1 << 63)).to_be_bytes())
+            }
+            InnerValue::Real(v) => {
+                // Negative floats are ordered in reverse by their bits, so flip every bit.
+                let bits = v.to_bits();
+                let bits = match bits & (1 << 31) != 0 {
+                    true => !bits,
+                    false => bits ^ (1 << 31),
+                };
+                bytes.extend_from_slice(&bits.to_be_bytes())
+            }
+            InnerValue::Varchar(v) => {
+                let v = v.as_bytes();
+                let len = v.len().min(size);
+                bytes.extend_from_slice(&v[..len]);
+                bytes.resize(bytes.len() + size - len, 0);
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+/// Size of an encoded record ID in bytes.
+pub(crate) const RID_SIZE: u32 = 8;
+
+/// Return an index entry for the encoded key and record ID. Record IDs are encoded as
+/// big-endian so that entries with the same key are ordered by record ID.
+pub(crate) fn make_entry(key: &[u8], rid: RecordId) -> Vec<u8> {
+    let mut entry = Vec::with_capacity(key.len() + RID_SIZE as usize);
+    entry.extend_from_slice(key);
+    entry.extend_from_slice(&rid.page_id.to_be_bytes());
+    entry.extend_from_slice(&rid.slot_index.to_be_bytes());
+    entry
+}
+
+/// Return the record ID of an index entry.
+pub(crate) fn get_entry_rid(entry: &[u8]) -> RecordId {
+    let rid = &entry[entry.len() - RID_SIZE as usize..];
+    let mut page_id = [0; 4];
+    let mut slot_index = [0; 4];
+    page_id.copy_from_slice(&rid[..4]);
+    slot_index.copy_from_slice(&rid[4..]);
+    RecordId {
+        page_id: u32::from_be_bytes(page_id),
+        slot_index: u32::from_be_bytes(slot_index),
+    }
+}
+
+/// Custom errors to be used by indexes.
+#[derive(Debug, Eq, PartialEq)]
+pub enum IndexError {
+    /// Error to be thrown when an index is created for a key schema that can't be indexed.
+    UnsupportedKeyType,
+
+    /// Error to be thrown when a key is not an instance of the index's key schema.
+    KeySchemaMismatch,
+
+    /// Error to be thrown when an index can't grow any further to hold a new entry.
+    IndexFull,
+
+    /// Error to be thrown when an index page has an inconsistent header and can't be read.
+    PageCorrupted,
+
+    /// Errors to be thrown when the buffer manager encounters a recoverable error.
+    BufMgrNoBufFrame,
+    BufMgrPagePinned,
+    BufMgrPageBufDNE,
+    BufMgrPageDiskDNE,
+    BufMgrDiskFailure,
+}
+
+impl From<BufferError> for IndexError {
+    fn from(e: BufferError) -> Self {
+        match e {
+            BufferError::NoBufFrame => IndexError::BufMgrNoBufFrame,
+            BufferError::PagePinned => IndexError::BufMgrPagePinned,
+            BufferError::PageBufDNE => IndexError::BufMgrPageBufDNE,
+            BufferError::PageDiskDNE => IndexError::BufMgrPageDiskDNE,
+            BufferError::Disk(_) => IndexError::BufMgrDiskFailure,
+            BufferError::InvalidPage(_) => IndexError::PageCorrupted,
+        }
+    }
+}
+
+impl From<RecordErr> for IndexError {
+    fn from(_: RecordErr) -> Self {
+        IndexError::KeySchemaMismatch
+    }
+}
@@ -5,6 +5,14 @@
 
 /// Utility functions for reading and writing byte arrays.
 
+/// The byte order of a multi-byte value in a byte array. Values are stored in little-endian
+/// order unless specified otherwise.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
 /// Read a boolean at the specified offset in the byte array.
 #[inline]
 pub fn read_bool(array: &[u8], offset: u32) -> Result<bool, IoError> {
@@ -36,60 +44,143 @@ pub fn write_bool(array: &mut [u8], offset: u32, value: bool) -> Result<(), IoEr
     Ok(())
 }
 
-/// Read an unsigned 32-bit integer at the specified offset in the byte array.
+/// Read an unsigned 8-bit integer at the specified offset in the byte array.
 #[inline]
-pub fn read_u32(array: &[u8], offset: u32) -> Result<u32, IoError> {
+pub fn read_u8(array: &[u8], offset: u32) -> Result<u8, IoError> {
     let offset = offset as usize;
-    check_overflow(array.len(), offset, 4)?;
+    check_overflow(array.len(), offset, 1)?;
 
-    let mut bytes = [0; 4];
-    for i in 0..4 {
-        bytes[i] = array[offset + i];
+    Ok(array[offset])
+}
+
+/// Write an unsigned 8-bit integer at the specified offset in the byte array.
+#[inline]
+pub fn write_u8(array: &mut [u8], offset: u32, value: u8) -> Result<(), IoError> {
+    let offset = offset as usize;
+    check_overflow(array.len(), offset, 1)?;
+
+    array[offset] = value;
+
+    Ok(())
+}
+
+/// Read an unsigned 16-bit integer at the specified offset in the byte array.
+#[inline]
+pub fn read_u16(array: &[u8], offset: u32) -> Result<u16, IoError> {
+    read_u16_endian(array, offset, Endian::Little)
+}
+
+/// Read an unsigned 16-bit integer with the specified byte order at the specified offset in the
+/// byte array.
+#[inline]
+pub fn read_u16_endian(array: &[u8], offset: u32, endian: Endian) -> Result<u16, IoError> {
+    let bytes = read_array(array, offset)?;
+    match endian {
+        Endian::Little => Ok(u16::from_le_bytes(bytes)),
+        Endian::Big => Ok(u16::from_be_bytes(bytes)),
     }
+}
+
+/// Write an unsigned 16-bit integer at the specified offset in the byte array.
+#[inline]
+pub fn write_u16(array: &mut [u8], offset: u32, value: u16) -> Result<(), IoError> {
+    write_u16_endian(array, offset, value, Endian::Little)
+}
+
+/// Write an unsigned 16-bit integer with the specified byte order at the specified offset in the
+/// byte array.
+#[inline]
+pub fn write_u16_endian(
+    array: &mut [u8],
+    offset: u32,
+    value: u16,
+    endian: Endian,
+) -> Result<(), IoError> {
+    let bytes = match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    };
+    write_array(array, offset, bytes)
+}
+
+/// Read an unsigned 32-bit integer at the specified offset in the byte array.
+#[inline]
+pub fn read_u32(array: &[u8], offset: u32) -> Result<u32, IoError> {
+    read_u32_endian(array, offset, Endian::Little)
+}
 
-    Ok(u32::from_le_bytes(bytes))
+/// Read an unsigned 32-bit integer with the specified byte order at the specified offset in the
+/// byte array.
+#[inline]
+pub fn read_u32_endian(array: &[u8], offset: u32, endian: Endian) -> Result<u32, IoError> {
+    let bytes = read_array(array, offset)?;
+    match endian {
+        Endian::Little => Ok(u32::from_le_bytes(bytes)),
+        Endian::Big => Ok(u32::from_be_bytes(bytes)),
+    }
 }
 
 /// Write an unsigned 32-bit integer at the specified offset in the byte array. Any existing
 /// value is overwritten.
 #[inline]
 pub fn write_u32(array: &mut [u8], offset: u32, value: u32) -> Result<(), IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 4)?;
-
-    for i in 0..4 {
-        array[offset + i] = ((value >> (i * 8)) & 0xff) as u8;
-    }
+    write_u32_endian(array, offset, value, Endian::Little)
+}
 
-    Ok(())
+/// Write an unsigned 32-bit integer with the specified byte order at the specified offset in the
+/// byte array. Any existing value is overwritten.
+#[inline]
+pub fn write_u32_endian(
+    array: &mut [u8],
+    offset: u32,
+    value: u32,
+    endian: Endian,
+) -> Result<(), IoError> {
+    let bytes = match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    };
+    write_array(array, offset, bytes)
 }
 
 /// Read an unsigned 64-bit integer at the specified offset in the byte array.
 #[inline]
 pub fn read_u64(array: &[u8], offset: u32) -> Result<u64, IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 8)?;
+    read_u64_endian(array, offset, Endian::Little)
+}
 
-    let mut bytes = [0; 8];
-    for i in 0..8 {
-        bytes[i] = array[offset + i];
+/// Read an unsigned 64-bit integer with the specified byte order at the specified offset in the
+/// byte array.
+#[inline]
+pub fn read_u64_endian(array: &[u8], offset: u32, endian: Endian) -> Result<u64, IoError> {
+    let bytes = read_array(array, offset)?;
+    match endian {
+        Endian::Little => Ok(u64::from_le_bytes(bytes)),
+        Endian::Big => Ok(u64::from_be_bytes(bytes)),
     }
-
-    Ok(u64::from_le_bytes(bytes))
 }
 
 /// Write an unsigned 64-bit integer at the specified offset in the byte array. Any existing
 /// value is overwritten.
 #[inline]
 pub fn write_u64(array: &mut [u8], offset: u32, value: u64) -> Result<(), IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 8)?;
-
-    for i in 0..8 {
-        array[offset + i] = ((value >> (i * 8)) & 0xff) as u8;
-    }
+    write_u64_endian(array, offset, value, Endian::Little)
+}
 
-    Ok(())
+/// Write an unsigned 64-bit integer with the specified byte order at the specified offset in the
+/// byte array. Any existing value is overwritten.
+#[inline]
+pub fn write_u64_endian(
+    array: &mut [u8],
+    offset: u32,
+    value: u64,
+    endian: Endian,
+) -> Result<(), IoError> {
+    let bytes = match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    };
+    write_array(array, offset, bytes)
 }
 
 /// Read a signed 8-bit integer at the specified offset in the byte array.
@@ -115,109 +206,157 @@ pub fn write_i8(array: &mut [u8], offset: u32, value: i8) -> Result<(), IoError>
 /// Read a signed 16-bit integer at the specified offset in the byte array.
 #[inline]
 pub fn read_i16(array: &[u8], offset: u32) -> Result<i16, IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 2)?;
-
-    let mut bytes = [0; 2];
-    bytes[0] = array[offset];
-    bytes[1] = array[offset + 1];
+    read_i16_endian(array, offset, Endian::Little)
+}
 
-    Ok(i16::from_le_bytes(bytes))
+/// Read a signed 16-bit integer with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn read_i16_endian(array: &[u8], offset: u32, endian: Endian) -> Result<i16, IoError> {
+    let bytes = read_array(array, offset)?;
+    match endian {
+        Endian::Little => Ok(i16::from_le_bytes(bytes)),
+        Endian::Big => Ok(i16::from_be_bytes(bytes)),
+    }
 }
 
 /// Write a signed 16-bit integer at the specified offset in the byte array.
 #[inline]
 pub fn write_i16(array: &mut [u8], offset: u32, value: i16) -> Result<(), IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 2)?;
-
-    array[offset] = (value & 0xff) as u8;
-    array[offset + 1] = ((value >> 8) & 0xff) as u8;
+    write_i16_endian(array, offset, value, Endian::Little)
+}
 
-    Ok(())
+/// Write a signed 16-bit integer with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn write_i16_endian(
+    array: &mut [u8],
+    offset: u32,
+    value: i16,
+    endian: Endian,
+) -> Result<(), IoError> {
+    let bytes = match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    };
+    write_array(array, offset, bytes)
 }
 
 /// Read a signed 32-bit integer at the specified offset in the byte array.
 #[inline]
 pub fn read_i32(array: &[u8], offset: u32) -> Result<i32, IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 4)?;
+    read_i32_endian(array, offset, Endian::Little)
+}
 
-    let mut bytes = [0; 4];
-    for i in 0..4 {
-        bytes[i] = array[offset + i];
+/// Read a signed 32-bit integer with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn read_i32_endian(array: &[u8], offset: u32, endian: Endian) -> Result<i32, IoError> {
+    let bytes = read_array(array, offset)?;
+    match endian {
+        Endian::Little => Ok(i32::from_le_bytes(bytes)),
+        Endian::Big => Ok(i32::from_be_bytes(bytes)),
     }
-
-    Ok(i32::from_le_bytes(bytes))
 }
 
 /// Write a signed 32-bit integer at the specified offset in the byte array.
 #[inline]
 pub fn write_i32(array: &mut [u8], offset: u32, value: i32) -> Result<(), IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 4)?;
-
-    for i in 0..4 {
-        array[offset + i] = ((value >> (i * 8)) & 0xff) as u8;
-    }
+    write_i32_endian(array, offset, value, Endian::Little)
+}
 
-    Ok(())
+/// Write a signed 32-bit integer with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn write_i32_endian(
+    array: &mut [u8],
+    offset: u32,
+    value: i32,
+    endian: Endian,
+) -> Result<(), IoError> {
+    let bytes = match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    };
+    write_array(array, offset, bytes)
 }
 
 /// Read a signed 64-bit integer at the specified offset in the byte array.
 #[inline]
 pub fn read_i64(array: &[u8], offset: u32) -> Result<i64, IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 8)?;
+    read_i64_endian(array, offset, Endian::Little)
+}
 
-    let mut bytes = [0; 8];
-    for i in 0..8 {
-        bytes[i] = array[offset + i];
+/// Read a signed 64-bit integer with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn read_i64_endian(array: &[u8], offset: u32, endian: Endian) -> Result<i64, IoError> {
+    let bytes = read_array(array, offset)?;
+    match endian {
+        Endian::Little => Ok(i64::from_le_bytes(bytes)),
+        Endian::Big => Ok(i64::from_be_bytes(bytes)),
     }
-
-    Ok(i64::from_le_bytes(bytes))
 }
 
 /// Write a signed 64-bit integer at the specified offset in the byte array.
 #[inline]
 pub fn write_i64(array: &mut [u8], offset: u32, value: i64) -> Result<(), IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 8)?;
-
-    for i in 0..8 {
-        array[offset + i] = ((value >> (i * 8)) & 0xff) as u8;
-    }
+    write_i64_endian(array, offset, value, Endian::Little)
+}
 
-    Ok(())
+/// Write a signed 64-bit integer with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn write_i64_endian(
+    array: &mut [u8],
+    offset: u32,
+    value: i64,
+    endian: Endian,
+) -> Result<(), IoError> {
+    let bytes = match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    };
+    write_array(array, offset, bytes)
 }
 
 /// Read a signed 32-bit float at the specified offset in the byte array.
 #[inline]
 pub fn read_f32(array: &[u8], offset: u32) -> Result<f32, IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 4)?;
+    read_f32_endian(array, offset, Endian::Little)
+}
 
-    let mut bytes = [0; 4];
-    for i in 0..4 {
-        bytes[i] = array[offset + i];
+/// Read a signed 32-bit float with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn read_f32_endian(array: &[u8], offset: u32, endian: Endian) -> Result<f32, IoError> {
+    let bytes = read_array(array, offset)?;
+    match endian {
+        Endian::Little => Ok(f32::from_le_bytes(bytes)),
+        Endian::Big => Ok(f32::from_be_bytes(bytes)),
     }
-
-    Ok(f32::from_le_bytes(bytes))
 }
 
 /// Write a signed 32-bit float at the specified offset in the byte array.
 #[inline]
 pub fn write_f32(array: &mut [u8], offset: u32, value: f32) -> Result<(), IoError> {
-    let offset = offset as usize;
-    check_overflow(array.len(), offset, 4)?;
-
-    let bytes = f32::to_le_bytes(value);
-
-    for i in 0..4 {
-        array[offset + i] = bytes[i];
-    }
+    write_f32_endian(array, offset, value, Endian::Little)
+}
 
-    Ok(())
+/// Write a signed 32-bit float with the specified byte order at the specified offset in the byte
+/// array.
+#[inline]
+pub fn write_f32_endian(
+    array: &mut [u8],
+    offset: u32,
+    value: f32,
+    endian: Endian,
+) -> Result<(), IoError> {
+    let bytes = match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    };
+    write_array(array, offset, bytes)
 }
 
 /// Read a variable-length string with a specified offset/length in the byte array.
@@ -262,20 +401,52 @@ pub fn write_str(array: &mut [u8], offset: u32, string: &str) -> Result<(), IoEr
 /// Read a 32-byte string at the specified offset in the byte array. It is assumed that the
 /// string is encoded as valid UTF-8.
 #[inline]
-pub fn read_str256(array: &[u8], offset: u32) -> Result<String, IoError> {
+pub fn read_str32(array: &[u8], offset: u32) -> Result<String, IoError> {
     read_str(array, offset, 32)
 }
 
 /// Write a 32-byte string at the specified offset in the byte array. Any existing value is
-/// overwritten. If is assumed that the string is encoded as valid UTF-8.
+/// overwritten and the unused remainder of the 32 bytes is zero-padded. It is assumed that the
+/// string is encoded as valid UTF-8.
 #[inline]
-pub fn write_str256(array: &mut [u8], offset: u32, string: &str) -> Result<(), IoError> {
-    if string.as_bytes().len() > 32 {
+pub fn write_str32(array: &mut [u8], offset: u32, string: &str) -> Result<(), IoError> {
+    let len = string.as_bytes().len();
+    if len > 32 {
         return Err(IoError::Custom(format!(
             "Length of string cannot exceed 32 bytes"
         )));
     }
-    write_str(array, offset, string)
+    check_overflow(array.len(), offset as usize, 32)?;
+
+    write_str(array, offset, string)?;
+    let start = offset as usize;
+    array[start + len..start + 32].fill(0);
+    Ok(())
+}
+
+/// Read a fixed number of bytes at the specified offset in the byte array.
+#[inline(always)]
+fn read_array<const N: usize>(array: &[u8], offset: u32) -> Result<[u8; N], IoError> {
+    let offset = offset as usize;
+    check_overflow(array.len(), offset, N)?;
+
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(&array[offset..offset + N]);
+    Ok(bytes)
+}
+
+/// Write a fixed number of bytes at the specified offset in the byte array.
+#[inline(always)]
+fn write_array<const N: usize>(
+    array: &mut [u8],
+    offset: u32,
+    bytes: [u8; N],
+) -> Result<(), IoError> {
+    let offset = offset as usize;
+    check_overflow(array.len(), offset, N)?;
+
+    array[offset..offset + N].copy_from_slice(&bytes);
+    Ok(())
 }
 
 /// Return an Error if inserting data of specified offset/length into an array of a given
@@ -381,7 +552,60 @@ mod tests {
     }
 
     #[test]
-    fn test_read_str256() {
+    fn test_read_write_u32_big_endian() {
+        let mut array = vec![0; 100];
+        let offset = 17;
+        let value: u32 = 0x0102_0304;
+
+        let result = write_u32_endian(array.as_mut_slice(), offset, value, Endian::Big);
+        assert!(result.is_ok());
+
+        // Assert that the most significant byte is stored first.
+        assert_eq!(array[offset as usize..offset as usize + 4], [1, 2, 3, 4]);
+
+        let result = read_u32_endian(array.as_slice(), offset, Endian::Big);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), value);
+
+        // Assert that reading with the other byte order reverses the bytes.
+        let result = read_u32_endian(array.as_slice(), offset, Endian::Little);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), value.swap_bytes());
+    }
+
+    #[test]
+    fn test_read_write_big_endian() {
+        let mut array = vec![0; 100];
+
+        write_u64_endian(&mut array, 0, 980981237789123, Endian::Big).unwrap();
+        write_i16_endian(&mut array, 8, -300, Endian::Big).unwrap();
+        write_i32_endian(&mut array, 10, -70_000, Endian::Big).unwrap();
+        write_i64_endian(&mut array, 14, -5_000_000_000, Endian::Big).unwrap();
+        write_f32_endian(&mut array, 22, -76_543.21, Endian::Big).unwrap();
+
+        assert_eq!(array[8..10], (-300_i16).to_be_bytes());
+        assert_eq!(
+            read_u64_endian(&array, 0, Endian::Big).unwrap(),
+            980981237789123
+        );
+        assert_eq!(read_i16_endian(&array, 8, Endian::Big).unwrap(), -300);
+        assert_eq!(read_i32_endian(&array, 10, Endian::Big).unwrap(), -70_000);
+        assert_eq!(
+            read_i64_endian(&array, 14, Endian::Big).unwrap(),
+            -5_000_000_000
+        );
+        assert_eq!(
+            read_f32_endian(&array, 22, Endian::Big).unwrap(),
+            -76_543.21
+        );
+
+        // Assert that big-endian reads and writes check for overflow.
+        assert!(read_u32_endian(&array, 97, Endian::Big).is_err());
+        assert!(write_u32_endian(&mut array, 97, 1, Endian::Big).is_err());
+    }
+
+    #[test]
+    fn test_read_str32() {
         let mut array = [0; PAGE_SIZE as usize];
 
         // Serialize expected string into byte array.
@@ -393,7 +617,7 @@ mod tests {
         }
 
         // Assert that read string is correct.
-        let result = read_str256(&array, offset as u32);
+        let result = read_str32(&array, offset as u32);
         assert!(result.is_ok());
 
         let actual = result.unwrap();
@@ -401,13 +625,13 @@ mod tests {
     }
 
     #[test]
-    fn test_write_str256() {
+    fn test_write_str32() {
         let mut array = [0; PAGE_SIZE as usize];
 
         // Serialize value into byte array with function.
         let value = "Hello, World!".to_string();
         let offset = 1262;
-        let result = write_str256(&mut array, offset as u32, &value);
+        let result = write_str32(&mut array, offset as u32, &value);
         assert!(result.is_ok());
 
         // Assert that serialized bytes are correct.
@@ -420,19 +644,40 @@ mod tests {
     }
 
     #[test]
-    fn test_write_str256_too_long() {
+    fn test_write_str32_too_long() {
         let mut array = [0; PAGE_SIZE as usize];
         let offset = 712;
         let long = "abcdefghijklmnopqrstuvwxyz";
         let too_long = "abcdefghijklmnopqrstuvwxyz abcdefghijklmnopqrstuvwxyz";
 
-        let result = write_str256(&mut array, offset as u32, long);
+        let result = write_str32(&mut array, offset as u32, long);
         assert!(result.is_ok());
 
-        let result = write_str256(&mut array, offset as u32, too_long);
+        let result = write_str32(&mut array, offset as u32, too_long);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_write_str32_overwrite() {
+        let mut array = [0xff; PAGE_SIZE as usize];
+        let offset = 305;
+
+        // Write a long string followed by a shorter one at the same offset.
+        let result = write_str32(&mut array, offset, "abcdefghijklmnopqrstuvwxyz");
+        assert!(result.is_ok());
+        let result = write_str32(&mut array, offset, "foo");
+        assert!(result.is_ok());
+
+        // Assert that the shorter string is read back exactly, without leftover bytes.
+        let result = read_str32(&array, offset);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "foo".to_string());
+
+        // Assert that bytes outside of the 32-byte region are untouched.
+        assert_eq!(array[offset as usize - 1], 0xff);
+        assert_eq!(array[offset as usize + 32], 0xff);
+    }
+
     #[test]
     fn test_read_write_string() {
         let mut array = vec![0; 100];
@@ -461,6 +706,44 @@ mod tests {
         assert_eq!(result.unwrap(), value)
     }
 
+    #[test]
+    fn test_read_write_u8() {
+        let mut array = vec![0; 100];
+        let offset = 43;
+
+        for value in [0, 1, 255] {
+            let result = write_u8(array.as_mut_slice(), offset, value);
+            assert!(result.is_ok());
+
+            let result = read_u8(array.as_slice(), offset);
+            assert!(result.is_ok());
+            assert_eq!(result.unwrap(), value)
+        }
+
+        // Assert that reads and writes fail with an overflow.
+        assert!(read_u8(array.as_slice(), 100).is_err());
+        assert!(write_u8(array.as_mut_slice(), 100, 255).is_err());
+    }
+
+    #[test]
+    fn test_read_write_u16() {
+        let mut array = vec![0; 100];
+        let offset = 43;
+
+        for value in [0, 256, 65535] {
+            let result = write_u16(array.as_mut_slice(), offset, value);
+            assert!(result.is_ok());
+
+            let result = read_u16(array.as_slice(), offset);
+            assert!(result.is_ok());
+            assert_eq!(result.unwrap(), value)
+        }
+
+        // Assert that reads and writes fail with an overflow.
+        assert!(read_u16(array.as_slice(), 99).is_err());
+        assert!(write_u16(array.as_mut_slice(), 99, 65535).is_err());
+    }
+
     #[test]
     fn test_read_write_i8() {
         let mut array = vec![0; 100];
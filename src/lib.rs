@@ -6,6 +6,7 @@
 pub mod bitmap;
 pub mod buffer;
 pub mod catalog;
+pub mod concurrency;
 pub mod constants;
 pub mod disk;
 pub mod executor;
@@ -15,3 +16,4 @@ pub mod log;
 pub mod page;
 pub mod plan;
 pub mod relation;
+pub mod repl;
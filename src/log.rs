@@ -3,27 +3,710 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
-use crate::constants::{LsnT, TransactionIdT};
-use std::collections::HashMap;
+use crate::buffer::{BufferError, BufferManager};
+use crate::constants::{LsnT, PageIdT, TransactionIdT, INVALID_LSN, SYSTEM_TXN_ID};
+use crate::io::{read_u32, write_u32};
+use crate::page::{PageBytes, PageError, RelationPage};
+use crate::relation::record::{Record, RecordId};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io::Write;
+use std::sync::Mutex;
 
-pub struct LogManager;
+/// The log manager appends log records describing modifications of relation pages to a
+/// write-ahead log file. Each log record is assigned a log sequence number (LSN), which increases
+/// monotonically in the order that records are appended.
+///
+/// Appended log records are held in an in-memory log buffer until they are flushed. Before the
+/// buffer manager writes a modified page to disk, it flushes the log up to the LSN of the page,
+/// so that the log records for a page always reach disk before the page itself.
+///
+/// The log manager periodically takes checkpoints, so that recovery doesn't need to scan the log
+/// from its beginning.
+pub struct LogManager {
+    log_filename: String,
 
-struct LogRecovery {
-    log_buffer: String,
+    /// Log records that haven't been flushed yet. This lock is held while log records are
+    /// written, so that log records are written to the file in LSN order.
+    log_buffer: Mutex<LogBuffer>,
+}
+
+impl LogManager {
+    /// Create a new log manager with an empty log file.
+    pub fn new(filename: &str) -> Self {
+        fs::write(filename, []).unwrap();
+
+        Self {
+            log_filename: filename.to_string(),
+            log_buffer: Mutex::new(LogBuffer {
+                records: Vec::new(),
+                next_lsn: INVALID_LSN + 1,
+                flushed_lsn: INVALID_LSN,
+                checkpoint_lsn: INVALID_LSN,
+                active_txns: BTreeSet::new(),
+            }),
+        }
+    }
+
+    /// Create a log manager for an existing log file, such as a log file that was written before
+    /// the database crashed. New log records are assigned LSNs following the last log record in
+    /// the file. A partially written record at the end of the file is discarded.
+    pub fn open(filename: &str) -> Self {
+        let bytes = fs::read(filename).unwrap();
+
+        let mut last_lsn = INVALID_LSN;
+        let mut checkpoint_lsn = INVALID_LSN;
+        let mut active_txns = BTreeSet::new();
+        let mut offset = 0;
+        while let Some((record, size)) = LogRecord::deserialize(&bytes[offset..]) {
+            last_lsn = record.lsn;
+            if let LogOperation::Checkpoint { .. } = record.operation {
+                checkpoint_lsn = record.lsn;
+            }
+            track_active_txn(&mut active_txns, &record);
+            offset += size as usize;
+        }
+        if offset < bytes.len() {
+            let file = fs::OpenOptions::new().write(true).open(filename).unwrap();
+            file.set_len(offset as u64).unwrap();
+        }
+
+        Self {
+            log_filename: filename.to_string(),
+            log_buffer: Mutex::new(LogBuffer {
+                records: Vec::new(),
+                next_lsn: last_lsn + 1,
+                flushed_lsn: last_lsn,
+                checkpoint_lsn,
+                active_txns,
+            }),
+        }
+    }
+
+    /// Append a log record for an operation performed on behalf of the specified transaction to
+    /// the log buffer, and return its LSN. The log record isn't written to the log file until it
+    /// is flushed.
+    pub fn append(&self, txn_id: TransactionIdT, operation: LogOperation) -> LsnT {
+        self.append_record(txn_id, INVALID_LSN, operation)
+    }
+
+    /// Append a log record to the log buffer and return its LSN. `undone_lsn` is the LSN of the
+    /// log record undone by the operation, or `INVALID_LSN` if the operation isn't an undo.
+    fn append_record(
+        &self,
+        txn_id: TransactionIdT,
+        undone_lsn: LsnT,
+        operation: LogOperation,
+    ) -> LsnT {
+        let mut log_buffer = self.log_buffer.lock().unwrap();
+        let lsn = log_buffer.next_lsn;
+        let record = LogRecord {
+            lsn,
+            txn_id,
+            undone_lsn,
+            operation,
+        };
+        track_active_txn(&mut log_buffer.active_txns, &record);
+        log_buffer.records.push(record);
+        log_buffer.next_lsn += 1;
+        lsn
+    }
+
+    /// Take a checkpoint and return its LSN.
+    ///
+    /// Every dirty page in the buffer is flushed to disk, so that the operations logged before the
+    /// checkpoint began never need to be redone. Then, a checkpoint log record is written, which
+    /// lists the transactions that are active and the pages that were modified while the
+    /// checkpoint was taken (the dirty page table). Recovery starts its analysis pass at the
+    /// latest checkpoint instead of the beginning of the log.
+    pub fn checkpoint(&self, buffer_manager: &BufferManager) -> Result<LsnT, BufferError> {
+        let redo_lsn = self.log_buffer.lock().unwrap().next_lsn;
+        buffer_manager.flush_all_pages()?;
+        let dirty_pages = buffer_manager.get_pages_modified_since(redo_lsn);
+
+        // The active transactions are collected while the log buffer is locked, so that they are
+        // consistent with the position of the checkpoint in the log.
+        let lsn = {
+            let mut log_buffer = self.log_buffer.lock().unwrap();
+            let lsn = log_buffer.next_lsn;
+            let active_txns = log_buffer.active_txns.iter().copied().collect();
+            log_buffer.records.push(LogRecord {
+                lsn,
+                txn_id: SYSTEM_TXN_ID,
+                undone_lsn: INVALID_LSN,
+                operation: LogOperation::Checkpoint {
+                    redo_lsn,
+                    active_txns,
+                    dirty_pages,
+                },
+            });
+            log_buffer.next_lsn += 1;
+            lsn
+        };
+        self.flush_to_lsn(lsn);
+
+        self.log_buffer.lock().unwrap().checkpoint_lsn = lsn;
+        Ok(lsn)
+    }
+
+    /// Write every buffered log record with an LSN less than or equal to the specified LSN to
+    /// the log file. Log records that have already been flushed are not written again.
+    pub fn flush_to_lsn(&self, lsn: LsnT) {
+        let mut log_buffer = self.log_buffer.lock().unwrap();
+        if lsn <= log_buffer.flushed_lsn {
+            return;
+        }
+
+        let count = log_buffer
+            .records
+            .partition_point(|record| record.lsn <= lsn);
+        if count == 0 {
+            return;
+        }
+
+        let mut bytes = Vec::new();
+        for record in log_buffer.records[..count].iter() {
+            bytes.extend_from_slice(&record.serialize());
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&self.log_filename)
+            .unwrap();
+        file.write_all(&bytes).unwrap();
+        file.sync_data().unwrap();
+
+        log_buffer.flushed_lsn = log_buffer.records[count - 1].lsn;
+        log_buffer.records.drain(..count);
+    }
+
+    /// Write every buffered log record to the log file.
+    pub fn flush(&self) {
+        self.flush_to_lsn(LsnT::MAX);
+    }
+
+    /// Return the LSN of the latest log record written to the log file, or `INVALID_LSN` if no
+    /// log record has been flushed yet.
+    pub fn get_flushed_lsn(&self) -> LsnT {
+        self.log_buffer.lock().unwrap().flushed_lsn
+    }
 
-    /// Mapping of active transactions to latest LSN
-    active: HashMap<TransactionIdT, LsnT>,
+    /// Read every log record in LSN order, including buffered log records that haven't been
+    /// flushed yet. A partially written record at the end of the log file is ignored.
+    pub fn read_records(&self) -> Vec<LogRecord> {
+        let log_buffer = self.log_buffer.lock().unwrap();
+        let bytes = fs::read(&self.log_filename).unwrap();
 
-    /// Mapping of LSN to log file offset for undo operations
-    lsn_offsets: HashMap<LsnT, i32>,
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while let Some((record, size)) = LogRecord::deserialize(&bytes[offset..]) {
+            records.push(record);
+            offset += size as usize;
+        }
+        records.extend(log_buffer.records.iter().cloned());
+        records
+    }
+
+    /// Return the LSN of the latest checkpoint, or `INVALID_LSN` if no checkpoint has been taken.
+    pub fn get_checkpoint_lsn(&self) -> LsnT {
+        self.log_buffer.lock().unwrap().checkpoint_lsn
+    }
+
+    /// Return the name of the log file.
+    pub fn get_filename(&self) -> &str {
+        self.log_filename.as_str()
+    }
+
+    /// Recover the relation pages managed by the specified buffer manager after a crash, and
+    /// return the IDs of the transactions that were rolled back.
+    ///
+    /// Recovery is performed in three passes over the log:
+    /// 1) Analysis: Starting from the latest checkpoint, transactions that were active at the
+    ///    checkpoint or appear after it are losers unless they have a commit or abort log record.
+    ///    Operations that aren't performed on behalf of a user transaction are never rolled back.
+    /// 2) Redo: Every operation logged after the checkpoint began is reapplied to pages whose LSN
+    ///    is older than the log record, which restores the state of every page at the time of the
+    ///    crash.
+    /// 3) Undo: The operations of loser transactions are undone in reverse order, by applying
+    ///    their before images. Each undo is logged as a compensation log record, which is redone
+    ///    but never undone, so that operations aren't undone twice if recovery is interrupted.
+    ///    An abort log record is appended for each loser once all of its operations are undone.
+    ///
+    /// The overflow pages of a deleted record are freed without logging their contents, so the
+    /// deletion can't be undone. Return an error if a loser freed the overflow pages of a record,
+    /// rather than restoring an overflow stub which references the freed pages.
+    ///
+    /// TODO: Inserted records stored in overflow pages are redone from their overflow stubs as if
+    /// they were regular records, since insertion log records don't distinguish overflow stubs.
+    pub fn recover(
+        &self,
+        buffer_manager: &BufferManager,
+    ) -> Result<Vec<TransactionIdT>, RecoveryError> {
+        let records = self.read_records();
+        let checkpoint_lsn = self.get_checkpoint_lsn();
+        let start = records.partition_point(|record| record.lsn < checkpoint_lsn);
+
+        // Analysis pass
+        let mut redo_lsn = INVALID_LSN;
+        let mut active = BTreeSet::new();
+        for record in records[start..].iter() {
+            match &record.operation {
+                LogOperation::Checkpoint {
+                    redo_lsn: lsn,
+                    active_txns,
+                    ..
+                } if record.lsn == checkpoint_lsn => {
+                    redo_lsn = *lsn;
+                    active.extend(active_txns.iter().copied());
+                }
+                _ => track_active_txn(&mut active, record),
+            }
+        }
+        let losers: Vec<TransactionIdT> = active.into_iter().collect();
+
+        // Redo pass
+        let start = records.partition_point(|record| record.lsn < redo_lsn);
+        for record in records[start..].iter() {
+            let rid = match record.operation.get_rid() {
+                Some(rid) => rid,
+                None => continue,
+            };
+            self.apply_to_page(buffer_manager, rid, |page| {
+                if RelationPage::get_lsn(page) >= record.lsn {
+                    return Ok(None);
+                }
+                apply(page, &record.operation)?;
+                Ok(Some(record.lsn))
+            })?;
+        }
+
+        // Undo pass
+        let undone: HashSet<LsnT> = records
+            .iter()
+            .map(|record| record.undone_lsn)
+            .filter(|lsn| *lsn != INVALID_LSN)
+            .collect();
+        for record in records.iter().rev() {
+            if !losers.contains(&record.txn_id)
+                || record.undone_lsn != INVALID_LSN
+                || undone.contains(&record.lsn)
+            {
+                continue;
+            }
+            if let LogOperation::FreeOverflow { rid } = record.operation {
+                return Err(RecoveryError::OverflowFreed(rid));
+            }
+            let rid = match record.operation.get_rid() {
+                Some(rid) => rid,
+                None => continue,
+            };
+            self.apply_to_page(buffer_manager, rid, |page| {
+                let operation = invert(page, &record.operation)?;
+                apply(page, &operation)?;
+                Ok(Some(self.append_record(
+                    record.txn_id,
+                    record.lsn,
+                    operation,
+                )))
+            })?;
+        }
+
+        for txn_id in losers.iter() {
+            self.append(*txn_id, LogOperation::Abort);
+        }
+        self.flush();
+
+        Ok(losers)
+    }
+
+    /// Apply a function to the relation page containing the specified record while holding its
+    /// write latch. If the function returns an LSN, the page is stamped with the LSN and flagged
+    /// as dirty.
+    fn apply_to_page(
+        &self,
+        buffer_manager: &BufferManager,
+        rid: RecordId,
+        f: impl FnOnce(&mut PageBytes) -> Result<Option<LsnT>, PageError>,
+    ) -> Result<(), RecoveryError> {
+        let frame_arc = buffer_manager.fetch_page(rid.page_id)?;
+        let mut frame = frame_arc.write().unwrap();
+
+        let page = frame.get_mut_page().unwrap();
+        let result = f(page);
+        if let Ok(Some(lsn)) = result {
+            RelationPage::set_lsn(page, lsn);
+            frame.set_lsn(lsn);
+            frame.set_dirty_flag(true);
+        }
+
+        result?;
+        Ok(())
+    }
+}
+
+/// The in-memory tail of the log.
+struct LogBuffer {
+    /// Log records that haven't been flushed, in LSN order
+    records: Vec<LogRecord>,
+
+    /// LSN to be assigned to the next log record
+    next_lsn: LsnT,
+
+    /// LSN of the latest log record written to the log file
+    flushed_lsn: LsnT,
+
+    /// LSN of the latest checkpoint log record
+    checkpoint_lsn: LsnT,
+
+    /// Transactions with log records that haven't committed or aborted yet
+    active_txns: BTreeSet<TransactionIdT>,
+}
+
+/// Update a set of active transactions with a log record appended after them. A transaction is
+/// active from its first log record until it commits or aborts.
+fn track_active_txn(active_txns: &mut BTreeSet<TransactionIdT>, record: &LogRecord) {
+    if record.txn_id == SYSTEM_TXN_ID {
+        return;
+    }
+    match record.operation {
+        LogOperation::Commit | LogOperation::Abort => {
+            active_txns.remove(&record.txn_id);
+        }
+        _ => {
+            active_txns.insert(record.txn_id);
+        }
+    }
 }
 
-impl LogRecovery {
-    pub fn redo() {
-        todo!()
+/// Apply a logged operation to a relation page.
+fn apply(page: &mut PageBytes, operation: &LogOperation) -> Result<(), PageError> {
+    match operation {
+        LogOperation::Insert { rid, after } => {
+            RelationPage::restore_record(page, rid.slot_index, after)
+        }
+        LogOperation::Update { rid, after, .. } => {
+            let record = Record::from_bytes(after.clone(), *rid);
+            RelationPage::update_record(page, record, rid.slot_index)
+        }
+        LogOperation::MarkDelete { rid, .. } => {
+            RelationPage::flag_delete_record(page, rid.slot_index)
+        }
+        LogOperation::ApplyDelete { rid, .. } => {
+            RelationPage::commit_delete_record(page, rid.slot_index)
+        }
+        LogOperation::RollbackDelete { rid } => {
+            RelationPage::rollback_delete_record(page, rid.slot_index)
+        }
+        LogOperation::FreeOverflow { .. }
+        | LogOperation::Commit
+        | LogOperation::Abort
+        | LogOperation::Checkpoint { .. } => Ok(()),
     }
+}
+
+/// Return the operation which undoes a logged operation, given the relation page that the
+/// operation was applied to.
+fn invert(page: &PageBytes, operation: &LogOperation) -> Result<LogOperation, PageError> {
+    let operation = match operation.clone() {
+        LogOperation::Insert { rid, after } => LogOperation::ApplyDelete { rid, before: after },
+        LogOperation::Update { rid, before, after } => LogOperation::Update {
+            rid,
+            before: after,
+            after: before,
+        },
+        LogOperation::MarkDelete { rid, .. } => LogOperation::RollbackDelete { rid },
+        LogOperation::ApplyDelete { rid, before } => LogOperation::Insert { rid, after: before },
+        LogOperation::RollbackDelete { rid } => LogOperation::MarkDelete {
+            rid,
+            before: RelationPage::get_record_bytes(page, rid.slot_index)?.to_vec(),
+        },
+        operation => operation,
+    };
+    Ok(operation)
+}
+
+/// Constants for log record header.
+const LOG_SIZE_OFFSET: u32 = 0;
+const LOG_LSN_OFFSET: u32 = 4;
+const LOG_TXN_ID_OFFSET: u32 = 8;
+const LOG_UNDONE_LSN_OFFSET: u32 = 12;
+const LOG_TYPE_OFFSET: u32 = 16;
+const LOG_PAGE_ID_OFFSET: u32 = 20;
+const LOG_SLOT_INDEX_OFFSET: u32 = 24;
+const LOG_IMAGES_OFFSET: u32 = 28;
+
+/// Constants for log record types.
+const LOG_INSERT: u32 = 1;
+const LOG_UPDATE: u32 = 2;
+const LOG_MARK_DELETE: u32 = 3;
+const LOG_APPLY_DELETE: u32 = 4;
+const LOG_ROLLBACK_DELETE: u32 = 5;
+const LOG_COMMIT: u32 = 6;
+const LOG_ABORT: u32 = 7;
+const LOG_CHECKPOINT: u32 = 8;
+const LOG_FREE_OVERFLOW: u32 = 9;
+
+/// A record in the write-ahead log, which describes a single operation performed on behalf of a
+/// transaction.
+///
+/// Data format (number denotes size in bytes):
+/// +----------+---------+------------+----------------+----------+
+/// | SIZE (4) | LSN (4) | TXN ID (4) | UNDONE LSN (4) | TYPE (4) |
+/// +----------+---------+------------+----------------+----------+
+/// | PAGE ID (4) | SLOT INDEX (4) | BEFORE IMAGE SIZE (4) | BEFORE IMAGE |
+/// +-------------+----------------+-----------------------+--------------+
+/// | AFTER IMAGE SIZE (4) | AFTER IMAGE |
+/// +----------------------+-------------+
+///
+/// The size is the total size of the log record in bytes. The undone LSN of a compensation log
+/// record is the LSN of the log record that it undoes, and is `INVALID_LSN` for other log
+/// records. The record ID and images that don't apply to the type of operation are empty.
+///
+/// The before image of a checkpoint log record is the list of active transaction IDs, and its
+/// after image is the redo LSN followed by the list of dirty page IDs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogRecord {
+    lsn: LsnT,
+    txn_id: TransactionIdT,
+    undone_lsn: LsnT,
+    operation: LogOperation,
+}
+
+impl LogRecord {
+    /// Return the LSN of this log record.
+    pub fn get_lsn(&self) -> LsnT {
+        self.lsn
+    }
+
+    /// Return the ID of the transaction that the operation was performed on behalf of.
+    pub fn get_txn_id(&self) -> TransactionIdT {
+        self.txn_id
+    }
+
+    /// Return the LSN of the log record undone by this compensation log record, or None if this
+    /// isn't a compensation log record.
+    pub fn get_undone_lsn(&self) -> Option<LsnT> {
+        match self.undone_lsn {
+            INVALID_LSN => None,
+            lsn => Some(lsn),
+        }
+    }
+
+    /// Return the operation described by this log record.
+    pub fn get_operation(&self) -> &LogOperation {
+        &self.operation
+    }
+
+    /// Serialize this log record into bytes.
+    fn serialize(&self) -> Vec<u8> {
+        let empty = RecordId {
+            page_id: 0,
+            slot_index: 0,
+        };
+        let (txns, pages);
+        let (log_type, rid, before, after): (u32, RecordId, &[u8], &[u8]) = match &self.operation {
+            LogOperation::Insert { rid, after } => (LOG_INSERT, *rid, &[], after),
+            LogOperation::Update { rid, before, after } => (LOG_UPDATE, *rid, before, after),
+            LogOperation::MarkDelete { rid, before } => (LOG_MARK_DELETE, *rid, before, &[]),
+            LogOperation::ApplyDelete { rid, before } => (LOG_APPLY_DELETE, *rid, before, &[]),
+            LogOperation::RollbackDelete { rid } => (LOG_ROLLBACK_DELETE, *rid, &[], &[]),
+            LogOperation::FreeOverflow { rid } => (LOG_FREE_OVERFLOW, *rid, &[], &[]),
+            LogOperation::Commit => (LOG_COMMIT, empty, &[], &[]),
+            LogOperation::Abort => (LOG_ABORT, empty, &[], &[]),
+            LogOperation::Checkpoint {
+                redo_lsn,
+                active_txns,
+                dirty_pages,
+            } => {
+                txns = serialize_u32s(active_txns);
+                pages = serialize_u32s(&[&[*redo_lsn], dirty_pages.as_slice()].concat());
+                (LOG_CHECKPOINT, empty, &txns, &pages)
+            }
+        };
+
+        let size = LOG_IMAGES_OFFSET + 8 + before.len() as u32 + after.len() as u32;
+        let mut bytes = vec![0; size as usize];
+        write_u32(&mut bytes, LOG_SIZE_OFFSET, size).unwrap();
+        write_u32(&mut bytes, LOG_LSN_OFFSET, self.lsn).unwrap();
+        write_u32(&mut bytes, LOG_TXN_ID_OFFSET, self.txn_id).unwrap();
+        write_u32(&mut bytes, LOG_UNDONE_LSN_OFFSET, self.undone_lsn).unwrap();
+        write_u32(&mut bytes, LOG_TYPE_OFFSET, log_type).unwrap();
+        write_u32(&mut bytes, LOG_PAGE_ID_OFFSET, rid.page_id).unwrap();
+        write_u32(&mut bytes, LOG_SLOT_INDEX_OFFSET, rid.slot_index).unwrap();
+
+        let mut offset = LOG_IMAGES_OFFSET;
+        for image in [before, after] {
+            write_u32(&mut bytes, offset, image.len() as u32).unwrap();
+            offset += 4;
+            bytes[offset as usize..offset as usize + image.len()].copy_from_slice(image);
+            offset += image.len() as u32;
+        }
+        bytes
+    }
+
+    /// Deserialize a log record from the start of the specified bytes, and return it along with
+    /// its size in bytes. Return None if the bytes don't start with a complete log record.
+    fn deserialize(bytes: &[u8]) -> Option<(Self, u32)> {
+        let size = read_u32(bytes, LOG_SIZE_OFFSET).ok()?;
+        if size < LOG_IMAGES_OFFSET + 8 || size as usize > bytes.len() {
+            return None;
+        }
+        let bytes = &bytes[..size as usize];
+
+        let lsn = read_u32(bytes, LOG_LSN_OFFSET).ok()?;
+        let txn_id = read_u32(bytes, LOG_TXN_ID_OFFSET).ok()?;
+        let undone_lsn = read_u32(bytes, LOG_UNDONE_LSN_OFFSET).ok()?;
+        let rid = RecordId {
+            page_id: read_u32(bytes, LOG_PAGE_ID_OFFSET).ok()?,
+            slot_index: read_u32(bytes, LOG_SLOT_INDEX_OFFSET).ok()?,
+        };
+
+        let mut images = Vec::with_capacity(2);
+        let mut offset = LOG_IMAGES_OFFSET as usize;
+        for _ in 0..2 {
+            let len = read_u32(bytes, offset as u32).ok()? as usize;
+            offset += 4;
+            images.push(bytes.get(offset..offset + len)?.to_vec());
+            offset += len;
+        }
+        let after = images.pop().unwrap();
+        let before = images.pop().unwrap();
+
+        let operation = match read_u32(bytes, LOG_TYPE_OFFSET).ok()? {
+            LOG_INSERT => LogOperation::Insert { rid, after },
+            LOG_UPDATE => LogOperation::Update { rid, before, after },
+            LOG_MARK_DELETE => LogOperation::MarkDelete { rid, before },
+            LOG_APPLY_DELETE => LogOperation::ApplyDelete { rid, before },
+            LOG_ROLLBACK_DELETE => LogOperation::RollbackDelete { rid },
+            LOG_FREE_OVERFLOW => LogOperation::FreeOverflow { rid },
+            LOG_COMMIT => LogOperation::Commit,
+            LOG_ABORT => LogOperation::Abort,
+            LOG_CHECKPOINT => {
+                let mut pages = deserialize_u32s(&after)?;
+                if pages.is_empty() {
+                    return None;
+                }
+                LogOperation::Checkpoint {
+                    redo_lsn: pages.remove(0),
+                    active_txns: deserialize_u32s(&before)?,
+                    dirty_pages: pages,
+                }
+            }
+            _ => return None,
+        };
+        let record = Self {
+            lsn,
+            txn_id,
+            undone_lsn,
+            operation,
+        };
+        Some((record, size))
+    }
+}
+
+/// Serialize a list of 32-bit integers into bytes.
+fn serialize_u32s(values: &[u32]) -> Vec<u8> {
+    let mut bytes = vec![0; values.len() * 4];
+    for (i, value) in values.iter().enumerate() {
+        write_u32(&mut bytes, i as u32 * 4, *value).unwrap();
+    }
+    bytes
+}
+
+/// Deserialize a list of 32-bit integers from bytes. Return None if the bytes can't be evenly
+/// divided into integers.
+fn deserialize_u32s(bytes: &[u8]) -> Option<Vec<u32>> {
+    let chunks = bytes.chunks_exact(4);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+    chunks.map(|chunk| read_u32(chunk, 0).ok()).collect()
+}
+
+/// An operation described by a log record. Operations on a record of a relation page carry the
+/// bytes stored in its slot before and after the operation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LogOperation {
+    /// A record was inserted.
+    Insert { rid: RecordId, after: Vec<u8> },
+
+    /// A record was updated in place. Updates that reallocate the record are logged as the
+    /// deletion of the old record and the insertion of the new record.
+    Update {
+        rid: RecordId,
+        before: Vec<u8>,
+        after: Vec<u8>,
+    },
+
+    /// A record was flagged for deletion.
+    MarkDelete { rid: RecordId, before: Vec<u8> },
+
+    /// A record was removed from its page.
+    ApplyDelete { rid: RecordId, before: Vec<u8> },
+
+    /// The deletion flag of a record was cleared.
+    RollbackDelete { rid: RecordId },
+
+    /// The overflow pages of a deleted record were freed. The pages aren't relation pages, so
+    /// this operation is never redone, and can't be undone since their contents aren't logged.
+    FreeOverflow { rid: RecordId },
+
+    /// The transaction committed.
+    Commit,
+
+    /// The transaction aborted, and all of its operations were undone.
+    Abort,
+
+    /// A checkpoint was taken. Operations logged before the redo LSN are reflected on disk, and
+    /// the listed transactions were active at the time of the checkpoint.
+    Checkpoint {
+        redo_lsn: LsnT,
+        active_txns: Vec<TransactionIdT>,
+        dirty_pages: Vec<PageIdT>,
+    },
+}
+
+impl LogOperation {
+    /// Return the ID of the record that this operation was applied to, or None if the operation
+    /// doesn't modify a relation page.
+    pub fn get_rid(&self) -> Option<RecordId> {
+        match self {
+            LogOperation::Insert { rid, .. }
+            | LogOperation::Update { rid, .. }
+            | LogOperation::MarkDelete { rid, .. }
+            | LogOperation::ApplyDelete { rid, .. }
+            | LogOperation::RollbackDelete { rid } => Some(*rid),
+            LogOperation::FreeOverflow { .. }
+            | LogOperation::Commit
+            | LogOperation::Abort
+            | LogOperation::Checkpoint { .. } => None,
+        }
+    }
+}
+
+/// Custom errors to be used by recovery.
+#[derive(Debug)]
+pub enum RecoveryError {
+    /// Error to be thrown when a page referenced by the log can't be fetched.
+    BufferFailure(BufferError),
+
+    /// Error to be thrown when a logged operation can't be applied to (or undone from) a page,
+    /// which implies that the page or the log is inconsistent.
+    PageFailure(PageError),
+
+    /// Error to be thrown when the deletion of a record stored in overflow pages must be undone,
+    /// since the overflow pages were freed and their contents can't be restored.
+    OverflowFreed(RecordId),
+}
+
+impl From<BufferError> for RecoveryError {
+    fn from(e: BufferError) -> Self {
+        RecoveryError::BufferFailure(e)
+    }
+}
 
-    pub fn undo() {
-        todo!()
+impl From<PageError> for RecoveryError {
+    fn from(e: PageError) -> Self {
+        RecoveryError::PageFailure(e)
     }
 }
@@ -0,0 +1,35 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use jin::buffer::replacement::ReplacerAlgorithm;
+use jin::buffer::BufferManager;
+use jin::catalog::SystemCatalog;
+use jin::constants::{BUFFER_SIZE, DB_FILENAME};
+use jin::disk::DiskManager;
+use jin::repl::Repl;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Open the database file (creating it if it doesn't exist) and run the REPL on stdin. Every
+/// page is flushed to disk on exit.
+fn main() {
+    let disk_manager = match Path::new(DB_FILENAME).exists() {
+        true => DiskManager::open(DB_FILENAME),
+        false => DiskManager::new(DB_FILENAME),
+    };
+    let buffer_manager = Arc::new(BufferManager::new(
+        BUFFER_SIZE,
+        disk_manager,
+        ReplacerAlgorithm::LRU,
+    ));
+    let system_catalog = Arc::new(SystemCatalog::new(buffer_manager.clone()));
+
+    let repl = Repl::new(system_catalog);
+    let stdin = io::stdin();
+    repl.run(stdin.lock(), io::stdout()).unwrap();
+
+    buffer_manager.flush_all_pages().unwrap();
+}
@@ -3,9 +3,14 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
-use crate::constants::{LsnT, PageIdT, PAGE_SIZE};
-use crate::io::{read_u32, write_u32};
+use crate::constants::{
+    LsnT, PageIdT, RecordSlotIdT, RelationIdT, CATALOG_ROOT_ID, MAX_RECORD_SIZE, PAGE_SIZE,
+};
+use crate::io::{read_str32, read_u32, write_str32, write_u32};
 use crate::relation::record::{Record, RecordId};
+use crate::relation::{Attribute, Schema};
+use std::collections::HashMap;
+use std::convert::TryFrom;
 
 /// Type alias for a byte array that represents an arbitrary page on disk.
 pub type PageBytes = [u8; PAGE_SIZE as usize];
@@ -66,6 +71,19 @@ const INVALID_PAGE_ID: u32 = 0;
 /// 0 before using it to index the record on the page.
 const DELETE_MASK: u32 = 1_u32 << 31;
 
+/// The overflow mask is used to mark records whose data does not fit in a single page. Similarly
+/// to the delete mask, the second leftmost bit of the record's size value in the header is set
+/// to 1. Instead of the record's data, the slot then points to a fixed-size overflow stub which
+/// holds the total length of the record and the ID of the first page in a chain of overflow
+/// pages containing the actual record data.
+///
+/// Overflow stub format (number denotes size in bytes):
+/// +------------------+---------------------------+
+/// | RECORD SIZE (4)  | FIRST OVERFLOW PAGE ID (4) |
+/// +------------------+---------------------------+
+const OVERFLOW_MASK: u32 = 1_u32 << 30;
+const OVERFLOW_STUB_SIZE: u32 = 8;
+
 /// An in-memory representation of a database page with slotted-page architecture.
 ///
 /// Contains a header and variable-length records that grow in opposite directions, similarly to
@@ -144,6 +162,11 @@ impl RelationPage {
         write_u32(bytes, NEXT_PAGE_ID_OFFSET, id).unwrap()
     }
 
+    /// Clear the next page ID, making this page the last page.
+    pub fn clear_next_page_id(bytes: &mut PageBytes) {
+        write_u32(bytes, NEXT_PAGE_ID_OFFSET, INVALID_PAGE_ID).unwrap()
+    }
+
     /// Get a pointer to the next free space.
     pub fn get_free_pointer(bytes: &PageBytes) -> u32 {
         read_u32(bytes, FREE_POINTER_OFFSET).unwrap()
@@ -159,23 +182,34 @@ impl RelationPage {
         read_u32(bytes, NUM_RECORDS_OFFSET).unwrap()
     }
 
+    /// Get the number of records contained in the page which haven't been deleted (or flagged
+    /// for deletion). Only the record pointers are read.
+    pub fn get_num_live_records(bytes: &PageBytes) -> u32 {
+        (0..RelationPage::get_num_records(bytes))
+            .filter(|&slot| {
+                let (_, size_addr) = RelationPage::get_ptr_addrs(bytes, slot).unwrap();
+                !RelationPage::is_deleted(read_u32(bytes, size_addr).unwrap())
+            })
+            .count() as u32
+    }
+
     /// Set the number of records contained in the page.
     pub fn set_num_records(bytes: &mut PageBytes, num: u32) {
         write_u32(bytes, NUM_RECORDS_OFFSET, num).unwrap()
     }
 
     /// Get the log sequence number of the page.
-    fn get_lsn(bytes: &PageBytes) -> u32 {
+    pub fn get_lsn(bytes: &PageBytes) -> LsnT {
         read_u32(bytes, LSN_OFFSET).unwrap()
     }
 
     /// Set the log sequence number of the page.
-    fn set_lsn(bytes: &mut PageBytes, lsn: LsnT) {
+    pub fn set_lsn(bytes: &mut PageBytes, lsn: LsnT) {
         write_u32(bytes, LSN_OFFSET, lsn).unwrap()
     }
 
     /// Return the amount of free space left in the page in bytes.
-    fn get_free_space(bytes: &PageBytes) -> u32 {
+    pub fn get_free_space(bytes: &PageBytes) -> u32 {
         let free_ptr = RelationPage::get_free_pointer(bytes) + 1;
         let num_records = RelationPage::get_num_records(bytes);
 
@@ -186,7 +220,88 @@ impl RelationPage {
         }
     }
 
+    /// Verify that the page header is internally consistent. This is a cheap sanity check that
+    /// should be performed before trusting any offsets in a page that was read from disk.
+    ///
+    /// Specifically, check that:
+    /// - the free pointer lies within the page,
+    /// - the slot directory does not overrun the free pointer, and
+    /// - every occupied slot refers to data that lies between the free pointer and the end of
+    ///   the page.
+    pub fn validate(bytes: &PageBytes) -> Result<(), PageError> {
+        let free_ptr = RelationPage::get_free_pointer(bytes) as u64;
+        let num_records = RelationPage::get_num_records(bytes) as u64;
+
+        if free_ptr >= PAGE_SIZE as u64 {
+            return Err(PageError::InvalidHeader);
+        }
+
+        let header = RECORDS_OFFSET as u64 + num_records * RECORD_POINTER_SIZE as u64;
+        if header > free_ptr + 1 {
+            return Err(PageError::InvalidHeader);
+        }
+
+        for slot in 0..num_records as u32 {
+            let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
+            let offset = read_u32(bytes, offset_addr).unwrap() as u64;
+            let size = RelationPage::get_size(read_u32(bytes, size_addr).unwrap()) as u64;
+
+            if size == 0 {
+                continue;
+            }
+            if offset <= free_ptr || offset + size > PAGE_SIZE as u64 {
+                return Err(PageError::InvalidHeader);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify that the record data in the page is tightly packed according to the slot directory.
+    /// This is a more thorough (and more expensive) check than `validate()`, intended for
+    /// debugging the offset adjustments performed by updates and deletes.
+    ///
+    /// Specifically, check that the data of every occupied slot lies between the free pointer
+    /// and the end of the page without overlapping or leaving gaps, and that the free space
+    /// recomputed from the slot directory matches the free space implied by the free pointer.
+    pub fn verify_offsets(bytes: &PageBytes) -> Result<(), PageError> {
+        RelationPage::validate(bytes)?;
+
+        let mut extents = Vec::new();
+        for slot in 0..RelationPage::get_num_records(bytes) {
+            let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
+            let offset = read_u32(bytes, offset_addr).unwrap();
+            let size = RelationPage::get_size(read_u32(bytes, size_addr).unwrap());
+            if size > 0 {
+                extents.push((offset, size));
+            }
+        }
+        extents.sort_unstable();
+
+        // Walk the records from the free pointer to the end of the page.
+        let mut expected_offset = RelationPage::get_free_pointer(bytes) + 1;
+        for (offset, size) in extents.iter() {
+            if *offset != expected_offset {
+                return Err(PageError::InvalidHeader);
+            }
+            expected_offset += size;
+        }
+        if expected_offset != PAGE_SIZE {
+            return Err(PageError::InvalidHeader);
+        }
+
+        let header = RECORDS_OFFSET + RelationPage::get_num_records(bytes) * RECORD_POINTER_SIZE;
+        let data_size: u32 = extents.iter().map(|(_, size)| size).sum();
+        if PAGE_SIZE - header - data_size != RelationPage::get_free_space(bytes) {
+            return Err(PageError::InvalidHeader);
+        }
+
+        Ok(())
+    }
+
     /// Read the record at the specified slot index.
+    /// If the record is stored in overflow pages, then return an error. The caller must reassemble
+    /// the record from the overflow chain referenced by `get_overflow_stub()` instead.
     pub fn read_record(bytes: &PageBytes, slot: u32) -> Result<Record, PageError> {
         let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
         let offset = read_u32(bytes, offset_addr).unwrap() as usize;
@@ -197,6 +312,11 @@ impl RelationPage {
             return Err(PageError::RecordDeleted);
         }
 
+        // Check that the record data is actually stored in this page.
+        if RelationPage::is_overflow(size) {
+            return Err(PageError::RecordOverflow);
+        }
+
         let record_bytes = Vec::from(&bytes[offset..offset + size as usize]);
         let rid = RecordId {
             page_id: RelationPage::get_id(bytes),
@@ -206,10 +326,141 @@ impl RelationPage {
         Ok(Record::from_bytes(record_bytes, rid))
     }
 
+    /// Return the bytes stored in the specified slot, which are either the record data or an
+    /// overflow stub. Records that are flagged for deletion are still considered, and slots whose
+    /// deletion has been committed are empty.
+    pub fn get_record_bytes(bytes: &PageBytes, slot: u32) -> Result<&[u8], PageError> {
+        let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
+        let offset = read_u32(bytes, offset_addr).unwrap() as usize;
+        let size = RelationPage::get_size(read_u32(bytes, size_addr).unwrap()) as usize;
+
+        Ok(&bytes[offset..offset + size])
+    }
+
+    /// Return whether the record at the specified slot is flagged for deletion, but not yet
+    /// committed.
+    pub fn is_flagged(bytes: &PageBytes, slot: u32) -> Result<bool, PageError> {
+        let (_, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
+        let size = read_u32(bytes, size_addr).unwrap();
+        Ok(size & DELETE_MASK != 0)
+    }
+
+    /// Return an iterator over every live record in the page, paired with its slot index.
+    /// Empty slots and records flagged for deletion are skipped. Overflow stubs are skipped as
+    /// well, since their data can't be reassembled from this page alone.
+    pub fn iter_records(bytes: &PageBytes) -> impl Iterator<Item = (RecordSlotIdT, Record)> + '_ {
+        (0..RelationPage::get_num_records(bytes)).filter_map(move |slot| {
+            RelationPage::read_record(bytes, slot)
+                .ok()
+                .map(|record| (slot, record))
+        })
+    }
+
+    /// Return the free space needed to insert a record of the specified length, including its
+    /// size/offset entry. Records longer than MAX_RECORD_SIZE are inserted as overflow stubs.
+    pub fn get_required_space(record_len: u32) -> u32 {
+        match record_len > MAX_RECORD_SIZE {
+            true => OVERFLOW_STUB_SIZE + RECORD_POINTER_SIZE,
+            false => record_len + RECORD_POINTER_SIZE,
+        }
+    }
+
     /// Insert a record in the page and update the header.
     pub fn insert_record(bytes: &mut PageBytes, record: &mut Record) -> Result<(), PageError> {
-        // Bounds-check for record insertion.
-        if record.len() + RECORD_POINTER_SIZE > RelationPage::get_free_space(bytes) {
+        let slot = RelationPage::insert_bytes(bytes, record.as_bytes(), record.len())?;
+
+        // Update record's ID.
+        record.allocate(RelationPage::get_id(bytes), slot);
+
+        Ok(())
+    }
+
+    /// Insert an overflow stub for a record whose data is stored in a chain of overflow pages,
+    /// and return the slot index of the stub.
+    /// `record_size` is the total size of the record and `first_page_id` is the ID of the first
+    /// overflow page in the chain.
+    pub fn insert_overflow_stub(
+        bytes: &mut PageBytes,
+        record_size: u32,
+        first_page_id: PageIdT,
+    ) -> Result<RecordSlotIdT, PageError> {
+        let mut stub = [0; OVERFLOW_STUB_SIZE as usize];
+        write_u32(&mut stub, 0, record_size).unwrap();
+        write_u32(&mut stub, 4, first_page_id).unwrap();
+
+        RelationPage::insert_bytes(bytes, &stub, OVERFLOW_STUB_SIZE | OVERFLOW_MASK)
+    }
+
+    /// Write the given record data into the specified slot, which must either be a slot whose
+    /// deletion has been committed or the next slot to be inserted. This is used by recovery to
+    /// reinsert a record at the slot index recorded in the log.
+    pub fn restore_record(bytes: &mut PageBytes, slot: u32, data: &[u8]) -> Result<(), PageError> {
+        if slot == RelationPage::get_num_records(bytes) {
+            RelationPage::insert_bytes(bytes, data, data.len() as u32)?;
+            return Ok(());
+        }
+
+        let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
+        if read_u32(bytes, size_addr).unwrap() != 0 {
+            return Err(PageError::SlotOccupied);
+        }
+
+        // The slot's size/offset entry already exists, so only the record data needs space.
+        let len = data.len() as u32;
+        if len > RelationPage::get_free_space(bytes) {
+            return Err(PageError::PageOverflow);
+        }
+
+        let free_ptr = RelationPage::get_free_pointer(bytes);
+        let new_free_ptr = free_ptr - len;
+        let start = (new_free_ptr + 1) as usize;
+        bytes[start..start + data.len()].copy_from_slice(data);
+
+        RelationPage::set_free_pointer(bytes, new_free_ptr);
+        write_u32(bytes, offset_addr, new_free_ptr + 1).unwrap();
+        write_u32(bytes, size_addr, len).unwrap();
+
+        Ok(())
+    }
+
+    /// Return the total record size and first overflow page ID of the record at the specified
+    /// slot index, or None if the record is stored directly in this page (or has been removed).
+    /// Records that are flagged for deletion are still considered.
+    pub fn get_overflow_stub(
+        bytes: &PageBytes,
+        slot: u32,
+    ) -> Result<Option<(u32, PageIdT)>, PageError> {
+        let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
+        let offset = read_u32(bytes, offset_addr).unwrap();
+        let size = read_u32(bytes, size_addr).unwrap();
+
+        if !RelationPage::is_overflow(size) {
+            return Ok(None);
+        }
+
+        let record_size = read_u32(bytes, offset).unwrap();
+        let first_page_id = read_u32(bytes, offset + 4).unwrap();
+
+        Ok(Some((record_size, first_page_id)))
+    }
+
+    /// Write the given data into the page as a new slot and return its slot index.
+    /// `size_entry` is the value written to the size field of the slot's header entry, which may
+    /// contain flags in addition to the length of `data`.
+    fn insert_bytes(
+        bytes: &mut PageBytes,
+        data: &[u8],
+        size_entry: u32,
+    ) -> Result<RecordSlotIdT, PageError> {
+        let len = data.len() as u32;
+
+        // Bounds-check for record insertion. A record that exactly fills the remaining free
+        // space (including its new size/offset entry) is allowed.
+        let required = match len.checked_add(RECORD_POINTER_SIZE) {
+            Some(required) => required,
+            None => return Err(PageError::PageOverflow),
+        };
+        if required > RelationPage::get_free_space(bytes) {
             return Err(PageError::PageOverflow);
         }
 
@@ -218,27 +469,28 @@ impl RelationPage {
         let offset_addr = RECORDS_OFFSET + num_records * RECORD_POINTER_SIZE;
         let size_addr = offset_addr + 4;
 
+        // Calculate the new free pointer, guarding against wrap-around in case the header and
+        // free pointer are inconsistent.
         let free_ptr = RelationPage::get_free_pointer(bytes);
-        let new_free_ptr = free_ptr - record.len() as u32;
+        let new_free_ptr = match free_ptr.checked_sub(len) {
+            Some(ptr) if ptr + 1 >= offset_addr + RECORD_POINTER_SIZE => ptr,
+            _ => return Err(PageError::PageOverflow),
+        };
 
         // Write record data to allocated space.
         let start = (new_free_ptr + 1) as usize;
         let end = (free_ptr + 1) as usize;
-        let record_data = record.as_bytes();
         for i in start..end {
-            bytes[i] = record_data[i - start];
+            bytes[i] = data[i - start];
         }
 
         // Update header.
         RelationPage::set_free_pointer(bytes, new_free_ptr);
         RelationPage::set_num_records(bytes, num_records + 1);
         write_u32(bytes, offset_addr, new_free_ptr + 1).unwrap();
-        write_u32(bytes, size_addr, record_data.len() as u32).unwrap();
-
-        // Update record's ID.
-        record.allocate(RelationPage::get_id(bytes), num_records);
+        write_u32(bytes, size_addr, size_entry).unwrap();
 
-        Ok(())
+        Ok(num_records)
     }
 
     /// Update the record at the specified slot index. If the page does not have enough space to
@@ -290,26 +542,50 @@ impl RelationPage {
         let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
         let offset = read_u32(bytes, offset_addr).unwrap() as usize;
         let old_size = read_u32(bytes, size_addr).unwrap();
-        let new_size = new_record.size();
+        let new_size = new_record.len();
+        debug_assert_eq!(new_size as usize, new_record.as_bytes().len());
 
         // Check that the record has not been deleted.
         if RelationPage::is_deleted(old_size) {
             return Err(PageError::RecordDeleted);
         }
 
+        // Records stored in overflow pages cannot be updated in place.
+        if RelationPage::is_overflow(old_size) {
+            return Err(PageError::RecordOverflow);
+        }
+
         // Check that there is enough space to insert the updated record.
         // If there is not enough space, then the caller must delete-then-insert instead.
         if RelationPage::get_free_space(bytes) + old_size < new_size {
             return Err(PageError::PageOverflow);
         }
 
-        // Shift over bytes using a temporary buffer.
+        // Calculate the shifted positions with checked arithmetic, so that an inconsistent header
+        // results in an overflow error rather than an out-of-bounds write.
         let free_ptr = RelationPage::get_free_pointer(bytes);
-
+        let header = RECORDS_OFFSET + RelationPage::get_num_records(bytes) * RECORD_POINTER_SIZE;
+        let shift = || {
+            let end = (offset as u32)
+                .checked_add(old_size)
+                .filter(|&end| end <= PAGE_SIZE)?;
+            let dst = free_ptr.checked_add(old_size)?.checked_sub(new_size)?;
+            let cnt = (offset as u32).checked_sub(free_ptr)?;
+            let new_offset = end.checked_sub(new_size)?;
+            Some((dst, cnt, new_offset))
+        };
+        let (dst, cnt, new_offset) = match shift() {
+            Some((dst, cnt, new_offset)) if dst + 1 >= header => {
+                (dst as usize, cnt as usize, new_offset as usize)
+            }
+            _ => return Err(PageError::PageOverflow),
+        };
         let src = free_ptr as usize;
-        let dst = (free_ptr + old_size - new_size) as usize;
-        let cnt = offset - free_ptr as usize;
+        assert!(dst + cnt <= PAGE_SIZE as usize);
+        assert!(src + cnt <= PAGE_SIZE as usize);
+        assert!(new_offset + new_size as usize <= PAGE_SIZE as usize);
 
+        // Shift over bytes using a temporary buffer.
         let mut buf = vec![0; cnt];
         for i in 0..cnt {
             buf[i] = bytes[src + i];
@@ -319,7 +595,6 @@ impl RelationPage {
         }
 
         // Write update to newly adjusted space.
-        let new_offset = (offset as u32 + old_size - new_size) as usize;
         let new_bytes = new_record.as_bytes();
         for i in 0..new_size as usize {
             bytes[new_offset + i] = new_bytes[i];
@@ -362,6 +637,25 @@ impl RelationPage {
         Ok(())
     }
 
+    /// Unflag the record at the specified slot index for deletion. Return an error if the
+    /// deletion of the record has already been committed. Unflagging a record that isn't flagged
+    /// has no effect.
+    pub fn rollback_delete_record(bytes: &mut PageBytes, slot: u32) -> Result<(), PageError> {
+        let (_, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
+
+        let size = read_u32(bytes, size_addr).unwrap();
+
+        // Check that the deletion has not already been committed.
+        if size == 0 {
+            return Err(PageError::RecordDeleted);
+        }
+
+        let new_size = RelationPage::unset_delete_bit(size);
+        write_u32(bytes, size_addr, new_size).unwrap();
+
+        Ok(())
+    }
+
     /// Delete the record at the specified slot index.
     /// If the record has been flagged for deletion, then we are committing the deletion and
     /// actually removing the record from the page.
@@ -391,13 +685,11 @@ impl RelationPage {
     pub fn commit_delete_record(bytes: &mut PageBytes, slot: u32) -> Result<(), PageError> {
         let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot)?;
         let offset = read_u32(bytes, offset_addr).unwrap();
-        let mut size = read_u32(bytes, size_addr).unwrap();
+        let size = read_u32(bytes, size_addr).unwrap();
 
-        // If the record is flagged for deletion, we obtain the correct record size before
-        // proceeding.
-        if RelationPage::is_deleted(size) {
-            size = RelationPage::unset_delete_bit(size);
-        }
+        // If the record is flagged for deletion (or is an overflow stub), we obtain the correct
+        // record size before proceeding.
+        let size = RelationPage::get_size(size);
 
         // Shift over bytes using a temporary buffer.
         let free_ptr = RelationPage::get_free_pointer(bytes);
@@ -433,6 +725,57 @@ impl RelationPage {
         Ok(())
     }
 
+    /// Compact the page by removing the slots of records whose deletion has been committed.
+    ///
+    /// Committed deletes leave behind an empty (offset = 0, size = 0) entry in the header so that
+    /// the slot indices of the remaining records stay stable. Over time, these empty entries
+    /// accumulate and waste header space. Compaction rebuilds the slot directory with only the
+    /// remaining records (including records that are flagged, but not yet committed, for
+    /// deletion) and re-packs their data against the end of the page.
+    ///
+    /// Since slot indices change, a mapping of old slot indices to new slot indices is returned
+    /// so that the caller can update any record IDs that point into this page.
+    pub fn compact(bytes: &mut PageBytes) -> HashMap<RecordSlotIdT, RecordSlotIdT> {
+        // Copy out every remaining record along with its (possibly flagged) size entry.
+        let mut records = Vec::new();
+        for slot in 0..RelationPage::get_num_records(bytes) {
+            let (offset_addr, size_addr) = RelationPage::get_ptr_addrs(bytes, slot).unwrap();
+            let offset = read_u32(bytes, offset_addr).unwrap() as usize;
+            let raw_size = read_u32(bytes, size_addr).unwrap();
+            if raw_size == 0 {
+                continue;
+            }
+            let size = RelationPage::get_size(raw_size) as usize;
+            records.push((slot, raw_size, Vec::from(&bytes[offset..offset + size])));
+        }
+
+        // Clear the slot directory and record data, then re-insert the remaining records.
+        for byte in bytes[RECORDS_OFFSET as usize..].iter_mut() {
+            *byte = 0;
+        }
+        let mut free_ptr = PAGE_SIZE - 1;
+        let mut mapping = HashMap::with_capacity(records.len());
+
+        for (new_slot, (old_slot, raw_size, data)) in records.iter().enumerate() {
+            let new_slot = new_slot as u32;
+            free_ptr -= data.len() as u32;
+
+            let start = (free_ptr + 1) as usize;
+            bytes[start..start + data.len()].copy_from_slice(data);
+
+            let offset_addr = RECORDS_OFFSET + new_slot * RECORD_POINTER_SIZE;
+            write_u32(bytes, offset_addr, free_ptr + 1).unwrap();
+            write_u32(bytes, offset_addr + 4, *raw_size).unwrap();
+
+            mapping.insert(*old_slot, new_slot);
+        }
+
+        RelationPage::set_free_pointer(bytes, free_ptr);
+        RelationPage::set_num_records(bytes, records.len() as u32);
+
+        mapping
+    }
+
     /// Return true if the specified record is empty or flagged for deletion, false otherwise.
     fn is_deleted(record_size: u32) -> bool {
         record_size & DELETE_MASK != 0 || record_size == 0
@@ -448,6 +791,16 @@ impl RelationPage {
         record_size & !DELETE_MASK
     }
 
+    /// Return true if the specified record is an overflow stub, false otherwise.
+    fn is_overflow(record_size: u32) -> bool {
+        record_size & OVERFLOW_MASK != 0
+    }
+
+    /// Return the number of bytes occupied in the page by a given record, ignoring any flags.
+    fn get_size(record_size: u32) -> u32 {
+        record_size & !(DELETE_MASK | OVERFLOW_MASK)
+    }
+
     /// Return the byte array addresses of the offset and size at a given slot index.
     /// Return an error if the slot index is out of bounds.
     #[inline]
@@ -466,9 +819,582 @@ impl RelationPage {
     }
 }
 
-/// An in-memory representation of a database for an index. The index contains
+/// Constants for overflow page header.
+const OVERFLOW_NEXT_PAGE_ID_OFFSET: u32 = 4;
+const OVERFLOW_DATA_SIZE_OFFSET: u32 = 8;
+const OVERFLOW_DATA_OFFSET: u32 = 12;
+
+/// The maximum number of record bytes that can be stored in a single overflow page.
+pub const OVERFLOW_PAGE_CAPACITY: u32 = PAGE_SIZE - OVERFLOW_DATA_OFFSET;
+
+/// An in-memory representation of a page that holds a portion of a record that is too large to
+/// fit in a single relation page. Overflow pages are connected together as a singly linked list,
+/// which is referenced by an overflow stub in a relation page.
+///
+/// Data format (number denotes size in bytes):
+/// +--------------+------------------+---------------+------------------------+
+/// |  PAGE ID (4) | NEXT PAGE ID (4) | DATA SIZE (4) |  RECORD DATA (...)     |
+/// +--------------+------------------+---------------+------------------------+
+pub struct OverflowPage;
+
+impl OverflowPage {
+    /// Initialize an overflow page with a chunk of record data.
+    /// Assumes that `bytes` is a newly initialized page byte array with its page ID set.
+    /// Return an error if the data does not fit in a single overflow page.
+    pub fn init(bytes: &mut PageBytes, data: &[u8]) -> Result<(), PageError> {
+        if data.len() as u32 > OVERFLOW_PAGE_CAPACITY {
+            return Err(PageError::PageOverflow);
+        }
+        let start = OVERFLOW_DATA_OFFSET as usize;
+        bytes[start..start + data.len()].copy_from_slice(data);
+        write_u32(bytes, OVERFLOW_DATA_SIZE_OFFSET, data.len() as u32).unwrap();
+        Ok(())
+    }
+
+    /// Get the next page ID in the overflow chain.
+    pub fn get_next_page_id(bytes: &PageBytes) -> Option<PageIdT> {
+        let pid = read_u32(bytes, OVERFLOW_NEXT_PAGE_ID_OFFSET).unwrap();
+        match pid == INVALID_PAGE_ID {
+            true => None,
+            false => Some(pid),
+        }
+    }
+
+    /// Set the next page ID in the overflow chain.
+    pub fn set_next_page_id(bytes: &mut PageBytes, id: PageIdT) {
+        write_u32(bytes, OVERFLOW_NEXT_PAGE_ID_OFFSET, id).unwrap()
+    }
+
+    /// Return the chunk of record data contained in this page.
+    pub fn get_data(bytes: &PageBytes) -> &[u8] {
+        let size = read_u32(bytes, OVERFLOW_DATA_SIZE_OFFSET).unwrap();
+        let start = OVERFLOW_DATA_OFFSET as usize;
+        &bytes[start..start + size as usize]
+    }
+}
+
+/// Constants for dictionary page header.
+const DICTIONARY_NUM_ENTRIES_OFFSET: u32 = 4;
+const DICTIONARY_NEXT_PAGE_ID_OFFSET: u32 = 8;
+const DICTIONARY_ENTRIES_OFFSET: u32 = 12;
+const DICTIONARY_NAME_SIZE: u32 = 32;
+const DICTIONARY_ENTRY_SIZE: u32 = DICTIONARY_NAME_SIZE + 8;
+
+/// The kind of database object recorded by a dictionary entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DictionaryEntryKind {
+    /// A relation, whose root page is its schema page.
+    Relation = 0,
+
+    /// An index, whose root page is the root page of the index.
+    Index = 1,
+}
+
+impl TryFrom<u32> for DictionaryEntryKind {
+    type Error = PageError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(DictionaryEntryKind::Relation),
+            1 => Ok(DictionaryEntryKind::Index),
+            _ => Err(PageError::InvalidHeader),
+        }
+    }
+}
+
+/// An in-memory representation of a dictionary page, which maps the names of relations and
+/// indexes to the ID of the root page of their metadata (such as a schema page). The first
+/// dictionary page is located at CATALOG_ROOT_ID, and is chained to additional dictionary pages
+/// once it's full.
+///
+/// Data format (number denotes size in bytes):
+/// +-------------+-----------------+------------------+-----------+------------------+----------+
+/// | PAGE ID (4) | NUM ENTRIES (4) | NEXT PAGE ID (4) | NAME (32) | ROOT PAGE ID (4) | KIND (4) |
+/// +-------------+-----------------+------------------+-----------+------------------+----------+
+///
+/// The name, root page ID, and kind are repeated for each entry.
+///
+/// Names are stored as zero-padded UTF-8 strings of at most 32 bytes. Relations and indexes
+/// share a single namespace.
+pub struct DictionaryPage;
+
+impl DictionaryPage {
+    /// Get the number of entries in the dictionary page.
+    pub fn get_num_entries(bytes: &PageBytes) -> u32 {
+        read_u32(bytes, DICTIONARY_NUM_ENTRIES_OFFSET).unwrap()
+    }
+
+    /// Set the number of entries in the dictionary page.
+    fn set_num_entries(bytes: &mut PageBytes, num: u32) {
+        write_u32(bytes, DICTIONARY_NUM_ENTRIES_OFFSET, num).unwrap()
+    }
+
+    /// Get the ID of the next dictionary page in the chain. The first dictionary page is never
+    /// part of another page's chain, so its ID marks the end of the chain. This allows a page of
+    /// zeros to be read as an empty dictionary page.
+    pub fn get_next_page_id(bytes: &PageBytes) -> Option<PageIdT> {
+        let pid = read_u32(bytes, DICTIONARY_NEXT_PAGE_ID_OFFSET).unwrap();
+        match pid == CATALOG_ROOT_ID {
+            true => None,
+            false => Some(pid),
+        }
+    }
+
+    /// Set the ID of the next dictionary page in the chain.
+    pub fn set_next_page_id(bytes: &mut PageBytes, id: PageIdT) {
+        write_u32(bytes, DICTIONARY_NEXT_PAGE_ID_OFFSET, id).unwrap()
+    }
+
+    /// Get the root page ID of the relation or index with the specified name.
+    pub fn get(bytes: &PageBytes, name: &str) -> Option<PageIdT> {
+        let idx = DictionaryPage::find(bytes, name)?;
+        let addr = DictionaryPage::get_entry_addr(idx) + DICTIONARY_NAME_SIZE;
+        Some(read_u32(bytes, addr).unwrap())
+    }
+
+    /// Return the name, root page ID, and kind of the entry at the specified index, or None if
+    /// the index is out of bounds or the entry has an unknown kind. Entries are ordered by when
+    /// they were first set.
+    pub fn get_entry(
+        bytes: &PageBytes,
+        idx: u32,
+    ) -> Option<(String, PageIdT, DictionaryEntryKind)> {
+        if idx >= DictionaryPage::get_num_entries(bytes) {
+            return None;
+        }
+        let addr = DictionaryPage::get_entry_addr(idx);
+        let name = read_str32(bytes, addr).unwrap();
+        let root_id = read_u32(bytes, addr + DICTIONARY_NAME_SIZE).unwrap();
+        let kind = read_u32(bytes, addr + DICTIONARY_NAME_SIZE + 4).unwrap();
+        let kind = DictionaryEntryKind::try_from(kind).ok()?;
+        Some((name, root_id, kind))
+    }
+
+    /// Set the root page ID and kind of the entry with the specified name. If an entry with the
+    /// name already exists, it is overwritten in place. Otherwise, a new entry is appended.
+    /// Return an error if the name is too long or the page is full.
+    pub fn set(
+        bytes: &mut PageBytes,
+        name: &str,
+        root_id: PageIdT,
+        kind: DictionaryEntryKind,
+    ) -> Result<(), PageError> {
+        if name.len() as u32 > DICTIONARY_NAME_SIZE {
+            return Err(PageError::NameTooLong);
+        }
+
+        let addr = match DictionaryPage::find(bytes, name) {
+            Some(idx) => DictionaryPage::get_entry_addr(idx),
+            None => {
+                let num_entries = DictionaryPage::get_num_entries(bytes);
+                let addr = DictionaryPage::get_entry_addr(num_entries);
+                if addr + DICTIONARY_ENTRY_SIZE > PAGE_SIZE {
+                    return Err(PageError::PageOverflow);
+                }
+                write_str32(bytes, addr, name).unwrap();
+                DictionaryPage::set_num_entries(bytes, num_entries + 1);
+                addr
+            }
+        };
+
+        write_u32(bytes, addr + DICTIONARY_NAME_SIZE, root_id).unwrap();
+        write_u32(bytes, addr + DICTIONARY_NAME_SIZE + 4, kind as u32).unwrap();
+
+        Ok(())
+    }
+
+    /// Remove the entry with the specified name. Subsequent entries are shifted left to fill the
+    /// gap, preserving their order. Return whether an entry was removed.
+    pub fn remove(bytes: &mut PageBytes, name: &str) -> bool {
+        let idx = match DictionaryPage::find(bytes, name) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        let num_entries = DictionaryPage::get_num_entries(bytes);
+
+        let start = DictionaryPage::get_entry_addr(idx) as usize;
+        let end = DictionaryPage::get_entry_addr(num_entries) as usize;
+        let entry_size = DICTIONARY_ENTRY_SIZE as usize;
+
+        // Shift subsequent entries left and zero out the now-unused tail entry.
+        bytes.copy_within(start + entry_size..end, start);
+        bytes[end - entry_size..end].fill(0);
+        DictionaryPage::set_num_entries(bytes, num_entries - 1);
+
+        true
+    }
+
+    /// Return the index of the entry with the specified name, if it exists.
+    fn find(bytes: &PageBytes, name: &str) -> Option<u32> {
+        (0..DictionaryPage::get_num_entries(bytes)).find(|&idx| {
+            let addr = DictionaryPage::get_entry_addr(idx);
+            read_str32(bytes, addr).is_ok_and(|entry| entry == name)
+        })
+    }
+
+    /// Return the address of the entry at the specified index.
+    fn get_entry_addr(idx: u32) -> u32 {
+        DICTIONARY_ENTRIES_OFFSET + idx * DICTIONARY_ENTRY_SIZE
+    }
+}
+
+/// Constants for index page header.
+const INDEX_LEAF_FLAG_OFFSET: u32 = 4;
+const INDEX_NUM_KEYS_OFFSET: u32 = 8;
+const INDEX_KEY_SIZE_OFFSET: u32 = 12;
+const INDEX_NEXT_LEAF_ID_OFFSET: u32 = 16;
+const INDEX_ENTRIES_OFFSET: u32 = 20;
+const INDEX_CHILD_ID_SIZE: u32 = 4;
+
+/// An in-memory representation of a B+ tree node page for an index. Every key in an index page
+/// has the same fixed size, which is stored in the header.
+///
+/// Leaf nodes contain sorted keys, and the ID of the next leaf node so that leaves can be
+/// scanned in key order. Internal nodes contain sorted keys interleaved with child page IDs,
+/// where the keys in the subtree of CHILD i are less than KEY i, and the keys in the subtree of
+/// CHILD i+1 are greater than or equal to KEY i.
+///
+/// Header format (number denotes size in bytes):
+/// +--------------+-------------+--------------+--------------+-----------------------+
+/// |  PAGE ID (4) | IS LEAF (4) | NUM KEYS (4) | KEY SIZE (4) | NEXT LEAF PAGE ID (4) |
+/// +--------------+-------------+--------------+--------------+-----------------------+
+///
+/// Leaf entries:
+/// +---------+---------+-----+
+/// |  KEY 0  |  KEY 1  | ... |
+/// +---------+---------+-----+
+///
+/// Internal entries:
+/// +-----------+---------+-----------+---------+-----+-----------+
+/// |  CHILD 0  |  KEY 0  |  CHILD 1  |  KEY 1  | ... |  CHILD N  |
+/// +-----------+---------+-----------+---------+-----+-----------+
 pub struct IndexPage;
 
+impl IndexPage {
+    /// Return whether the index page is a leaf node.
+    pub fn is_leaf(bytes: &PageBytes) -> bool {
+        read_u32(bytes, INDEX_LEAF_FLAG_OFFSET).unwrap() == 1
+    }
+
+    /// Get the number of keys in the index page.
+    pub fn get_num_keys(bytes: &PageBytes) -> u32 {
+        read_u32(bytes, INDEX_NUM_KEYS_OFFSET).unwrap()
+    }
+
+    /// Get the size of each key in the index page.
+    pub fn get_key_size(bytes: &PageBytes) -> u32 {
+        read_u32(bytes, INDEX_KEY_SIZE_OFFSET).unwrap()
+    }
+
+    /// Get the ID of the next leaf page. Only meaningful for leaf nodes.
+    pub fn get_next_leaf_id(bytes: &PageBytes) -> Option<PageIdT> {
+        let pid = read_u32(bytes, INDEX_NEXT_LEAF_ID_OFFSET).unwrap();
+        match pid == INVALID_PAGE_ID {
+            true => None,
+            false => Some(pid),
+        }
+    }
+
+    /// Get the key at the specified index.
+    pub fn get_key(bytes: &PageBytes, idx: u32) -> &[u8] {
+        let key_size = IndexPage::get_key_size(bytes);
+        let addr = match IndexPage::is_leaf(bytes) {
+            true => INDEX_ENTRIES_OFFSET + idx * key_size,
+            false => {
+                INDEX_ENTRIES_OFFSET + idx * (key_size + INDEX_CHILD_ID_SIZE) + INDEX_CHILD_ID_SIZE
+            }
+        } as usize;
+        &bytes[addr..addr + key_size as usize]
+    }
+
+    /// Get the child page ID at the specified index. Only meaningful for internal nodes.
+    pub fn get_child_id(bytes: &PageBytes, idx: u32) -> PageIdT {
+        let key_size = IndexPage::get_key_size(bytes);
+        let addr = INDEX_ENTRIES_OFFSET + idx * (key_size + INDEX_CHILD_ID_SIZE);
+        read_u32(bytes, addr).unwrap()
+    }
+
+    /// Return the maximum number of keys of the specified size that fit in a single node.
+    pub fn get_max_keys(key_size: u32, is_leaf: bool) -> u32 {
+        match is_leaf {
+            true => (PAGE_SIZE - INDEX_ENTRIES_OFFSET) / key_size,
+            false => {
+                (PAGE_SIZE - INDEX_ENTRIES_OFFSET - INDEX_CHILD_ID_SIZE)
+                    / (key_size + INDEX_CHILD_ID_SIZE)
+            }
+        }
+    }
+
+    /// Overwrite the contents of the index page with a leaf node. Return an error if the keys
+    /// don't fit in the page.
+    pub fn write_leaf(
+        bytes: &mut PageBytes,
+        key_size: u32,
+        keys: &[Vec<u8>],
+        next_leaf_id: Option<PageIdT>,
+    ) -> Result<(), PageError> {
+        if keys.len() as u32 > IndexPage::get_max_keys(key_size, true) {
+            return Err(PageError::PageOverflow);
+        }
+        IndexPage::write_header(bytes, true, keys.len() as u32, key_size);
+        write_u32(
+            bytes,
+            INDEX_NEXT_LEAF_ID_OFFSET,
+            next_leaf_id.unwrap_or(INVALID_PAGE_ID),
+        )
+        .unwrap();
+
+        let mut addr = INDEX_ENTRIES_OFFSET as usize;
+        for key in keys {
+            bytes[addr..addr + key_size as usize].copy_from_slice(key);
+            addr += key_size as usize;
+        }
+        Ok(())
+    }
+
+    /// Overwrite the contents of the index page with an internal node. There must be exactly
+    /// one more child than there are keys. Return an error if the keys don't fit in the page.
+    pub fn write_internal(
+        bytes: &mut PageBytes,
+        key_size: u32,
+        keys: &[Vec<u8>],
+        children: &[PageIdT],
+    ) -> Result<(), PageError> {
+        if keys.len() as u32 > IndexPage::get_max_keys(key_size, false) {
+            return Err(PageError::PageOverflow);
+        }
+        assert_eq!(keys.len() + 1, children.len());
+        IndexPage::write_header(bytes, false, keys.len() as u32, key_size);
+
+        let mut addr = INDEX_ENTRIES_OFFSET;
+        for (i, child_id) in children.iter().enumerate() {
+            write_u32(bytes, addr, *child_id).unwrap();
+            addr += INDEX_CHILD_ID_SIZE;
+            if let Some(key) = keys.get(i) {
+                bytes[addr as usize..(addr + key_size) as usize].copy_from_slice(key);
+                addr += key_size;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the header of the index page and clear any existing entries.
+    fn write_header(bytes: &mut PageBytes, is_leaf: bool, num_keys: u32, key_size: u32) {
+        bytes[INDEX_LEAF_FLAG_OFFSET as usize..].fill(0);
+        write_u32(bytes, INDEX_LEAF_FLAG_OFFSET, is_leaf as u32).unwrap();
+        write_u32(bytes, INDEX_NUM_KEYS_OFFSET, num_keys).unwrap();
+        write_u32(bytes, INDEX_KEY_SIZE_OFFSET, key_size).unwrap();
+    }
+}
+
+/// Constants for hash directory page header.
+const HASH_GLOBAL_DEPTH_OFFSET: u32 = 4;
+const HASH_BUCKET_IDS_OFFSET: u32 = 8;
+const HASH_BUCKET_ID_SIZE: u32 = 4;
+
+/// Maximum global depth of a hash directory page, such that its bucket page IDs fit in a
+/// single page.
+pub const HASH_MAX_GLOBAL_DEPTH: u32 = 10;
+
+/// An in-memory representation of the directory page of an extendible hash index. The directory
+/// contains 2^(GLOBAL DEPTH) bucket page IDs, and a key whose hash is H is stored in the bucket at
+/// index H mod 2^(GLOBAL DEPTH). Multiple directory slots may point to the same bucket.
+///
+/// Header format (number denotes size in bytes):
+/// +--------------+------------------+
+/// |  PAGE ID (4) | GLOBAL DEPTH (4) |
+/// +--------------+------------------+
+///
+/// Directory entries:
+/// +---------------------+---------------------+-----+
+/// |  BUCKET PAGE ID (4) |  BUCKET PAGE ID (4) | ... |
+/// +---------------------+---------------------+-----+
+pub struct HashDirectoryPage;
+
+impl HashDirectoryPage {
+    /// Get the global depth of the directory.
+    pub fn get_global_depth(bytes: &PageBytes) -> u32 {
+        read_u32(bytes, HASH_GLOBAL_DEPTH_OFFSET).unwrap()
+    }
+
+    /// Get the bucket page ID at the specified directory index.
+    pub fn get_bucket_id(bytes: &PageBytes, idx: u32) -> PageIdT {
+        read_u32(bytes, HASH_BUCKET_IDS_OFFSET + idx * HASH_BUCKET_ID_SIZE).unwrap()
+    }
+
+    /// Overwrite the contents of the directory page. There must be exactly 2^(GLOBAL DEPTH)
+    /// bucket page IDs. Return an error if the global depth exceeds the maximum.
+    pub fn write(
+        bytes: &mut PageBytes,
+        global_depth: u32,
+        bucket_ids: &[PageIdT],
+    ) -> Result<(), PageError> {
+        if global_depth > HASH_MAX_GLOBAL_DEPTH {
+            return Err(PageError::PageOverflow);
+        }
+        assert_eq!(bucket_ids.len(), 1 << global_depth);
+
+        bytes[HASH_GLOBAL_DEPTH_OFFSET as usize..].fill(0);
+        write_u32(bytes, HASH_GLOBAL_DEPTH_OFFSET, global_depth).unwrap();
+
+        let mut addr = HASH_BUCKET_IDS_OFFSET;
+        for bucket_id in bucket_ids {
+            write_u32(bytes, addr, *bucket_id).unwrap();
+            addr += HASH_BUCKET_ID_SIZE;
+        }
+        Ok(())
+    }
+}
+
+/// Constants for hash bucket page header.
+const HASH_LOCAL_DEPTH_OFFSET: u32 = 4;
+const HASH_NUM_ENTRIES_OFFSET: u32 = 8;
+const HASH_ENTRY_SIZE_OFFSET: u32 = 12;
+const HASH_ENTRIES_OFFSET: u32 = 16;
+
+/// An in-memory representation of a bucket page of an extendible hash index. Every entry in a
+/// bucket page has the same fixed size, which is stored in the header. Entries are not ordered.
+///
+/// Header format (number denotes size in bytes):
+/// +--------------+-----------------+-----------------+----------------+
+/// |  PAGE ID (4) | LOCAL DEPTH (4) | NUM ENTRIES (4) | ENTRY SIZE (4) |
+/// +--------------+-----------------+-----------------+----------------+
+///
+/// Bucket entries:
+/// +-----------+-----------+-----+
+/// |  ENTRY 0  |  ENTRY 1  | ... |
+/// +-----------+-----------+-----+
+pub struct HashBucketPage;
+
+impl HashBucketPage {
+    /// Get the local depth of the bucket.
+    pub fn get_local_depth(bytes: &PageBytes) -> u32 {
+        read_u32(bytes, HASH_LOCAL_DEPTH_OFFSET).unwrap()
+    }
+
+    /// Get the number of entries in the bucket.
+    pub fn get_num_entries(bytes: &PageBytes) -> u32 {
+        read_u32(bytes, HASH_NUM_ENTRIES_OFFSET).unwrap()
+    }
+
+    /// Get the size of each entry in the bucket.
+    pub fn get_entry_size(bytes: &PageBytes) -> u32 {
+        read_u32(bytes, HASH_ENTRY_SIZE_OFFSET).unwrap()
+    }
+
+    /// Get the entry at the specified index.
+    pub fn get_entry(bytes: &PageBytes, idx: u32) -> &[u8] {
+        let entry_size = HashBucketPage::get_entry_size(bytes);
+        let addr = (HASH_ENTRIES_OFFSET + idx * entry_size) as usize;
+        &bytes[addr..addr + entry_size as usize]
+    }
+
+    /// Return the maximum number of entries of the specified size that fit in a single bucket.
+    pub fn get_max_entries(entry_size: u32) -> u32 {
+        (PAGE_SIZE - HASH_ENTRIES_OFFSET) / entry_size
+    }
+
+    /// Overwrite the contents of the bucket page. Return an error if the entries don't fit in
+    /// the page.
+    pub fn write(
+        bytes: &mut PageBytes,
+        local_depth: u32,
+        entry_size: u32,
+        entries: &[Vec<u8>],
+    ) -> Result<(), PageError> {
+        if entries.len() as u32 > HashBucketPage::get_max_entries(entry_size) {
+            return Err(PageError::PageOverflow);
+        }
+
+        bytes[HASH_LOCAL_DEPTH_OFFSET as usize..].fill(0);
+        write_u32(bytes, HASH_LOCAL_DEPTH_OFFSET, local_depth).unwrap();
+        write_u32(bytes, HASH_NUM_ENTRIES_OFFSET, entries.len() as u32).unwrap();
+        write_u32(bytes, HASH_ENTRY_SIZE_OFFSET, entry_size).unwrap();
+
+        let mut addr = HASH_ENTRIES_OFFSET as usize;
+        for entry in entries {
+            bytes[addr..addr + entry_size as usize].copy_from_slice(entry);
+            addr += entry_size as usize;
+        }
+        Ok(())
+    }
+}
+
+/// Constants for schema page header.
+const SCHEMA_RELATION_ID_OFFSET: u32 = 4;
+const SCHEMA_HEAP_ROOT_ID_OFFSET: u32 = 8;
+const SCHEMA_LENGTH_OFFSET: u32 = 12;
+const SCHEMA_DATA_OFFSET: u32 = 16;
+
+/// Maximum length in bytes of an attribute name in a schema page, which is the same as the
+/// maximum length of a relation name.
+const SCHEMA_ATTR_NAME_SIZE: u32 = 32;
+
+/// An in-memory representation of a schema page, which stores the metadata of a single relation
+/// so that it can be reconstructed when the database is reopened.
+///
+/// Header format (number denotes size in bytes):
+/// +--------------+-----------------+------------------+-------------------+
+/// |  PAGE ID (4) | RELATION ID (4) | HEAP ROOT ID (4) | SCHEMA LENGTH (4) |
+/// +--------------+-----------------+------------------+-------------------+
+///
+/// The header is followed by the schema of the relation, in the format of `Schema::serialize`.
+pub struct SchemaPage;
+
+impl SchemaPage {
+    /// Get the ID of the relation described by this page.
+    pub fn get_relation_id(bytes: &PageBytes) -> RelationIdT {
+        read_u32(bytes, SCHEMA_RELATION_ID_OFFSET).unwrap()
+    }
+
+    /// Get the ID of the root page of the heap of the relation.
+    pub fn get_heap_root_id(bytes: &PageBytes) -> PageIdT {
+        read_u32(bytes, SCHEMA_HEAP_ROOT_ID_OFFSET).unwrap()
+    }
+
+    /// Read the attributes of the relation's schema, in column order. Return an error if the
+    /// schema can't be deserialized.
+    pub fn get_attributes(bytes: &PageBytes) -> Result<Vec<Attribute>, PageError> {
+        let len = read_u32(bytes, SCHEMA_LENGTH_OFFSET).unwrap();
+        let data = bytes
+            .get(SCHEMA_DATA_OFFSET as usize..)
+            .and_then(|rest| rest.get(..len as usize))
+            .ok_or(PageError::InvalidHeader)?;
+
+        let schema = Schema::deserialize(data).map_err(|_| PageError::InvalidHeader)?;
+        Ok(schema.get_attributes().to_vec())
+    }
+
+    /// Overwrite the contents of the schema page. Return an error if an attribute name is too
+    /// long or the attributes don't fit in the page.
+    pub fn write(
+        bytes: &mut PageBytes,
+        relation_id: RelationIdT,
+        heap_root_id: PageIdT,
+        attrs: &[Attribute],
+    ) -> Result<(), PageError> {
+        if attrs
+            .iter()
+            .any(|attr| attr.get_name().len() as u32 > SCHEMA_ATTR_NAME_SIZE)
+        {
+            return Err(PageError::NameTooLong);
+        }
+        let data = Schema::new(attrs.to_vec()).serialize();
+        if SCHEMA_DATA_OFFSET as usize + data.len() > PAGE_SIZE as usize {
+            return Err(PageError::PageOverflow);
+        }
+
+        bytes[SCHEMA_RELATION_ID_OFFSET as usize..].fill(0);
+        write_u32(bytes, SCHEMA_RELATION_ID_OFFSET, relation_id).unwrap();
+        write_u32(bytes, SCHEMA_HEAP_ROOT_ID_OFFSET, heap_root_id).unwrap();
+        write_u32(bytes, SCHEMA_LENGTH_OFFSET, data.len() as u32).unwrap();
+
+        let start = SCHEMA_DATA_OFFSET as usize;
+        bytes[start..start + data.len()].copy_from_slice(&data);
+        Ok(())
+    }
+}
+
 /// Custom errors to be used by pages.
 #[derive(Debug)]
 pub enum PageError {
@@ -478,16 +1404,30 @@ pub enum PageError {
     /// Error to be thrown when a slot index is out of bounds.
     SlotOutOfBounds,
 
+    /// Error to be thrown when a record is restored into a slot that already contains a record.
+    SlotOccupied,
+
     /// Error to be thrown when a specified record has already been deleted and a
     /// read/update/delete operation cannot proceed.
     RecordDeleted,
+
+    /// Error to be thrown when a specified record is stored in overflow pages and cannot be
+    /// read or updated from the relation page alone.
+    RecordOverflow,
+
+    /// Error to be thrown when a name is too long to be stored in a page.
+    NameTooLong,
+
+    /// Error to be thrown when a page header is inconsistent (e.g. due to corruption) and its
+    /// offsets cannot be trusted.
+    InvalidHeader,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::io::{read_bool, read_f32, read_i32, read_str, read_u32};
-    use crate::relation::record::NULL_BITMAP_SIZE;
+    use crate::relation::record::null_bitmap_size;
     use crate::relation::types::{size_of, DataType};
     use crate::relation::Attribute;
     use crate::relation::Schema;
@@ -522,7 +1462,7 @@ mod tests {
                 Attribute::new("varch", DataType::Varchar, false, false, false),
                 Attribute::new("bool", DataType::Boolean, false, false, false),
                 Attribute::new("int", DataType::Int, false, false, false),
-                Attribute::new("deci", DataType::Decimal, false, false, false),
+                Attribute::new("deci", DataType::Real, false, false, false),
             ])),
         )
         .unwrap();
@@ -557,16 +1497,16 @@ mod tests {
         );
         assert_eq!(read_u32(&page, size_addr).unwrap(), record.len());
 
-        let bitmap_size = NULL_BITMAP_SIZE;
+        let bitmap_size = null_bitmap_size(4);
         let bitmap_addr = PAGE_SIZE - record.len();
         let str_offset_addr = bitmap_addr + bitmap_size;
         let str_size_addr = str_offset_addr + 4;
         let bool_addr = str_size_addr + 4;
         let int_addr = bool_addr + size_of(DataType::Boolean);
         let deci_addr = int_addr + size_of(DataType::Int);
-        let str_val_addr = deci_addr + size_of(DataType::Decimal);
+        let str_val_addr = deci_addr + size_of(DataType::Real);
 
-        assert_eq!(read_u32(&page, bitmap_addr).unwrap(), 0);
+        assert_eq!(page[bitmap_addr as usize], 0);
         assert_eq!(
             read_u32(&page, str_offset_addr).unwrap(),
             record.len() - varchar_len
@@ -580,4 +1520,550 @@ mod tests {
             "Hello, World!".to_string()
         );
     }
+
+    /// Return an unallocated record containing a single varchar of the specified length.
+    fn varchar_record(len: usize) -> Record {
+        Record::new(
+            vec![Some(Box::new("x".repeat(len)))],
+            Arc::new(Schema::new(vec![Attribute::new(
+                "varch",
+                DataType::Varchar,
+                false,
+                false,
+                false,
+            )])),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_insert_record_exact_fit() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+
+        // Build a record that exactly fills the remaining free space (including its pointer).
+        let overhead = varchar_record(0).len();
+        let free_space = RelationPage::get_free_space(&page);
+        let mut record = varchar_record((free_space - overhead - RECORD_POINTER_SIZE) as usize);
+        assert_eq!(record.len() + RECORD_POINTER_SIZE, free_space);
+
+        RelationPage::insert_record(&mut page, &mut record).unwrap();
+        assert_eq!(RelationPage::get_free_space(&page), 0);
+        assert_eq!(
+            RelationPage::get_free_pointer(&page),
+            RECORDS_OFFSET + RECORD_POINTER_SIZE - 1
+        );
+        assert_eq!(
+            RelationPage::read_record(&page, 0).unwrap().as_bytes(),
+            record.as_bytes()
+        );
+
+        // Assert that a full page rejects even the smallest record.
+        let result = RelationPage::insert_record(&mut page, &mut varchar_record(0));
+        assert!(matches!(result, Err(PageError::PageOverflow)));
+        assert_eq!(RelationPage::get_num_records(&page), 1);
+    }
+
+    #[test]
+    fn test_insert_record_one_byte_too_large() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+
+        // Build a record that is one byte larger than the remaining free space.
+        let overhead = varchar_record(0).len();
+        let free_space = RelationPage::get_free_space(&page);
+        let mut record = varchar_record((free_space - overhead - RECORD_POINTER_SIZE + 1) as usize);
+
+        let result = RelationPage::insert_record(&mut page, &mut record);
+        assert!(matches!(result, Err(PageError::PageOverflow)));
+        assert!(!record.is_allocated());
+
+        // Assert that the page header was left untouched.
+        assert_eq!(RelationPage::get_num_records(&page), 0);
+        assert_eq!(RelationPage::get_free_pointer(&page), PAGE_SIZE - 1);
+        assert_eq!(RelationPage::get_free_space(&page), free_space);
+    }
+
+    #[test]
+    fn test_compact_page() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+
+        // Insert five records of differing lengths, then delete the second and fourth.
+        let mut records = Vec::new();
+        for i in 0..5 {
+            let mut record = varchar_record(10 + i);
+            RelationPage::insert_record(&mut page, &mut record).unwrap();
+            records.push(record);
+        }
+        let free_space = RelationPage::get_free_space(&page);
+        for &slot in [1, 3].iter() {
+            RelationPage::flag_delete_record(&mut page, slot).unwrap();
+            RelationPage::commit_delete_record(&mut page, slot).unwrap();
+        }
+        assert_eq!(RelationPage::get_num_records(&page), 5);
+
+        // Compact the page and assert that the dead slots were removed.
+        let mapping = RelationPage::compact(&mut page);
+        assert_eq!(RelationPage::get_num_records(&page), 3);
+        assert_eq!(mapping.len(), 3);
+        assert_eq!(mapping[&0], 0);
+        assert_eq!(mapping[&2], 1);
+        assert_eq!(mapping[&4], 2);
+        assert!(!mapping.contains_key(&1));
+        assert!(!mapping.contains_key(&3));
+
+        // Assert that both the record data and the dead slot entries were reclaimed.
+        assert_eq!(
+            RelationPage::get_free_space(&page),
+            free_space + records[1].len() + records[3].len() + 2 * RECORD_POINTER_SIZE
+        );
+
+        // Assert that all live records are still readable at their new slots.
+        for (&old_slot, &new_slot) in mapping.iter() {
+            let record = RelationPage::read_record(&page, new_slot).unwrap();
+            assert_eq!(record.as_bytes(), records[old_slot as usize].as_bytes());
+            assert_eq!(record.get_id().unwrap().slot_index, new_slot);
+        }
+    }
+
+    #[test]
+    fn test_iter_records() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+
+        // Insert three records and flag the second one for deletion.
+        let mut records = Vec::new();
+        for i in 0..3 {
+            let mut record = varchar_record(10 + i);
+            RelationPage::insert_record(&mut page, &mut record).unwrap();
+            records.push(record);
+        }
+        RelationPage::flag_delete_record(&mut page, 1).unwrap();
+
+        // Assert that only the live records are yielded with their slot indices.
+        let live: Vec<(u32, Record)> = RelationPage::iter_records(&page).collect();
+        assert_eq!(live.len(), 2);
+        assert_eq!(live[0].0, 0);
+        assert_eq!(live[0].1.as_bytes(), records[0].as_bytes());
+        assert_eq!(live[1].0, 2);
+        assert_eq!(live[1].1.as_bytes(), records[2].as_bytes());
+    }
+
+    #[test]
+    fn test_update_record() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+        for i in 0..3 {
+            RelationPage::insert_record(&mut page, &mut varchar_record(10 + i)).unwrap();
+        }
+
+        // Grow and then shrink the middle record, and assert that every record is intact.
+        for &len in [50, 5].iter() {
+            let record = varchar_record(len);
+            RelationPage::update_record(&mut page, record.clone(), 1).unwrap();
+            let updated = RelationPage::read_record(&page, 1).unwrap();
+            assert_eq!(updated.len(), record.len());
+            assert_eq!(updated.as_bytes(), record.as_bytes());
+
+            for (slot, len) in [(0, 10), (2, 12)].iter() {
+                let other = RelationPage::read_record(&page, *slot).unwrap();
+                assert_eq!(other.as_bytes(), varchar_record(*len).as_bytes());
+            }
+            assert!(RelationPage::validate(&page).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_update_record_overflow() {
+        // Fill the page until no more records fit.
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+        while RelationPage::insert_record(&mut page, &mut varchar_record(100)).is_ok() {}
+        let free_space = RelationPage::get_free_space(&page);
+
+        // Assert that growing a record past the free space fails without modifying the page.
+        let original = page;
+        let record = varchar_record(100 + free_space as usize + 1);
+        assert!(matches!(
+            RelationPage::update_record(&mut page, record, 0),
+            Err(PageError::PageOverflow)
+        ));
+        assert_eq!(page[..], original[..]);
+
+        // Assert that growing a record into exactly the free space succeeds.
+        let record = varchar_record(100 + free_space as usize);
+        RelationPage::update_record(&mut page, record.clone(), 0).unwrap();
+        assert_eq!(
+            RelationPage::read_record(&page, 0).unwrap().as_bytes(),
+            record.as_bytes()
+        );
+        assert_eq!(RelationPage::get_free_space(&page), 0);
+        assert!(RelationPage::validate(&page).is_ok());
+
+        // Assert that an inconsistent free pointer results in an error rather than a panic.
+        let mut corrupted = original;
+        RelationPage::set_free_pointer(&mut corrupted, PAGE_SIZE - 1);
+        assert!(matches!(
+            RelationPage::update_record(&mut corrupted, varchar_record(150), 0),
+            Err(PageError::PageOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_validate_page() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+        assert!(RelationPage::validate(&page).is_ok());
+
+        for i in 0..3 {
+            RelationPage::insert_record(&mut page, &mut varchar_record(10 + i)).unwrap();
+        }
+        RelationPage::flag_delete_record(&mut page, 1).unwrap();
+        RelationPage::commit_delete_record(&mut page, 1).unwrap();
+        assert!(RelationPage::validate(&page).is_ok());
+    }
+
+    #[test]
+    fn test_validate_corrupted_page() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+        RelationPage::insert_record(&mut page, &mut varchar_record(10)).unwrap();
+
+        // Free pointer past the end of the page.
+        let mut corrupted = page;
+        RelationPage::set_free_pointer(&mut corrupted, PAGE_SIZE + 100);
+        assert!(matches!(
+            RelationPage::validate(&corrupted),
+            Err(PageError::InvalidHeader)
+        ));
+
+        // Slot directory overrunning the free pointer.
+        let mut corrupted = page;
+        RelationPage::set_num_records(&mut corrupted, PAGE_SIZE);
+        assert!(matches!(
+            RelationPage::validate(&corrupted),
+            Err(PageError::InvalidHeader)
+        ));
+
+        // Record data extending past the end of the page.
+        let mut corrupted = page;
+        write_u32(&mut corrupted, RECORDS_OFFSET + 4, PAGE_SIZE).unwrap();
+        assert!(matches!(
+            RelationPage::validate(&corrupted),
+            Err(PageError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn test_dictionary_get_set() {
+        let mut page = RawPage::new(0);
+        assert_eq!(DictionaryPage::get(&page, "users"), None);
+
+        DictionaryPage::set(&mut page, "users", 3, DictionaryEntryKind::Relation).unwrap();
+        DictionaryPage::set(&mut page, "orders", 7, DictionaryEntryKind::Index).unwrap();
+
+        assert_eq!(DictionaryPage::get(&page, "users"), Some(3));
+        assert_eq!(DictionaryPage::get(&page, "orders"), Some(7));
+        assert_eq!(DictionaryPage::get(&page, "user"), None);
+        assert_eq!(DictionaryPage::get_num_entries(&page), 2);
+
+        assert_eq!(
+            DictionaryPage::get_entry(&page, 0),
+            Some(("users".to_string(), 3, DictionaryEntryKind::Relation))
+        );
+        assert_eq!(
+            DictionaryPage::get_entry(&page, 1),
+            Some(("orders".to_string(), 7, DictionaryEntryKind::Index))
+        );
+        assert_eq!(DictionaryPage::get_entry(&page, 2), None);
+
+        let long_name = "x".repeat(33);
+        assert!(matches!(
+            DictionaryPage::set(&mut page, &long_name, 1, DictionaryEntryKind::Relation),
+            Err(PageError::NameTooLong)
+        ));
+    }
+
+    #[test]
+    fn test_set_existing_entry() {
+        let mut page = RawPage::new(0);
+        DictionaryPage::set(&mut page, "users", 3, DictionaryEntryKind::Relation).unwrap();
+        DictionaryPage::set(&mut page, "orders", 7, DictionaryEntryKind::Relation).unwrap();
+        assert_eq!(DictionaryPage::get_num_entries(&page), 2);
+
+        // Updating an existing relation's root should not grow the entry count.
+        DictionaryPage::set(&mut page, "users", 11, DictionaryEntryKind::Relation).unwrap();
+        assert_eq!(DictionaryPage::get(&page, "users"), Some(11));
+        assert_eq!(DictionaryPage::get(&page, "orders"), Some(7));
+        assert_eq!(DictionaryPage::get_num_entries(&page), 2);
+    }
+
+    #[test]
+    fn test_dictionary_overflow() {
+        let mut page = RawPage::new(0);
+        let capacity = (PAGE_SIZE - DICTIONARY_ENTRIES_OFFSET) / DICTIONARY_ENTRY_SIZE;
+        for i in 0..capacity {
+            let name = format!("relation_{}", i);
+            DictionaryPage::set(&mut page, &name, i, DictionaryEntryKind::Relation).unwrap();
+        }
+
+        // Assert that a new entry is rejected once the page is full, without modifying it.
+        let original = page;
+        assert!(matches!(
+            DictionaryPage::set(&mut page, "overflow", 1, DictionaryEntryKind::Relation),
+            Err(PageError::PageOverflow)
+        ));
+        assert_eq!(page[..], original[..]);
+        assert_eq!(DictionaryPage::get_num_entries(&page), capacity);
+
+        // Assert that existing entries can still be updated.
+        DictionaryPage::set(&mut page, "relation_0", 42, DictionaryEntryKind::Relation).unwrap();
+        assert_eq!(DictionaryPage::get(&page, "relation_0"), Some(42));
+    }
+
+    #[test]
+    fn test_dictionary_remove() {
+        let mut page = RawPage::new(0);
+        DictionaryPage::set(&mut page, "users", 3, DictionaryEntryKind::Relation).unwrap();
+        DictionaryPage::set(&mut page, "orders", 7, DictionaryEntryKind::Relation).unwrap();
+        DictionaryPage::set(&mut page, "items", 9, DictionaryEntryKind::Relation).unwrap();
+
+        assert!(DictionaryPage::remove(&mut page, "orders"));
+        assert!(!DictionaryPage::remove(&mut page, "orders"));
+
+        assert_eq!(DictionaryPage::get_num_entries(&page), 2);
+        assert_eq!(DictionaryPage::get(&page, "users"), Some(3));
+        assert_eq!(DictionaryPage::get(&page, "items"), Some(9));
+        assert_eq!(DictionaryPage::get(&page, "orders"), None);
+
+        // The unused tail entry should be zeroed out.
+        let tail = DictionaryPage::get_entry_addr(2) as usize;
+        assert!(page[tail..tail + DICTIONARY_ENTRY_SIZE as usize]
+            .iter()
+            .all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_index_page() {
+        let mut page = RawPage::new(9);
+        let keys: Vec<Vec<u8>> = vec![vec![1, 1, 1], vec![2, 2, 2], vec![3, 3, 3]];
+
+        // Write a leaf node and read it back.
+        IndexPage::write_leaf(&mut page, 3, &keys, Some(12)).unwrap();
+        assert_eq!(RawPage::get_id(&page), 9);
+        assert!(IndexPage::is_leaf(&page));
+        assert_eq!(IndexPage::get_num_keys(&page), 3);
+        assert_eq!(IndexPage::get_next_leaf_id(&page), Some(12));
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(IndexPage::get_key(&page, i as u32), key.as_slice());
+        }
+
+        // Overwrite the page with an internal node and read it back.
+        IndexPage::write_internal(&mut page, 3, &keys[..2], &[4, 5, 6]).unwrap();
+        assert!(!IndexPage::is_leaf(&page));
+        assert_eq!(IndexPage::get_num_keys(&page), 2);
+        assert_eq!(IndexPage::get_next_leaf_id(&page), None);
+        assert_eq!(IndexPage::get_key(&page, 0), keys[0].as_slice());
+        assert_eq!(IndexPage::get_key(&page, 1), keys[1].as_slice());
+        for i in 0..3 {
+            assert_eq!(IndexPage::get_child_id(&page, i), 4 + i);
+        }
+
+        // Assert that too many keys can't be written to a page.
+        let max_keys = IndexPage::get_max_keys(3, true) as usize;
+        let keys = vec![vec![0, 0, 0]; max_keys + 1];
+        assert!(IndexPage::write_leaf(&mut page, 3, &keys[..max_keys], None).is_ok());
+        assert!(IndexPage::write_leaf(&mut page, 3, &keys, None).is_err());
+    }
+
+    #[test]
+    fn test_hash_pages() {
+        // Write a directory page and read it back.
+        let mut page = RawPage::new(3);
+        HashDirectoryPage::write(&mut page, 2, &[4, 5, 4, 6]).unwrap();
+        assert_eq!(RawPage::get_id(&page), 3);
+        assert_eq!(HashDirectoryPage::get_global_depth(&page), 2);
+        assert_eq!(HashDirectoryPage::get_bucket_id(&page, 2), 4);
+        assert_eq!(HashDirectoryPage::get_bucket_id(&page, 3), 6);
+
+        // Assert that a directory can't exceed the maximum global depth.
+        let depth = HASH_MAX_GLOBAL_DEPTH + 1;
+        let bucket_ids = vec![4; 1 << depth];
+        assert!(HashDirectoryPage::write(&mut page, depth, &bucket_ids).is_err());
+
+        // Write a bucket page and read it back.
+        let mut page = RawPage::new(4);
+        let entries: Vec<Vec<u8>> = vec![vec![1, 1, 1], vec![2, 2, 2]];
+        HashBucketPage::write(&mut page, 1, 3, &entries).unwrap();
+        assert_eq!(RawPage::get_id(&page), 4);
+        assert_eq!(HashBucketPage::get_local_depth(&page), 1);
+        assert_eq!(HashBucketPage::get_num_entries(&page), 2);
+        assert_eq!(HashBucketPage::get_entry(&page, 1), entries[1].as_slice());
+
+        // Assert that too many entries can't be written to a bucket.
+        let max_entries = HashBucketPage::get_max_entries(3) as usize;
+        let entries = vec![vec![0, 0, 0]; max_entries + 1];
+        assert!(HashBucketPage::write(&mut page, 1, 3, &entries[..max_entries]).is_ok());
+        assert!(HashBucketPage::write(&mut page, 1, 3, &entries).is_err());
+    }
+
+    #[test]
+    fn test_verify_offsets() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+        assert!(RelationPage::verify_offsets(&page).is_ok());
+        for len in [10, 20, 30].iter() {
+            RelationPage::insert_record(&mut page, &mut varchar_record(*len)).unwrap();
+        }
+        assert!(RelationPage::verify_offsets(&page).is_ok());
+
+        // Assert that a gap between records is detected.
+        let mut corrupted = page;
+        let offset_addr = RECORDS_OFFSET + RECORD_POINTER_SIZE;
+        let offset = read_u32(&corrupted, offset_addr).unwrap();
+        write_u32(&mut corrupted, offset_addr, offset + 1).unwrap();
+        assert!(matches!(
+            RelationPage::verify_offsets(&corrupted),
+            Err(PageError::InvalidHeader)
+        ));
+
+        // Assert that a free pointer inconsistent with the record data is detected.
+        let mut corrupted = page;
+        let free_ptr = RelationPage::get_free_pointer(&corrupted);
+        RelationPage::set_free_pointer(&mut corrupted, free_ptr - 1);
+        assert!(matches!(
+            RelationPage::verify_offsets(&corrupted),
+            Err(PageError::InvalidHeader)
+        ));
+    }
+
+    /// Return a varchar record of the specified length, filled with a character identifying the
+    /// specified seed so that records shifted onto each other's data can be told apart.
+    fn seeded_record(seed: usize, len: usize) -> Record {
+        let c = (b'a' + (seed % 26) as u8) as char;
+        Record::new(
+            vec![Some(Box::new(c.to_string().repeat(len)))],
+            Arc::new(Schema::new(vec![Attribute::new(
+                "varch",
+                DataType::Varchar,
+                false,
+                false,
+                false,
+            )])),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_offsets_survive_update_delete_cycles() {
+        let mut page = RawPage::new(5);
+        RelationPage::init(&mut page);
+
+        // Insert records, tracking the expected contents of each slot.
+        let mut expected = Vec::new();
+        for slot in 0..20 {
+            let mut record = seeded_record(slot, 10 + slot * 7);
+            RelationPage::insert_record(&mut page, &mut record).unwrap();
+            expected.push(Some(record.as_bytes().to_vec()));
+        }
+
+        // Perform randomized updates and deletes with a fixed-seed xorshift generator, so that
+        // failures are reproducible.
+        let mut state: u32 = 0x2545_f491;
+        let mut next = |bound: u32| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state % bound
+        };
+        for op in 0..200 {
+            let live: Vec<usize> = (0..expected.len())
+                .filter(|&slot| expected[slot].is_some())
+                .collect();
+
+            match next(4) {
+                // Re-insert a record once enough records have been deleted.
+                _ if live.len() < 5 => {
+                    let mut record = seeded_record(op, next(100) as usize);
+                    RelationPage::insert_record(&mut page, &mut record).unwrap();
+                    expected.push(Some(record.as_bytes().to_vec()));
+                }
+                0 => {
+                    let slot = live[next(live.len() as u32) as usize];
+                    RelationPage::flag_delete_record(&mut page, slot as u32).unwrap();
+                    RelationPage::commit_delete_record(&mut page, slot as u32).unwrap();
+                    expected[slot] = None;
+                }
+                _ => {
+                    let slot = live[next(live.len() as u32) as usize];
+                    let record = seeded_record(op, next(300) as usize);
+                    let bytes = record.as_bytes().to_vec();
+                    match RelationPage::update_record(&mut page, record, slot as u32) {
+                        Ok(()) => expected[slot] = Some(bytes),
+                        Err(PageError::PageOverflow) => {}
+                        Err(e) => panic!("unexpected error: {:?}", e),
+                    }
+                }
+            }
+
+            // Assert that every live record still decodes to its expected value.
+            RelationPage::verify_offsets(&page).unwrap();
+            for (slot, bytes) in expected.iter().enumerate() {
+                match bytes {
+                    Some(bytes) => assert_eq!(
+                        RelationPage::read_record(&page, slot as u32)
+                            .unwrap()
+                            .as_bytes(),
+                        &bytes[..]
+                    ),
+                    None => assert!(RelationPage::get_record_bytes(&page, slot as u32)
+                        .unwrap()
+                        .is_empty()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_schema_page() {
+        let mut page = RawPage::new(2);
+        let attrs = vec![
+            Attribute::new("id", DataType::Int, true, true, false),
+            Attribute::new("price", DataType::Decimal, false, false, true).with_scale(2),
+            Attribute::new("name", DataType::Varchar, false, false, true)
+                .with_max_len(16)
+                .with_default(Box::new("n/a".to_string())),
+        ];
+        SchemaPage::write(&mut page, 5, 1, &attrs).unwrap();
+        assert_eq!(RawPage::get_id(&page), 2);
+        assert_eq!(SchemaPage::get_relation_id(&page), 5);
+        assert_eq!(SchemaPage::get_heap_root_id(&page), 1);
+
+        let read = SchemaPage::get_attributes(&page).unwrap();
+        assert_eq!(read.len(), 3);
+        for (attr, expected) in read.iter().zip(attrs.iter()) {
+            assert_eq!(attr.get_name(), expected.get_name());
+            assert_eq!(attr.get_data_type(), expected.get_data_type());
+            assert_eq!(attr.is_primary(), expected.is_primary());
+            assert_eq!(attr.is_serial(), expected.is_serial());
+            assert_eq!(attr.is_nullable(), expected.is_nullable());
+            assert_eq!(attr.get_scale(), expected.get_scale());
+            assert_eq!(attr.get_max_len(), expected.get_max_len());
+            assert_eq!(attr.get_default(), expected.get_default());
+        }
+
+        // Assert that attribute names which don't fit in the page are rejected.
+        let attrs = vec![Attribute::new(
+            &"x".repeat(33),
+            DataType::Int,
+            false,
+            false,
+            false,
+        )];
+        assert!(matches!(
+            SchemaPage::write(&mut page, 5, 1, &attrs),
+            Err(PageError::NameTooLong)
+        ));
+    }
 }
@@ -0,0 +1,143 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::index::Index;
+use crate::relation::Relation;
+use std::sync::Arc;
+
+/// Estimated cost of descending an index to its first matching key, in units of records read
+/// by a sequential scan.
+const INDEX_LOOKUP_COST: f64 = 3.0;
+
+/// Estimated cost of reading a record found in an index, in units of records read by a
+/// sequential scan. Records found in an index are read in random order, which costs more than
+/// reading them in order.
+const INDEX_FETCH_COST: f64 = 2.0;
+
+/// Estimated fraction of the records of a relation that match an equality predicate on a column
+/// that isn't unique.
+const DEFAULT_SELECTIVITY: f64 = 0.1;
+
+/// The method used to read the records of a relation that satisfy a predicate.
+pub enum AccessPath {
+    /// Read every record of the relation in order.
+    SeqScan,
+
+    /// Look up the matching records in an index on the predicate column.
+    IndexScan(Arc<dyn Index>),
+}
+
+/// Choose the cheaper method to read the records of a relation that satisfy an equality
+/// predicate on the specified column.
+///
+/// An index is usable if its key is exactly the predicate column. The cost of a sequential scan
+/// is the number of records in the relation, and the cost of an index scan is a fixed lookup
+/// cost plus the cost of fetching the expected matches: a single record if the column is the
+/// primary key, and `DEFAULT_SELECTIVITY` of the records otherwise. Ties go to the sequential
+/// scan.
+pub fn choose_access_path(relation: &Relation, column: u32) -> AccessPath {
+    let index = relation
+        .get_indexes()
+        .into_iter()
+        .find(|index| index.get_meta().get_key_attrs() == [column]);
+    let index = match index {
+        Some(index) => index,
+        None => return AccessPath::SeqScan,
+    };
+
+    let rows = relation.approx_row_count() as f64;
+    let schema = relation.get_schema();
+    let primary: Vec<u32> = (0..schema.attr_len())
+        .filter(|&idx| schema.get_attributes()[idx as usize].is_primary())
+        .collect();
+    let matches = match primary == [column] {
+        true => rows.min(1.0),
+        false => rows * DEFAULT_SELECTIVITY,
+    };
+
+    let seq_scan_cost = rows;
+    let index_scan_cost = INDEX_LOOKUP_COST + matches * INDEX_FETCH_COST;
+    match index_scan_cost < seq_scan_cost {
+        true => AccessPath::IndexScan(index),
+        false => AccessPath::SeqScan,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::replacement::ReplacerAlgorithm;
+    use crate::buffer::BufferManager;
+    use crate::catalog::SystemCatalog;
+    use crate::concurrency::transaction::Transaction;
+    use crate::constants::SYSTEM_TXN_ID;
+    use crate::disk::DiskManager;
+    use crate::relation::record::Record;
+    use crate::relation::types::{DataType, Value};
+    use crate::relation::{Attribute, Schema};
+
+    /// Return a catalog with a relation "foo" of the specified number of records, with an index
+    /// on its primary key "id" and on "group".
+    fn setup(rows: i32) -> SystemCatalog {
+        let buffer_manager =
+            BufferManager::new(16, DiskManager::in_memory(), ReplacerAlgorithm::Slow);
+        let catalog = SystemCatalog::new(Arc::new(buffer_manager));
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("id", DataType::Int, true, false, false),
+            Attribute::new("group", DataType::Int, false, false, false),
+            Attribute::new("other", DataType::Int, false, false, false),
+        ]));
+        let relation = catalog.create_relation("foo", schema.clone()).unwrap();
+
+        let txn = Transaction::new(SYSTEM_TXN_ID);
+        for id in 0..rows {
+            let values: Vec<Option<Box<dyn Value>>> = vec![
+                Some(Box::new(id)),
+                Some(Box::new(id % 4)),
+                Some(Box::new(id)),
+            ];
+            let record = Record::new(values, schema.clone()).unwrap();
+            relation.insert(record, &txn).unwrap();
+        }
+        catalog.create_index("foo_id", "foo", &[0]).unwrap();
+        catalog.create_index("foo_group", "foo", &[1]).unwrap();
+        catalog
+    }
+
+    fn index_name(path: AccessPath) -> Option<String> {
+        match path {
+            AccessPath::SeqScan => None,
+            AccessPath::IndexScan(index) => Some(index.get_meta().get_name().to_string()),
+        }
+    }
+
+    #[test]
+    fn test_choose_access_path() {
+        let catalog = setup(100);
+        let relation = catalog.get_relation("foo").unwrap();
+
+        // Assert that indexed columns are looked up in their index.
+        assert_eq!(
+            index_name(choose_access_path(&relation, 0)),
+            Some("foo_id".to_string())
+        );
+        assert_eq!(
+            index_name(choose_access_path(&relation, 1)),
+            Some("foo_group".to_string())
+        );
+
+        // Assert that an unindexed column is scanned.
+        assert_eq!(index_name(choose_access_path(&relation, 2)), None);
+    }
+
+    #[test]
+    fn test_choose_access_path_small_relation() {
+        // Assert that a relation with fewer records than an index lookup costs is scanned.
+        let catalog = setup(2);
+        let relation = catalog.get_relation("foo").unwrap();
+        assert_eq!(index_name(choose_access_path(&relation, 0)), None);
+        assert_eq!(index_name(choose_access_path(&relation, 1)), None);
+    }
+}
@@ -5,20 +5,135 @@
 
 use crate::plan::{PlanVariant, QueryPlanNode};
 use crate::relation::record::Record;
-use crate::relation::Schema;
+use crate::relation::types::{DataType, ValueError};
+use crate::relation::{Attribute, Schema};
 use std::sync::{Arc, Mutex, RwLock};
 
+/// Aggregate functions which can be computed over a column.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AggFn {
+    /// Number of rows, including rows where the column is null.
+    CountStar,
+
+    /// Number of non-null values.
+    Count,
+
+    /// Sum of non-null values. Only valid for numeric columns.
+    Sum,
+
+    /// Minimum non-null value.
+    Min,
+
+    /// Maximum non-null value.
+    Max,
+
+    /// Average of non-null values. Only valid for numeric columns.
+    Avg,
+}
+
+impl AggFn {
+    /// Return the name of this aggregate function.
+    pub fn get_name(&self) -> &str {
+        match self {
+            AggFn::CountStar | AggFn::Count => "count",
+            AggFn::Sum => "sum",
+            AggFn::Min => "min",
+            AggFn::Max => "max",
+            AggFn::Avg => "avg",
+        }
+    }
+}
+
 pub struct AggregationPlanNode {
+    /// Schema of the records to be aggregated.
+    input_schema: Arc<Schema>,
+
+    /// Indices of the columns to group records by.
+    group_by: Vec<u32>,
+
+    /// Aggregates to be computed for each group, and the indices of the columns they are
+    /// computed over.
+    aggregates: Vec<(AggFn, u32)>,
+
     children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
     output_schema: Arc<Schema>,
 }
 
 impl AggregationPlanNode {
-    pub fn new(output_schema: Arc<Schema>) -> Self {
-        Self {
-            children: Arc::new(RwLock::new(Vec::new())),
-            output_schema,
+    /// Create a new aggregation plan node. The output schema contains the group-by columns
+    /// followed by a column for each aggregate.
+    ///
+    /// Return an error if a SUM or AVG aggregate is computed over a non-numeric column.
+    pub fn new(
+        input_schema: Arc<Schema>,
+        group_by: Vec<u32>,
+        aggregates: Vec<(AggFn, u32)>,
+    ) -> Result<Self, ValueError> {
+        let input_attrs = input_schema.get_attributes();
+
+        let mut attrs: Vec<Attribute> = group_by
+            .iter()
+            .map(|idx| input_attrs[*idx as usize].clone())
+            .collect();
+        for (agg, idx) in aggregates.iter() {
+            let input_attr = &input_attrs[*idx as usize];
+            let name = match agg {
+                AggFn::CountStar => "count(*)".to_string(),
+                _ => format!("{}({})", agg.get_name(), input_attr.get_name()),
+            };
+            let attr = match agg {
+                AggFn::CountStar | AggFn::Count => {
+                    Attribute::new(&name, DataType::BigInt, false, false, false)
+                }
+                _ => Attribute::new(
+                    &name,
+                    get_output_type(*agg, input_attr.get_data_type())?,
+                    false,
+                    false,
+                    true,
+                )
+                .with_scale(input_attr.get_scale()),
+            };
+            attrs.push(attr);
         }
+
+        Ok(Self {
+            input_schema,
+            group_by,
+            aggregates,
+            children: Arc::new(RwLock::new(Vec::new())),
+            output_schema: Arc::new(Schema::new(attrs)),
+        })
+    }
+
+    pub fn get_input_schema(&self) -> Arc<Schema> {
+        self.input_schema.clone()
+    }
+
+    pub fn get_group_by(&self) -> &[u32] {
+        &self.group_by
+    }
+
+    pub fn get_aggregates(&self) -> &[(AggFn, u32)] {
+        &self.aggregates
+    }
+}
+
+/// Return the data type of an aggregate computed over a column of the specified type, or an
+/// error if the aggregate isn't defined for that type.
+fn get_output_type(agg: AggFn, data_type: DataType) -> Result<DataType, ValueError> {
+    let is_integer = matches!(
+        data_type,
+        DataType::TinyInt | DataType::SmallInt | DataType::Int | DataType::BigInt
+    );
+    let is_numeric = is_integer || matches!(data_type, DataType::Real | DataType::Decimal);
+    match agg {
+        AggFn::CountStar | AggFn::Count => Ok(DataType::BigInt),
+        AggFn::Min | AggFn::Max => Ok(data_type),
+        AggFn::Sum | AggFn::Avg if !is_numeric => Err(ValueError::NonNumeric),
+        AggFn::Sum if is_integer => Ok(DataType::BigInt),
+        AggFn::Sum => Ok(data_type),
+        AggFn::Avg => Ok(DataType::Real),
     }
 }
 
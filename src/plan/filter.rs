@@ -0,0 +1,53 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::plan::{PlanVariant, QueryPlanNode};
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A predicate evaluated on each record passing through a filter.
+pub type Predicate = Box<dyn Fn(&Record) -> bool + Send + Sync>;
+
+pub struct FilterPlanNode {
+    /// Predicate that records must satisfy to be outputted by this plan.
+    predicate: Predicate,
+
+    children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
+    output_schema: Arc<Schema>,
+}
+
+impl FilterPlanNode {
+    pub fn new(predicate: Predicate, output_schema: Arc<Schema>) -> Self {
+        Self {
+            predicate,
+            children: Arc::new(RwLock::new(Vec::new())),
+            output_schema,
+        }
+    }
+
+    /// Return whether the record satisfies the predicate of this plan.
+    pub fn evaluate(&self, record: &Record) -> bool {
+        (self.predicate)(record)
+    }
+}
+
+impl QueryPlanNode for FilterPlanNode {
+    fn next(&self) -> Option<Arc<Mutex<Record>>> {
+        todo!()
+    }
+
+    fn get_children(&self) -> Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>> {
+        Arc::clone(&self.children)
+    }
+
+    fn get_output_schema(&self) -> Arc<Schema> {
+        Arc::clone(&self.output_schema)
+    }
+
+    fn get_variant(&self) -> PlanVariant {
+        PlanVariant::Filter
+    }
+}
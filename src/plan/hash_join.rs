@@ -3,23 +3,65 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
+use crate::executor::join_schema;
 use crate::plan::{PlanVariant, QueryPlanNode};
 use crate::relation::record::Record;
 use crate::relation::Schema;
 use std::sync::{Arc, Mutex, RwLock};
 
 pub struct HashJoinPlanNode {
+    /// Schema of the records of the left (build) side of the join.
+    left_schema: Arc<Schema>,
+
+    /// Indices of the join key columns in the left schema.
+    left_key_attrs: Vec<u32>,
+
+    /// Schema of the records of the right (probe) side of the join.
+    right_schema: Arc<Schema>,
+
+    /// Indices of the join key columns in the right schema, in the same order as the left.
+    right_key_attrs: Vec<u32>,
+
     children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
     output_schema: Arc<Schema>,
 }
 
 impl HashJoinPlanNode {
-    pub fn new(output_schema: Arc<Schema>) -> Self {
+    /// Create a new hash join plan node, joining records whose left and right key columns are
+    /// equal. The output schema contains the left attributes followed by the right attributes.
+    pub fn new(
+        left_schema: Arc<Schema>,
+        left_key_attrs: Vec<u32>,
+        right_schema: Arc<Schema>,
+        right_key_attrs: Vec<u32>,
+    ) -> Self {
+        assert_eq!(left_key_attrs.len(), right_key_attrs.len());
+        let output_schema = join_schema(&left_schema, &right_schema);
         Self {
+            left_schema,
+            left_key_attrs,
+            right_schema,
+            right_key_attrs,
             children: Arc::new(RwLock::new(Vec::new())),
             output_schema,
         }
     }
+
+    pub fn get_left_schema(&self) -> Arc<Schema> {
+        self.left_schema.clone()
+    }
+
+    pub fn get_left_key_attrs(&self) -> &[u32] {
+        &self.left_key_attrs
+    }
+
+    pub fn get_right_schema(&self) -> Arc<Schema> {
+        self.right_schema.clone()
+    }
+
+    pub fn get_right_key_attrs(&self) -> &[u32] {
+        &self.right_key_attrs
+    }
 }
 
 impl QueryPlanNode for HashJoinPlanNode {
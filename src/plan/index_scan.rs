@@ -0,0 +1,83 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::constants::RelationIdT;
+use crate::plan::{PlanVariant, QueryPlanNode};
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::sync::{Arc, Mutex, RwLock};
+
+pub struct IndexScanPlanNode {
+    /// Relation scanned by this plan.
+    relation_id: RelationIdT,
+
+    /// Lower bound (inclusive) of the keys to be scanned, or None to scan from the first key.
+    low: Option<Record>,
+
+    /// Upper bound (inclusive) of the keys to be scanned, or None to scan to the last key.
+    high: Option<Record>,
+
+    children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
+    output_schema: Arc<Schema>,
+}
+
+impl IndexScanPlanNode {
+    /// Create a new index scan plan node, which scans every key of the index by default.
+    pub fn new(relation_id: RelationIdT, output_schema: Arc<Schema>) -> Self {
+        Self {
+            relation_id,
+            low: None,
+            high: None,
+            children: Arc::new(RwLock::new(Vec::new())),
+            output_schema,
+        }
+    }
+
+    /// Only scan the records with the specified key.
+    pub fn with_key(self, key: Record) -> Self {
+        self.with_range(Some(key.clone()), Some(key))
+    }
+
+    /// Only scan the records whose keys are between the specified bounds (inclusive). A bound of
+    /// None leaves that end of the range open.
+    pub fn with_range(mut self, low: Option<Record>, high: Option<Record>) -> Self {
+        self.low = low;
+        self.high = high;
+        self
+    }
+
+    /// Return the ID of the relation scanned by this plan.
+    pub fn get_relation_id(&self) -> RelationIdT {
+        self.relation_id
+    }
+
+    /// Return the lower bound of the keys to be scanned.
+    pub fn get_low(&self) -> Option<&Record> {
+        self.low.as_ref()
+    }
+
+    /// Return the upper bound of the keys to be scanned.
+    pub fn get_high(&self) -> Option<&Record> {
+        self.high.as_ref()
+    }
+}
+
+impl QueryPlanNode for IndexScanPlanNode {
+    fn next(&self) -> Option<Arc<Mutex<Record>>> {
+        todo!()
+    }
+
+    fn get_children(&self) -> Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>> {
+        Arc::clone(&self.children)
+    }
+
+    fn get_output_schema(&self) -> Arc<Schema> {
+        Arc::clone(&self.output_schema)
+    }
+
+    fn get_variant(&self) -> PlanVariant {
+        PlanVariant::IndexScan
+    }
+}
@@ -13,6 +13,9 @@ pub struct InsertPlanNode {
     /// Relation affected by this insert plan.
     relation_id: RelationIdT,
 
+    /// Literal records to be inserted, in addition to any records produced by a child.
+    records: Vec<Record>,
+
     children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
     output_schema: Arc<Schema>,
 }
@@ -21,10 +24,27 @@ impl InsertPlanNode {
     pub fn new(relation_id: RelationIdT, output_schema: Arc<Schema>) -> Self {
         Self {
             relation_id,
+            records: Vec::new(),
             children: Arc::new(RwLock::new(Vec::new())),
             output_schema,
         }
     }
+
+    /// Set the literal records to be inserted.
+    pub fn with_records(mut self, records: Vec<Record>) -> Self {
+        self.records = records;
+        self
+    }
+
+    /// Return the ID of the relation affected by this plan.
+    pub fn get_relation_id(&self) -> RelationIdT {
+        self.relation_id
+    }
+
+    /// Return the literal records to be inserted.
+    pub fn get_records(&self) -> &[Record] {
+        &self.records
+    }
 }
 
 impl QueryPlanNode for InsertPlanNode {
@@ -0,0 +1,59 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::plan::{PlanVariant, QueryPlanNode};
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::sync::{Arc, Mutex, RwLock};
+
+pub struct LimitPlanNode {
+    /// Maximum number of records to be outputted by this plan.
+    limit: usize,
+
+    /// Number of records to be skipped before outputting records.
+    offset: usize,
+
+    children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
+    output_schema: Arc<Schema>,
+}
+
+impl LimitPlanNode {
+    pub fn new(limit: usize, offset: usize, output_schema: Arc<Schema>) -> Self {
+        Self {
+            limit,
+            offset,
+            children: Arc::new(RwLock::new(Vec::new())),
+            output_schema,
+        }
+    }
+
+    /// Return the maximum number of records to be outputted by this plan.
+    pub fn get_limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Return the number of records to be skipped before outputting records.
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl QueryPlanNode for LimitPlanNode {
+    fn next(&self) -> Option<Arc<Mutex<Record>>> {
+        todo!()
+    }
+
+    fn get_children(&self) -> Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>> {
+        Arc::clone(&self.children)
+    }
+
+    fn get_output_schema(&self) -> Arc<Schema> {
+        Arc::clone(&self.output_schema)
+    }
+
+    fn get_variant(&self) -> PlanVariant {
+        PlanVariant::Limit
+    }
+}
@@ -12,10 +12,21 @@ use crate::relation::record::Record;
 use crate::relation::Schema;
 use std::sync::{Arc, Mutex, RwLock};
 
+pub mod access_path;
 pub mod aggr;
+pub mod delete;
+pub mod distinct;
+pub mod filter;
 pub mod hash_join;
+pub mod index_scan;
 pub mod insert;
+pub mod limit;
+pub mod nested_index_join;
+pub mod parser;
+pub mod projection;
 pub mod seq_scan;
+pub mod sort;
+pub mod update;
 
 /// A public trait for query plan nodes.
 pub trait QueryPlanNode {
@@ -23,6 +34,10 @@ pub trait QueryPlanNode {
     /// This method is invoked repeatedly by the parent node during query execution.
     fn next(&self) -> Option<Arc<Mutex<Record>>>;
 
+    /// Restart iteration from the beginning, so that the following call to `next()` returns the
+    /// first record again. Nodes that don't produce records may keep this default no-op.
+    fn reset(&self) {}
+
     /// Return all child nodes.
     fn get_children(&self) -> Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>;
 
@@ -47,12 +62,81 @@ pub trait QueryPlanNode {
 
     /// Return the variant of this plan node.
     fn get_variant(&self) -> PlanVariant;
+
+    /// Render this node and its descendants as text, one node per line. Each line shows the
+    /// variant and output columns of a node, and children are indented below their parent by
+    /// two spaces per level, starting at the specified level.
+    fn explain(&self, indent: usize) -> String {
+        let schema = self.get_output_schema();
+        let columns: Vec<String> = schema
+            .get_attributes()
+            .iter()
+            .map(|attr| format!("{} {}", attr.get_name(), attr.get_data_type()))
+            .collect();
+        let mut text = format!(
+            "{}{:?} ({})\n",
+            "  ".repeat(indent),
+            self.get_variant(),
+            columns.join(", ")
+        );
+
+        let children = self.get_children();
+        for child in children.read().unwrap().iter() {
+            text.push_str(&child.explain(indent + 1));
+        }
+        text
+    }
 }
 
-#[derive(Clone, Copy)]
+/// Render a plan tree as text, as in `QueryPlanNode::explain`.
+pub fn explain_plan(root: &dyn QueryPlanNode) -> String {
+    root.explain(0)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum PlanVariant {
     Aggregation,
+    Delete,
+    Distinct,
+    Filter,
     Insert,
     HashJoin,
+    IndexScan,
+    Limit,
+    NestedIndexJoin,
+    Projection,
     SeqScan,
+    Sort,
+    Update,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::filter::FilterPlanNode;
+    use crate::plan::limit::LimitPlanNode;
+    use crate::plan::seq_scan::SeqScanPlanNode;
+    use crate::relation::types::DataType;
+    use crate::relation::Attribute;
+
+    #[test]
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn test_explain_plan() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("id", DataType::Int, true, false, false),
+            Attribute::new("name", DataType::Varchar, false, false, true),
+        ]));
+        let scan = SeqScanPlanNode::new(0, schema.clone());
+        let mut filter = FilterPlanNode::new(Box::new(|_| true), schema.clone());
+        filter.insert_child(Arc::new(Box::new(scan)));
+        let mut limit = LimitPlanNode::new(10, 0, schema);
+        limit.insert_child(Arc::new(Box::new(filter)));
+
+        let expected = [
+            "Limit (id INT, name VARCHAR)",
+            "  Filter (id INT, name VARCHAR)",
+            "    SeqScan (id INT, name VARCHAR)",
+        ];
+        assert_eq!(explain_plan(&limit), expected.join("\n") + "\n");
+    }
 }
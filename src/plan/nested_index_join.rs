@@ -0,0 +1,100 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::constants::RelationIdT;
+use crate::executor::join_schema;
+use crate::plan::{PlanVariant, QueryPlanNode};
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::sync::{Arc, Mutex, RwLock};
+
+pub struct NestedIndexJoinPlanNode {
+    /// Schema of the records of the outer side of the join.
+    outer_schema: Arc<Schema>,
+
+    /// Indices of the join key columns in the outer schema, in the key order of the index.
+    outer_key_attrs: Vec<u32>,
+
+    /// Schema of the key records used to probe the index, which contains the join key columns of
+    /// the outer schema.
+    key_schema: Arc<Schema>,
+
+    /// Relation on the inner side of the join.
+    inner_relation_id: RelationIdT,
+
+    /// Schema of the records of the inner relation.
+    inner_schema: Arc<Schema>,
+
+    children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
+    output_schema: Arc<Schema>,
+}
+
+impl NestedIndexJoinPlanNode {
+    /// Create a new nested index join plan node, joining each outer record with the inner records
+    /// whose index keys are equal to its join key columns. The output schema contains the outer
+    /// attributes followed by the inner attributes.
+    pub fn new(
+        outer_schema: Arc<Schema>,
+        outer_key_attrs: Vec<u32>,
+        inner_relation_id: RelationIdT,
+        inner_schema: Arc<Schema>,
+    ) -> Self {
+        let attrs = outer_schema.get_attributes();
+        let key_schema = Arc::new(Schema::new(
+            outer_key_attrs
+                .iter()
+                .map(|idx| attrs[*idx as usize].clone())
+                .collect(),
+        ));
+        let output_schema = join_schema(&outer_schema, &inner_schema);
+        Self {
+            outer_schema,
+            outer_key_attrs,
+            key_schema,
+            inner_relation_id,
+            inner_schema,
+            children: Arc::new(RwLock::new(Vec::new())),
+            output_schema,
+        }
+    }
+
+    pub fn get_outer_schema(&self) -> Arc<Schema> {
+        self.outer_schema.clone()
+    }
+
+    pub fn get_outer_key_attrs(&self) -> &[u32] {
+        &self.outer_key_attrs
+    }
+
+    pub fn get_key_schema(&self) -> Arc<Schema> {
+        self.key_schema.clone()
+    }
+
+    pub fn get_inner_relation_id(&self) -> RelationIdT {
+        self.inner_relation_id
+    }
+
+    pub fn get_inner_schema(&self) -> Arc<Schema> {
+        self.inner_schema.clone()
+    }
+}
+
+impl QueryPlanNode for NestedIndexJoinPlanNode {
+    fn next(&self) -> Option<Arc<Mutex<Record>>> {
+        todo!()
+    }
+
+    fn get_children(&self) -> Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>> {
+        Arc::clone(&self.children)
+    }
+
+    fn get_output_schema(&self) -> Arc<Schema> {
+        Arc::clone(&self.output_schema)
+    }
+
+    fn get_variant(&self) -> PlanVariant {
+        PlanVariant::NestedIndexJoin
+    }
+}
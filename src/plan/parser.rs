@@ -0,0 +1,853 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::catalog::SystemCatalog;
+use crate::plan::filter::{FilterPlanNode, Predicate};
+use crate::plan::limit::LimitPlanNode;
+use crate::plan::projection::ProjectionPlanNode;
+use crate::plan::seq_scan::SeqScanPlanNode;
+use crate::plan::QueryPlanNode;
+use crate::relation::record::{Record, RecordErr};
+use crate::relation::types::{parse_value, DataType, InnerValue, Value};
+use crate::relation::{Attribute, Schema};
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A lexical token of a statement.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    /// A keyword or identifier.
+    Word(String),
+
+    /// A numeric literal, which may be negative or fractional.
+    Number(String),
+
+    /// A string literal, without its quotes.
+    Str(String),
+
+    /// A comparison operator: =, <>, !=, <, <=, > or >=.
+    Operator(String),
+
+    LParen,
+    RParen,
+    Comma,
+    Semicolon,
+    Star,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) | Token::Number(word) | Token::Operator(word) => {
+                write!(f, "{}", word)
+            }
+            Token::Str(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Semicolon => write!(f, ";"),
+            Token::Star => write!(f, "*"),
+        }
+    }
+}
+
+/// Split a statement into tokens. Words are runs of alphanumeric characters and underscores
+/// that don't begin with a digit, and string literals are enclosed in single quotes (with a
+/// quote escaped as two quotes).
+fn tokenize(sql: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            '*' => Token::Star,
+            '=' | '<' | '>' | '!' => {
+                chars.next();
+                let operator = match (c, chars.peek().copied()) {
+                    ('<', Some('=')) | ('<', Some('>')) | ('>', Some('=')) | ('!', Some('=')) => {
+                        format!("{}{}", c, chars.next().unwrap())
+                    }
+                    ('!', _) => return Err(ParseError::InvalidCharacter(c)),
+                    _ => c.to_string(),
+                };
+                tokens.push(Token::Operator(operator));
+                continue;
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '\'' => {
+                chars.next();
+                let mut literal = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            literal.push('\'');
+                            chars.next();
+                        }
+                        Some('\'') => break,
+                        Some(c) => literal.push(c),
+                        None => return Err(ParseError::UnterminatedString),
+                    }
+                }
+                tokens.push(Token::Str(literal));
+                continue;
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut number = String::new();
+                number.push(c);
+                chars.next();
+                while let Some(&c) = chars.peek() {
+                    if !c.is_alphanumeric() && c != '.' {
+                        break;
+                    }
+                    number.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Number(number));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !c.is_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+                continue;
+            }
+            c => return Err(ParseError::InvalidCharacter(c)),
+        };
+        tokens.push(token);
+        chars.next();
+    }
+    Ok(tokens)
+}
+
+/// A cursor over the tokens of a statement.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(sql: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            tokens: tokenize(sql)?,
+            pos: 0,
+        })
+    }
+
+    /// Return the next token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consume and return the next token.
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or(ParseError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// Consume the next token, which must be the specified token.
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => Err(ParseError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    /// Consume the next token, which must be a word. Return the word.
+    fn expect_word(&mut self) -> Result<String, ParseError> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            token => Err(ParseError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    /// Consume the next token, which must be the specified keyword (ignoring case).
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        let word = self.expect_word()?;
+        match word.eq_ignore_ascii_case(keyword) {
+            true => Ok(()),
+            false => Err(ParseError::UnexpectedToken(word)),
+        }
+    }
+
+    /// Consume the next token if it's the specified keyword (ignoring case). Return whether it
+    /// was consumed.
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consume an optional trailing semicolon, and assert that no tokens remain.
+    fn expect_end(&mut self) -> Result<(), ParseError> {
+        if self.peek() == Some(&Token::Semicolon) {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(token) => Err(ParseError::UnexpectedToken(token.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse a statement of the form
+/// `CREATE TABLE name (column TYPE [PRIMARY KEY] [NOT NULL] [SERIAL], ...)`
+/// and return the name of the relation and its schema. Keywords and data types are matched
+/// ignoring case, and a trailing semicolon is accepted.
+///
+/// Columns are nullable unless declared NOT NULL or PRIMARY KEY. A varchar column may declare
+/// its maximum length as `VARCHAR(n)`.
+pub fn parse_create_table(sql: &str) -> Result<(String, Schema), ParseError> {
+    let mut parser = Parser::new(sql)?;
+    parser.expect_keyword("CREATE")?;
+    parser.expect_keyword("TABLE")?;
+    let name = parser.expect_word()?;
+    parser.expect(Token::LParen)?;
+
+    let mut attributes: Vec<Attribute> = Vec::new();
+    loop {
+        let column = parser.expect_word()?;
+        if attributes.iter().any(|attr| attr.get_name() == column) {
+            return Err(ParseError::DuplicateColumn(column));
+        }
+
+        let type_name = parser.expect_word()?;
+        let data_type =
+            DataType::from_str(&type_name).map_err(|_| ParseError::InvalidDataType(type_name))?;
+        let mut max_len = None;
+        if data_type == DataType::Varchar && parser.peek() == Some(&Token::LParen) {
+            parser.next()?;
+            max_len = match parser.next()? {
+                Token::Number(len) => {
+                    Some(len.parse().map_err(|_| ParseError::UnexpectedToken(len))?)
+                }
+                token => return Err(ParseError::UnexpectedToken(token.to_string())),
+            };
+            parser.expect(Token::RParen)?;
+        }
+
+        let (mut primary, mut not_null, mut serial) = (false, false, false);
+        loop {
+            if parser.accept_keyword("PRIMARY") {
+                parser.expect_keyword("KEY")?;
+                primary = true;
+            } else if parser.accept_keyword("NOT") {
+                parser.expect_keyword("NULL")?;
+                not_null = true;
+            } else if parser.accept_keyword("SERIAL") {
+                serial = true;
+            } else {
+                break;
+            }
+        }
+
+        let attr = Attribute::new(&column, data_type, primary, serial, !(primary || not_null));
+        attributes.push(match max_len {
+            Some(max_len) => attr.with_max_len(max_len),
+            None => attr,
+        });
+
+        match parser.next()? {
+            Token::Comma => continue,
+            Token::RParen => break,
+            token => return Err(ParseError::UnexpectedToken(token.to_string())),
+        }
+    }
+    parser.expect_end()?;
+
+    Ok((name, Schema::new(attributes)))
+}
+
+/// Parse a statement of the form `INSERT INTO name VALUES (value, ...)` and return a record of
+/// the values, which are coerced to the data types of the schema. The schema must be the schema
+/// of the named relation, which isn't checked.
+///
+/// Values may be numbers, quoted strings, TRUE, FALSE or NULL. Dates and timestamps are given as
+/// quoted strings.
+pub fn parse_insert(sql: &str, schema: Arc<Schema>) -> Result<Record, ParseError> {
+    let mut parser = Parser::new(sql)?;
+    parser.expect_keyword("INSERT")?;
+    parser.expect_keyword("INTO")?;
+    parser.expect_word()?;
+    parser.expect_keyword("VALUES")?;
+    parser.expect(Token::LParen)?;
+
+    let mut literals = Vec::new();
+    loop {
+        match parser.next()? {
+            token @ Token::Word(_) | token @ Token::Number(_) | token @ Token::Str(_) => {
+                literals.push(token)
+            }
+            token => return Err(ParseError::UnexpectedToken(token.to_string())),
+        }
+        match parser.next()? {
+            Token::Comma => continue,
+            Token::RParen => break,
+            token => return Err(ParseError::UnexpectedToken(token.to_string())),
+        }
+    }
+    parser.expect_end()?;
+
+    if literals.len() != schema.attr_len() as usize {
+        return Err(ParseError::ArityMismatch {
+            expected: schema.attr_len(),
+            found: literals.len() as u32,
+        });
+    }
+    let values = literals
+        .into_iter()
+        .zip(schema.get_attributes().iter())
+        .map(|(literal, attr)| parse_literal(literal, attr))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Record::new(values, schema)?)
+}
+
+/// Coerce a literal token to a value of the data type of an attribute. Return None for NULL.
+fn parse_literal(literal: Token, attr: &Attribute) -> Result<Option<Box<dyn Value>>, ParseError> {
+    let data_type = attr.get_data_type();
+    let text = match literal {
+        Token::Word(word) if word.eq_ignore_ascii_case("NULL") => return Ok(None),
+        Token::Word(word)
+            if data_type == DataType::Boolean
+                && (word.eq_ignore_ascii_case("TRUE") || word.eq_ignore_ascii_case("FALSE")) =>
+        {
+            word.to_ascii_lowercase()
+        }
+        Token::Number(number)
+            if matches!(
+                data_type,
+                DataType::TinyInt
+                    | DataType::SmallInt
+                    | DataType::Int
+                    | DataType::BigInt
+                    | DataType::Real
+                    | DataType::Decimal
+            ) =>
+        {
+            number
+        }
+        Token::Str(s)
+            if matches!(
+                data_type,
+                DataType::Varchar | DataType::Date | DataType::Timestamp
+            ) =>
+        {
+            s
+        }
+        _ => return Err(ParseError::TypeMismatch(attr.get_name().to_string())),
+    };
+    parse_value(&text, data_type)
+        .map(Some)
+        .map_err(|_| ParseError::TypeMismatch(attr.get_name().to_string()))
+}
+
+/// Parse a statement of the form `SELECT column, ... FROM name [WHERE column op value] [LIMIT n]`
+/// and return a plan of the query. The columns may be given as `*` to select every column, and
+/// the WHERE clause is a single comparison of a column with a value, using one of the
+/// operators =, <>, !=, <, <=, > and >=.
+///
+/// The plan is a sequential scan of the relation, followed by a filter (for a WHERE clause), a
+/// projection (unless every column is selected), and a limit (for a LIMIT clause). Each node is
+/// the only child of the next.
+pub fn parse_select(
+    sql: &str,
+    catalog: &SystemCatalog,
+) -> Result<Box<dyn QueryPlanNode>, ParseError> {
+    let mut parser = Parser::new(sql)?;
+    parser.expect_keyword("SELECT")?;
+
+    let mut columns = Vec::new();
+    if parser.peek() == Some(&Token::Star) {
+        parser.next()?;
+    } else {
+        loop {
+            columns.push(parser.expect_word()?);
+            if parser.peek() != Some(&Token::Comma) {
+                break;
+            }
+            parser.next()?;
+        }
+    }
+
+    parser.expect_keyword("FROM")?;
+    let name = parser.expect_word()?;
+    let relation = catalog
+        .get_relation(&name)
+        .ok_or(ParseError::RelationNotFound(name))?;
+    let schema = relation.get_schema();
+    let column_index = |column: &str| {
+        schema
+            .get_column_index(column)
+            .ok_or_else(|| ParseError::ColumnNotFound(column.to_string()))
+    };
+
+    let mut node: Box<dyn QueryPlanNode> =
+        Box::new(SeqScanPlanNode::new(relation.get_id(), schema.clone()));
+
+    if parser.accept_keyword("WHERE") {
+        let column = parser.expect_word()?;
+        let idx = column_index(&column)?;
+        let operator = match parser.next()? {
+            Token::Operator(operator) => operator,
+            token => return Err(ParseError::UnexpectedToken(token.to_string())),
+        };
+        let literal = parser.next()?;
+        let value = parse_literal(literal, &schema.get_attributes()[idx as usize])?;
+        let predicate = comparison(
+            idx,
+            &operator,
+            value.map(|value| value.get_inner()),
+            schema.clone(),
+        );
+
+        let mut filter = FilterPlanNode::new(predicate, schema.clone());
+        set_child(&mut filter, node);
+        node = Box::new(filter);
+    }
+
+    if !columns.is_empty() {
+        let indices = columns
+            .iter()
+            .map(|column| column_index(column))
+            .collect::<Result<Vec<u32>, _>>()?;
+        let attributes = indices
+            .iter()
+            .map(|idx| schema.get_attributes()[*idx as usize].clone())
+            .collect();
+        let mut projection =
+            ProjectionPlanNode::new(schema.clone(), indices, Arc::new(Schema::new(attributes)));
+        set_child(&mut projection, node);
+        node = Box::new(projection);
+    }
+
+    if parser.accept_keyword("LIMIT") {
+        let limit = match parser.next()? {
+            Token::Number(limit) => limit
+                .parse()
+                .map_err(|_| ParseError::UnexpectedToken(limit))?,
+            token => return Err(ParseError::UnexpectedToken(token.to_string())),
+        };
+        let mut limit = LimitPlanNode::new(limit, 0, node.get_output_schema());
+        set_child(&mut limit, node);
+        node = Box::new(limit);
+    }
+    parser.expect_end()?;
+
+    Ok(node)
+}
+
+/// Set the only child of a plan node.
+// Plan nodes are not Send or Sync, but a plan is only built and executed by a single thread.
+#[allow(clippy::arc_with_non_send_sync)]
+fn set_child(node: &mut dyn QueryPlanNode, child: Box<dyn QueryPlanNode>) {
+    node.insert_child(Arc::new(child));
+}
+
+/// Return a predicate comparing the value of a column with a value. As in SQL, a comparison
+/// involving a null value is never satisfied.
+fn comparison(
+    idx: u32,
+    operator: &str,
+    value: Option<InnerValue>,
+    schema: Arc<Schema>,
+) -> Predicate {
+    let accepts: fn(Ordering) -> bool = match operator {
+        "=" => |ord| ord == Ordering::Equal,
+        "<>" | "!=" => |ord| ord != Ordering::Equal,
+        "<" => |ord| ord == Ordering::Less,
+        "<=" => |ord| ord != Ordering::Greater,
+        ">" => |ord| ord == Ordering::Greater,
+        _ => |ord| ord != Ordering::Less,
+    };
+    Box::new(
+        move |record| match (record.get_value(idx, schema.clone()), value.as_ref()) {
+            (Ok(Some(lhs)), Some(rhs)) => accepts(lhs.get_inner().cmp(rhs)),
+            _ => false,
+        },
+    )
+}
+
+/// Custom errors to be used by the statement parser.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// Error to be thrown when a statement contains a character that can't begin a token.
+    InvalidCharacter(char),
+
+    /// Error to be thrown when a string literal has no closing quote.
+    UnterminatedString,
+
+    /// Error to be thrown when a statement ends before it's complete.
+    UnexpectedEnd,
+
+    /// Error to be thrown when a token doesn't fit the statement. Contains the token.
+    UnexpectedToken(String),
+
+    /// Error to be thrown when a column type doesn't name a data type. Contains the type name.
+    InvalidDataType(String),
+
+    /// Error to be thrown when a column is declared more than once. Contains the column name.
+    DuplicateColumn(String),
+
+    /// Error to be thrown when an inserted row doesn't have a value for every column.
+    ArityMismatch { expected: u32, found: u32 },
+
+    /// Error to be thrown when an inserted value can't be coerced to the data type of its
+    /// column. Contains the column name.
+    TypeMismatch(String),
+
+    /// Error to be thrown when a record can't be created from the inserted values, such as when
+    /// a non-nullable column is null.
+    InvalidRecord(RecordErr),
+
+    /// Error to be thrown when a statement names a relation that doesn't exist. Contains the
+    /// name.
+    RelationNotFound(String),
+
+    /// Error to be thrown when a statement names a column that isn't in the relation. Contains
+    /// the column name.
+    ColumnNotFound(String),
+}
+
+impl From<RecordErr> for ParseError {
+    fn from(e: RecordErr) -> Self {
+        ParseError::InvalidRecord(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::replacement::ReplacerAlgorithm;
+    use crate::buffer::BufferManager;
+    use crate::disk::DiskManager;
+    use crate::plan::PlanVariant;
+    use crate::relation::types::Date;
+
+    /// Return the variants of a plan and its descendants, from the root down. Every node is
+    /// expected to have at most one child.
+    fn plan_shape(node: &dyn QueryPlanNode) -> Vec<PlanVariant> {
+        let mut shape = vec![node.get_variant()];
+        let mut child = node.get_nth_child(0);
+        while let Some(node) = child {
+            shape.push(node.get_variant());
+            child = node.get_nth_child(0);
+        }
+        shape
+    }
+
+    fn select_catalog() -> SystemCatalog {
+        let buffer_manager =
+            BufferManager::new(16, DiskManager::in_memory(), ReplacerAlgorithm::Slow);
+        let catalog = SystemCatalog::new(Arc::new(buffer_manager));
+        catalog.create_relation("users", insert_schema()).unwrap();
+        catalog
+    }
+
+    #[test]
+    fn test_parse_create_table() {
+        let (name, schema) = parse_create_table(
+            "create table users (
+                id INT PRIMARY KEY SERIAL,
+                name varchar(32) NOT NULL,
+                email VARCHAR,
+                active Boolean
+            );",
+        )
+        .unwrap();
+        assert_eq!(name, "users");
+
+        let attrs = schema.get_attributes();
+        assert_eq!(attrs.len(), 4);
+        assert_eq!(
+            attrs[0],
+            Attribute::new("id", DataType::Int, true, true, false)
+        );
+        assert_eq!(
+            attrs[1],
+            Attribute::new("name", DataType::Varchar, false, false, false).with_max_len(32)
+        );
+        assert_eq!(
+            attrs[2],
+            Attribute::new("email", DataType::Varchar, false, false, true)
+        );
+        assert_eq!(
+            attrs[3],
+            Attribute::new("active", DataType::Boolean, false, false, true)
+        );
+    }
+
+    fn insert_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Attribute::new("id", DataType::Int, true, false, false),
+            Attribute::new("name", DataType::Varchar, false, false, true),
+            Attribute::new("active", DataType::Boolean, false, false, true),
+            Attribute::new("balance", DataType::Real, false, false, true),
+            Attribute::new("joined", DataType::Date, false, false, true),
+        ]))
+    }
+
+    #[test]
+    fn test_parse_insert() {
+        let schema = insert_schema();
+        let record = parse_insert(
+            "insert into users values (-7, 'O''Brien', TRUE, 2.5, '2021-02-28');",
+            schema.clone(),
+        )
+        .unwrap();
+
+        let values: Vec<Option<InnerValue>> = (0..schema.attr_len())
+            .map(|idx| {
+                let value = record.get_value(idx, schema.clone()).unwrap();
+                value.map(|value| value.get_inner())
+            })
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                Some(InnerValue::Int(-7)),
+                Some(InnerValue::Varchar("O'Brien".to_string())),
+                Some(InnerValue::Boolean(true)),
+                Some(InnerValue::Real(2.5)),
+                Some(InnerValue::Date(Date::from_ymd(2021, 2, 28).unwrap().0)),
+            ]
+        );
+
+        // Assert that NULL is accepted for nullable columns.
+        let record = parse_insert(
+            "INSERT INTO users VALUES (1, NULL, null, NULL, NULL)",
+            schema.clone(),
+        )
+        .unwrap();
+        assert!(record.is_null(1, schema.clone()).unwrap());
+        assert!(record.is_null(4, schema).unwrap());
+    }
+
+    #[test]
+    fn test_parse_malformed_insert() {
+        let cases = vec![
+            (
+                "INSERT INTO users VALUES (1, 'a', true, 1.0)",
+                ParseError::ArityMismatch {
+                    expected: 5,
+                    found: 4,
+                },
+            ),
+            (
+                "INSERT INTO users VALUES (1, 'a', true, 1.0, '2021-01-01', 2)",
+                ParseError::ArityMismatch {
+                    expected: 5,
+                    found: 6,
+                },
+            ),
+            (
+                "INSERT INTO users VALUES ('1', 'a', true, 1.0, NULL)",
+                ParseError::TypeMismatch("id".to_string()),
+            ),
+            (
+                "INSERT INTO users VALUES (1, 2, true, 1.0, NULL)",
+                ParseError::TypeMismatch("name".to_string()),
+            ),
+            (
+                "INSERT INTO users VALUES (1.5, 'a', true, 1.0, NULL)",
+                ParseError::TypeMismatch("id".to_string()),
+            ),
+            (
+                "INSERT INTO users VALUES (1, 'a', yes, 1.0, NULL)",
+                ParseError::TypeMismatch("active".to_string()),
+            ),
+            (
+                "INSERT INTO users VALUES (1, 'a', true, 1.0, 'yesterday')",
+                ParseError::TypeMismatch("joined".to_string()),
+            ),
+            (
+                "INSERT INTO users VALUES (NULL, 'a', true, 1.0, NULL)",
+                ParseError::InvalidRecord(RecordErr::NotNullable),
+            ),
+            (
+                "INSERT INTO users VALUES (1, 'a)",
+                ParseError::UnterminatedString,
+            ),
+            ("INSERT INTO users VALUES (1,", ParseError::UnexpectedEnd),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(
+                parse_insert(sql, insert_schema()).err(),
+                Some(expected),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_select() {
+        let catalog = select_catalog();
+
+        let plan = parse_select("SELECT * FROM users", &catalog).unwrap();
+        assert_eq!(plan_shape(plan.as_ref()), vec![PlanVariant::SeqScan]);
+        assert_eq!(plan.get_output_schema().attr_len(), 5);
+
+        let plan = parse_select("select name, id from users limit 10;", &catalog).unwrap();
+        assert_eq!(
+            plan_shape(plan.as_ref()),
+            vec![
+                PlanVariant::Limit,
+                PlanVariant::Projection,
+                PlanVariant::SeqScan
+            ]
+        );
+        let schema = plan.get_output_schema();
+        let names: Vec<&str> = schema
+            .get_attributes()
+            .iter()
+            .map(|attr| attr.get_name())
+            .collect();
+        assert_eq!(names, vec!["name", "id"]);
+
+        let plan = parse_select(
+            "SELECT id FROM users WHERE joined >= '2021-01-01' LIMIT 1",
+            &catalog,
+        )
+        .unwrap();
+        assert_eq!(
+            plan_shape(plan.as_ref()),
+            vec![
+                PlanVariant::Limit,
+                PlanVariant::Projection,
+                PlanVariant::Filter,
+                PlanVariant::SeqScan
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_comparison() {
+        let schema = insert_schema();
+        let record = parse_insert(
+            "INSERT INTO users VALUES (5, 'Alice', true, NULL, NULL)",
+            schema.clone(),
+        )
+        .unwrap();
+
+        let satisfies = |operator: &str, value: Option<InnerValue>, idx: u32| {
+            comparison(idx, operator, value, schema.clone())(&record)
+        };
+        assert!(satisfies("=", Some(InnerValue::Int(5)), 0));
+        assert!(!satisfies("<>", Some(InnerValue::Int(5)), 0));
+        assert!(satisfies("!=", Some(InnerValue::Int(4)), 0));
+        assert!(satisfies("<", Some(InnerValue::Int(6)), 0));
+        assert!(satisfies("<=", Some(InnerValue::Int(5)), 0));
+        assert!(!satisfies(">", Some(InnerValue::Int(5)), 0));
+        assert!(satisfies(">=", Some(InnerValue::Int(5)), 0));
+        assert!(satisfies(
+            ">",
+            Some(InnerValue::Varchar("Aaron".to_string())),
+            1
+        ));
+
+        // Comparisons involving null values are never satisfied.
+        assert!(!satisfies("=", None, 0));
+        assert!(!satisfies("<>", Some(InnerValue::Real(1.0)), 3));
+    }
+
+    #[test]
+    fn test_parse_malformed_select() {
+        let catalog = select_catalog();
+        let cases = vec![
+            (
+                "SELECT * FROM missing",
+                ParseError::RelationNotFound("missing".to_string()),
+            ),
+            (
+                "SELECT id, email FROM users",
+                ParseError::ColumnNotFound("email".to_string()),
+            ),
+            (
+                "SELECT * FROM users WHERE email = 'a'",
+                ParseError::ColumnNotFound("email".to_string()),
+            ),
+            (
+                "SELECT * FROM users WHERE id = 'a'",
+                ParseError::TypeMismatch("id".to_string()),
+            ),
+            (
+                "SELECT * FROM users WHERE id 1",
+                ParseError::UnexpectedToken("1".to_string()),
+            ),
+            (
+                "SELECT * FROM users LIMIT -1",
+                ParseError::UnexpectedToken("-1".to_string()),
+            ),
+            (
+                "SELECT FROM users",
+                ParseError::UnexpectedToken("users".to_string()),
+            ),
+            ("SELECT * FROM users WHERE", ParseError::UnexpectedEnd),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(parse_select(sql, &catalog).err(), Some(expected), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_parse_malformed_create_table() {
+        let cases = vec![
+            ("", ParseError::UnexpectedEnd),
+            (
+                "CREATE INDEX foo (id INT)",
+                ParseError::UnexpectedToken("INDEX".to_string()),
+            ),
+            ("CREATE TABLE foo (id INT", ParseError::UnexpectedEnd),
+            (
+                "CREATE TABLE foo ()",
+                ParseError::UnexpectedToken(")".to_string()),
+            ),
+            (
+                "CREATE TABLE foo (id INTEGR)",
+                ParseError::InvalidDataType("INTEGR".to_string()),
+            ),
+            (
+                "CREATE TABLE foo (id INT NOT)",
+                ParseError::UnexpectedToken(")".to_string()),
+            ),
+            (
+                "CREATE TABLE foo (id INT, id BIGINT)",
+                ParseError::DuplicateColumn("id".to_string()),
+            ),
+            (
+                "CREATE TABLE foo (id INT) bar",
+                ParseError::UnexpectedToken("bar".to_string()),
+            ),
+            (
+                "CREATE TABLE foo (id INT) + 1",
+                ParseError::InvalidCharacter('+'),
+            ),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(parse_create_table(sql).unwrap_err(), expected, "{}", sql);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::plan::{PlanVariant, QueryPlanNode};
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::sync::{Arc, Mutex, RwLock};
+
+pub struct ProjectionPlanNode {
+    /// Schema of the records to be projected.
+    input_schema: Arc<Schema>,
+
+    /// Indices of the columns in the input schema that make up each output column, in output
+    /// order.
+    indices: Vec<u32>,
+
+    children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
+    output_schema: Arc<Schema>,
+}
+
+impl ProjectionPlanNode {
+    /// Create a new projection plan node. The output schema must have an attribute for each
+    /// index, with the same data type as the corresponding input attribute.
+    pub fn new(input_schema: Arc<Schema>, indices: Vec<u32>, output_schema: Arc<Schema>) -> Self {
+        Self {
+            input_schema,
+            indices,
+            children: Arc::new(RwLock::new(Vec::new())),
+            output_schema,
+        }
+    }
+
+    pub fn get_input_schema(&self) -> Arc<Schema> {
+        self.input_schema.clone()
+    }
+
+    pub fn get_indices(&self) -> &[u32] {
+        &self.indices
+    }
+}
+
+impl QueryPlanNode for ProjectionPlanNode {
+    fn next(&self) -> Option<Arc<Mutex<Record>>> {
+        todo!()
+    }
+
+    fn get_children(&self) -> Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>> {
+        Arc::clone(&self.children)
+    }
+
+    fn get_output_schema(&self) -> Arc<Schema> {
+        Arc::clone(&self.output_schema)
+    }
+
+    fn get_variant(&self) -> PlanVariant {
+        PlanVariant::Projection
+    }
+}
@@ -3,23 +3,33 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
+use crate::constants::RelationIdT;
 use crate::plan::{PlanVariant, QueryPlanNode};
 use crate::relation::record::Record;
 use crate::relation::Schema;
 use std::sync::{Arc, Mutex, RwLock};
 
 pub struct SeqScanPlanNode {
+    /// Relation scanned by this plan.
+    relation_id: RelationIdT,
+
     children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
     output_schema: Arc<Schema>,
 }
 
 impl SeqScanPlanNode {
-    pub fn new(output_schema: Arc<Schema>) -> Self {
+    pub fn new(relation_id: RelationIdT, output_schema: Arc<Schema>) -> Self {
         Self {
+            relation_id,
             children: Arc::new(RwLock::new(Vec::new())),
             output_schema,
         }
     }
+
+    /// Return the ID of the relation scanned by this plan.
+    pub fn get_relation_id(&self) -> RelationIdT {
+        self.relation_id
+    }
 }
 
 impl QueryPlanNode for SeqScanPlanNode {
@@ -0,0 +1,50 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::plan::{PlanVariant, QueryPlanNode};
+use crate::relation::record::Record;
+use crate::relation::Schema;
+use std::sync::{Arc, Mutex, RwLock};
+
+pub struct SortPlanNode {
+    /// Indices of the columns to sort records by, in order of precedence, and whether each
+    /// column is sorted in ascending order.
+    sort_keys: Vec<(u32, bool)>,
+
+    children: Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>>,
+    output_schema: Arc<Schema>,
+}
+
+impl SortPlanNode {
+    pub fn new(sort_keys: Vec<(u32, bool)>, output_schema: Arc<Schema>) -> Self {
+        Self {
+            sort_keys,
+            children: Arc::new(RwLock::new(Vec::new())),
+            output_schema,
+        }
+    }
+
+    pub fn get_sort_keys(&self) -> &[(u32, bool)] {
+        &self.sort_keys
+    }
+}
+
+impl QueryPlanNode for SortPlanNode {
+    fn next(&self) -> Option<Arc<Mutex<Record>>> {
+        todo!()
+    }
+
+    fn get_children(&self) -> Arc<RwLock<Vec<Arc<Box<dyn QueryPlanNode>>>>> {
+        Arc::clone(&self.children)
+    }
+
+    fn get_output_schema(&self) -> Arc<Schema> {
+        Arc::clone(&self.output_schema)
+    }
+
+    fn get_variant(&self) -> PlanVariant {
+        PlanVariant::Sort
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use std::io::{BufRead, Write};
+
+/// Custom errors to be used when reading CSV.
+#[derive(Debug)]
+pub enum CsvError {
+    /// Error to be thrown when the input can't be read.
+    Io(std::io::Error),
+
+    /// Error to be thrown when the input ends inside of a quoted field.
+    UnterminatedQuote,
+
+    /// Error to be thrown when a row contains an unexpected character, such as a character
+    /// following the closing quote of a field.
+    UnexpectedChar(char),
+}
+
+impl From<std::io::Error> for CsvError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Read the next row of comma-separated fields from the reader. Return None if the reader has
+/// no more rows.
+///
+/// A field may be enclosed in double quotes, in which case it may contain commas, line breaks,
+/// and double quotes (escaped by doubling them). An empty field that isn't quoted is returned as
+/// None, so that a null can be distinguished from an empty string.
+pub fn read_row(reader: &mut impl BufRead) -> Result<Option<Vec<Option<String>>>, CsvError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    let mut chars: Vec<char> = line.chars().collect();
+    let mut fields = Vec::new();
+    let mut i = 0;
+    loop {
+        let mut field = String::new();
+        if chars.get(i) == Some(&'"') {
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some('"') if chars.get(i + 1) == Some(&'"') => {
+                        field.push('"');
+                        i += 2;
+                    }
+                    Some('"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&c) => {
+                        field.push(c);
+                        i += 1;
+                    }
+                    // The quoted field contains a line break, so continue with the next line.
+                    None => {
+                        line.clear();
+                        if reader.read_line(&mut line)? == 0 {
+                            return Err(CsvError::UnterminatedQuote);
+                        }
+                        chars.extend(line.chars());
+                    }
+                }
+            }
+            fields.push(Some(field));
+        } else {
+            while let Some(&c) = chars.get(i) {
+                match c {
+                    ',' | '\r' | '\n' => break,
+                    '"' => return Err(CsvError::UnexpectedChar(c)),
+                    c => field.push(c),
+                }
+                i += 1;
+            }
+            fields.push(match field.is_empty() {
+                true => None,
+                false => Some(field),
+            });
+        }
+
+        // A field is either followed by a comma, or ends the row.
+        match chars.get(i) {
+            Some(',') => i += 1,
+            Some(&c) => match chars[i..].iter().all(|&c| c == '\r' || c == '\n') {
+                true => break,
+                false => return Err(CsvError::UnexpectedChar(c)),
+            },
+            None => break,
+        }
+    }
+    Ok(Some(fields))
+}
+
+/// Write a row of comma-separated fields to the writer, followed by a line break. Fields are
+/// written in the format read by `read_row`: None is written as an empty field, and a field is
+/// quoted if it's empty or contains a comma, double quote, or line break.
+pub fn write_row(writer: &mut impl Write, fields: &[Option<String>]) -> std::io::Result<()> {
+    let mut row = String::new();
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            row.push(',');
+        }
+        if let Some(field) = field {
+            match field.is_empty() || field.contains(&[',', '"', '\r', '\n'][..]) {
+                true => {
+                    row.push('"');
+                    row.push_str(&field.replace('"', "\"\""));
+                    row.push('"');
+                }
+                false => row.push_str(field),
+            }
+        }
+    }
+    row.push('\n');
+    writer.write_all(row.as_bytes())
+}
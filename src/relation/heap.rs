@@ -3,15 +3,21 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
-use crate::buffer::{BufferError, BufferManager};
+use crate::buffer::{BufferError, BufferFrame, BufferManager, DeletePolicy};
+use crate::concurrency::transaction::Transaction;
 use crate::constants::{PageIdT, MAX_RECORD_SIZE};
+use crate::index::IndexError;
+use crate::log::LogOperation;
 
-use crate::relation::record::{Record, RecordId};
+use crate::relation::record::{Record, RecordErr, RecordId};
 
-use crate::page::{PageError, RelationPage};
+use crate::page::{
+    OverflowPage, PageBytes, PageError, RawPage, RelationPage, OVERFLOW_PAGE_CAPACITY,
+};
 
+use std::collections::{BTreeMap, VecDeque};
 use std::convert::From;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// A heap is a collection of pages on disk which corresponds to a given relation.
 /// Pages are connected together as a doubly linked list. Each page contains in its
@@ -22,53 +28,260 @@ pub struct Heap {
 
     /// Buffer manager to request necessary pages for relation operations.
     buffer_manager: Arc<BufferManager>,
+
+    /// Free space map from the ID of each page in the heap to its free space in bytes, or None
+    /// if the map must be rebuilt from the pages. Entries are updated whenever records are
+    /// inserted into or deleted from a page, but are only approximate, since pages may be
+    /// modified concurrently (or by recovery) between an update and its use.
+    free_space: Mutex<Option<BTreeMap<PageIdT, u32>>>,
 }
 
 impl Heap {
     /// Create a new heap for a database relation.
     pub fn new(buffer_manager: Arc<BufferManager>) -> Result<Self, BufferError> {
-        let frame_arc = buffer_manager.create_page()?;
-        let mut frame = frame_arc.write().unwrap();
+        let head_page_id = {
+            let frame_arc = buffer_manager.create_page()?;
+            let mut frame = frame_arc.write().unwrap();
 
-        let head_page_id = match frame.get_mut_page() {
-            Some(page) => {
-                RelationPage::init(page);
-                RelationPage::get_id(page)
+            match frame.get_mut_page() {
+                Some(page) => {
+                    RelationPage::init(page);
+                    RelationPage::get_id(page)
+                }
+                None => panic!("Head frame latch contained no page"),
             }
-            None => panic!("Head frame latch contained no page"),
         };
 
-        buffer_manager.unpin_w(frame);
+        // The creation of heap pages isn't logged, so write the page through to disk.
+        buffer_manager.flush_page(head_page_id)?;
 
         Ok(Self {
             root_id: head_page_id,
             buffer_manager,
+            free_space: Mutex::new(None),
         })
     }
 
+    /// Return an existing heap whose first page has the specified ID, such as a heap that was
+    /// written to disk before the database was reopened.
+    pub fn open(buffer_manager: Arc<BufferManager>, root_id: PageIdT) -> Self {
+        Self {
+            root_id,
+            buffer_manager,
+            free_space: Mutex::new(None),
+        }
+    }
+
+    /// Return the ID of the first page in the heap.
+    pub fn get_root_id(&self) -> PageIdT {
+        self.root_id
+    }
+
+    /// Return the number of pages in the heap, excluding overflow pages.
+    pub fn get_num_pages(&self) -> Result<u32, HeapError> {
+        let mut count = 0;
+        self.for_each_page(|_| count += 1)?;
+        Ok(count)
+    }
+
+    /// Return the number of records in the heap which haven't been deleted (or flagged for
+    /// deletion), by reading the record pointers of every page.
+    pub fn get_num_records(&self) -> Result<u32, HeapError> {
+        let mut count = 0;
+        self.for_each_page(|page| count += RelationPage::get_num_live_records(page))?;
+        Ok(count)
+    }
+
+    /// Apply a function to every page of the heap, from the root page to the last page. Each page
+    /// is only pinned while the function is applied.
+    fn for_each_page(&self, mut f: impl FnMut(&PageBytes)) -> Result<(), HeapError> {
+        let mut page_id = Some(self.root_id);
+        while let Some(pid) = page_id {
+            let frame_arc = self.buffer_manager.fetch_page(pid)?;
+            let frame = frame_arc.read().unwrap();
+
+            let page = frame.get_page().unwrap();
+            if let Err(e) = RelationPage::validate(page) {
+                return Err(e.into());
+            }
+            f(page);
+            page_id = RelationPage::get_next_page_id(page);
+        }
+        Ok(())
+    }
+
+    /// Read every live record in the specified page of the heap, along with the ID of the next
+    /// page in the heap. Records stored in overflow pages are reassembled from their chains.
+    ///
+    /// The page is only pinned while its records are copied out, so a full scan of the heap can
+    /// be performed by calling this method repeatedly, starting from the root page.
+    pub fn scan_page(&self, page_id: PageIdT) -> Result<(Vec<Record>, Option<PageIdT>), HeapError> {
+        self.read_page(page_id, false)
+    }
+
+    /// Read every record in the specified page of the heap that hasn't been deleted, including
+    /// records that are flagged for deletion, along with the ID of the next page in the heap.
+    ///
+    /// A record that is flagged for deletion may be restored by a rollback, so it must be taken
+    /// into account by checks such as primary key uniqueness.
+    pub fn scan_page_with_flagged(
+        &self,
+        page_id: PageIdT,
+    ) -> Result<(Vec<Record>, Option<PageIdT>), HeapError> {
+        self.read_page(page_id, true)
+    }
+
+    /// Read the records in the specified page of the heap, optionally including records that are
+    /// flagged for deletion, along with the ID of the next page in the heap.
+    fn read_page(
+        &self,
+        page_id: PageIdT,
+        with_flagged: bool,
+    ) -> Result<(Vec<Record>, Option<PageIdT>), HeapError> {
+        let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+        let frame = frame_arc.read().unwrap();
+
+        let page = frame.get_page().unwrap();
+
+        // Verify the page header before trusting any of its offsets.
+        if let Err(e) = RelationPage::validate(page) {
+            return Err(e.into());
+        }
+
+        // Records are stored as either a full record or an overflow stub.
+        let mut entries = Vec::new();
+        for slot in 0..RelationPage::get_num_records(page) {
+            match RelationPage::read_record(page, slot) {
+                Ok(record) => entries.push((slot, Ok(record))),
+                Err(PageError::RecordOverflow) => {
+                    let stub = RelationPage::get_overflow_stub(page, slot)
+                        .unwrap()
+                        .unwrap();
+                    entries.push((slot, Err(stub)));
+                }
+                Err(PageError::RecordDeleted)
+                    if with_flagged && RelationPage::is_flagged(page, slot).unwrap() =>
+                {
+                    match RelationPage::get_overflow_stub(page, slot).unwrap() {
+                        Some(stub) => entries.push((slot, Err(stub))),
+                        None => {
+                            let bytes = RelationPage::get_record_bytes(page, slot).unwrap();
+                            let rid = RecordId {
+                                page_id,
+                                slot_index: slot,
+                            };
+                            entries.push((slot, Ok(Record::from_bytes(bytes.to_vec(), rid))));
+                        }
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+        let next_page_id = RelationPage::get_next_page_id(page);
+
+        drop(frame);
+        drop(frame_arc);
+
+        let mut records = Vec::with_capacity(entries.len());
+        for (slot, entry) in entries {
+            match entry {
+                Ok(record) => records.push(record),
+                Err((record_size, first_page_id)) => {
+                    let bytes = self.read_overflow_chain(first_page_id, record_size)?;
+                    let rid = RecordId {
+                        page_id,
+                        slot_index: slot,
+                    };
+                    records.push(Record::from_bytes(bytes, rid));
+                }
+            }
+        }
+
+        Ok((records, next_page_id))
+    }
+
+    /// Return an iterator over every live record in the heap, starting from the root page.
+    pub fn scan(&self) -> HeapIterator<'_> {
+        HeapIterator {
+            heap: self,
+            records: VecDeque::new(),
+            next_page_id: Some(self.root_id),
+        }
+    }
+
     /// Read the specified record from the relation.
+    /// If the record is stored in overflow pages, it is reassembled from the overflow chain.
     pub fn read(&self, rid: RecordId) -> Result<Record, HeapError> {
         let frame_arc = self.buffer_manager.fetch_page(rid.page_id)?;
         let frame = frame_arc.read().unwrap();
 
         let page = frame.get_page().unwrap();
 
-        Ok(RelationPage::read_record(page, rid.slot_index)?)
+        // Verify the page header before trusting any of its offsets.
+        if let Err(e) = RelationPage::validate(page) {
+            return Err(e.into());
+        }
+
+        let result = RelationPage::read_record(page, rid.slot_index);
+        let stub = match result {
+            Err(PageError::RecordOverflow) => {
+                RelationPage::get_overflow_stub(page, rid.slot_index).unwrap()
+            }
+            _ => None,
+        };
+
+        drop(frame);
+        drop(frame_arc);
+
+        match stub {
+            Some((record_size, first_page_id)) => {
+                let bytes = self.read_overflow_chain(first_page_id, record_size)?;
+                Ok(Record::from_bytes(bytes, rid))
+            }
+            None => Ok(result?),
+        }
     }
 
     /// Insert a record into the relation. If there is currently no space available in the buffer
     /// pool to fetch/create pages, return an error.
     ///
-    /// This method traverses the doubly-linked list of pages until it encounters a page that has
-    /// enough space to insert the record. If no page in the heap has enough space, we create a
-    /// new page, insert the record, and append the new page to the end of the linked list.
-    pub fn insert(&self, mut record: Record) -> Result<RecordId, HeapError> {
-        // Assert that the record has not already been allocated and can fit in a page.
+    /// The record is first inserted into a page that the free space map reports as having enough
+    /// space, preferring the pages with the lowest IDs, so that space freed by deletions is
+    /// reused. If the map is stale and none of those pages fit the record, this method traverses
+    /// the doubly-linked list of pages until it encounters a page that has enough space to insert
+    /// the record. If no page in the heap has enough space, we create a new page, insert the
+    /// record, and append the new page to the end of the linked list.
+    ///
+    /// Records that are too large to fit in a single page are written out to a chain of overflow
+    /// pages, and only a small overflow stub referencing the chain is inserted into the heap.
+    pub fn insert(&self, mut record: Record, txn: &Transaction) -> Result<RecordId, HeapError> {
+        // Assert that the record has not already been allocated.
         if record.is_allocated() {
             return Err(HeapError::RecordAlreadyAlloc);
         }
-        if record.len() > MAX_RECORD_SIZE {
-            return Err(HeapError::RecordTooLarge);
+
+        // Spill the record data into overflow pages if it can't fit in a page.
+        let stub = match record.len() > MAX_RECORD_SIZE {
+            true => Some((record.len(), self.write_overflow_chain(record.as_bytes())?)),
+            false => None,
+        };
+
+        // Try the pages that the free space map reports as having enough space. The map may be
+        // stale, in which case the insertions fail (correcting the map) and the heap is traversed
+        // instead.
+        let required = RelationPage::get_required_space(record.len());
+        for page_id in self.find_free_pages(required)? {
+            let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+            let mut frame = frame_arc.write().unwrap();
+            let page = frame.get_mut_page().unwrap();
+            let result = Heap::insert_into_page(page, &mut record, stub);
+            self.set_free_space(page_id, page);
+            if result.is_ok() {
+                self.log_insert(&mut frame, txn, record.get_id().unwrap());
+                frame.set_dirty_flag(true);
+
+                return Ok(record.get_id().unwrap());
+            }
         }
 
         // Traverse the heap.
@@ -82,9 +295,11 @@ impl Heap {
 
             // 2) Attempt to insert the record into the current page.
             // If the insertion was successful, return the newly initialized record ID.
-            if RelationPage::insert_record(page, &mut record).is_ok() {
+            let result = Heap::insert_into_page(page, &mut record, stub);
+            self.set_free_space(page_id, page);
+            if result.is_ok() {
+                self.log_insert(&mut frame, txn, record.get_id().unwrap());
                 frame.set_dirty_flag(true);
-                self.buffer_manager.unpin_w(frame);
 
                 return Ok(record.get_id().unwrap());
             }
@@ -93,15 +308,13 @@ impl Heap {
             // is no next page, create a new page, insert the record, and link the new page to
             // the end of the heap.
             match RelationPage::get_next_page_id(page) {
-                Some(pid) => {
-                    self.buffer_manager.unpin_w(frame);
-                    page_id = pid
-                }
+                Some(pid) => page_id = pid,
                 None => {
                     // RELEASE write latch to current page BEFORE calling buffer manager to prevent
                     // deadlocks.
                     let prev_pid = RelationPage::get_id(page);
-                    self.buffer_manager.unpin_w(frame);
+                    drop(frame);
+                    drop(frame_arc);
 
                     // ACQUIRE write latch to new page, insert record, and add prev page ID.
                     let new_frame_arc = self.buffer_manager.create_page()?;
@@ -111,30 +324,268 @@ impl Heap {
                     let new_pid = RelationPage::get_id(new_page);
                     RelationPage::init(new_page);
 
-                    RelationPage::insert_record(new_page, &mut record).unwrap();
+                    Heap::insert_into_page(new_page, &mut record, stub).unwrap();
                     RelationPage::set_prev_page_id(new_page, prev_pid);
+                    self.set_free_space(new_pid, new_page);
+                    self.log_insert(&mut new_frame, txn, record.get_id().unwrap());
                     new_frame.set_dirty_flag(true);
 
                     // RELEASE write latch to new page.
-                    self.buffer_manager.unpin_w(new_frame);
+                    drop(new_frame);
+                    drop(new_frame_arc);
+
+                    let prev_pid = self.link_page(new_pid, prev_pid)?;
+
+                    // Linking pages into the heap isn't logged, so write both pages through to
+                    // disk. This flushes the log up to the insertion into the new page as well.
+                    self.buffer_manager.flush_page(new_pid)?;
+                    self.buffer_manager.flush_page(prev_pid)?;
+
+                    // Return inserted record ID.
+                    return Ok(record.get_id().unwrap());
+                }
+            }
+        }
+    }
+
+    /// Insert several records into the relation, and return their record IDs in input order.
+    ///
+    /// Unlike inserting the records one at a time, the heap is traversed only once. Each page is
+    /// write-latched once and filled with as many of the remaining records as fit before moving
+    /// on to the next page, and new pages are appended to the end of the heap as needed. If an
+    /// error is returned, the records before the failing record remain inserted.
+    pub fn insert_many(
+        &self,
+        records: Vec<Record>,
+        txn: &Transaction,
+    ) -> Result<Vec<RecordId>, HeapError> {
+        // Assert that none of the records have already been allocated.
+        if records.iter().any(|record| record.is_allocated()) {
+            return Err(HeapError::RecordAlreadyAlloc);
+        }
+
+        // Spill the data of large records into overflow pages before latching any heap page.
+        let mut pending = Vec::with_capacity(records.len());
+        for record in records {
+            let stub = match record.len() > MAX_RECORD_SIZE {
+                true => Some((record.len(), self.write_overflow_chain(record.as_bytes())?)),
+                false => None,
+            };
+            pending.push((record, stub));
+        }
+
+        let mut rids = Vec::with_capacity(pending.len());
+        let mut pending = pending.into_iter().peekable();
+        let mut appended = Vec::new();
 
-                    // ACQUIRE write latch to prev page, and add next page ID.
-                    let prev_frame_arc = self.buffer_manager.fetch_page(prev_pid)?;
-                    let mut prev_frame = prev_frame_arc.write().unwrap();
+        let mut page_id = self.root_id;
+        while pending.peek().is_some() {
+            // 1) Obtain a write latch for the current page's frame.
+            let frame_arc = self.buffer_manager.fetch_page(page_id)?;
+            let mut frame = frame_arc.write().unwrap();
+
+            // 2) Insert records into the current page until it is full.
+            while let Some((record, stub)) = pending.peek_mut() {
+                let page = frame.get_mut_page().unwrap();
+                if Heap::insert_into_page(page, record, *stub).is_err() {
+                    break;
+                }
+                let rid = record.get_id().unwrap();
+                self.log_insert(&mut frame, txn, rid);
+                frame.set_dirty_flag(true);
+                rids.push(rid);
+                pending.next();
+            }
+
+            // 3) Move on to the next page, appending a new page if the current page is the last.
+            let page = frame.get_page().unwrap();
+            self.set_free_space(page_id, page);
+            let next_page_id = RelationPage::get_next_page_id(page);
+            let prev_pid = RelationPage::get_id(page);
+
+            // RELEASE write latch to current page BEFORE calling buffer manager to prevent
+            // deadlocks.
+            drop(frame);
+            drop(frame_arc);
+
+            page_id = match next_page_id {
+                Some(pid) => pid,
+                None if pending.peek().is_some() => {
+                    let (new_pid, prev_pid) = self.append_page(prev_pid)?;
+                    appended.push((new_pid, prev_pid));
+                    new_pid
+                }
+                None => break,
+            };
+        }
 
-                    let prev_page = prev_frame.get_mut_page().unwrap();
+        // Linking pages into the heap isn't logged, so write appended pages and their
+        // predecessors through to disk. This flushes the log up to their insertions as well.
+        for (new_pid, prev_pid) in appended {
+            self.buffer_manager.flush_page(new_pid)?;
+            self.buffer_manager.flush_page(prev_pid)?;
+        }
+
+        Ok(rids)
+    }
 
+    /// Create an empty page and link it to the end of the heap, starting the search for the last
+    /// page from the specified page. Return the IDs of the new page and the page it was linked
+    /// after.
+    fn append_page(&self, prev_pid: PageIdT) -> Result<(PageIdT, PageIdT), HeapError> {
+        // ACQUIRE write latch to new page, and add prev page ID.
+        let new_frame_arc = self.buffer_manager.create_page()?;
+        let mut new_frame = new_frame_arc.write().unwrap();
+
+        let new_page = new_frame.get_mut_page().unwrap();
+        let new_pid = RelationPage::get_id(new_page);
+        RelationPage::init(new_page);
+        RelationPage::set_prev_page_id(new_page, prev_pid);
+        new_frame.set_dirty_flag(true);
+
+        // RELEASE write latch to new page.
+        drop(new_frame);
+        drop(new_frame_arc);
+
+        let prev_pid = self.link_page(new_pid, prev_pid)?;
+        Ok((new_pid, prev_pid))
+    }
+
+    /// Link a new page to the end of the heap, starting the search for the last page from the
+    /// specified page, and return the ID of the page it was linked after. The new page must
+    /// already have the specified page as its previous page.
+    ///
+    /// Another page may have been appended concurrently since the specified page was found to be
+    /// the last page, in which case the new page is linked after the actual last page instead.
+    fn link_page(&self, new_pid: PageIdT, mut prev_pid: PageIdT) -> Result<PageIdT, HeapError> {
+        let initial_prev_pid = prev_pid;
+        loop {
+            // ACQUIRE write latch to prev page, and add next page ID if it is the last page.
+            let prev_frame_arc = self.buffer_manager.fetch_page(prev_pid)?;
+            let mut prev_frame = prev_frame_arc.write().unwrap();
+
+            let prev_page = prev_frame.get_mut_page().unwrap();
+            match RelationPage::get_next_page_id(prev_page) {
+                Some(pid) => {
+                    prev_pid = pid;
+                }
+                None => {
                     RelationPage::set_next_page_id(prev_page, new_pid);
                     prev_frame.set_dirty_flag(true);
+                    break;
+                }
+            }
+        }
 
-                    // RELEASE write latch to prev page.
-                    self.buffer_manager.unpin_w(prev_frame);
+        // Traversals only follow next page IDs, so the previous page ID of the new page can be
+        // fixed up after it is linked.
+        if prev_pid != initial_prev_pid {
+            let new_frame_arc = self.buffer_manager.fetch_page(new_pid)?;
+            let mut new_frame = new_frame_arc.write().unwrap();
+            RelationPage::set_prev_page_id(new_frame.get_mut_page().unwrap(), prev_pid);
+            new_frame.set_dirty_flag(true);
+        }
 
-                    // Return inserted record ID.
-                    return Ok(record.get_id().unwrap());
+        Ok(prev_pid)
+    }
+
+    /// Remove a page from the heap by linking its previous and next pages to each other. The page
+    /// itself is left unmodified.
+    fn unlink_page(&self, prev_pid: PageIdT, next_pid: Option<PageIdT>) -> Result<(), HeapError> {
+        let prev_frame_arc = self.buffer_manager.fetch_page(prev_pid)?;
+        let mut prev_frame = prev_frame_arc.write().unwrap();
+
+        let prev_page = prev_frame.get_mut_page().unwrap();
+        match next_pid {
+            Some(pid) => RelationPage::set_next_page_id(prev_page, pid),
+            None => RelationPage::clear_next_page_id(prev_page),
+        }
+        prev_frame.set_dirty_flag(true);
+
+        drop(prev_frame);
+        drop(prev_frame_arc);
+
+        if let Some(pid) = next_pid {
+            let next_frame_arc = self.buffer_manager.fetch_page(pid)?;
+            let mut next_frame = next_frame_arc.write().unwrap();
+            RelationPage::set_prev_page_id(next_frame.get_mut_page().unwrap(), prev_pid);
+            next_frame.set_dirty_flag(true);
+        }
+
+        Ok(())
+    }
+
+    /// Compact every page of the heap, and deallocate the pages that are left without any
+    /// records, other than the root page. Return the number of pages freed and the old and new
+    /// IDs of the records that were moved.
+    ///
+    /// Compaction removes the slots of deleted records, so every record that follows a deleted
+    /// slot in its page is assigned a new record ID. Record IDs held elsewhere (such as in
+    /// indexes, or in the lock set of a transaction) are invalidated, so no other operation may
+    /// access the heap during a vacuum. Compaction isn't logged, so the remaining pages are
+    /// written through to disk.
+    pub fn vacuum(&self) -> Result<VacuumStats, HeapError> {
+        let mut stats = VacuumStats::default();
+        let mut kept_pids = Vec::new();
+
+        let mut page_id = Some(self.root_id);
+        while let Some(pid) = page_id {
+            // ACQUIRE write latch to page, and compact it.
+            let frame_arc = self.buffer_manager.fetch_page(pid)?;
+            let mut frame = frame_arc.write().unwrap();
+
+            let page = frame.get_mut_page().unwrap();
+            if let Err(e) = RelationPage::validate(page) {
+                return Err(e.into());
+            }
+            let mut moved: Vec<_> = RelationPage::compact(page)
+                .into_iter()
+                .filter(|(old_slot, new_slot)| old_slot != new_slot)
+                .collect();
+            moved.sort_unstable();
+            stats
+                .moved
+                .extend(moved.into_iter().map(|(old_slot, new_slot)| {
+                    let old_rid = RecordId {
+                        page_id: pid,
+                        slot_index: old_slot,
+                    };
+                    let new_rid = RecordId {
+                        page_id: pid,
+                        slot_index: new_slot,
+                    };
+                    (old_rid, new_rid)
+                }));
+
+            let prev_pid = RelationPage::get_prev_page_id(page);
+            page_id = RelationPage::get_next_page_id(page);
+            let is_empty = RelationPage::get_num_records(page) == 0;
+            frame.set_dirty_flag(true);
+
+            // RELEASE write latch to page.
+            drop(frame);
+            drop(frame_arc);
+
+            // The root page identifies the heap, so it is kept even if it is empty.
+            match (is_empty, prev_pid) {
+                (true, Some(prev_pid)) if pid != self.root_id => {
+                    self.unlink_page(prev_pid, page_id)?;
+                    self.buffer_manager
+                        .delete_page(pid, DeletePolicy::Discard)?;
+                    stats.pages_freed += 1;
                 }
+                _ => kept_pids.push(pid),
             }
         }
+
+        // Free space has changed throughout the heap, so rebuild the map when it is next used.
+        *self.free_space.lock().unwrap() = None;
+
+        for pid in kept_pids {
+            self.buffer_manager.flush_page(pid)?;
+        }
+
+        Ok(stats)
     }
 
     /// Update a record in this relation and return the ID of the updated record. If the size of
@@ -143,7 +594,12 @@ impl Heap {
     ///
     /// Argument `record` should be an unallocated Record instance with the same schema as
     /// the record being updated. `rid` specifies the location of the record to be updated.
-    pub fn update(&self, record: Record, rid: RecordId) -> Result<RecordId, HeapError> {
+    pub fn update(
+        &self,
+        record: Record,
+        rid: RecordId,
+        txn: &Transaction,
+    ) -> Result<RecordId, HeapError> {
         if record.is_allocated() {
             return Err(HeapError::RecordAlreadyAlloc);
         }
@@ -152,57 +608,311 @@ impl Heap {
         let mut frame = frame_arc.write().unwrap();
 
         let page = frame.get_mut_page().unwrap();
-        match RelationPage::update_record(page, record.clone(), rid.slot_index) {
-            Ok(_) => {
-                self.buffer_manager.unpin_w(frame);
-                Ok(rid)
-            }
-            Err(e) => match e {
-                PageError::PageOverflow => {
-                    RelationPage::flag_delete_record(page, rid.slot_index)?;
-                    RelationPage::commit_delete_record(page, rid.slot_index)?;
 
-                    self.buffer_manager.unpin_w(frame);
+        // Attempt to update the record in place. Records that are (or will be) stored in
+        // overflow pages are always reallocated.
+        if record.len() <= MAX_RECORD_SIZE {
+            let before = RelationPage::get_record_bytes(page, rid.slot_index)
+                .unwrap_or_default()
+                .to_vec();
+            match RelationPage::update_record(page, record.clone(), rid.slot_index) {
+                Ok(_) => {
+                    self.set_free_space(rid.page_id, page);
+                    let after = record.as_bytes().to_vec();
+                    self.log(&mut frame, txn, LogOperation::Update { rid, before, after });
+                    frame.set_dirty_flag(true);
+                    return Ok(rid);
+                }
+                Err(PageError::PageOverflow) | Err(PageError::RecordOverflow) => {}
+                Err(e) => {
+                    return Err(e.into());
+                }
+            }
+        }
 
-                    let new_id = self.insert(record).unwrap();
+        // Otherwise, delete the old record and insert the new record.
+        let stub = RelationPage::get_overflow_stub(page, rid.slot_index)?;
+        let before = RelationPage::get_record_bytes(page, rid.slot_index)?.to_vec();
+        let result = RelationPage::flag_delete_record(page, rid.slot_index)
+            .and_then(|_| RelationPage::commit_delete_record(page, rid.slot_index));
+        self.set_free_space(rid.page_id, page);
+        if result.is_ok() {
+            self.log(&mut frame, txn, LogOperation::ApplyDelete { rid, before });
+            if stub.is_some() {
+                self.log(&mut frame, txn, LogOperation::FreeOverflow { rid });
+            }
+        }
+        frame.set_dirty_flag(true);
+        drop(frame);
+        drop(frame_arc);
+        result?;
 
-                    Ok(new_id)
-                }
-                _ => Err(e.into()),
-            },
+        if let Some((_, first_page_id)) = stub {
+            self.free_overflow_chain(first_page_id)?;
         }
+
+        self.insert(record, txn)
     }
 
     /// Flag the specified record as deleted.
     /// The record is not actually deleted until the delete operation is committed.
-    pub fn flag_delete(&self, rid: RecordId) -> Result<(), HeapError> {
+    pub fn flag_delete(&self, rid: RecordId, txn: &Transaction) -> Result<(), HeapError> {
+        let frame_arc = self.buffer_manager.fetch_page(rid.page_id)?;
+        let mut frame = frame_arc.write().unwrap();
+
+        let page = frame.get_mut_page().unwrap();
+        let result = RelationPage::flag_delete_record(page, rid.slot_index);
+        if result.is_ok() {
+            let before = RelationPage::get_record_bytes(page, rid.slot_index)
+                .unwrap()
+                .to_vec();
+            self.log(&mut frame, txn, LogOperation::MarkDelete { rid, before });
+        }
+        frame.set_dirty_flag(true);
+
+        Ok(result?)
+    }
+
+    /// Commit a delete operation for the specified record.
+    /// If the record is stored in overflow pages, the overflow pages are deleted as well.
+    pub fn commit_delete(&self, rid: RecordId, txn: &Transaction) -> Result<(), HeapError> {
         let frame_arc = self.buffer_manager.fetch_page(rid.page_id)?;
         let mut frame = frame_arc.write().unwrap();
 
         let page = frame.get_mut_page().unwrap();
-        RelationPage::flag_delete_record(page, rid.slot_index)?;
+        let stub = RelationPage::get_overflow_stub(page, rid.slot_index);
+        let before = RelationPage::get_record_bytes(page, rid.slot_index).map(|b| b.to_vec());
+        let result = RelationPage::commit_delete_record(page, rid.slot_index);
+        self.set_free_space(rid.page_id, page);
+        if let (Ok(_), Ok(before)) = (&result, before) {
+            self.log(&mut frame, txn, LogOperation::ApplyDelete { rid, before });
+            if let Ok(Some(_)) = stub {
+                self.log(&mut frame, txn, LogOperation::FreeOverflow { rid });
+            }
+        }
+        frame.set_dirty_flag(true);
 
-        self.buffer_manager.unpin_w(frame);
+        drop(frame);
+        drop(frame_arc);
+        result?;
+
+        if let Some((_, first_page_id)) = stub? {
+            self.free_overflow_chain(first_page_id)?;
+        }
 
         Ok(())
     }
 
-    /// Commit a delete operation for the specified record.
-    pub fn commit_delete(&self, rid: RecordId) -> Result<(), HeapError> {
+    /// Rollback a delete operation for the specified record, which must be flagged for deletion
+    /// but not yet committed.
+    pub fn rollback_delete(&self, rid: RecordId, txn: &Transaction) -> Result<(), HeapError> {
         let frame_arc = self.buffer_manager.fetch_page(rid.page_id)?;
         let mut frame = frame_arc.write().unwrap();
 
         let page = frame.get_mut_page().unwrap();
-        RelationPage::commit_delete_record(page, rid.slot_index)?;
+        let result = RelationPage::rollback_delete_record(page, rid.slot_index);
+        if result.is_ok() {
+            self.log(&mut frame, txn, LogOperation::RollbackDelete { rid });
+        }
+        frame.set_dirty_flag(true);
+
+        Ok(result?)
+    }
+
+    /// Append a log record for an operation performed on behalf of a transaction on the page in
+    /// the given frame, and stamp the page (and frame) with its LSN, if logging is enabled. This
+    /// must be called while holding the write latch of the frame, so that the page is never
+    /// unpinned with an LSN older than its latest modification.
+    fn log(&self, frame: &mut BufferFrame, txn: &Transaction, operation: LogOperation) {
+        if let Some(log_manager) = self.buffer_manager.get_log_manager() {
+            let lsn = log_manager.append(txn.get_id(), operation);
+            RelationPage::set_lsn(frame.get_mut_page().unwrap(), lsn);
+            frame.set_lsn(lsn);
+        }
+    }
+
+    /// Log the insertion of the specified record into the page in the given frame. The after
+    /// image is the data stored in the slot, which is the overflow stub for records in overflow
+    /// pages.
+    fn log_insert(&self, frame: &mut BufferFrame, txn: &Transaction, rid: RecordId) {
+        let after = RelationPage::get_record_bytes(frame.get_page().unwrap(), rid.slot_index)
+            .unwrap()
+            .to_vec();
+        self.log(frame, txn, LogOperation::Insert { rid, after });
+    }
+
+    /// Return the IDs of the pages that the free space map reports as having at least the
+    /// specified free space, in ascending order. The map is rebuilt from the pages of the heap if
+    /// necessary.
+    fn find_free_pages(&self, required: u32) -> Result<Vec<PageIdT>, HeapError> {
+        if self.free_space.lock().unwrap().is_none() {
+            // Pages are latched while the map is rebuilt, so don't hold the map's lock meanwhile
+            // (the map is updated while pages are latched).
+            let mut map = BTreeMap::new();
+            self.for_each_page(|page| {
+                map.insert(
+                    RelationPage::get_id(page),
+                    RelationPage::get_free_space(page),
+                );
+            })?;
+            self.free_space.lock().unwrap().get_or_insert(map);
+        }
 
-        self.buffer_manager.unpin_w(frame);
+        let free_space = self.free_space.lock().unwrap();
+        Ok(free_space
+            .iter()
+            .flatten()
+            .filter(|(_, &free)| free >= required)
+            .map(|(&page_id, _)| page_id)
+            .collect())
+    }
+
+    /// Record the current free space of a page of the heap in the free space map. The page
+    /// should be latched, so that the free space can't change concurrently.
+    fn set_free_space(&self, page_id: PageIdT, page: &PageBytes) {
+        if let Some(map) = self.free_space.lock().unwrap().as_mut() {
+            map.insert(page_id, RelationPage::get_free_space(page));
+        }
+    }
+
+    /// Insert a record (or its overflow stub, if specified) into the given page.
+    fn insert_into_page(
+        page: &mut PageBytes,
+        record: &mut Record,
+        stub: Option<(u32, PageIdT)>,
+    ) -> Result<(), PageError> {
+        match stub {
+            Some((record_size, first_page_id)) => {
+                let slot = RelationPage::insert_overflow_stub(page, record_size, first_page_id)?;
+                record.allocate(RelationPage::get_id(page), slot);
+                Ok(())
+            }
+            None => RelationPage::insert_record(page, record),
+        }
+    }
+
+    /// Write the given record data out to a newly created chain of overflow pages and return the
+    /// ID of the first page in the chain.
+    fn write_overflow_chain(&self, data: &[u8]) -> Result<PageIdT, HeapError> {
+        // Create the pages back-to-front so that each page can be linked to its successor
+        // without fetching it again.
+        let mut next_page_id = None;
+        for chunk in data.chunks(OVERFLOW_PAGE_CAPACITY as usize).rev() {
+            let frame_arc = match self.buffer_manager.create_page() {
+                Ok(frame_arc) => frame_arc,
+                Err(e) => {
+                    // Release the partially written chain before giving up.
+                    if let Some(pid) = next_page_id {
+                        self.free_overflow_chain(pid)?;
+                    }
+                    return Err(e.into());
+                }
+            };
+            let mut frame = frame_arc.write().unwrap();
+
+            let page = frame.get_mut_page().unwrap();
+            OverflowPage::init(page, chunk).unwrap();
+            if let Some(pid) = next_page_id {
+                OverflowPage::set_next_page_id(page, pid);
+            }
+            next_page_id = Some(RawPage::get_id(page));
+        }
+
+        Ok(next_page_id.unwrap())
+    }
+
+    /// Read the record data stored in the chain of overflow pages starting at the given page.
+    fn read_overflow_chain(
+        &self,
+        first_page_id: PageIdT,
+        record_size: u32,
+    ) -> Result<Vec<u8>, HeapError> {
+        let mut data = Vec::with_capacity(record_size as usize);
+        let mut page_id = Some(first_page_id);
+
+        while let Some(pid) = page_id {
+            let frame_arc = self.buffer_manager.fetch_page(pid)?;
+            let frame = frame_arc.read().unwrap();
+
+            let page = frame.get_page().unwrap();
+            data.extend_from_slice(OverflowPage::get_data(page));
+            page_id = OverflowPage::get_next_page_id(page);
+        }
+
+        if data.len() as u32 != record_size {
+            return Err(HeapError::RecordDNE);
+        }
+
+        Ok(data)
+    }
+
+    /// Delete every page in the chain of overflow pages starting at the given page.
+    fn free_overflow_chain(&self, first_page_id: PageIdT) -> Result<(), HeapError> {
+        let mut page_id = Some(first_page_id);
+
+        while let Some(pid) = page_id {
+            let frame_arc = self.buffer_manager.fetch_page(pid)?;
+            let frame = frame_arc.read().unwrap();
+
+            page_id = OverflowPage::get_next_page_id(frame.get_page().unwrap());
+
+            drop(frame);
+            drop(frame_arc);
+            self.buffer_manager
+                .delete_page(pid, DeletePolicy::Discard)?;
+        }
 
         Ok(())
     }
+}
 
-    /// Rollback a delete operation for the specified record.
-    pub fn rollback_delete(&self, rid: RecordId) -> Result<(), HeapError> {
-        todo!()
+/// Statistics of a vacuum of a heap.
+#[derive(Debug, Default)]
+pub struct VacuumStats {
+    /// Number of empty pages that were removed from the heap and deallocated
+    pub pages_freed: u32,
+
+    /// Old and new IDs of every record that was moved, in the order of the pages of the heap and
+    /// then of their old slot indices
+    pub moved: Vec<(RecordId, RecordId)>,
+}
+
+impl VacuumStats {
+    /// Return the number of records that were moved.
+    pub fn records_moved(&self) -> u32 {
+        self.moved.len() as u32
+    }
+}
+
+/// An iterator over every live record in a heap.
+///
+/// Pages are read one at a time with `Heap::scan_page`, so a page is only pinned while its
+/// records are copied out and no latch is held between calls to `next()`. Records that are
+/// inserted or deleted concurrently may or may not be yielded. Panics if a page of the heap can't
+/// be read.
+pub struct HeapIterator<'a> {
+    heap: &'a Heap,
+
+    /// Records of the most recently read page that have not been yielded yet
+    records: VecDeque<Record>,
+
+    /// ID of the next page to be read, or None if every page has been read
+    next_page_id: Option<PageIdT>,
+}
+
+impl Iterator for HeapIterator<'_> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        loop {
+            if let Some(record) = self.records.pop_front() {
+                return Some(record);
+            }
+            let page_id = self.next_page_id?;
+            let (records, next_page_id) = self.heap.scan_page(page_id).unwrap();
+            self.records = records.into();
+            self.next_page_id = next_page_id;
+        }
     }
 }
 
@@ -213,9 +923,9 @@ pub enum HeapError {
     /// allocated elsewhere on disk.
     RecordAlreadyAlloc,
 
-    /// Error to be thrown when a record is too large to be inserted into the database.
-    /// This error should eventually become obsolete once records of arbitrary size become
-    /// supported.
+    /// Error to be thrown when a record is too large to be inserted into a page.
+    /// Records larger than a single page are stored in overflow pages by the heap, so this error
+    /// should not surface from heap operations.
     RecordTooLarge,
 
     /// Error to be thrown when a record specified with a page ID and slot index does not exist.
@@ -225,11 +935,29 @@ pub enum HeapError {
     /// for deletion and an operation cannot proceed.
     RecordDeleted,
 
+    /// Error to be thrown when a page in the heap has an inconsistent header and can't be read.
+    PageCorrupted,
+
+    /// Error to be thrown when a record to be inserted has a null primary key value.
+    PrimaryKeyNull,
+
+    /// Error to be thrown when a record to be inserted has the same primary key as an existing
+    /// record in the relation.
+    DuplicatePrimaryKey,
+
+    /// Error to be thrown when a record cannot be inserted due to its contents, such as a serial
+    /// value that conflicts with previously assigned values.
+    InvalidRecord(RecordErr),
+
+    /// Error to be thrown when an index of the relation can't be updated for a modified record.
+    IndexFailure(IndexError),
+
     /// Errors to be thrown when the buffer manager encounters a recoverable error.
     BufMgrNoBufFrame,
     BufMgrPagePinned,
     BufMgrPageBufDNE,
     BufMgrPageDiskDNE,
+    BufMgrDiskFailure,
 }
 
 impl From<BufferError> for HeapError {
@@ -239,16 +967,33 @@ impl From<BufferError> for HeapError {
             BufferError::PagePinned => HeapError::BufMgrPagePinned,
             BufferError::PageBufDNE => HeapError::BufMgrPageBufDNE,
             BufferError::PageDiskDNE => HeapError::BufMgrPageDiskDNE,
+            BufferError::Disk(_) => HeapError::BufMgrDiskFailure,
+            BufferError::InvalidPage(_) => HeapError::PageCorrupted,
         }
     }
 }
 
+impl From<RecordErr> for HeapError {
+    fn from(e: RecordErr) -> Self {
+        HeapError::InvalidRecord(e)
+    }
+}
+
+impl From<IndexError> for HeapError {
+    fn from(e: IndexError) -> Self {
+        HeapError::IndexFailure(e)
+    }
+}
+
 impl From<PageError> for HeapError {
     fn from(e: PageError) -> Self {
         match e {
-            PageError::PageOverflow => HeapError::RecordTooLarge,
+            PageError::PageOverflow | PageError::NameTooLong => HeapError::RecordTooLarge,
             PageError::SlotOutOfBounds => HeapError::RecordDNE,
+            PageError::SlotOccupied => HeapError::RecordAlreadyAlloc,
             PageError::RecordDeleted => HeapError::RecordDeleted,
+            PageError::RecordOverflow => HeapError::RecordTooLarge,
+            PageError::InvalidHeader => HeapError::PageCorrupted,
         }
     }
 }
@@ -0,0 +1,249 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A scalar JSON value. Numbers are kept as text, so that they can be parsed exactly into the
+/// data type of an attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+}
+
+/// Custom errors to be used when parsing JSON.
+#[derive(Debug, Eq, PartialEq)]
+pub enum JsonError {
+    /// Error to be thrown when the input ends before the object is complete.
+    UnexpectedEnd,
+
+    /// Error to be thrown when the input contains an unexpected character.
+    UnexpectedChar(char),
+
+    /// Error to be thrown when a value is a nested array or object.
+    NestedValue,
+}
+
+/// Parse a JSON object of scalar values, and return its fields in the order that they appear.
+/// Only flat objects are supported, which is all that's needed to represent a single record.
+pub fn parse_object(json: &str) -> Result<Vec<(String, JsonValue)>, JsonError> {
+    let mut parser = Parser {
+        chars: json.chars().peekable(),
+    };
+    let mut fields = Vec::new();
+
+    parser.expect('{')?;
+    if parser.peek() != Some('}') {
+        loop {
+            let key = parser.parse_string()?;
+            parser.expect(':')?;
+            fields.push((key, parser.parse_value()?));
+            match parser.next()? {
+                ',' => continue,
+                '}' => break,
+                c => return Err(JsonError::UnexpectedChar(c)),
+            }
+        }
+    } else {
+        parser.next()?;
+    }
+
+    // Assert that nothing but whitespace follows the object.
+    match parser.peek() {
+        Some(c) => Err(JsonError::UnexpectedChar(c)),
+        None => Ok(fields),
+    }
+}
+
+/// Append the specified string to a JSON string as a quoted and escaped JSON string.
+pub fn write_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// A recursive-descent parser over the characters of a JSON string.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// Skip whitespace and return the next character without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        while let Some(c) = self.chars.peek() {
+            if !c.is_ascii_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+        self.chars.peek().copied()
+    }
+
+    /// Skip whitespace and consume the next character.
+    fn next(&mut self) -> Result<char, JsonError> {
+        self.peek();
+        self.chars.next().ok_or(JsonError::UnexpectedEnd)
+    }
+
+    /// Skip whitespace and consume the specified character.
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        match self.next()? {
+            c if c == expected => Ok(()),
+            c => Err(JsonError::UnexpectedChar(c)),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, JsonError> {
+        match self.peek().ok_or(JsonError::UnexpectedEnd)? {
+            '"' => Ok(JsonValue::String(self.parse_string()?)),
+            't' => self.parse_literal("true", JsonValue::Bool(true)),
+            'f' => self.parse_literal("false", JsonValue::Bool(false)),
+            'n' => self.parse_literal("null", JsonValue::Null),
+            '-' | '0'..='9' => Ok(JsonValue::Number(self.parse_number()?)),
+            '[' | '{' => Err(JsonError::NestedValue),
+            c => Err(JsonError::UnexpectedChar(c)),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        for expected in literal.chars() {
+            match self.chars.next() {
+                Some(c) if c == expected => continue,
+                Some(c) => return Err(JsonError::UnexpectedChar(c)),
+                None => return Err(JsonError::UnexpectedEnd),
+            }
+        }
+        Ok(value)
+    }
+
+    /// Parse a number, and return its text. The text is verified to follow the JSON grammar:
+    /// an optional minus sign, an integer part without leading zeros, an optional fraction, and
+    /// an optional exponent.
+    fn parse_number(&mut self) -> Result<String, JsonError> {
+        let mut text = String::new();
+        if self.chars.peek() == Some(&'-') {
+            text.push('-');
+            self.chars.next();
+        }
+        match self.chars.next() {
+            Some('0') => text.push('0'),
+            Some(c @ '1'..='9') => {
+                text.push(c);
+                self.take_digits(&mut text);
+            }
+            Some(c) => return Err(JsonError::UnexpectedChar(c)),
+            None => return Err(JsonError::UnexpectedEnd),
+        }
+        if self.chars.peek() == Some(&'.') {
+            text.push('.');
+            self.chars.next();
+            if self.take_digits(&mut text) == 0 {
+                return Err(self.unexpected());
+            }
+        }
+        if let Some(&c @ ('e' | 'E')) = self.chars.peek() {
+            text.push(c);
+            self.chars.next();
+            if let Some(&c @ ('+' | '-')) = self.chars.peek() {
+                text.push(c);
+                self.chars.next();
+            }
+            if self.take_digits(&mut text) == 0 {
+                return Err(self.unexpected());
+            }
+        }
+        Ok(text)
+    }
+
+    /// Consume a run of digits into the specified string, and return the number of digits.
+    fn take_digits(&mut self, text: &mut String) -> usize {
+        let mut count = 0;
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            text.push(c);
+            self.chars.next();
+            count += 1;
+        }
+        count
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.chars.next().ok_or(JsonError::UnexpectedEnd)? {
+                '"' => return Ok(s),
+                '\\' => {
+                    let c = match self.chars.next().ok_or(JsonError::UnexpectedEnd)? {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.parse_unicode_escape()?,
+                        c => return Err(JsonError::UnexpectedChar(c)),
+                    };
+                    s.push(c);
+                }
+                c if (c as u32) < 0x20 => return Err(JsonError::UnexpectedChar(c)),
+                c => s.push(c),
+            }
+        }
+    }
+
+    /// Parse the hex digits of a unicode escape sequence (following "\u"). Characters outside
+    /// of the basic multilingual plane are escaped as a surrogate pair.
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.parse_hex4()?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                self.parse_literal("\\u", JsonValue::Null)?;
+                let low = self.parse_hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(JsonError::UnexpectedChar('u'));
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            code => code,
+        };
+        char::from_u32(code).ok_or(JsonError::UnexpectedChar('u'))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let c = self.chars.next().ok_or(JsonError::UnexpectedEnd)?;
+            let digit = c.to_digit(16).ok_or(JsonError::UnexpectedChar(c))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    /// Return an error for the next character, without consuming it.
+    fn unexpected(&mut self) -> JsonError {
+        match self.chars.peek() {
+            Some(&c) => JsonError::UnexpectedChar(c),
+            None => JsonError::UnexpectedEnd,
+        }
+    }
+}
@@ -3,16 +3,28 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
+pub mod csv;
 pub mod heap;
+pub mod json;
 pub mod record;
 pub mod types;
 
-use crate::constants::RelationIdT;
-use crate::relation::heap::{Heap, HeapError};
-use crate::relation::record::{Record, RecordId};
-use crate::relation::types::{size_of, DataType};
+use crate::concurrency::transaction::{Transaction, WriteRecord, WriteType};
+use crate::constants::{RelationIdT, SYSTEM_TXN_ID};
+use crate::index::Index;
+use crate::io::{read_u32, read_u8, write_str, write_u32, write_u8, IoError};
+use crate::relation::csv::{read_row, write_row, CsvError};
+use crate::relation::heap::{Heap, HeapError, VacuumStats};
+use crate::relation::record::{null_bitmap_size, Record, RecordErr, RecordId};
+use crate::relation::types::{parse_value, size_of, DataType, InnerValue, Value};
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// An index and the key of a record in that index.
+type IndexEntry = (Arc<dyn Index>, Record);
 
 /// Database relation (i.e. table) represented on disk.
 pub struct Relation {
@@ -22,24 +34,77 @@ pub struct Relation {
     /// User-defined name for this relation
     name: String,
 
-    /// Schema for the attributes of this relation
-    schema: Arc<Schema>,
+    /// Schema for the attributes of this relation. The schema is only replaced to register the
+    /// validators of checks that were read from disk.
+    schema: RwLock<Arc<Schema>>,
 
     /// Collection of pages on disk which contain records
     heap: Arc<Heap>,
+
+    /// Next value to be assigned to each serial attribute, indexed by column. This lock is held
+    /// for the duration of an insertion or update so that constraint checks are atomic.
+    serial_counters: Mutex<Vec<i64>>,
+
+    /// Indexes on this relation, which are updated as records are inserted, updated, and deleted
+    indexes: RwLock<Vec<Arc<dyn Index>>>,
+
+    /// Index entries of records that are flagged for deletion, which are removed once the
+    /// deletion is committed
+    pending_deletes: Mutex<HashMap<RecordId, Vec<IndexEntry>>>,
 }
 
 impl Relation {
     /// Initialize a new in-memory representation of a relation.
     pub fn new(id: RelationIdT, name: String, schema: Arc<Schema>, heap: Arc<Heap>) -> Self {
+        let serial_counters = Mutex::new(vec![0; schema.attr_len() as usize]);
         Self {
             id,
             name,
-            schema,
+            schema: RwLock::new(schema),
             heap,
+            serial_counters,
+            indexes: RwLock::new(Vec::new()),
+            pending_deletes: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Initialize an in-memory representation of a relation whose heap already contains records,
+    /// such as a relation that was written to disk before the database was reopened. The counter
+    /// of each serial attribute continues from the largest value in the heap, including records
+    /// that are flagged for deletion.
+    pub fn open(
+        id: RelationIdT,
+        name: String,
+        schema: Arc<Schema>,
+        heap: Arc<Heap>,
+    ) -> Result<Self, HeapError> {
+        let relation = Relation::new(id, name, schema.clone(), heap.clone());
+
+        let serial_indices: Vec<usize> = (0..schema.get_attributes().len())
+            .filter(|&idx| schema.get_attributes()[idx].is_serial())
+            .collect();
+        if serial_indices.is_empty() {
+            return Ok(relation);
+        }
+
+        let mut counters = relation.serial_counters.lock().unwrap();
+        let mut page_id = Some(heap.get_root_id());
+        while let Some(pid) = page_id {
+            let (records, next_page_id) = heap.scan_page_with_flagged(pid)?;
+            for record in records.iter() {
+                for &idx in serial_indices.iter() {
+                    if let Some(value) = record.get_value(idx as u32, schema.clone())? {
+                        counters[idx] = counters[idx].max(serial_to_i64(value)? + 1);
+                    }
+                }
+            }
+            page_id = next_page_id;
+        }
+        drop(counters);
+
+        Ok(relation)
+    }
+
     /// Return the relation ID.
     pub fn get_id(&self) -> RelationIdT {
         self.id
@@ -52,7 +117,85 @@ impl Relation {
 
     /// Return an immutable reference to this relation's schema.
     pub fn get_schema(&self) -> Arc<Schema> {
-        self.schema.clone()
+        self.schema.read().unwrap().clone()
+    }
+
+    /// Register the validators of checks that were read from disk, by replacing the schema of
+    /// this relation with an equal schema whose checks have validators. Return whether the
+    /// schema was replaced, which is only the case if the schemas are equal.
+    pub fn register_checks(&self, schema: Arc<Schema>) -> bool {
+        let mut current = self.schema.write().unwrap();
+        if **current != *schema {
+            return false;
+        }
+        *current = schema;
+        true
+    }
+
+    /// Return the heap which stores the records of this relation.
+    pub fn get_heap(&self) -> Arc<Heap> {
+        self.heap.clone()
+    }
+
+    /// Add an entry for every existing record of this relation to an empty index, and register
+    /// the index so that it is updated by subsequent modifications. Modifications are blocked
+    /// while the index is back-filled, so that no record is missed.
+    pub fn add_index(&self, index: Arc<dyn Index>) -> Result<(), HeapError> {
+        let mut indexes = self.indexes.write().unwrap();
+
+        let meta = index.get_meta();
+        let schema = self.get_schema();
+        let txn = Transaction::new(SYSTEM_TXN_ID);
+        let mut page_id = Some(self.heap.get_root_id());
+        while let Some(pid) = page_id {
+            let (records, next_page_id) = self.heap.scan_page(pid)?;
+            for record in records.iter() {
+                let key = meta.project_key(record, schema.clone())?;
+                index.set(&key, record.get_id().unwrap(), &txn)?;
+            }
+            page_id = next_page_id;
+        }
+
+        indexes.push(index);
+        Ok(())
+    }
+
+    /// Return every index registered on this relation.
+    pub fn get_indexes(&self) -> Vec<Arc<dyn Index>> {
+        self.indexes.read().unwrap().clone()
+    }
+
+    /// Return the number of records in this relation, by walking the pages of its heap. Records
+    /// that are flagged for deletion are not counted. The count is approximate, since records
+    /// may be inserted or deleted concurrently while the pages are walked. Panics if a page of
+    /// the heap can't be read.
+    pub fn approx_row_count(&self) -> u32 {
+        self.heap.get_num_records().unwrap()
+    }
+
+    /// Return the number of pages in the heap of this relation, excluding overflow pages. Panics
+    /// if a page of the heap can't be read.
+    pub fn page_count(&self) -> u32 {
+        self.heap.get_num_pages().unwrap()
+    }
+
+    /// Return an iterator over every live record of this relation. Records that are flagged for
+    /// deletion are not yielded. Panics if a page of the heap can't be read.
+    pub fn scan(&self) -> impl Iterator<Item = Record> + '_ {
+        self.heap.scan()
+    }
+
+    /// Return the number of live records in this relation, by scanning every record. Unlike
+    /// `approx_row_count`, records are read rather than counted from page headers. Panics if a
+    /// page of the heap can't be read.
+    pub fn count(&self) -> u32 {
+        self.scan().count() as u32
+    }
+
+    /// Return whether this relation has no live records. The scan stops at the first record.
+    /// Panics if a page of the heap can't be read.
+    pub fn is_empty(&self) -> bool {
+        self.scan().next().is_none()
     }
 
     /// Read and return a record from this relation.
@@ -61,28 +204,549 @@ impl Relation {
     }
 
     /// Insert a record into this relation. Return the record ID of the inserted record.
-    pub fn insert(&self, record: Record) -> Result<RecordId, HeapError> {
-        self.heap.insert(record)
+    ///
+    /// Null serial values are populated with the next value of the attribute's counter. Return an
+    /// error if a supplied serial value is less than the attribute's counter, or if the primary
+    /// key of the record is null or already exists in the relation.
+    ///
+    /// An entry for the record is added to every registered index. If an index can't be updated,
+    /// the record and its entries in the other indexes are removed and an error is returned. The
+    /// insertion is recorded in the write set of the transaction.
+    pub fn insert(
+        self: &Arc<Self>,
+        mut record: Record,
+        txn: &Transaction,
+    ) -> Result<RecordId, HeapError> {
+        let mut counters = self.serial_counters.lock().unwrap();
+
+        self.assign_serials(&mut counters, &mut record)?;
+        self.check_constraints(&record)?;
+        self.check_primary_key(&record, None)?;
+
+        // Hold the indexes until the insertion is complete, so that an index can't be added
+        // between the heap insertion and the index updates.
+        let indexes = self.indexes.read().unwrap();
+        let keys = self.project_keys(&indexes, &record)?;
+
+        let rid = self.heap.insert(record, txn)?;
+
+        for (idx, (index, key)) in indexes.iter().zip(keys.iter()).enumerate() {
+            if let Err(e) = index.set(key, rid, txn) {
+                for (index, key) in indexes.iter().zip(keys.iter()).take(idx) {
+                    let _ = index.delete(key, rid, txn);
+                }
+                let _ = self.heap.flag_delete(rid, txn);
+                let _ = self.heap.commit_delete(rid, txn);
+                return Err(e.into());
+            }
+        }
+
+        self.record_write(txn, rid, WriteType::Insert);
+        Ok(rid)
     }
 
     /// Update a record in this relation. Return the record ID of the updated record.
-    pub fn update(&self, record: Record, rid: RecordId) -> Result<RecordId, HeapError> {
-        self.heap.update(record, rid)
+    ///
+    /// The new record is checked in the same way as an inserted record, except that its primary
+    /// key may be the primary key of the record being updated. Serial values are not assigned.
+    ///
+    /// If the key of the record in a registered index changes, or the record is moved to a new
+    /// record ID, the entry for the old key is replaced by an entry for the new key. Return an
+    /// error if an index can't be updated, in which case the record remains updated. The update
+    /// is recorded in the write set of the transaction along with the record it replaced.
+    pub fn update(
+        self: &Arc<Self>,
+        record: Record,
+        rid: RecordId,
+        txn: &Transaction,
+    ) -> Result<RecordId, HeapError> {
+        let _counters = self.serial_counters.lock().unwrap();
+
+        self.check_constraints(&record)?;
+        self.check_primary_key(&record, Some(rid))?;
+
+        let old_record = self.heap.read(rid)?;
+        let indexes = self.indexes.read().unwrap();
+        if indexes.is_empty() {
+            let new_rid = self.heap.update(record, rid, txn)?;
+            self.record_write(txn, new_rid, WriteType::Update(old_record));
+            return Ok(new_rid);
+        }
+
+        let old_keys = self.project_keys(&indexes, &old_record)?;
+        let new_keys = self.project_keys(&indexes, &record)?;
+
+        let new_rid = self.heap.update(record, rid, txn)?;
+        self.record_write(txn, new_rid, WriteType::Update(old_record));
+
+        for (index, (old_key, new_key)) in indexes.iter().zip(old_keys.iter().zip(new_keys.iter()))
+        {
+            if new_rid == rid && old_key.equals(new_key, index.get_meta().get_schema()) {
+                continue;
+            }
+            index.delete(old_key, rid, txn)?;
+            index.set(new_key, new_rid, txn)?;
+        }
+
+        Ok(new_rid)
+    }
+
+    /// Update every live record in this relation that satisfies the predicate, replacing it with
+    /// the record returned by `transform`. Return the number of updated records.
+    ///
+    /// Matching records are collected before any record is updated, so records that are moved by
+    /// an update are never visited twice. Return an error if a record can't be updated, in which
+    /// case the records preceding it remain updated. Panics if a page of the heap can't be read.
+    pub fn update_where(
+        self: &Arc<Self>,
+        predicate: impl Fn(&Record) -> bool,
+        transform: impl Fn(&Record) -> Record,
+        txn: &Transaction,
+    ) -> Result<usize, HeapError> {
+        let matches: Vec<Record> = self.scan().filter(|record| predicate(record)).collect();
+        for record in matches.iter() {
+            // The replacement may be derived from the scanned record, so it is unallocated before
+            // the update. .unwrap() ok since scanned records are allocated.
+            let replacement = transform(record).to_unallocated();
+            self.update(replacement, record.get_id().unwrap(), txn)?;
+        }
+        Ok(matches.len())
+    }
+
+    /// Delete every live record in this relation that satisfies the predicate, by flagging the
+    /// deletion of each record. Return the number of deleted records. Deletions on behalf of a
+    /// user transaction are committed when the transaction commits, and other deletions are
+    /// committed immediately.
+    ///
+    /// The IDs of matching records are collected before any record is deleted. Return an error if
+    /// a record can't be deleted, in which case the records preceding it remain deleted. Panics if
+    /// a page of the heap can't be read.
+    pub fn delete_where(
+        self: &Arc<Self>,
+        predicate: impl Fn(&Record) -> bool,
+        txn: &Transaction,
+    ) -> Result<usize, HeapError> {
+        // .unwrap() ok since scanned records are allocated.
+        let rids: Vec<RecordId> = self
+            .scan()
+            .filter(|record| predicate(record))
+            .map(|record| record.get_id().unwrap())
+            .collect();
+        for rid in rids.iter() {
+            self.flag_delete(*rid, txn)?;
+            if txn.get_id() == SYSTEM_TXN_ID {
+                self.commit_delete(*rid, txn)?;
+            }
+        }
+        Ok(rids.len())
     }
 
-    /// Flag a record in this relation for deletion.
-    pub fn flag_delete(&self, rid: RecordId) -> Result<(), HeapError> {
-        self.heap.flag_delete(rid)
+    /// Flag a record in this relation for deletion. The entries for the record in registered
+    /// indexes remain until the deletion is committed. The deletion is recorded in the write set
+    /// of the transaction, so that it is committed or rolled back along with the transaction.
+    pub fn flag_delete(
+        self: &Arc<Self>,
+        rid: RecordId,
+        txn: &Transaction,
+    ) -> Result<(), HeapError> {
+        let indexes = self.indexes.read().unwrap();
+        if indexes.is_empty() {
+            self.heap.flag_delete(rid, txn)?;
+            self.record_write(txn, rid, WriteType::Delete);
+            return Ok(());
+        }
+
+        let keys = self.project_keys(&indexes, &self.heap.read(rid)?)?;
+        self.heap.flag_delete(rid, txn)?;
+
+        let entries = indexes.iter().cloned().zip(keys).collect();
+        self.pending_deletes.lock().unwrap().insert(rid, entries);
+        self.record_write(txn, rid, WriteType::Delete);
+        Ok(())
+    }
+
+    /// Record a modification of this relation in the write set of a transaction. Modifications on
+    /// behalf of the system transaction are never committed or aborted, so they aren't recorded.
+    fn record_write(self: &Arc<Self>, txn: &Transaction, rid: RecordId, write_type: WriteType) {
+        if txn.get_id() == SYSTEM_TXN_ID {
+            return;
+        }
+        txn.append_write_record(WriteRecord {
+            relation: self.clone(),
+            rid,
+            write_type,
+        });
     }
 
-    /// Commit a delete operation for a record in this relation.
-    pub fn commit_delete(&self, rid: RecordId) -> Result<(), HeapError> {
-        self.heap.commit_delete(rid)
+    /// Commit a delete operation for a record in this relation, and remove the entries for the
+    /// record from registered indexes.
+    pub fn commit_delete(&self, rid: RecordId, txn: &Transaction) -> Result<(), HeapError> {
+        self.heap.commit_delete(rid, txn)?;
+
+        let entries = self.pending_deletes.lock().unwrap().remove(&rid);
+        for (index, key) in entries.into_iter().flatten() {
+            index.delete(&key, rid, txn)?;
+        }
+        Ok(())
     }
 
     /// Rollback a delete operation for a record in this relation.
-    pub fn rollback_delete(&self, rid: RecordId) -> Result<(), HeapError> {
-        self.heap.rollback_delete(rid)
+    pub fn rollback_delete(&self, rid: RecordId, txn: &Transaction) -> Result<(), HeapError> {
+        self.heap.rollback_delete(rid, txn)?;
+        self.pending_deletes.lock().unwrap().remove(&rid);
+        Ok(())
+    }
+
+    /// Compact the heap of this relation and deallocate its empty pages, as in `Heap::vacuum`.
+    /// Return the number of pages freed and the old and new IDs of the records that were moved.
+    ///
+    /// The entries of moved records in registered indexes, including records whose deletion is
+    /// pending, are updated to their new record IDs. Insertions, updates, and deletions are
+    /// blocked during a vacuum, but any other record ID held for this relation (such as in the
+    /// lock set of a transaction) is invalidated, so no transaction may be active on the
+    /// relation.
+    #[allow(clippy::readonly_write_lock)]
+    pub fn vacuum(&self) -> Result<VacuumStats, HeapError> {
+        // Updates and deletions hold a read lock on the indexes, so they are blocked by holding a
+        // write lock.
+        let _counters = self.serial_counters.lock().unwrap();
+        let indexes = self.indexes.write().unwrap();
+
+        let stats = self.heap.vacuum()?;
+
+        // Moved records are visited in order of their old slot indices, and records only move to
+        // lower slots, so an entry is never replaced before the entry it collides with is moved.
+        let txn = Transaction::new(SYSTEM_TXN_ID);
+        let mut pending_deletes = self.pending_deletes.lock().unwrap();
+        for &(old_rid, new_rid) in stats.moved.iter() {
+            let (entries, is_pending) = match pending_deletes.remove(&old_rid) {
+                Some(entries) => (entries, true),
+                None if indexes.is_empty() => continue,
+                None => match self.heap.read(new_rid) {
+                    Ok(record) => {
+                        let keys = self.project_keys(&indexes, &record)?;
+                        (indexes.iter().cloned().zip(keys).collect(), false)
+                    }
+                    // Records that were flagged for deletion before an index was added have no
+                    // entries in it.
+                    Err(HeapError::RecordDeleted) => continue,
+                    Err(e) => return Err(e),
+                },
+            };
+
+            for (index, key) in entries.iter() {
+                index.delete(key, old_rid, &txn)?;
+                index.set(key, new_rid, &txn)?;
+            }
+            if is_pending {
+                pending_deletes.insert(new_rid, entries);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Project a record of this relation to a key record for each of the specified indexes.
+    fn project_keys(
+        &self,
+        indexes: &[Arc<dyn Index>],
+        record: &Record,
+    ) -> Result<Vec<Record>, HeapError> {
+        let schema = self.get_schema();
+        let mut keys = Vec::with_capacity(indexes.len());
+        for index in indexes.iter() {
+            keys.push(index.get_meta().project_key(record, schema.clone())?);
+        }
+        Ok(keys)
+    }
+
+    /// Populate null serial values in a record to be inserted, and advance the counter of each
+    /// serial attribute past the value in the record.
+    fn assign_serials(&self, counters: &mut [i64], record: &mut Record) -> Result<(), RecordErr> {
+        let schema = self.get_schema();
+        for (i, attr) in schema.get_attributes().iter().enumerate() {
+            if !attr.is_serial() {
+                continue;
+            }
+            let idx = i as u32;
+            let counter = counters[i];
+
+            let value = match record.get_value(idx, schema.clone())? {
+                Some(value) => {
+                    let value = serial_to_i64(value)?;
+                    if value < counter {
+                        return Err(RecordErr::SerialConflict);
+                    }
+                    value
+                }
+                None => {
+                    let value = serial_from_i64(attr.get_data_type(), counter)?;
+                    record.set_value(idx, Some(value), schema.clone())?;
+                    counter
+                }
+            };
+            counters[i] = value + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Check that the non-nullable attributes of a record to be inserted (or updated) are not
+    /// null, and run the checks of each attribute on its non-null values. The record may not
+    /// have been created with Record::new, so its values are checked again.
+    fn check_constraints(&self, record: &Record) -> Result<(), HeapError> {
+        let schema = self.get_schema();
+        for (idx, attr) in schema.get_attributes().iter().enumerate() {
+            match record.get_value(idx as u32, schema.clone())? {
+                Some(value) => attr.check(value.as_ref())?,
+                None if !attr.is_nullable() => return Err(RecordErr::NotNullable.into()),
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the primary key of a record to be inserted (or updated) is not null and does
+    /// not already exist in the relation, other than in the record with the excluded ID.
+    /// Uniqueness is checked with a full scan of the heap, including records that are flagged
+    /// for deletion, since their deletion may be rolled back.
+    fn check_primary_key(
+        &self,
+        record: &Record,
+        excluded_rid: Option<RecordId>,
+    ) -> Result<(), HeapError> {
+        let schema = self.get_schema();
+        let key_indices: Vec<u32> = (0..schema.attr_len())
+            .filter(|&idx| schema.get_attributes()[idx as usize].is_primary())
+            .collect();
+        if key_indices.is_empty() {
+            return Ok(());
+        }
+
+        let key = self.get_key(record, &key_indices)?;
+        if key.iter().any(|value| value.is_none()) {
+            return Err(HeapError::PrimaryKeyNull);
+        }
+
+        let mut page_id = Some(self.heap.get_root_id());
+        while let Some(pid) = page_id {
+            let (records, next_page_id) = self.heap.scan_page_with_flagged(pid)?;
+            for existing in records.iter() {
+                if existing.get_id() != excluded_rid && self.get_key(existing, &key_indices)? == key
+                {
+                    return Err(HeapError::DuplicatePrimaryKey);
+                }
+            }
+            page_id = next_page_id;
+        }
+
+        Ok(())
+    }
+
+    /// Return the values of a record at the specified column indices.
+    fn get_key(
+        &self,
+        record: &Record,
+        indices: &[u32],
+    ) -> Result<Vec<Option<InnerValue>>, RecordErr> {
+        let schema = self.get_schema();
+        let mut key = Vec::with_capacity(indices.len());
+        for idx in indices {
+            let value = record.get_value(*idx, schema.clone())?;
+            key.push(value.map(|v| v.get_inner()));
+        }
+        Ok(key)
+    }
+}
+
+/// Convert a serial value into a 64-bit integer. Return an error if the value is not an integer.
+fn serial_to_i64(value: Box<dyn Value>) -> Result<i64, RecordErr> {
+    match value.get_inner() {
+        InnerValue::TinyInt(v) => Ok(v as i64),
+        InnerValue::SmallInt(v) => Ok(v as i64),
+        InnerValue::Int(v) => Ok(v as i64),
+        InnerValue::BigInt(v) => Ok(v),
+        _ => Err(RecordErr::ValSchemaMismatch),
+    }
+}
+
+/// Convert a 64-bit integer into a serial value of the specified data type. Return an error if
+/// the data type is not an integer, or if the serial values for the data type are exhausted.
+fn serial_from_i64(data_type: DataType, value: i64) -> Result<Box<dyn Value>, RecordErr> {
+    let result: Option<Box<dyn Value>> = match data_type {
+        DataType::TinyInt => i8::try_from(value)
+            .ok()
+            .map(|v| Box::new(v) as Box<dyn Value>),
+        DataType::SmallInt => i16::try_from(value)
+            .ok()
+            .map(|v| Box::new(v) as Box<dyn Value>),
+        DataType::Int => i32::try_from(value)
+            .ok()
+            .map(|v| Box::new(v) as Box<dyn Value>),
+        DataType::BigInt => Some(Box::new(value)),
+        _ => return Err(RecordErr::ValSchemaMismatch),
+    };
+    result.ok_or(RecordErr::SerialConflict)
+}
+
+/// Import the rows of a CSV file into the specified relation, and return the number of inserted
+/// records. Each field is parsed into the data type of its attribute, and an empty field is null
+/// (see `csv::read_row`).
+///
+/// If `has_header` is true, the first row names the attribute of each field, and attributes that
+/// aren't named are null. Otherwise, each row contains a field for every attribute in the order
+/// of the schema. Rows are inserted as they are read, so the rows preceding an invalid row remain
+/// inserted if an error is returned.
+pub fn import_csv(
+    relation: &Arc<Relation>,
+    mut reader: impl BufRead,
+    has_header: bool,
+) -> Result<usize, ImportError> {
+    let schema = relation.get_schema();
+    let attrs = schema.get_attributes();
+    let mut row_num = 0;
+
+    // Find the index of the attribute of each field.
+    let mut columns: Vec<usize> = (0..attrs.len()).collect();
+    if has_header {
+        row_num += 1;
+        let header = read_row(&mut reader)
+            .map_err(|e| ImportError::from_csv(e, row_num))?
+            .unwrap_or_default();
+        columns.clear();
+        for name in header.into_iter().map(Option::unwrap_or_default) {
+            match schema.get_column_index(&name) {
+                Some(idx) if !columns.contains(&(idx as usize)) => columns.push(idx as usize),
+                _ => return Err(ImportError::InvalidHeader(name)),
+            }
+        }
+    }
+
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let mut count = 0;
+    while let Some(fields) =
+        read_row(&mut reader).map_err(|e| ImportError::from_csv(e, row_num + 1))?
+    {
+        row_num += 1;
+        if fields.len() != columns.len() {
+            return Err(ImportError::FieldCount(row_num));
+        }
+
+        let mut values: Vec<Option<Box<dyn Value>>> = attrs.iter().map(|_| None).collect();
+        for (field, &idx) in fields.into_iter().zip(columns.iter()) {
+            if let Some(text) = field {
+                let attr = &attrs[idx];
+                let value = parse_value(&text, attr.get_data_type())
+                    .map_err(|_| ImportError::InvalidValue(row_num, attr.get_name().to_string()))?;
+                values[idx] = Some(value);
+            }
+        }
+
+        let record = Record::new(values, schema.clone())
+            .map_err(|e| ImportError::InvalidRecord(row_num, e))?;
+        relation.insert(record, &txn)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Custom errors to be used when importing CSV. Rows are numbered from 1, including the header.
+#[derive(Debug)]
+pub enum ImportError {
+    /// Error to be thrown when the input can't be read.
+    Io(std::io::Error),
+
+    /// Error to be thrown when a row isn't valid CSV, such as a row with an unterminated quote.
+    MalformedRow(usize),
+
+    /// Error to be thrown when the header names an unknown attribute, or an attribute more than
+    /// once. Contains the name.
+    InvalidHeader(String),
+
+    /// Error to be thrown when a row has a different number of fields than the header or schema.
+    FieldCount(usize),
+
+    /// Error to be thrown when a field can't be parsed into the data type of its attribute.
+    /// Contains the row number and the name of the attribute.
+    InvalidValue(usize, String),
+
+    /// Error to be thrown when the values of a row aren't a valid record, such as a row with a
+    /// null value for a non-nullable attribute.
+    InvalidRecord(usize, RecordErr),
+
+    /// Error to be thrown when a record can't be inserted into the relation.
+    Heap(HeapError),
+}
+
+impl ImportError {
+    /// Convert an error from reading the specified row into an import error.
+    fn from_csv(e: CsvError, row_num: usize) -> Self {
+        match e {
+            CsvError::Io(e) => Self::Io(e),
+            _ => Self::MalformedRow(row_num),
+        }
+    }
+}
+
+impl From<HeapError> for ImportError {
+    fn from(e: HeapError) -> Self {
+        Self::Heap(e)
+    }
+}
+
+/// Export every live record of the specified relation as a row of a CSV file, and return the
+/// number of written rows. Values are written in the format read by `import_csv`, and nulls are
+/// written as empty fields. If `write_header` is true, a header row with the name of each
+/// attribute is written first. Panics if a page of the relation's heap can't be read.
+pub fn export_csv(
+    relation: &Relation,
+    mut writer: impl Write,
+    write_header: bool,
+) -> Result<usize, ExportError> {
+    let schema = relation.get_schema();
+
+    if write_header {
+        let header: Vec<Option<String>> = schema
+            .get_attributes()
+            .iter()
+            .map(|attr| Some(attr.get_name().to_string()))
+            .collect();
+        write_row(&mut writer, &header)?;
+    }
+
+    let mut count = 0;
+    for record in relation.scan() {
+        let mut fields = Vec::with_capacity(schema.attr_len() as usize);
+        for idx in 0..schema.attr_len() {
+            let value = record.get_value(idx, schema.clone())?;
+            fields.push(value.map(|value| value.get_inner().to_string()));
+        }
+        write_row(&mut writer, &fields)?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+/// Custom errors to be used when exporting CSV.
+#[derive(Debug)]
+pub enum ExportError {
+    /// Error to be thrown when the output can't be written.
+    Io(std::io::Error),
+
+    /// Error to be thrown when a record of the relation can't be decoded.
+    Record(RecordErr),
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<RecordErr> for ExportError {
+    fn from(e: RecordErr) -> Self {
+        Self::Record(e)
     }
 }
 
@@ -95,7 +759,7 @@ impl Relation {
 /// Attributes may include "full_name", "year_enrolled", "field_of_study", each with different
 /// metadata such as the data type, or whether the field is nullable.
 /// The schema is defined as the collection of each defined attribute.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct Schema {
     attributes: Vec<Attribute>,
     byte_len: u32,
@@ -128,10 +792,17 @@ impl Schema {
 
     /// Return the number of bytes of the fixed-length values of a record defined by this schema.
     /// Variable-length values such as varchar are encoded as a fixed-length offset/length pair.
+    /// The null bitmap of the record is NOT included.
     pub fn byte_len(&self) -> u32 {
         self.byte_len
     }
 
+    /// Return the number of bytes of a record defined by this schema, excluding the data of
+    /// variable-length values. This is the size of the null bitmap and the fixed-length values.
+    pub fn record_fixed_len(&self) -> u32 {
+        null_bitmap_size(self.attr_len()) + self.byte_len
+    }
+
     /// Return the index of the column which corresponds to the given attribute.
     /// Attributes can be queried by passing in the name as a string slice.
     pub fn get_column_index(&self, attr_name: &str) -> Option<u32> {
@@ -142,18 +813,212 @@ impl Schema {
         }
         None
     }
+
+    /// Return the attribute with the given name, or None if no such attribute exists.
+    pub fn get_attribute(&self, attr_name: &str) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.get_name() == attr_name)
+    }
+
+    /// Return the attribute at the given column index, or None if the index is out of bounds.
+    pub fn get_attribute_at(&self, idx: u32) -> Option<&Attribute> {
+        self.attributes.get(idx as usize)
+    }
+
+    /// Serialize this schema into bytes.
+    ///
+    /// Data format (number denotes size in bytes):
+    /// +--------------------+-------------+-----+
+    /// | NUM ATTRIBUTES (4) | ATTRIBUTE 1 | ... |
+    /// +--------------------+-------------+-----+
+    ///
+    /// Each attribute is stored as its length-prefixed name followed by its data type,
+    /// constraint flags, decimal scale, varchar maximum length (only meaningful if the maximum
+    /// length flag is set) and length-prefixed default value, encoded by `InnerValue::to_bytes`
+    /// (empty unless the default flag is set), followed by the length-prefixed names of its
+    /// checks:
+    /// +-----------------+------+---------------+-----------+-----------+----------------+
+    /// | NAME LENGTH (4) | NAME | DATA TYPE (1) | FLAGS (1) | SCALE (1) | MAX LENGTH (4) |
+    /// +-----------------+------+---------------+-----------+-----------+----------------+
+    /// +--------------------+---------+----------------+-----------------------+------------+-----+
+    /// | DEFAULT LENGTH (4) | DEFAULT | NUM CHECKS (4) | CHECK NAME LENGTH (4) | CHECK NAME | ... |
+    /// +--------------------+---------+----------------+-----------------------+------------+-----+
+    ///
+    /// Validators of checks are closures, so they aren't serialized. Deserialized checks reject
+    /// every value until their validators are registered again (see
+    /// `SystemCatalog::get_or_create_relation`).
+    pub fn serialize(&self) -> Vec<u8> {
+        let defaults: Vec<Vec<u8>> = self
+            .attributes
+            .iter()
+            .map(|attr| {
+                attr.get_default()
+                    .map(InnerValue::to_bytes)
+                    .unwrap_or_default()
+            })
+            .collect();
+        let size = 4 + self
+            .attributes
+            .iter()
+            .zip(defaults.iter())
+            .map(|(attr, default)| {
+                let checks: usize = attr.get_check_names().iter().map(|c| 4 + c.len()).sum();
+                ATTR_MIN_SIZE + attr.get_name().len() + default.len() + checks
+            })
+            .sum::<usize>();
+        let mut bytes = vec![0; size];
+
+        write_u32(&mut bytes, 0, self.attr_len()).unwrap();
+        let mut offset = 4;
+        for (attr, default) in self.attributes.iter().zip(defaults.iter()) {
+            let name = attr.get_name();
+            write_u32(&mut bytes, offset, name.len() as u32).unwrap();
+            write_str(&mut bytes, offset + 4, name).unwrap();
+            offset += 4 + name.len() as u32;
+
+            let mut flags = 0;
+            if attr.is_primary() {
+                flags |= ATTR_PRIMARY_FLAG;
+            }
+            if attr.is_serial() {
+                flags |= ATTR_SERIAL_FLAG;
+            }
+            if attr.is_nullable() {
+                flags |= ATTR_NULLABLE_FLAG;
+            }
+            if attr.get_max_len().is_some() {
+                flags |= ATTR_MAX_LEN_FLAG;
+            }
+            if attr.get_default().is_some() {
+                flags |= ATTR_DEFAULT_FLAG;
+            }
+            write_u8(&mut bytes, offset, attr.get_data_type() as u8).unwrap();
+            write_u8(&mut bytes, offset + 1, flags).unwrap();
+            write_u8(&mut bytes, offset + 2, attr.get_scale()).unwrap();
+            write_u32(&mut bytes, offset + 3, attr.get_max_len().unwrap_or(0)).unwrap();
+            offset += 7;
+
+            write_u32(&mut bytes, offset, default.len() as u32).unwrap();
+            let start = (offset + 4) as usize;
+            bytes[start..start + default.len()].copy_from_slice(default);
+            offset += 4 + default.len() as u32;
+
+            let checks = attr.get_check_names();
+            write_u32(&mut bytes, offset, checks.len() as u32).unwrap();
+            offset += 4;
+            for check in checks {
+                write_u32(&mut bytes, offset, check.len() as u32).unwrap();
+                write_str(&mut bytes, offset + 4, check).unwrap();
+                offset += 4 + check.len() as u32;
+            }
+        }
+        bytes
+    }
+
+    /// Deserialize a schema from bytes written by `Schema::serialize`. Return an error if the
+    /// bytes are truncated or don't describe a valid schema.
+    pub fn deserialize(bytes: &[u8]) -> Result<Schema, SchemaError> {
+        let num_attrs = read_u32(bytes, 0)?;
+
+        // Each attribute takes up at least ATTR_MIN_SIZE bytes, so a larger count can't be valid.
+        if num_attrs as usize > bytes.len() / ATTR_MIN_SIZE {
+            return Err(SchemaError::Truncated);
+        }
+
+        let mut attributes = Vec::with_capacity(num_attrs as usize);
+        let mut offset = 4;
+        for _ in 0..num_attrs {
+            let len = read_u32(bytes, offset)?;
+            let name = bytes
+                .get((offset + 4) as usize..)
+                .and_then(|rest| rest.get(..len as usize))
+                .ok_or(SchemaError::Truncated)?;
+            let name = std::str::from_utf8(name).map_err(|_| SchemaError::InvalidName)?;
+            offset += 4 + len;
+
+            let data_type = DataType::try_from(read_u8(bytes, offset)?)
+                .map_err(|_| SchemaError::InvalidDataType)?;
+            let flags = read_u8(bytes, offset + 1)?;
+            let scale = read_u8(bytes, offset + 2)?;
+            let max_len = read_u32(bytes, offset + 3)?;
+            offset += 7;
+
+            let default_len = read_u32(bytes, offset)?;
+            let default = bytes
+                .get((offset + 4) as usize..)
+                .and_then(|rest| rest.get(..default_len as usize))
+                .ok_or(SchemaError::Truncated)?;
+            offset += 4 + default_len;
+
+            let num_checks = read_u32(bytes, offset)?;
+            offset += 4;
+            let mut checks = Vec::new();
+            for _ in 0..num_checks {
+                let len = read_u32(bytes, offset)?;
+                let check = bytes
+                    .get((offset + 4) as usize..)
+                    .and_then(|rest| rest.get(..len as usize))
+                    .ok_or(SchemaError::Truncated)?;
+                let check = std::str::from_utf8(check).map_err(|_| SchemaError::InvalidName)?;
+                checks.push(check);
+                offset += 4 + len;
+            }
+
+            let attr = Attribute::new(
+                name,
+                data_type,
+                flags & ATTR_PRIMARY_FLAG != 0,
+                flags & ATTR_SERIAL_FLAG != 0,
+                flags & ATTR_NULLABLE_FLAG != 0,
+            );
+            let mut attr = attr.with_scale(scale);
+            if flags & ATTR_MAX_LEN_FLAG != 0 {
+                attr = attr.with_max_len(max_len);
+            }
+            if flags & ATTR_DEFAULT_FLAG != 0 {
+                let default = InnerValue::from_bytes(default, data_type)
+                    .map_err(|_| SchemaError::InvalidDefault)?;
+                attr = attr.with_default(default.into_value());
+            }
+            for check in checks {
+                attr = attr.with_unregistered_check(check);
+            }
+            attributes.push(attr);
+        }
+        Ok(Schema::new(attributes))
+    }
 }
 
+/// Bit flags for the constraints of a serialized attribute.
+const ATTR_PRIMARY_FLAG: u8 = 1;
+const ATTR_SERIAL_FLAG: u8 = 1 << 1;
+const ATTR_NULLABLE_FLAG: u8 = 1 << 2;
+const ATTR_MAX_LEN_FLAG: u8 = 1 << 3;
+const ATTR_DEFAULT_FLAG: u8 = 1 << 4;
+
+/// Size in bytes of a serialized attribute with an empty name, no default value and no checks.
+const ATTR_MIN_SIZE: usize = 19;
+
 /// An attribute describes details about a single column in a record, such as its name, data
 /// type, and whether it can be null.
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Attribute {
     name: String,
     data_type: DataType,
     primary: bool,
     serial: bool,
     nullable: bool,
+    scale: u8,
+    max_len: Option<u32>,
+
+    /// Value to be stored when a record omits this attribute.
+    default: Option<InnerValue>,
+
+    /// Constraints that non-null values of this attribute must satisfy. Only the names of checks
+    /// are persisted in schema pages, since validators are closures.
+    checks: Vec<Check>,
 }
 
 impl Attribute {
@@ -170,9 +1035,33 @@ impl Attribute {
             primary,
             serial,
             nullable,
+            scale: 0,
+            max_len: None,
+            default: None,
+            checks: Vec::new(),
         }
     }
 
+    /// Set the number of digits after the decimal point for a decimal attribute.
+    pub fn with_scale(mut self, scale: u8) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the maximum length in bytes of the values of a varchar attribute. Longer values are
+    /// rejected when a record is created.
+    pub fn with_max_len(mut self, max_len: u32) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Set the value to be stored when a record omits this attribute. The value must have the
+    /// data type of the attribute, or records omitting it can't be created.
+    pub fn with_default(mut self, value: Box<dyn Value>) -> Self {
+        self.default = Some(value.get_inner());
+        self
+    }
+
     pub fn get_name(&self) -> &str {
         self.name.as_str()
     }
@@ -192,4 +1081,245 @@ impl Attribute {
     pub fn is_nullable(&self) -> bool {
         self.nullable
     }
+
+    pub fn get_scale(&self) -> u8 {
+        self.scale
+    }
+
+    pub fn get_max_len(&self) -> Option<u32> {
+        self.max_len
+    }
+
+    pub fn get_default(&self) -> Option<&InnerValue> {
+        self.default.as_ref()
+    }
+
+    /// Add a named constraint that non-null values of this attribute must satisfy, such as
+    /// "age >= 0".
+    pub fn with_check<F>(mut self, name: &str, validator: F) -> Self
+    where
+        F: Fn(&dyn Value) -> bool + Send + Sync + 'static,
+    {
+        self.checks.push(Check {
+            name: name.to_string(),
+            validator: Some(Arc::new(validator)),
+        });
+        self
+    }
+
+    /// Add a named constraint whose validator isn't known, such as a check read from disk.
+    /// Every value is rejected by the check until its validator is registered again.
+    fn with_unregistered_check(mut self, name: &str) -> Self {
+        self.checks.push(Check {
+            name: name.to_string(),
+            validator: None,
+        });
+        self
+    }
+
+    /// Return the names of the checks of this attribute, in the order that they are run.
+    pub fn get_check_names(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .map(|check| check.name.as_str())
+            .collect()
+    }
+
+    /// Return whether any check of this attribute was read from disk without its validator.
+    pub fn has_unregistered_checks(&self) -> bool {
+        self.checks.iter().any(|check| check.validator.is_none())
+    }
+
+    /// Run the constraints of this attribute on a non-null value. Return an error naming the
+    /// first constraint that isn't satisfied, or whose validator hasn't been registered.
+    pub fn check(&self, value: &dyn Value) -> Result<(), RecordErr> {
+        for check in self.checks.iter() {
+            match &check.validator {
+                Some(validator) if validator(value) => {}
+                Some(_) => return Err(RecordErr::CheckViolation(check.name.clone())),
+                None => return Err(RecordErr::CheckUnregistered(check.name.clone())),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Function returning whether a value satisfies a check.
+type Validator = dyn Fn(&dyn Value) -> bool + Send + Sync;
+
+/// A named constraint on the values of an attribute. Checks are compared by name, since
+/// validators can't be compared.
+#[derive(Clone)]
+struct Check {
+    name: String,
+
+    /// Validator of the check, or None if the check was read from disk and its validator hasn't
+    /// been registered again.
+    validator: Option<Arc<Validator>>,
+}
+
+impl std::fmt::Debug for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Check({})", self.name)
+    }
+}
+
+impl PartialEq for Check {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Check {}
+
+/// Custom errors to be used by schemas.
+#[derive(Debug, Eq, PartialEq)]
+pub enum SchemaError {
+    /// Error to be thrown when serialized bytes end before the schema is complete.
+    Truncated,
+
+    /// Error to be thrown when a serialized attribute or check name isn't valid UTF-8.
+    InvalidName,
+
+    /// Error to be thrown when a serialized attribute has an unknown data type.
+    InvalidDataType,
+
+    /// Error to be thrown when the serialized default value of an attribute can't be decoded
+    /// as its data type.
+    InvalidDefault,
+}
+
+impl From<IoError> for SchemaError {
+    fn from(_: IoError) -> Self {
+        SchemaError::Truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relation::types::Decimal;
+
+    fn assert_attributes_eq(actual: &[Attribute], expected: &[Attribute]) {
+        assert_eq!(actual.len(), expected.len());
+        for (attr, expected) in actual.iter().zip(expected.iter()) {
+            assert_eq!(attr.get_name(), expected.get_name());
+            assert_eq!(attr.get_data_type(), expected.get_data_type());
+            assert_eq!(attr.is_primary(), expected.is_primary());
+            assert_eq!(attr.is_serial(), expected.is_serial());
+            assert_eq!(attr.is_nullable(), expected.is_nullable());
+            assert_eq!(attr.get_scale(), expected.get_scale());
+            assert_eq!(attr.get_max_len(), expected.get_max_len());
+            assert_eq!(attr.get_default(), expected.get_default());
+            assert_eq!(attr.get_check_names(), expected.get_check_names());
+        }
+    }
+
+    fn mixed_schema() -> Schema {
+        Schema::new(vec![
+            Attribute::new("id", DataType::Int, true, true, false),
+            Attribute::new("active", DataType::Boolean, false, false, false)
+                .with_default(Box::new(true)),
+            Attribute::new("name", DataType::Varchar, false, false, true)
+                .with_max_len(64)
+                .with_default(Box::new("n/a".to_string()))
+                .with_check("name_not_empty", |value| {
+                    value.get_inner() != InnerValue::Varchar(String::new())
+                }),
+            Attribute::new("price", DataType::Decimal, false, false, true)
+                .with_scale(2)
+                .with_default(Box::new(Decimal::new(1999, 2))),
+            Attribute::new("créé", DataType::Timestamp, false, false, true),
+        ])
+    }
+
+    #[test]
+    fn test_schema_get_attribute() {
+        let schema = mixed_schema();
+
+        // Assert that an existing column resolves to its full attribute.
+        let attr = schema.get_attribute("price").unwrap();
+        assert_eq!(attr.get_name(), "price");
+        assert_eq!(attr.get_data_type(), DataType::Decimal);
+        assert!(attr.is_nullable());
+        assert_eq!(attr.get_scale(), 2);
+
+        let idx = schema.get_column_index("price").unwrap();
+        assert_eq!(schema.get_attribute_at(idx).unwrap().get_name(), "price");
+
+        // Assert that a missing column doesn't resolve.
+        assert!(schema.get_attribute("missing").is_none());
+        assert!(schema.get_attribute_at(schema.attr_len()).is_none());
+    }
+
+    #[test]
+    fn test_schema_record_fixed_len() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Boolean, false, false, false),
+            Attribute::new("bar", DataType::SmallInt, false, false, false),
+            Attribute::new("baz", DataType::BigInt, false, false, true),
+        ]));
+        let record = Record::new(
+            vec![Some(Box::new(true)), Some(Box::new(7_i16)), None],
+            schema.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(schema.record_fixed_len(), record.len());
+        assert_eq!(schema.record_fixed_len(), 1 + schema.byte_len());
+    }
+
+    #[test]
+    fn test_schema_serialize_round_trip() {
+        let schema = mixed_schema();
+        let bytes = schema.serialize();
+
+        let result = Schema::deserialize(&bytes).unwrap();
+        assert_attributes_eq(result.get_attributes(), schema.get_attributes());
+        assert_eq!(result.byte_len(), schema.byte_len());
+
+        // Assert that deserialized checks reject values until their validators are registered.
+        let name = "x".to_string();
+        assert!(schema.get_attributes()[2].check(&name).is_ok());
+        assert!(result.get_attributes()[2].has_unregistered_checks());
+        assert_eq!(
+            result.get_attributes()[2].check(&name),
+            Err(RecordErr::CheckUnregistered("name_not_empty".to_string()))
+        );
+
+        // An empty schema is serialized as an attribute count of 0.
+        let empty = Schema::new(vec![]).serialize();
+        assert_eq!(empty, vec![0; 4]);
+        assert_eq!(Schema::deserialize(&empty).unwrap().attr_len(), 0);
+    }
+
+    #[test]
+    fn test_schema_deserialize_invalid() {
+        let bytes = mixed_schema().serialize();
+
+        // Assert that every truncation of the bytes is rejected.
+        for len in 0..bytes.len() {
+            assert_eq!(
+                Schema::deserialize(&bytes[..len]).unwrap_err(),
+                SchemaError::Truncated
+            );
+        }
+
+        // Assert that an attribute count larger than the bytes can hold is rejected.
+        let mut corrupt = bytes.clone();
+        corrupt[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            Schema::deserialize(&corrupt).unwrap_err(),
+            SchemaError::Truncated
+        );
+
+        // Assert that an unknown data type is rejected. The data type of the first attribute
+        // follows its 2-byte name.
+        let mut corrupt = bytes;
+        corrupt[10] = 0xff;
+        assert_eq!(
+            Schema::deserialize(&corrupt).unwrap_err(),
+            SchemaError::InvalidDataType
+        );
+    }
 }
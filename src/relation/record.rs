@@ -3,28 +3,32 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
-use crate::bitmap::{get_nth_bit, set_nth_bit};
+use crate::bitmap::{clear_nth_bit_in_bytes, get_nth_bit_in_bytes, set_nth_bit_in_bytes};
 use crate::constants::{PageIdT, RecordSlotIdT};
 use crate::io::{
-    read_bool, read_f32, read_i16, read_i32, read_i64, read_i8, read_str, read_u32, read_u64,
-    write_bool, write_f32, write_i16, write_i32, write_i64, write_i8, write_str, write_u32,
-    write_u64, IoError,
+    read_bool, read_f32, read_i16, read_i32, read_i64, read_i8, read_str, read_u32, write_str,
+    write_u32, IoError,
 };
-use crate::relation::types::{size_of, DataType, InnerValue, Value};
-use crate::relation::Schema;
+use crate::relation::json::{self, JsonValue};
+use crate::relation::types::{size_of, DataType, Date, Decimal, InnerValue, Timestamp, Value};
+use crate::relation::{Attribute, Schema};
+use std::str::FromStr;
 use std::sync::Arc;
 
 /// Constants for record offsets.
-pub const NULL_BITMAP_SIZE: u32 = 8;
 const NULL_BITMAP_OFFSET: u32 = 0;
-const FIXED_VALUES_OFFSET: u32 = NULL_BITMAP_OFFSET + NULL_BITMAP_SIZE;
 
-pub type NullBitmapT = u64;
+/// Return the size of the null bitmap in bytes for a record with the specified number of
+/// attributes. Each attribute is represented by a single bit.
+pub fn null_bitmap_size(attr_len: u32) -> u32 {
+    attr_len.div_ceil(8)
+}
 
 /// A database record with variable-length attributes.
 ///
 /// The initial section of the record contains a null bitmap which represents which attributes
-/// are null and should be ignored.
+/// are null and should be ignored. The bitmap is sized to the number of attributes in the
+/// schema, rounded up to the nearest byte.
 ///
 /// The next section of a record contains fixed-length values. Data types such as numerics,
 /// booleans, and dates are encoded as is, while variable-length data types such as varchar are
@@ -49,9 +53,6 @@ pub struct Record {
 
     /// Raw byte array for this record.
     bytes: Vec<u8>,
-
-    /// A null bitmap that defines which values in the record are null.
-    bitmap: NullBitmapT,
 }
 
 impl Record {
@@ -60,6 +61,9 @@ impl Record {
     /// A newly created record is initially unallocated, and thus does not have a record ID. A
     /// record can be allocated by calling .allocate() with the corresponding page ID and slot
     /// index.
+    ///
+    /// Omitted (None) values are replaced with the default value of their attribute, if it has
+    /// one. Otherwise, they're stored as null.
     pub fn new(
         values: Vec<Option<Box<dyn Value>>>,
         schema: Arc<Schema>,
@@ -69,12 +73,12 @@ impl Record {
             return Err(RecordErr::ValSchemaMismatch);
         }
 
-        // Initialize empty byte vector and null bitmap of new record.
-        let mut bytes: Vec<u8> = vec![0; (NULL_BITMAP_SIZE + schema.byte_len()) as usize];
-        let mut bitmap: NullBitmapT = 0;
+        // Initialize empty byte vector of new record, with space for the null bitmap.
+        let bitmap_size = null_bitmap_size(schema.attr_len());
+        let mut bytes: Vec<u8> = vec![0; schema.record_fixed_len() as usize];
 
         // Byte array address to begin writing values.
-        let mut addr = FIXED_VALUES_OFFSET;
+        let mut addr = NULL_BITMAP_OFFSET + bitmap_size;
 
         // Keep track of metadata to write to variable-length section.
         let mut varchars: Vec<(u32, String)> = Vec::new();
@@ -86,82 +90,43 @@ impl Record {
             .zip(schema.get_attributes().iter())
             .enumerate()
         {
-            match val.as_ref() {
+            // Substitute the default value of the attribute for an omitted value.
+            let default = match val {
+                Some(_) => None,
+                None => attr.get_default().map(|value| value.clone().into_value()),
+            };
+            match val.as_ref().or(default.as_ref()) {
                 Some(value) => {
                     if value.get_data_type() != attr.get_data_type() {
                         return Err(RecordErr::ValSchemaMismatch);
                     }
-                    match value.get_data_type() {
-                        DataType::Boolean => {
-                            if let InnerValue::Boolean(inner) = value.get_inner() {
-                                write_bool(bytes.as_mut_slice(), addr, inner).unwrap();
-                                addr += 1;
-                            } else {
-                                unreachable!();
-                            }
-                        }
-                        DataType::TinyInt => {
-                            if let InnerValue::TinyInt(inner) = value.get_inner() {
-                                write_i8(bytes.as_mut_slice(), addr, inner).unwrap();
-                                addr += 1;
-                            } else {
-                                unreachable!();
-                            }
-                        }
-                        DataType::SmallInt => {
-                            if let InnerValue::SmallInt(inner) = value.get_inner() {
-                                write_i16(bytes.as_mut_slice(), addr, inner).unwrap();
-                                addr += 2;
-                            } else {
-                                unreachable!();
-                            }
-                        }
-                        DataType::Int => {
-                            if let InnerValue::Int(inner) = value.get_inner() {
-                                write_i32(bytes.as_mut_slice(), addr, inner).unwrap();
-                                addr += 4;
-                            } else {
-                                unreachable!();
-                            }
-                        }
-                        DataType::BigInt => {
-                            if let InnerValue::BigInt(inner) = value.get_inner() {
-                                write_i64(bytes.as_mut_slice(), addr, inner).unwrap();
-                                addr += 8;
-                            } else {
-                                unreachable!()
-                            }
-                        }
-                        DataType::Decimal => {
-                            if let InnerValue::Decimal(inner) = value.get_inner() {
-                                write_f32(bytes.as_mut_slice(), addr, inner).unwrap();
-                                addr += 4;
-                            } else {
-                                unreachable!()
-                            }
-                        }
-                        DataType::Varchar => {
-                            if let InnerValue::Varchar(inner) = value.get_inner() {
-                                // Allocate space for offset/length and write the length as a fixed-length
-                                // value for now.
-                                // Offset and actual string data will be handled after all fixed-lengths are
-                                // written.
-                                varchars.push((addr, inner.clone()));
-                                write_u32(bytes.as_mut_slice(), addr + 4, inner.len() as u32)
-                                    .unwrap();
-                                addr += 8; // Increment by length of 2 unsigned 32-bit integers.
-                                var_len += inner.len(); // Increase space needed for variable-length section.
-                            } else {
-                                unreachable!()
+                    attr.check(value.as_ref())?;
+                    match value.get_inner() {
+                        InnerValue::Varchar(inner) => {
+                            if let Some(max_len) = attr.get_max_len() {
+                                if inner.len() > max_len as usize {
+                                    return Err(RecordErr::VarcharTooLong);
+                                }
                             }
+                            // Allocate space for offset/length and write the length as a
+                            // fixed-length value for now.
+                            // Offset and actual string data will be handled after all
+                            // fixed-lengths are written.
+                            write_u32(bytes.as_mut_slice(), addr + 4, inner.len() as u32).unwrap();
+                            var_len += inner.len(); // Increase space needed for variable-length section.
+                            varchars.push((addr, inner));
                         }
+                        _ => write_fixed_value(bytes.as_mut_slice(), addr, value.as_ref(), attr)?,
                     }
+                    addr += size_of(attr.get_data_type());
                 }
                 None => {
-                    if !attr.is_nullable() {
+                    // Serial values may be omitted, since they are populated by the relation
+                    // upon insertion.
+                    if !attr.is_nullable() && !attr.is_serial() {
                         return Err(RecordErr::NotNullable);
                     }
-                    set_nth_bit(&mut bitmap, i as u32).unwrap();
+                    set_nth_bit_in_bytes(bytes.as_mut_slice(), i as u32).unwrap();
                     addr += size_of(attr.get_data_type());
                 }
             }
@@ -176,14 +141,7 @@ impl Record {
             addr += varchar.len() as u32;
         }
 
-        // 3) Write the null bitmap into the byte vector.
-        write_u64(bytes.as_mut_slice(), NULL_BITMAP_OFFSET, bitmap).unwrap();
-
-        Ok(Self {
-            id: None,
-            bytes,
-            bitmap,
-        })
+        Ok(Self { id: None, bytes })
     }
 
     /// Create a record from a byte vector.
@@ -191,15 +149,22 @@ impl Record {
     /// Used to initialize an in-memory representation of a record that has already been
     /// allocated to a relation page.
     pub fn from_bytes(bytes: Vec<u8>, rid: RecordId) -> Self {
-        let bitmap = read_u64(bytes.as_slice(), NULL_BITMAP_OFFSET).unwrap();
-
         Self {
             id: Some(rid),
             bytes,
-            bitmap,
         }
     }
 
+    /// Create an unallocated record from a byte vector.
+    ///
+    /// Used to decode raw record data that isn't tied to a location in a relation page, such as
+    /// a before-image read from the log. The schema is only used to sanity-check the length of
+    /// the byte vector.
+    pub fn from_bytes_unallocated(bytes: Vec<u8>, schema: Arc<Schema>) -> Self {
+        debug_assert!(bytes.len() >= schema.record_fixed_len() as usize);
+        Self { id: None, bytes }
+    }
+
     /// Return the raw byte array for this record.
     pub fn as_bytes(&self) -> &[u8] {
         self.bytes.as_slice()
@@ -223,6 +188,14 @@ impl Record {
         self.id.is_some()
     }
 
+    /// Return an unallocated copy of this record, which can be inserted into a relation.
+    pub fn to_unallocated(&self) -> Record {
+        Self {
+            id: None,
+            bytes: self.bytes.clone(),
+        }
+    }
+
     /// Index the schema and return the corresponding value contained in the Record. Return None
     /// if the value is null. Panic if the specified index is out-of-bounds.
     ///
@@ -247,35 +220,134 @@ impl Record {
             return Ok(None);
         }
 
-        let mut addr = FIXED_VALUES_OFFSET;
-        for (i, attr) in schema.get_attributes().iter().enumerate() {
-            if i == idx as usize {
-                let value: Box<dyn Value> = match attr.get_data_type() {
-                    DataType::Boolean => Box::new(read_bool(self.bytes.as_slice(), addr)?),
-                    DataType::TinyInt => Box::new(read_i8(self.bytes.as_slice(), addr)?),
-                    DataType::SmallInt => Box::new(read_i16(self.bytes.as_slice(), addr)?),
-                    DataType::Int => Box::new(read_i32(self.bytes.as_slice(), addr)?),
-                    DataType::BigInt => Box::new(read_i64(self.bytes.as_slice(), addr)?),
-                    DataType::Decimal => Box::new(read_f32(self.bytes.as_slice(), addr)?),
-                    DataType::Varchar => Box::new({
-                        let offset = read_u32(self.bytes.as_slice(), addr)?;
-                        let length = read_u32(self.bytes.as_slice(), addr + 4)?;
-                        read_str(self.bytes.as_slice(), offset, length)?
-                    }),
-                };
-                return Ok(Some(value));
+        let attr = &schema.get_attributes()[idx as usize];
+        let addr = Record::get_fixed_addr(idx, &schema);
+        let bytes = self.bytes.as_slice();
+
+        let value: Box<dyn Value> = match attr.get_data_type() {
+            DataType::Boolean => Box::new(read_bool(bytes, addr)?),
+            DataType::TinyInt => Box::new(read_i8(bytes, addr)?),
+            DataType::SmallInt => Box::new(read_i16(bytes, addr)?),
+            DataType::Int => Box::new(read_i32(bytes, addr)?),
+            DataType::BigInt => Box::new(read_i64(bytes, addr)?),
+            DataType::Real => Box::new(read_f32(bytes, addr)?),
+            DataType::Decimal => Box::new(Decimal::new(read_i64(bytes, addr)?, attr.get_scale())),
+            DataType::Date => Box::new(Date(read_i32(bytes, addr)?)),
+            DataType::Timestamp => Box::new(Timestamp(read_i64(bytes, addr)?)),
+            DataType::Varchar => Box::new({
+                let offset = read_u32(bytes, addr)?;
+                let length = read_u32(bytes, addr + 4)?;
+                read_str(bytes, offset, length)?
+            }),
+        };
+        Ok(Some(value))
+    }
+
+    /// Return the value contained in the Record for the attribute with the specified name. Return
+    /// None if the value is null, and an error if the schema has no such attribute.
+    pub fn get_value_by_name(
+        &self,
+        name: &str,
+        schema: Arc<Schema>,
+    ) -> Result<Option<Box<dyn Value>>, RecordErr> {
+        match schema.get_column_index(name) {
+            Some(idx) => self.get_value(idx, schema),
+            None => Err(RecordErr::ColumnNotFound),
+        }
+    }
+
+    /// Index the schema and replace the corresponding value contained in the Record, where this
+    /// record is an instance of `schema`. The record ID is preserved.
+    ///
+    /// Fixed-length values are overwritten in place. Setting a varchar value rebuilds the
+    /// variable-length section of the record.
+    pub fn set_value(
+        &mut self,
+        idx: u32,
+        value: Option<Box<dyn Value>>,
+        schema: Arc<Schema>,
+    ) -> Result<(), RecordErr> {
+        if idx >= schema.attr_len() {
+            return Err(RecordErr::IndexOutOfBounds);
+        }
+
+        let attr = &schema.get_attributes()[idx as usize];
+        let value = match value {
+            Some(value) => value,
+            None => return self.set_null(idx, schema),
+        };
+        if value.get_data_type() != attr.get_data_type() {
+            return Err(RecordErr::ValSchemaMismatch);
+        }
+
+        if attr.get_data_type() != DataType::Varchar {
+            let addr = Record::get_fixed_addr(idx, &schema);
+            write_fixed_value(self.bytes.as_mut_slice(), addr, value.as_ref(), attr)?;
+
+            let bitmap = &mut self.bytes[NULL_BITMAP_OFFSET as usize..];
+            clear_nth_bit_in_bytes(bitmap, idx).unwrap();
+            return Ok(());
+        }
+
+        let mut values = Vec::with_capacity(schema.attr_len() as usize);
+        for i in 0..schema.attr_len() {
+            values.push(self.get_value(i, schema.clone())?);
+        }
+        values[idx as usize] = Some(value);
+
+        let id = self.id;
+        *self = Record::new(values, schema)?;
+        self.id = id;
+
+        Ok(())
+    }
+
+    /// Return whether this record contains the same values as another record, where both records
+    /// are instances of the specified schema. A null value is only equal to another null value.
+    /// Record IDs are ignored.
+    pub fn equals(&self, other: &Record, schema: Arc<Schema>) -> bool {
+        for idx in 0..schema.attr_len() {
+            let lhs = self.get_value(idx, schema.clone());
+            let rhs = other.get_value(idx, schema.clone());
+            match (lhs, rhs) {
+                (Ok(None), Ok(None)) => {}
+                (Ok(Some(l)), Ok(Some(r))) => {
+                    if l.get_inner() != r.get_inner() {
+                        return false;
+                    }
+                }
+                _ => return false,
             }
-            match attr.get_data_type() {
-                DataType::Boolean => addr += 1,
-                DataType::TinyInt => addr += 1,
-                DataType::SmallInt => addr += 2,
-                DataType::Int => addr += 4,
-                DataType::BigInt => addr += 8,
-                DataType::Decimal => addr += 4,
-                DataType::Varchar => addr += 8,
+        }
+        true
+    }
+
+    /// Create a new record containing only the values at the specified indices (in the given
+    /// order), where this record is an instance of `schema`. Null values are preserved. Return an
+    /// error if `out_schema` does not match the data types of the selected columns.
+    pub fn project(
+        &self,
+        indices: &[u32],
+        schema: Arc<Schema>,
+        out_schema: Arc<Schema>,
+    ) -> Result<Record, RecordErr> {
+        if indices.len() as u32 != out_schema.attr_len() {
+            return Err(RecordErr::ValSchemaMismatch);
+        }
+
+        let attrs = schema.get_attributes();
+        let mut values = Vec::with_capacity(indices.len());
+        for (idx, out_attr) in indices.iter().zip(out_schema.get_attributes().iter()) {
+            if *idx >= schema.attr_len() {
+                return Err(RecordErr::IndexOutOfBounds);
             }
+            if attrs[*idx as usize].get_data_type() != out_attr.get_data_type() {
+                return Err(RecordErr::ValSchemaMismatch);
+            }
+            values.push(self.get_value(*idx, schema.clone())?);
         }
-        unreachable!()
+
+        Record::new(values, out_schema)
     }
 
     /// Return the size of this record in bytes.
@@ -283,9 +355,9 @@ impl Record {
         self.bytes.len() as u32
     }
 
-    /// Return the size of this record in bytes.
-    pub fn size(&self) -> u32 {
-        self.bytes.len() as u32
+    /// Return true if this record contains no bytes, false otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
     }
 
     /// Index the schema and return whether the corresponding value contained in the Record is
@@ -304,11 +376,23 @@ impl Record {
             return Err(RecordErr::IndexOutOfBounds);
         }
 
-        let is_null = get_nth_bit(&self.bitmap, idx).unwrap() == 1;
+        let bitmap = &self.bytes[NULL_BITMAP_OFFSET as usize..];
+        let is_null = get_nth_bit_in_bytes(bitmap, idx).unwrap() == 1;
 
         Ok(is_null)
     }
 
+    /// Return the address of the fixed-length section of the value at the specified index.
+    fn get_fixed_addr(idx: u32, schema: &Schema) -> u32 {
+        let attrs = &schema.get_attributes()[..idx as usize];
+        NULL_BITMAP_OFFSET
+            + null_bitmap_size(schema.attr_len())
+            + attrs
+                .iter()
+                .map(|attr| size_of(attr.get_data_type()))
+                .sum::<u32>()
+    }
+
     /// Index the schema and set the corresponding value contained in the Record to null. Panic
     /// if the specified index is out-of-bounds.
     pub fn set_null(&mut self, idx: u32, schema: Arc<Schema>) -> Result<(), RecordErr> {
@@ -321,16 +405,129 @@ impl Record {
             return Err(RecordErr::NotNullable);
         }
 
-        set_nth_bit(&mut self.bitmap, idx).unwrap();
-        write_u64(self.bytes.as_mut_slice(), NULL_BITMAP_OFFSET, self.bitmap).unwrap();
+        let bitmap = &mut self.bytes[NULL_BITMAP_OFFSET as usize..];
+        set_nth_bit_in_bytes(bitmap, idx).unwrap();
 
         Ok(())
     }
+
+    /// Return a JSON object mapping the name of each attribute in the schema to the
+    /// corresponding value contained in the Record, where this record is an instance of `schema`.
+    ///
+    /// Null values are emitted as null, booleans as booleans, and numeric values as numbers.
+    /// Varchars, dates, and timestamps are emitted as strings. Real values that aren't finite
+    /// can't be represented in JSON and are emitted as null.
+    pub fn to_json(&self, schema: Arc<Schema>) -> Result<String, RecordErr> {
+        let mut json = String::from("{");
+        for (idx, attr) in schema.get_attributes().iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+            json::write_string(&mut json, attr.get_name());
+            json.push(':');
+            match self.get_value(idx as u32, schema.clone())? {
+                Some(value) => write_json_value(&mut json, &value.get_inner()),
+                None => json.push_str("null"),
+            }
+        }
+        json.push('}');
+        Ok(json)
+    }
+
+    /// Create a new record from a JSON object mapping attribute names to values, where the
+    /// record is an instance of `schema`. Attributes missing from the object are null.
+    ///
+    /// Values must have the JSON type that `to_json` emits for the attribute's data type: a JSON
+    /// string is not accepted for a numeric attribute, and vice versa.
+    pub fn from_json(json: &str, schema: Arc<Schema>) -> Result<Self, RecordErr> {
+        let fields = json::parse_object(json).map_err(|_| RecordErr::InvalidJson)?;
+        let attrs = schema.get_attributes();
+
+        let mut values: Vec<Option<Box<dyn Value>>> = attrs.iter().map(|_| None).collect();
+        let mut present = vec![false; attrs.len()];
+        for (name, value) in fields {
+            let idx = schema
+                .get_column_index(&name)
+                .ok_or(RecordErr::ColumnNotFound)? as usize;
+            if present[idx] {
+                return Err(RecordErr::InvalidJson);
+            }
+            present[idx] = true;
+            values[idx] = json_to_value(value, attrs[idx].get_data_type())?;
+        }
+
+        Record::new(values, schema)
+    }
+}
+
+/// Append the specified value to a JSON string.
+fn write_json_value(json: &mut String, value: &InnerValue) {
+    match value {
+        InnerValue::Real(val) if !val.is_finite() => json.push_str("null"),
+        InnerValue::Varchar(val) => json::write_string(json, val),
+        InnerValue::Date(_) | InnerValue::Timestamp(_) => {
+            json::write_string(json, &value.to_string())
+        }
+        _ => json.push_str(&value.to_string()),
+    }
+}
+
+/// Convert a JSON value into a value of the specified data type. Return None if the JSON value
+/// is null, and an error if the JSON value has the wrong type or is out of range.
+fn json_to_value(
+    value: JsonValue,
+    data_type: DataType,
+) -> Result<Option<Box<dyn Value>>, RecordErr> {
+    let value: Box<dyn Value> = match (data_type, value) {
+        (_, JsonValue::Null) => return Ok(None),
+        (DataType::Boolean, JsonValue::Bool(val)) => Box::new(val),
+        (DataType::TinyInt, JsonValue::Number(val)) => Box::new(parse_json_text::<i8>(&val)?),
+        (DataType::SmallInt, JsonValue::Number(val)) => Box::new(parse_json_text::<i16>(&val)?),
+        (DataType::Int, JsonValue::Number(val)) => Box::new(parse_json_text::<i32>(&val)?),
+        (DataType::BigInt, JsonValue::Number(val)) => Box::new(parse_json_text::<i64>(&val)?),
+        (DataType::Real, JsonValue::Number(val)) => Box::new(parse_json_text::<f32>(&val)?),
+        (DataType::Decimal, JsonValue::Number(val)) => Box::new(parse_json_text::<Decimal>(&val)?),
+        (DataType::Varchar, JsonValue::String(val)) => Box::new(val),
+        (DataType::Date, JsonValue::String(val)) => Box::new(parse_json_text::<Date>(&val)?),
+        (DataType::Timestamp, JsonValue::String(val)) => {
+            Box::new(parse_json_text::<Timestamp>(&val)?)
+        }
+        _ => return Err(RecordErr::ValSchemaMismatch),
+    };
+    Ok(Some(value))
+}
+
+/// Parse the text of a JSON value into the specified type.
+fn parse_json_text<T: FromStr>(text: &str) -> Result<T, RecordErr> {
+    text.parse().map_err(|_| RecordErr::ValSchemaMismatch)
+}
+
+/// Write a fixed-length value at the specified address of a record's byte array. The value is
+/// assumed to have the same data type as the attribute. Variable-length values are not handled.
+fn write_fixed_value(
+    bytes: &mut [u8],
+    addr: u32,
+    value: &dyn Value,
+    attr: &Attribute,
+) -> Result<(), RecordErr> {
+    match value.get_inner() {
+        InnerValue::Decimal { mantissa, scale } => {
+            // Decimals are stored with the scale of the attribute.
+            let mantissa = Decimal::new(mantissa, scale)
+                .rescale(attr.get_scale())
+                .ok_or(RecordErr::ValSchemaMismatch)?;
+            Decimal::new(mantissa, attr.get_scale()).write_to(bytes, addr)?;
+        }
+        _ => {
+            value.write_to(bytes, addr)?;
+        }
+    }
+    Ok(())
 }
 
 /// A database record descriptor, comprised of the page ID and slot index that
 /// the record is located at.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct RecordId {
     pub page_id: PageIdT,
     pub slot_index: RecordSlotIdT,
@@ -342,6 +539,21 @@ pub enum RecordErr {
     ValSchemaMismatch,
     NotNullable,
     IndexOutOfBounds,
+    ColumnNotFound,
+    SerialConflict,
+    InvalidJson,
+
+    /// Error to be thrown when a value doesn't satisfy a check on its attribute. Contains the
+    /// name of the failed check.
+    CheckViolation(String),
+
+    /// Error to be thrown when a value is checked by a check that was read from disk, whose
+    /// validator hasn't been registered again. Contains the name of the check.
+    CheckUnregistered(String),
+
+    /// Error to be thrown when a varchar value is longer than the maximum length of its
+    /// attribute.
+    VarcharTooLong,
 }
 
 impl From<IoError> for RecordErr {
@@ -366,7 +578,7 @@ mod tests {
             Attribute::new("baz", DataType::SmallInt, false, false, true),
             Attribute::new("foobar", DataType::Int, false, false, false),
             Attribute::new("barbaz", DataType::BigInt, false, false, true),
-            Attribute::new("bazfoo", DataType::Decimal, false, false, true),
+            Attribute::new("bazfoo", DataType::Real, false, false, true),
             Attribute::new("foobarbaz", DataType::Varchar, false, false, true),
         ]));
 
@@ -401,13 +613,13 @@ mod tests {
         // Check that the record behaves as expected.
         assert_eq!(
             record.len(),
-            NULL_BITMAP_SIZE
+            null_bitmap_size(7)
                 + size_of(DataType::Boolean)
                 + size_of(DataType::TinyInt)
                 + size_of(DataType::SmallInt)
                 + size_of(DataType::Int)
                 + size_of(DataType::BigInt)
-                + size_of(DataType::Decimal)
+                + size_of(DataType::Real)
                 + size_of(DataType::Varchar)
                 + "Hello, World!".len() as u32
         );
@@ -421,7 +633,7 @@ mod tests {
         assert!(value.is_none());
 
         let value = record.get_value(5, schema.clone()).unwrap();
-        assert_eq!(value.unwrap().get_inner(), InnerValue::Decimal(-5.4321f32));
+        assert_eq!(value.unwrap().get_inner(), InnerValue::Real(-5.4321f32));
 
         let value = record.get_value(6, schema.clone()).unwrap();
         assert_eq!(
@@ -438,4 +650,439 @@ mod tests {
         record.allocate(0, 0);
         assert!(record.is_allocated());
     }
+
+    #[test]
+    fn test_record_from_bytes_unallocated() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Int, false, false, false),
+            Attribute::new("bar", DataType::Varchar, false, false, true),
+            Attribute::new("baz", DataType::BigInt, false, false, true),
+        ]));
+        let values: Vec<Option<Box<dyn Value>>> = vec![
+            Some(Box::new(42_i32)),
+            Some(Box::new("Hello, World!".to_string())),
+            None,
+        ];
+        let mut original = Record::new(values, schema.clone()).unwrap();
+        original.allocate(3, 7);
+
+        // Check that the record is decoded without a record ID.
+        let record = Record::from_bytes_unallocated(original.as_bytes().to_vec(), schema.clone());
+        assert!(!record.is_allocated());
+        assert!(record.get_id().is_none());
+        assert_eq!(record.as_bytes(), original.as_bytes());
+
+        // Check that each value decodes correctly.
+        let value = record.get_value(0, schema.clone()).unwrap();
+        assert_eq!(value.unwrap().get_inner(), InnerValue::Int(42));
+        let value = record.get_value(1, schema.clone()).unwrap();
+        assert_eq!(
+            value.unwrap().get_inner(),
+            InnerValue::Varchar("Hello, World!".to_string())
+        );
+        let value = record.get_value(2, schema.clone()).unwrap();
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn test_record_equals() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Int, false, false, false),
+            Attribute::new("bar", DataType::Varchar, false, false, true),
+        ]));
+        let create = |foo: i32, bar: Option<&str>| {
+            let bar: Option<Box<dyn Value>> = match bar {
+                Some(s) => Some(Box::new(s.to_string())),
+                None => None,
+            };
+            Record::new(vec![Some(Box::new(foo)), bar], schema.clone()).unwrap()
+        };
+
+        // Check that records with the same values are equal, regardless of record ID.
+        let record_1 = create(1, Some("Hello"));
+        let mut record_2 = create(1, Some("Hello"));
+        record_2.allocate(3, 4);
+        assert!(record_1.equals(&record_2, schema.clone()));
+
+        // Check that records differing in a varchar are not equal.
+        let record_3 = create(1, Some("World"));
+        assert!(!record_1.equals(&record_3, schema.clone()));
+
+        // Check that a null value only equals another null value.
+        let record_4 = create(1, None);
+        let record_5 = create(1, None);
+        assert!(!record_1.equals(&record_4, schema.clone()));
+        assert!(!record_4.equals(&record_1, schema.clone()));
+        assert!(record_4.equals(&record_5, schema.clone()));
+    }
+
+    #[test]
+    fn test_record_project() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Int, false, false, false),
+            Attribute::new("bar", DataType::Boolean, false, false, false),
+            Attribute::new("baz", DataType::Varchar, false, false, true),
+        ]));
+        let out_schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Int, false, false, false),
+            Attribute::new("baz", DataType::Varchar, false, false, true),
+        ]));
+
+        // Check that values carry over to the projected record.
+        let values: Vec<Option<Box<dyn Value>>> = vec![
+            Some(Box::new(42_i32)),
+            Some(Box::new(true)),
+            Some(Box::new("Hello".to_string())),
+        ];
+        let record = Record::new(values, schema.clone()).unwrap();
+        let projected = record
+            .project(&[0, 2], schema.clone(), out_schema.clone())
+            .unwrap();
+        let value = projected.get_value(0, out_schema.clone()).unwrap();
+        assert_eq!(value.unwrap().get_inner(), InnerValue::Int(42));
+        let value = projected.get_value(1, out_schema.clone()).unwrap();
+        assert_eq!(
+            value.unwrap().get_inner(),
+            InnerValue::Varchar("Hello".to_string())
+        );
+
+        // Check that null values carry over to the projected record.
+        let values: Vec<Option<Box<dyn Value>>> =
+            vec![Some(Box::new(7_i32)), Some(Box::new(false)), None];
+        let record = Record::new(values, schema.clone()).unwrap();
+        let projected = record
+            .project(&[0, 2], schema.clone(), out_schema.clone())
+            .unwrap();
+        assert!(!projected.is_null(0, out_schema.clone()).unwrap());
+        assert!(projected.is_null(1, out_schema.clone()).unwrap());
+
+        // Check that a mismatching output schema is rejected.
+        let result = record.project(&[1, 2], schema.clone(), out_schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+    }
+
+    #[test]
+    fn test_date_round_trip() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Int, false, false, false),
+            Attribute::new("bar", DataType::Date, false, false, true),
+        ]));
+
+        // Check conversion to and from (year, month, day).
+        assert_eq!(Date::from_ymd(1970, 1, 1), Some(Date(0)));
+        assert_eq!(Date::from_ymd(1969, 12, 31), Some(Date(-1)));
+        assert_eq!(Date::from_ymd(2000, 3, 1), Some(Date(11_017)));
+        assert_eq!(Date::from_ymd(2021, 2, 29), None);
+        assert_eq!(Date::from_ymd(2021, 13, 1), None);
+        let date = Date::from_ymd(2024, 2, 29).unwrap();
+        assert_eq!(date.to_ymd(), (2024, 2, 29));
+        assert_eq!(date.to_string(), "2024-02-29");
+
+        // Check that a date can be stored in a record and read back.
+        let values: Vec<Option<Box<dyn Value>>> = vec![Some(Box::new(7_i32)), Some(Box::new(date))];
+        let record = Record::new(values, schema.clone()).unwrap();
+        assert_eq!(
+            record.len(),
+            null_bitmap_size(2) + 4 + size_of(DataType::Date)
+        );
+
+        let value = record.get_value(1, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_data_type(), DataType::Date);
+        assert_eq!(value.get_inner(), InnerValue::Date(date.0));
+    }
+
+    #[test]
+    fn test_timestamp_round_trip() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Timestamp, false, false, false),
+            Attribute::new("bar", DataType::Int, false, false, false),
+        ]));
+
+        // 2021-03-14 01:59:26.535897 UTC
+        let timestamp = Timestamp(1_615_687_166_535_897);
+        assert_eq!(timestamp.to_string(), "2021-03-14 01:59:26.535897");
+
+        // Check that a timestamp can be stored in a record and read back exactly.
+        let values: Vec<Option<Box<dyn Value>>> =
+            vec![Some(Box::new(timestamp)), Some(Box::new(42_i32))];
+        let record = Record::new(values, schema.clone()).unwrap();
+
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_data_type(), DataType::Timestamp);
+        assert_eq!(value.get_inner(), InnerValue::Timestamp(timestamp.0));
+
+        let value = record.get_value(1, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::Int(42));
+
+        // Check that a timestamp can NOT be placed in an integer column.
+        let values: Vec<Option<Box<dyn Value>>> =
+            vec![Some(Box::new(timestamp)), Some(Box::new(timestamp))];
+        let result = Record::new(values, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+    }
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("price", DataType::Decimal, false, false, false).with_scale(2),
+            Attribute::new("qty", DataType::Int, false, false, false),
+        ]));
+
+        // Check that a decimal is stored exactly.
+        let values: Vec<Option<Box<dyn Value>>> =
+            vec![Some(Box::new(Decimal::new(1999, 2))), Some(Box::new(3_i32))];
+        let record = Record::new(values, schema.clone()).unwrap();
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(
+            value.get_inner(),
+            InnerValue::Decimal {
+                mantissa: 1999,
+                scale: 2
+            }
+        );
+        assert_eq!(value.get_inner().to_string(), "19.99");
+
+        // Check that a decimal with a smaller scale is rescaled to the attribute's scale.
+        let values: Vec<Option<Box<dyn Value>>> =
+            vec![Some(Box::new(Decimal::new(-5, 0))), Some(Box::new(3_i32))];
+        let record = Record::new(values, schema.clone()).unwrap();
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(
+            value.get_inner(),
+            InnerValue::Decimal {
+                mantissa: -500,
+                scale: 2
+            }
+        );
+        assert_eq!(value.get_inner().to_string(), "-5.00");
+
+        // Check that a decimal that would lose precision is rejected.
+        let values: Vec<Option<Box<dyn Value>>> = vec![
+            Some(Box::new(Decimal::new(19_999, 3))),
+            Some(Box::new(3_i32)),
+        ];
+        let result = Record::new(values, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+    }
+
+    #[test]
+    fn test_wide_null_bitmap() {
+        for &attr_len in [40_u32, 70].iter() {
+            // Declare a schema with many nullable attributes.
+            let attrs = (0..attr_len)
+                .map(|i| Attribute::new(&format!("col{}", i), DataType::Int, false, false, true))
+                .collect();
+            let schema = Arc::new(Schema::new(attrs));
+
+            // Create a record where column 35 is null.
+            let values: Vec<Option<Box<dyn Value>>> = (0..attr_len as i32)
+                .map(|i| match i {
+                    35 => None,
+                    _ => Some(Box::new(i) as Box<dyn Value>),
+                })
+                .collect();
+            let mut record = Record::new(values, schema.clone()).unwrap();
+            assert_eq!(
+                record.len(),
+                null_bitmap_size(attr_len) + attr_len * size_of(DataType::Int)
+            );
+
+            // Check that nullness of each column is read back correctly.
+            for i in 0..attr_len {
+                assert_eq!(record.is_null(i, schema.clone()).unwrap(), i == 35);
+            }
+            let value = record.get_value(36, schema.clone()).unwrap().unwrap();
+            assert_eq!(value.get_inner(), InnerValue::Int(36));
+
+            // Check that the last column can be set to null without affecting others.
+            record.set_null(attr_len - 1, schema.clone()).unwrap();
+            assert!(record.is_null(attr_len - 1, schema.clone()).unwrap());
+            assert!(!record.is_null(attr_len - 2, schema.clone()).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_set_value() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Int, false, false, false),
+            Attribute::new("bar", DataType::Varchar, false, false, true),
+            Attribute::new("baz", DataType::BigInt, false, false, true),
+        ]));
+        let values: Vec<Option<Box<dyn Value>>> = vec![
+            Some(Box::new(1_i32)),
+            Some(Box::new("Hello".to_string())),
+            None,
+        ];
+        let mut record = Record::new(values, schema.clone()).unwrap();
+        record.allocate(2, 3);
+
+        // Set an integer column in place.
+        record
+            .set_value(0, Some(Box::new(42_i32)), schema.clone())
+            .unwrap();
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::Int(42));
+
+        // Set a null column to a value.
+        record
+            .set_value(2, Some(Box::new(-7_i64)), schema.clone())
+            .unwrap();
+        let value = record.get_value(2, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::BigInt(-7));
+
+        // Set a varchar column to a longer value.
+        record
+            .set_value(
+                1,
+                Some(Box::new("Hello, World!".to_string())),
+                schema.clone(),
+            )
+            .unwrap();
+        let value = record.get_value(1, schema.clone()).unwrap().unwrap();
+        assert_eq!(
+            value.get_inner(),
+            InnerValue::Varchar("Hello, World!".to_string())
+        );
+
+        // Set a nullable column to null.
+        record.set_value(1, None, schema.clone()).unwrap();
+        assert!(record.get_value(1, schema.clone()).unwrap().is_none());
+
+        // Check that other values and the record ID are untouched.
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::Int(42));
+        let value = record.get_value(2, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::BigInt(-7));
+        assert_eq!(
+            record.get_id(),
+            Some(RecordId {
+                page_id: 2,
+                slot_index: 3
+            })
+        );
+
+        // Check that invalid updates are rejected.
+        let result = record.set_value(0, None, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::NotNullable);
+        let result = record.set_value(3, Some(Box::new(1_i32)), schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::IndexOutOfBounds);
+        let result = record.set_value(0, Some(Box::new(true)), schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+    }
+
+    #[test]
+    fn test_varchar_max_len() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("foo", DataType::Varchar, false, false, false).with_max_len(5),
+            Attribute::new("bar", DataType::Varchar, false, false, true),
+        ]));
+
+        // Assert that strings up to the maximum length are accepted, and that attributes without
+        // a maximum length are unbounded.
+        let long = "x".repeat(1000);
+        let values: Vec<Option<Box<dyn Value>>> =
+            vec![Some(Box::new("Hello".to_string())), Some(Box::new(long))];
+        let mut record = Record::new(values, schema.clone()).unwrap();
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::Varchar("Hello".to_string()));
+
+        // Assert that longer strings are rejected, both on creation and on update. The length is
+        // measured in bytes.
+        let values: Vec<Option<Box<dyn Value>>> = vec![Some(Box::new("Hello!".to_string())), None];
+        assert_eq!(
+            Record::new(values, schema.clone()).unwrap_err(),
+            RecordErr::VarcharTooLong
+        );
+        let result = record.set_value(0, Some(Box::new("héllo".to_string())), schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::VarcharTooLong);
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::Varchar("Hello".to_string()));
+    }
+
+    #[test]
+    fn test_record_to_json() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("id", DataType::Int, true, false, false),
+            Attribute::new("name", DataType::Varchar, false, false, false),
+            Attribute::new("active", DataType::Boolean, false, false, false),
+            Attribute::new("price", DataType::Decimal, false, false, false).with_scale(2),
+            Attribute::new("rating", DataType::Real, false, false, true),
+            Attribute::new("created", DataType::Date, false, false, false),
+            Attribute::new("note", DataType::Varchar, false, false, true),
+        ]));
+        let values: Vec<Option<Box<dyn Value>>> = vec![
+            Some(Box::new(7_i32)),
+            Some(Box::new("Say \"hi\"\n".to_string())),
+            Some(Box::new(true)),
+            Some(Box::new(Decimal::new(1234, 2))),
+            Some(Box::new(4.5_f32)),
+            Some(Box::new(Date::from_ymd(2021, 3, 14).unwrap())),
+            None,
+        ];
+        let record = Record::new(values, schema.clone()).unwrap();
+
+        assert_eq!(
+            record.to_json(schema).unwrap(),
+            "{\"id\":7,\"name\":\"Say \\\"hi\\\"\\n\",\"active\":true,\"price\":12.34,\
+             \"rating\":4.5,\"created\":\"2021-03-14\",\"note\":null}"
+        );
+    }
+
+    #[test]
+    fn test_record_from_json() {
+        let schema = Arc::new(Schema::new(vec![
+            Attribute::new("id", DataType::Int, true, false, false),
+            Attribute::new("name", DataType::Varchar, false, false, false),
+            Attribute::new("price", DataType::Decimal, false, false, true).with_scale(2),
+            Attribute::new("created", DataType::Timestamp, false, false, true),
+            Attribute::new("note", DataType::Varchar, false, false, true),
+        ]));
+
+        // Create a record from a valid object, where a nullable field is missing.
+        let json = r#"{ "name": "Say \"hi\" \u00e9", "id": -7, "price": 12.5,
+                        "created": "2021-03-14 01:59:26.535897" }"#;
+        let record = Record::from_json(json, schema.clone()).unwrap();
+        let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner(), InnerValue::Int(-7));
+        let value = record.get_value(1, schema.clone()).unwrap().unwrap();
+        assert_eq!(
+            value.get_inner(),
+            InnerValue::Varchar("Say \"hi\" \u{e9}".to_string())
+        );
+        let value = record.get_value(2, schema.clone()).unwrap().unwrap();
+        assert_eq!(value.get_inner().to_string(), "12.50");
+        let value = record.get_value(3, schema.clone()).unwrap().unwrap();
+        assert_eq!(
+            value.get_inner(),
+            InnerValue::Timestamp(1_615_687_166_535_897)
+        );
+        assert!(record.is_null(4, schema.clone()).unwrap());
+
+        // Check that the JSON of the record is read back as the same record.
+        let json = record.to_json(schema.clone()).unwrap();
+        let copy = Record::from_json(&json, schema.clone()).unwrap();
+        assert_eq!(copy.as_bytes(), record.as_bytes());
+
+        // Check that a missing non-nullable field is rejected.
+        let result = Record::from_json(r#"{"id": 1, "note": null}"#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::NotNullable);
+
+        // Check that values of the wrong type are rejected.
+        let result = Record::from_json(r#"{"id": "1", "name": "a"}"#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+        let result = Record::from_json(r#"{"id": 1.5, "name": "a"}"#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+        let result = Record::from_json(r#"{"id": 1, "name": 2}"#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+        let result = Record::from_json(r#"{"id": 1, "name": "a", "price": 1.234}"#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ValSchemaMismatch);
+
+        // Check that unknown fields and malformed objects are rejected.
+        let result = Record::from_json(r#"{"id": 1, "name": "a", "foo": 1}"#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::ColumnNotFound);
+        let result = Record::from_json(r#"{"id": 1, "name": "a""#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::InvalidJson);
+        let result = Record::from_json(r#"{"id": 1, "id": 2, "name": "a"}"#, schema.clone());
+        assert_eq!(result.unwrap_err(), RecordErr::InvalidJson);
+    }
 }
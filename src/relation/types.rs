@@ -3,7 +3,14 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
+use crate::io::{
+    read_bool, read_f32, read_i16, read_i32, read_i64, read_i8, read_str, read_u8, write_bool,
+    write_f32, write_i16, write_i32, write_i64, write_i8, write_str, write_u8, IoError,
+};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt::Formatter;
+use std::str::FromStr;
 
 /// Mapping between internal and built-in data types.
 pub type BOOLEAN = bool;
@@ -11,8 +18,11 @@ pub type TINYINT = i8;
 pub type SMALLINT = i16;
 pub type INT = i32;
 pub type BIGINT = i64;
-pub type DECIMAL = f32;
+pub type REAL = f32;
+pub type DECIMAL = Decimal;
 pub type VARCHAR = String;
+pub type DATE = Date;
+pub type TIMESTAMP = Timestamp;
 
 /// Return the size of a data type in bytes.
 pub fn size_of(data_type: DataType) -> u32 {
@@ -22,8 +32,35 @@ pub fn size_of(data_type: DataType) -> u32 {
         DataType::SmallInt => 2,
         DataType::Int => 4,
         DataType::BigInt => 8,
-        DataType::Decimal => 4,
+        DataType::Real => 4,
+        DataType::Decimal => 8,
         DataType::Varchar => 8,
+        DataType::Date => 4,
+        DataType::Timestamp => 8,
+    }
+}
+
+/// Parse a value of the specified data type from its text, which is in the format that the
+/// value is displayed in. Booleans are parsed from "true" and "false".
+pub fn parse_value(s: &str, data_type: DataType) -> Result<Box<dyn Value>, TypeParseError> {
+    fn parse<T: FromStr + Value + 'static>(s: &str) -> Result<Box<dyn Value>, TypeParseError> {
+        match s.parse::<T>() {
+            Ok(value) => Ok(Box::new(value)),
+            Err(_) => Err(TypeParseError(s.to_string())),
+        }
+    }
+
+    match data_type {
+        DataType::Boolean => parse::<BOOLEAN>(s),
+        DataType::TinyInt => parse::<TINYINT>(s),
+        DataType::SmallInt => parse::<SMALLINT>(s),
+        DataType::Int => parse::<INT>(s),
+        DataType::BigInt => parse::<BIGINT>(s),
+        DataType::Real => parse::<REAL>(s),
+        DataType::Decimal => parse::<DECIMAL>(s),
+        DataType::Varchar => Ok(Box::new(s.to_string())),
+        DataType::Date => parse::<DATE>(s),
+        DataType::Timestamp => parse::<TIMESTAMP>(s),
     }
 }
 
@@ -35,20 +72,118 @@ pub enum DataType {
     SmallInt,
     Int,
     BigInt,
+    Real,
     Decimal,
     Varchar,
+    Date,
+    Timestamp,
 }
 
+impl TryFrom<u8> for DataType {
+    type Error = ();
+
+    /// Convert a byte written by casting a data type with `as u8` back into the data type.
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        let data_type = match byte {
+            0 => DataType::Boolean,
+            1 => DataType::TinyInt,
+            2 => DataType::SmallInt,
+            3 => DataType::Int,
+            4 => DataType::BigInt,
+            5 => DataType::Real,
+            6 => DataType::Decimal,
+            7 => DataType::Varchar,
+            8 => DataType::Date,
+            9 => DataType::Timestamp,
+            _ => return Err(()),
+        };
+        Ok(data_type)
+    }
+}
+
+impl FromStr for DataType {
+    type Err = TypeParseError;
+
+    /// Parse a data type from its name, ignoring case. "BOOL" and "STRING" are accepted as
+    /// aliases for BOOLEAN and VARCHAR.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let data_type = match s.to_ascii_uppercase().as_str() {
+            "BOOLEAN" | "BOOL" => DataType::Boolean,
+            "TINYINT" => DataType::TinyInt,
+            "SMALLINT" => DataType::SmallInt,
+            "INT" => DataType::Int,
+            "BIGINT" => DataType::BigInt,
+            "REAL" => DataType::Real,
+            "DECIMAL" => DataType::Decimal,
+            "VARCHAR" | "STRING" => DataType::Varchar,
+            "DATE" => DataType::Date,
+            "TIMESTAMP" => DataType::Timestamp,
+            _ => return Err(TypeParseError(s.to_string())),
+        };
+        Ok(data_type)
+    }
+}
+
+impl std::fmt::Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DataType::Boolean => "BOOLEAN",
+            DataType::TinyInt => "TINYINT",
+            DataType::SmallInt => "SMALLINT",
+            DataType::Int => "INT",
+            DataType::BigInt => "BIGINT",
+            DataType::Real => "REAL",
+            DataType::Decimal => "DECIMAL",
+            DataType::Varchar => "VARCHAR",
+            DataType::Date => "DATE",
+            DataType::Timestamp => "TIMESTAMP",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Error to be returned when a string doesn't name a data type, or can't be parsed as a value.
+/// Contains the string.
+#[derive(Debug, Eq, PartialEq)]
+pub struct TypeParseError(pub String);
+
 /// An enum for contained values in a Value trait.
-#[derive(Debug, PartialEq)]
+///
+/// Values are compared and ordered by `InnerValue::total_cmp`, so every value is equal to itself
+/// (including NaN reals), and decimal values are equal if their numeric values are equal.
+/// Since `Option<InnerValue>` orders None first, null values are ordered before all other values.
+#[derive(Clone, Debug)]
 pub enum InnerValue {
     Boolean(BOOLEAN),
     TinyInt(TINYINT),
     SmallInt(SMALLINT),
     Int(INT),
     BigInt(BIGINT),
-    Decimal(DECIMAL),
+    Real(REAL),
+    Decimal { mantissa: i64, scale: u8 },
     Varchar(VARCHAR),
+    Date(i32),
+    Timestamp(i64),
+}
+
+impl PartialEq for InnerValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for InnerValue {}
+
+impl PartialOrd for InnerValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InnerValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
 }
 
 impl std::fmt::Display for InnerValue {
@@ -59,9 +194,463 @@ impl std::fmt::Display for InnerValue {
             InnerValue::SmallInt(val) => write!(f, "{}", val),
             InnerValue::Int(val) => write!(f, "{}", val),
             InnerValue::BigInt(val) => write!(f, "{}", val),
-            InnerValue::Decimal(val) => write!(f, "{}", val),
+            InnerValue::Real(val) => write!(f, "{}", val),
+            InnerValue::Decimal { mantissa, scale } => write!(
+                f,
+                "{}",
+                Decimal {
+                    mantissa: *mantissa,
+                    scale: *scale
+                }
+            ),
             InnerValue::Varchar(val) => write!(f, "{}", val),
+            InnerValue::Date(val) => write!(f, "{}", Date(*val)),
+            InnerValue::Timestamp(val) => write!(f, "{}", Timestamp(*val)),
+        }
+    }
+}
+
+impl InnerValue {
+    /// Convert this value into a boxed Value, which can be used to create a record.
+    pub fn into_value(self) -> Box<dyn Value> {
+        match self {
+            InnerValue::Boolean(val) => Box::new(val),
+            InnerValue::TinyInt(val) => Box::new(val),
+            InnerValue::SmallInt(val) => Box::new(val),
+            InnerValue::Int(val) => Box::new(val),
+            InnerValue::BigInt(val) => Box::new(val),
+            InnerValue::Real(val) => Box::new(val),
+            InnerValue::Decimal { mantissa, scale } => Box::new(Decimal { mantissa, scale }),
+            InnerValue::Varchar(val) => Box::new(val),
+            InnerValue::Date(val) => Box::new(Date(val)),
+            InnerValue::Timestamp(val) => Box::new(Timestamp(val)),
+        }
+    }
+
+    /// Encode this value in the format it is stored in a record, without the offset/length of a
+    /// varchar. Decimals are followed by their scale (1), so that they can be decoded without an
+    /// attribute.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let value = self.clone().into_value();
+        let size = match self {
+            InnerValue::Varchar(val) => val.len(),
+            InnerValue::Decimal { .. } => size_of(DataType::Decimal) as usize + 1,
+            _ => size_of(value.get_data_type()) as usize,
+        };
+        let mut bytes = vec![0; size];
+        // .unwrap() ok since the bytes are sized for the value.
+        let len = value.write_to(&mut bytes, 0).unwrap();
+        if let InnerValue::Decimal { scale, .. } = self {
+            write_u8(&mut bytes, len, *scale).unwrap();
+        }
+        bytes
+    }
+
+    /// Decode a value of the specified data type that was encoded by `to_bytes`. Return an error
+    /// if the bytes are too short for the data type, or if a varchar isn't valid UTF-8.
+    pub fn from_bytes(bytes: &[u8], data_type: DataType) -> Result<InnerValue, IoError> {
+        let value = match data_type {
+            DataType::Boolean => InnerValue::Boolean(read_bool(bytes, 0)?),
+            DataType::TinyInt => InnerValue::TinyInt(read_i8(bytes, 0)?),
+            DataType::SmallInt => InnerValue::SmallInt(read_i16(bytes, 0)?),
+            DataType::Int => InnerValue::Int(read_i32(bytes, 0)?),
+            DataType::BigInt => InnerValue::BigInt(read_i64(bytes, 0)?),
+            DataType::Real => InnerValue::Real(read_f32(bytes, 0)?),
+            DataType::Decimal => InnerValue::Decimal {
+                mantissa: read_i64(bytes, 0)?,
+                scale: read_u8(bytes, 8)?,
+            },
+            DataType::Varchar => InnerValue::Varchar(read_str(bytes, 0, bytes.len() as u32)?),
+            DataType::Date => InnerValue::Date(read_i32(bytes, 0)?),
+            DataType::Timestamp => InnerValue::Timestamp(read_i64(bytes, 0)?),
+        };
+        Ok(value)
+    }
+
+    /// Compare this value with another value, giving a total order over every value.
+    ///
+    /// Values of the same data type are ordered by their contained values. Real values are
+    /// ordered by `f32::total_cmp`, and decimal values with different scales are compared by
+    /// their numeric values. Values of different data types are ordered by data type, in the
+    /// order that the data types are declared.
+    pub fn total_cmp(&self, other: &InnerValue) -> Ordering {
+        match (self, other) {
+            (InnerValue::Boolean(a), InnerValue::Boolean(b)) => a.cmp(b),
+            (InnerValue::TinyInt(a), InnerValue::TinyInt(b)) => a.cmp(b),
+            (InnerValue::SmallInt(a), InnerValue::SmallInt(b)) => a.cmp(b),
+            (InnerValue::Int(a), InnerValue::Int(b)) => a.cmp(b),
+            (InnerValue::BigInt(a), InnerValue::BigInt(b)) => a.cmp(b),
+            (InnerValue::Real(a), InnerValue::Real(b)) => a.total_cmp(b),
+            (
+                InnerValue::Decimal {
+                    mantissa: a,
+                    scale: a_scale,
+                },
+                InnerValue::Decimal {
+                    mantissa: b,
+                    scale: b_scale,
+                },
+            ) => {
+                // Scale both mantissas up to the larger scale. If that overflows, fall back to
+                // comparing approximate values.
+                let scale = *a_scale.max(b_scale) as u32;
+                let rescale = |mantissa: i64, from: u8| {
+                    10_i128
+                        .checked_pow(scale - from as u32)
+                        .and_then(|factor| (mantissa as i128).checked_mul(factor))
+                };
+                match (rescale(*a, *a_scale), rescale(*b, *b_scale)) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    _ => {
+                        let a = *a as f64 / 10_f64.powi(*a_scale as i32);
+                        let b = *b as f64 / 10_f64.powi(*b_scale as i32);
+                        a.total_cmp(&b)
+                    }
+                }
+            }
+            (InnerValue::Varchar(a), InnerValue::Varchar(b)) => a.cmp(b),
+            (InnerValue::Date(a), InnerValue::Date(b)) => a.cmp(b),
+            (InnerValue::Timestamp(a), InnerValue::Timestamp(b)) => a.cmp(b),
+            _ => self.get_data_type_rank().cmp(&other.get_data_type_rank()),
+        }
+    }
+
+    /// Return the position of the data type of this value in the declaration order.
+    fn get_data_type_rank(&self) -> u8 {
+        match self {
+            InnerValue::Boolean(_) => 0,
+            InnerValue::TinyInt(_) => 1,
+            InnerValue::SmallInt(_) => 2,
+            InnerValue::Int(_) => 3,
+            InnerValue::BigInt(_) => 4,
+            InnerValue::Real(_) => 5,
+            InnerValue::Decimal { .. } => 6,
+            InnerValue::Varchar(_) => 7,
+            InnerValue::Date(_) => 8,
+            InnerValue::Timestamp(_) => 9,
+        }
+    }
+
+    /// Add another value to this value. Both values must be numeric values of the same data
+    /// type, and the sum has that data type. Decimal values with different scales are summed at
+    /// the larger scale.
+    pub fn checked_add(&self, other: &InnerValue) -> Result<InnerValue, ValueError> {
+        if !self.is_numeric() || !other.is_numeric() {
+            return Err(ValueError::NonNumeric);
+        }
+        let sum = match (self, other) {
+            (InnerValue::TinyInt(a), InnerValue::TinyInt(b)) => {
+                a.checked_add(*b).map(InnerValue::TinyInt)
+            }
+            (InnerValue::SmallInt(a), InnerValue::SmallInt(b)) => {
+                a.checked_add(*b).map(InnerValue::SmallInt)
+            }
+            (InnerValue::Int(a), InnerValue::Int(b)) => a.checked_add(*b).map(InnerValue::Int),
+            (InnerValue::BigInt(a), InnerValue::BigInt(b)) => {
+                a.checked_add(*b).map(InnerValue::BigInt)
+            }
+            (InnerValue::Real(a), InnerValue::Real(b)) => Some(InnerValue::Real(a + b)),
+            (
+                InnerValue::Decimal {
+                    mantissa: a,
+                    scale: a_scale,
+                },
+                InnerValue::Decimal {
+                    mantissa: b,
+                    scale: b_scale,
+                },
+            ) => {
+                let scale = *a_scale.max(b_scale);
+                let a = Decimal::new(*a, *a_scale).rescale(scale);
+                let b = Decimal::new(*b, *b_scale).rescale(scale);
+                a.zip(b)
+                    .and_then(|(a, b)| a.checked_add(b))
+                    .map(|mantissa| InnerValue::Decimal { mantissa, scale })
+            }
+            _ => return Err(ValueError::TypeMismatch),
+        };
+        sum.ok_or(ValueError::Overflow)
+    }
+
+    /// Divide this value by a count, such as when computing an average. The quotient of any
+    /// numeric value is a real value.
+    pub fn checked_div_u32(&self, divisor: u32) -> Result<InnerValue, ValueError> {
+        let value = self.to_f64()?;
+        if divisor == 0 {
+            return Err(ValueError::DivisionByZero);
+        }
+        Ok(InnerValue::Real((value / divisor as f64) as f32))
+    }
+
+    /// Convert a numeric value into a float. Large integers and decimals may lose precision.
+    pub fn to_f64(&self) -> Result<f64, ValueError> {
+        match self {
+            InnerValue::TinyInt(val) => Ok(*val as f64),
+            InnerValue::SmallInt(val) => Ok(*val as f64),
+            InnerValue::Int(val) => Ok(*val as f64),
+            InnerValue::BigInt(val) => Ok(*val as f64),
+            InnerValue::Real(val) => Ok(*val as f64),
+            InnerValue::Decimal { mantissa, scale } => {
+                Ok(*mantissa as f64 / 10_f64.powi(*scale as i32))
+            }
+            _ => Err(ValueError::NonNumeric),
+        }
+    }
+
+    /// Return whether this value has a numeric data type.
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            InnerValue::TinyInt(_)
+                | InnerValue::SmallInt(_)
+                | InnerValue::Int(_)
+                | InnerValue::BigInt(_)
+                | InnerValue::Real(_)
+                | InnerValue::Decimal { .. }
+        )
+    }
+}
+
+/// Custom errors to be used by value arithmetic.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ValueError {
+    /// Error to be thrown when an arithmetic operand isn't a numeric value, such as a varchar
+    /// or a boolean.
+    NonNumeric,
+
+    /// Error to be thrown when the operands of an arithmetic operation have different data
+    /// types.
+    TypeMismatch,
+
+    /// Error to be thrown when the result of an arithmetic operation overflows its data type.
+    Overflow,
+
+    /// Error to be thrown when a value is divided by zero.
+    DivisionByZero,
+}
+
+/// A calendar date, represented as the number of days since 1970-01-01 (which may be negative).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Date(pub i32);
+
+impl Date {
+    /// Create a date from a year, month (1-12), and day of the month (1-31) in the proleptic
+    /// Gregorian calendar. Return None if the date is invalid or out of range.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        // Shift the start of the year to March so that the leap day is the last day of the year.
+        let y = year as i64 - (month <= 2) as i64;
+        let era = y.div_euclid(400);
+        let yoe = y.rem_euclid(400);
+        let mp = (month as i64 + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+
+        i32::try_from(days).ok().map(Date)
+    }
+
+    /// Return the year, month (1-12), and day of the month (1-31) of this date.
+    pub fn to_ymd(&self) -> (i32, u32, u32) {
+        let z = self.0 as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = ((mp + 2) % 12 + 1) as u32;
+        let year = yoe + era * 400 + (month <= 2) as i64;
+
+        (year as i32, month, day)
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = self.to_ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = TypeParseError;
+
+    /// Parse a date in the format "YYYY-MM-DD", which is the format a date is displayed in.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || TypeParseError(s.to_string());
+        let mut parts = s.rsplitn(3, '-');
+        let day = parts.next().ok_or_else(err)?;
+        let month = parts.next().ok_or_else(err)?;
+        let year = parts.next().ok_or_else(err)?;
+        if !is_digits(month) || !is_digits(day) {
+            return Err(err());
+        }
+        Date::from_ymd(
+            year.parse().map_err(|_| err())?,
+            month.parse().map_err(|_| err())?,
+            day.parse().map_err(|_| err())?,
+        )
+        .ok_or_else(err)
+    }
+}
+
+/// An exact fixed-point number, represented as an integer mantissa scaled by a power of ten.
+/// The value of a decimal is `mantissa * 10^(-scale)`, so 19.99 is represented with a mantissa
+/// of 1999 and a scale of 2.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Decimal {
+    pub mantissa: i64,
+    pub scale: u8,
+}
+
+impl Decimal {
+    /// Create a new decimal.
+    pub fn new(mantissa: i64, scale: u8) -> Self {
+        Self { mantissa, scale }
+    }
+
+    /// Return the mantissa of this decimal rescaled to the specified scale. Return None if the
+    /// scale is smaller than the current scale (which would lose precision) or if the rescaled
+    /// mantissa overflows.
+    pub fn rescale(&self, scale: u8) -> Option<i64> {
+        let diff = scale.checked_sub(self.scale)?;
+        10_i64
+            .checked_pow(diff as u32)
+            .and_then(|factor| self.mantissa.checked_mul(factor))
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.mantissa);
+        }
+        let digits = format!(
+            "{:0width$}",
+            self.mantissa.unsigned_abs(),
+            width = self.scale as usize + 1
+        );
+        let (int, frac) = digits.split_at(digits.len() - self.scale as usize);
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        write!(f, "{}{}.{}", sign, int, frac)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = TypeParseError;
+
+    /// Parse a decimal in the format "-123.45". The scale of the decimal is the number of digits
+    /// after the decimal point.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || TypeParseError(s.to_string());
+        let (int, frac) = match s.split_once('.') {
+            Some((int, frac)) if !frac.is_empty() => (int, frac),
+            Some(_) => return Err(err()),
+            None => (s, ""),
+        };
+        let digits = int.strip_prefix('-').unwrap_or(int);
+        if !is_digits(digits) || !(frac.is_empty() || is_digits(frac)) {
+            return Err(err());
+        }
+        let scale = u8::try_from(frac.len()).map_err(|_| err())?;
+        let mantissa = format!("{}{}", int, frac).parse().map_err(|_| err())?;
+        Ok(Decimal::new(mantissa, scale))
+    }
+}
+
+/// A point in time, represented as the number of microseconds since 1970-01-01 00:00:00 UTC
+/// (which may be negative).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Timestamp(pub i64);
+
+/// Number of microseconds in a day.
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+impl Timestamp {
+    /// Return the date of this timestamp. Return None if the date is out of range.
+    pub fn get_date(&self) -> Option<Date> {
+        i32::try_from(self.0.div_euclid(MICROS_PER_DAY))
+            .ok()
+            .map(Date)
+    }
+}
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let micros = self.0.rem_euclid(MICROS_PER_DAY);
+        let secs = micros / 1_000_000;
+        let time = format!(
+            "{:02}:{:02}:{:02}.{:06}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            micros % 1_000_000
+        );
+        match self.get_date() {
+            Some(date) => write!(f, "{} {}", date, time),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = TypeParseError;
+
+    /// Parse a timestamp in the format "YYYY-MM-DD HH:MM:SS", optionally followed by a fraction
+    /// of a second with up to six digits. This is the format a timestamp is displayed in.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || TypeParseError(s.to_string());
+        let (date, time) = s.split_once(' ').ok_or_else(err)?;
+        let date: Date = date.parse().map_err(|_| err())?;
+        let (time, frac) = match time.split_once('.') {
+            Some((time, frac)) if !frac.is_empty() && frac.len() <= 6 => (time, frac),
+            Some(_) => return Err(err()),
+            None => (time, "0"),
+        };
+
+        let mut parts = time.split(':');
+        let mut micros = 0;
+        for limit in [24, 60, 60].iter() {
+            let part = parts
+                .next()
+                .filter(|part| is_digits(part))
+                .ok_or_else(err)?;
+            let val: i64 = part.parse().map_err(|_| err())?;
+            if val >= *limit {
+                return Err(err());
+            }
+            micros = micros * limit + val;
         }
+        if parts.next().is_some() || !is_digits(frac) {
+            return Err(err());
+        }
+        micros *= 1_000_000;
+        micros += format!("{:0<6}", frac).parse::<i64>().map_err(|_| err())?;
+
+        (date.0 as i64)
+            .checked_mul(MICROS_PER_DAY)
+            .and_then(|days| days.checked_add(micros))
+            .map(Timestamp)
+            .ok_or_else(err)
+    }
+}
+
+/// Return whether the specified string is a non-empty string of ASCII digits.
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Return the number of days in a month of the given year.
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
     }
 }
 
@@ -72,6 +661,11 @@ pub trait Value {
 
     /// Return the data type of the contained value.
     fn get_data_type(&self) -> DataType;
+
+    /// Write the encoded value at the specified offset in the byte array, and return the number
+    /// of bytes written. Variable-length values are written without their offset/length, which
+    /// is handled by the record.
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError>;
 }
 
 impl core::fmt::Debug for dyn Value {
@@ -90,6 +684,11 @@ impl Value for BOOLEAN {
     fn get_data_type(&self) -> DataType {
         DataType::Boolean
     }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_bool(array, offset, *self)?;
+        Ok(1)
+    }
 }
 
 impl Value for TINYINT {
@@ -100,6 +699,11 @@ impl Value for TINYINT {
     fn get_data_type(&self) -> DataType {
         DataType::TinyInt
     }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_i8(array, offset, *self)?;
+        Ok(1)
+    }
 }
 
 impl Value for SMALLINT {
@@ -110,6 +714,11 @@ impl Value for SMALLINT {
     fn get_data_type(&self) -> DataType {
         DataType::SmallInt
     }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_i16(array, offset, *self)?;
+        Ok(2)
+    }
 }
 
 impl Value for INT {
@@ -120,6 +729,11 @@ impl Value for INT {
     fn get_data_type(&self) -> DataType {
         DataType::Int
     }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_i32(array, offset, *self)?;
+        Ok(4)
+    }
 }
 
 impl Value for BIGINT {
@@ -130,16 +744,44 @@ impl Value for BIGINT {
     fn get_data_type(&self) -> DataType {
         DataType::BigInt
     }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_i64(array, offset, *self)?;
+        Ok(8)
+    }
+}
+
+impl Value for REAL {
+    fn get_inner(&self) -> InnerValue {
+        InnerValue::Real(*self)
+    }
+
+    fn get_data_type(&self) -> DataType {
+        DataType::Real
+    }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_f32(array, offset, *self)?;
+        Ok(4)
+    }
 }
 
 impl Value for DECIMAL {
     fn get_inner(&self) -> InnerValue {
-        InnerValue::Decimal(*self)
+        InnerValue::Decimal {
+            mantissa: self.mantissa,
+            scale: self.scale,
+        }
     }
 
     fn get_data_type(&self) -> DataType {
         DataType::Decimal
     }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_i64(array, offset, self.mantissa)?;
+        Ok(8)
+    }
 }
 
 impl Value for VARCHAR {
@@ -150,4 +792,310 @@ impl Value for VARCHAR {
     fn get_data_type(&self) -> DataType {
         DataType::Varchar
     }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_str(array, offset, self)?;
+        Ok(self.len() as u32)
+    }
+}
+
+impl Value for DATE {
+    fn get_inner(&self) -> InnerValue {
+        InnerValue::Date(self.0)
+    }
+
+    fn get_data_type(&self) -> DataType {
+        DataType::Date
+    }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_i32(array, offset, self.0)?;
+        Ok(4)
+    }
+}
+
+impl Value for TIMESTAMP {
+    fn get_inner(&self) -> InnerValue {
+        InnerValue::Timestamp(self.0)
+    }
+
+    fn get_data_type(&self) -> DataType {
+        DataType::Timestamp
+    }
+
+    fn write_to(&self, array: &mut [u8], offset: u32) -> Result<u32, IoError> {
+        write_i64(array, offset, self.0)?;
+        Ok(8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{read_bool, read_f32, read_i16, read_i32, read_i64, read_i8, read_str};
+
+    #[test]
+    fn test_parse_data_type() {
+        let data_types = [
+            DataType::Boolean,
+            DataType::TinyInt,
+            DataType::SmallInt,
+            DataType::Int,
+            DataType::BigInt,
+            DataType::Real,
+            DataType::Decimal,
+            DataType::Varchar,
+            DataType::Date,
+            DataType::Timestamp,
+        ];
+
+        // Assert that every data type is parsed from its name, ignoring case.
+        for data_type in data_types.iter() {
+            let name = data_type.to_string();
+            assert_eq!(name.parse::<DataType>(), Ok(*data_type));
+            assert_eq!(name.to_lowercase().parse::<DataType>(), Ok(*data_type));
+        }
+
+        // Assert that aliases are parsed.
+        assert_eq!("bool".parse::<DataType>(), Ok(DataType::Boolean));
+        assert_eq!("String".parse::<DataType>(), Ok(DataType::Varchar));
+
+        // Assert that an unknown name is rejected.
+        assert_eq!(
+            "integer8".parse::<DataType>(),
+            Err(TypeParseError("integer8".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_values() {
+        assert_eq!(
+            "2024-02-29".parse(),
+            Ok(Date::from_ymd(2024, 2, 29).unwrap())
+        );
+        assert_eq!(
+            "-0044-03-15".parse(),
+            Ok(Date::from_ymd(-44, 3, 15).unwrap())
+        );
+        assert!("2021-02-29".parse::<Date>().is_err());
+        assert!("2021-1-+1".parse::<Date>().is_err());
+
+        assert_eq!("-123.45".parse(), Ok(Decimal::new(-12_345, 2)));
+        assert_eq!("42".parse(), Ok(Decimal::new(42, 0)));
+        assert!("1.".parse::<Decimal>().is_err());
+        assert!("1e5".parse::<Decimal>().is_err());
+
+        let timestamp = Timestamp(1_615_687_166_535_897);
+        assert_eq!(timestamp.to_string().parse(), Ok(timestamp));
+        assert_eq!("1970-01-01 00:00:01.5".parse(), Ok(Timestamp(1_500_000)));
+        assert_eq!("1969-12-31 23:59:59".parse(), Ok(Timestamp(-1_000_000)));
+        assert!("1970-01-01 24:00:00".parse::<Timestamp>().is_err());
+        assert!("1970-01-01".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn test_write_to() {
+        let mut array = vec![0; 100];
+        let offset = 17;
+
+        // Assert that each value is written as the bytes that its read function decodes back.
+        assert_eq!(true.write_to(&mut array, offset).unwrap(), 1);
+        assert!(read_bool(&array, offset).unwrap());
+
+        assert_eq!((-100 as TINYINT).write_to(&mut array, offset).unwrap(), 1);
+        assert_eq!(read_i8(&array, offset).unwrap(), -100);
+
+        assert_eq!((-300 as SMALLINT).write_to(&mut array, offset).unwrap(), 2);
+        assert_eq!(read_i16(&array, offset).unwrap(), -300);
+
+        assert_eq!((-70_000 as INT).write_to(&mut array, offset).unwrap(), 4);
+        assert_eq!(read_i32(&array, offset).unwrap(), -70_000);
+
+        let value: BIGINT = -5_000_000_000;
+        assert_eq!(value.write_to(&mut array, offset).unwrap(), 8);
+        assert_eq!(read_i64(&array, offset).unwrap(), value);
+
+        let value: REAL = -76_543.21;
+        assert_eq!(value.write_to(&mut array, offset).unwrap(), 4);
+        assert_eq!(read_f32(&array, offset).unwrap(), value);
+
+        let value = Decimal::new(-1999, 2);
+        assert_eq!(value.write_to(&mut array, offset).unwrap(), 8);
+        assert_eq!(read_i64(&array, offset).unwrap(), -1999);
+
+        let value = "Hello, World!".to_string();
+        assert_eq!(value.write_to(&mut array, offset).unwrap(), 13);
+        assert_eq!(read_str(&array, offset, 13).unwrap(), value);
+
+        let value = Date::from_ymd(2021, 2, 28).unwrap();
+        assert_eq!(value.write_to(&mut array, offset).unwrap(), 4);
+        assert_eq!(Date(read_i32(&array, offset).unwrap()), value);
+
+        let value = Timestamp(1_614_470_400_000_000);
+        assert_eq!(value.write_to(&mut array, offset).unwrap(), 8);
+        assert_eq!(Timestamp(read_i64(&array, offset).unwrap()), value);
+
+        // Assert that values that don't fit in the array aren't written.
+        assert!((1 as INT).write_to(&mut array, 97).is_err());
+    }
+
+    #[test]
+    fn test_value_total_order() {
+        // Values of the same data type are ordered by value.
+        assert_eq!(
+            InnerValue::Int(-3).total_cmp(&InnerValue::Int(2)),
+            Ordering::Less
+        );
+        assert_eq!(
+            InnerValue::Varchar("b".to_string()).total_cmp(&InnerValue::Varchar("a".to_string())),
+            Ordering::Greater
+        );
+        assert_eq!(
+            InnerValue::Real(f32::NAN).total_cmp(&InnerValue::Real(f32::INFINITY)),
+            Ordering::Greater
+        );
+
+        // Decimal values with different scales are compared by numeric value.
+        let decimal = |mantissa, scale| InnerValue::Decimal { mantissa, scale };
+        assert_eq!(decimal(150, 2).total_cmp(&decimal(15, 1)), Ordering::Equal);
+        assert_eq!(decimal(-1, 0).total_cmp(&decimal(-999, 3)), Ordering::Less);
+        assert_eq!(decimal(1, 0).total_cmp(&decimal(1, 200)), Ordering::Greater);
+
+        // Values of different data types are ordered by data type.
+        assert_eq!(
+            InnerValue::BigInt(0).total_cmp(&InnerValue::Int(100)),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_value_sum_and_average() {
+        // Sum and average a list of integers.
+        let values: Vec<InnerValue> = [3, -1, 10, 4].iter().map(|v| InnerValue::Int(*v)).collect();
+        let mut sum = InnerValue::Int(0);
+        for value in values.iter() {
+            sum = sum.checked_add(value).unwrap();
+        }
+        assert_eq!(sum, InnerValue::Int(16));
+        assert_eq!(
+            sum.checked_div_u32(values.len() as u32).unwrap(),
+            InnerValue::Real(4.0)
+        );
+        assert_eq!(sum.to_f64().unwrap(), 16.0);
+
+        // Decimal values with different scales are summed at the larger scale.
+        let sum = InnerValue::Decimal {
+            mantissa: 15,
+            scale: 1,
+        }
+        .checked_add(&InnerValue::Decimal {
+            mantissa: 25,
+            scale: 2,
+        })
+        .unwrap();
+        assert!(matches!(
+            sum,
+            InnerValue::Decimal {
+                mantissa: 175,
+                scale: 2
+            }
+        ));
+        assert_eq!(sum.to_f64().unwrap(), 1.75);
+    }
+
+    #[test]
+    fn test_value_arithmetic_errors() {
+        let int = InnerValue::Int(1);
+        let varchar = InnerValue::Varchar("1".to_string());
+        assert_eq!(int.checked_add(&varchar), Err(ValueError::NonNumeric));
+        assert_eq!(varchar.checked_add(&int), Err(ValueError::NonNumeric));
+        assert_eq!(
+            InnerValue::Boolean(true).to_f64(),
+            Err(ValueError::NonNumeric)
+        );
+        assert_eq!(varchar.checked_div_u32(2), Err(ValueError::NonNumeric));
+
+        assert_eq!(
+            int.checked_add(&InnerValue::BigInt(1)),
+            Err(ValueError::TypeMismatch)
+        );
+        assert_eq!(
+            InnerValue::Int(i32::MAX).checked_add(&int),
+            Err(ValueError::Overflow)
+        );
+        assert_eq!(int.checked_div_u32(0), Err(ValueError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_value_ord() {
+        // Assert that comparison operators follow the total order.
+        assert!(InnerValue::Int(-3) < InnerValue::Int(2));
+        assert!(
+            InnerValue::Varchar("apple".to_string()) < InnerValue::Varchar("banana".to_string())
+        );
+        assert!(InnerValue::BigInt(0) > InnerValue::Int(100));
+        assert_eq!(InnerValue::Real(f32::NAN), InnerValue::Real(f32::NAN));
+        assert_eq!(
+            InnerValue::Decimal {
+                mantissa: 150,
+                scale: 2
+            },
+            InnerValue::Decimal {
+                mantissa: 15,
+                scale: 1
+            }
+        );
+
+        // Assert that null values are ordered before all other values.
+        assert!(None < Some(InnerValue::Int(i32::MIN)));
+        assert!(None < Some(InnerValue::Boolean(false)));
+
+        let mut values = vec![
+            Some(InnerValue::Varchar("b".to_string())),
+            Some(InnerValue::Int(7)),
+            None,
+            Some(InnerValue::Int(-1)),
+            Some(InnerValue::Varchar("a".to_string())),
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                None,
+                Some(InnerValue::Int(-1)),
+                Some(InnerValue::Int(7)),
+                Some(InnerValue::Varchar("a".to_string())),
+                Some(InnerValue::Varchar("b".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value_bytes_round_trip() {
+        let values = vec![
+            InnerValue::Boolean(true),
+            InnerValue::TinyInt(-8),
+            InnerValue::SmallInt(300),
+            InnerValue::Int(-70000),
+            InnerValue::BigInt(1 << 40),
+            InnerValue::Real(2.5),
+            InnerValue::Decimal {
+                mantissa: 1999,
+                scale: 2,
+            },
+            InnerValue::Varchar("héllo".to_string()),
+            InnerValue::Varchar(String::new()),
+            InnerValue::Date(18000),
+            InnerValue::Timestamp(-1),
+        ];
+        for value in values {
+            let data_type = value.clone().into_value().get_data_type();
+            let decoded = InnerValue::from_bytes(&value.to_bytes(), data_type).unwrap();
+            assert_eq!(decoded, value);
+        }
+
+        // Assert that bytes which are too short for the data type are rejected.
+        assert!(InnerValue::from_bytes(&[0; 3], DataType::Int).is_err());
+        assert!(InnerValue::from_bytes(&[0; 8], DataType::Decimal).is_err());
+    }
 }
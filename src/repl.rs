@@ -0,0 +1,231 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use crate::catalog::{CatalogError, SystemCatalog};
+use crate::concurrency::transaction::Transaction;
+use crate::constants::SYSTEM_TXN_ID;
+use crate::plan::explain_plan;
+use crate::plan::parser::{parse_create_table, parse_select, ParseError};
+use crate::relation::heap::HeapError;
+use crate::relation::record::{Record, RecordErr};
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+
+/// Usage hints printed by the .help command.
+const HELP: &str = "\
+.help                     Show this message
+.tables                   List the names of every relation
+.schema <relation>        Show the columns of a relation
+.quit                     Exit
+insert <relation> <json>  Insert a record given as a JSON object of column values
+CREATE TABLE <relation> (<column> <TYPE> [PRIMARY KEY] [NOT NULL] [SERIAL], ...)
+                          Create a relation
+EXPLAIN SELECT <column>, ... FROM <relation> [WHERE <column> <op> <value>] [LIMIT <n>]
+                          Show the plan of a query";
+
+/// Prompt printed before each command is read.
+const PROMPT: &str = "jin> ";
+
+/// A line-oriented command interpreter over the relations of a system catalog.
+pub struct Repl {
+    system_catalog: Arc<SystemCatalog>,
+}
+
+/// The result of a successfully dispatched command.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Response {
+    /// Text to be printed, which may be empty.
+    Output(String),
+
+    /// The REPL should exit.
+    Quit,
+}
+
+impl Repl {
+    pub fn new(system_catalog: Arc<SystemCatalog>) -> Self {
+        Self { system_catalog }
+    }
+
+    /// Read commands from the input line by line, and write their output (or errors) to the
+    /// output. Return when the .quit command is read or the input ends.
+    pub fn run<R: BufRead, W: Write>(&self, input: R, mut output: W) -> std::io::Result<()> {
+        writeln!(output, "Enter .help for usage hints.")?;
+        write!(output, "{}", PROMPT)?;
+        output.flush()?;
+
+        for line in input.lines() {
+            match self.dispatch(&line?) {
+                Ok(Response::Quit) => return Ok(()),
+                Ok(Response::Output(text)) if text.is_empty() => {}
+                Ok(Response::Output(text)) => writeln!(output, "{}", text)?,
+                Err(e) => writeln!(output, "Error: {}", e)?,
+            }
+            write!(output, "{}", PROMPT)?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Execute a single command and return its response. Blank lines are ignored.
+    pub fn dispatch(&self, line: &str) -> Result<Response, ReplError> {
+        let line = line.trim();
+        let (command, args) = match line.find(char::is_whitespace) {
+            Some(idx) => (&line[..idx], line[idx..].trim_start()),
+            None => (line, ""),
+        };
+
+        match command {
+            "" => Ok(Response::Output(String::new())),
+            ".help" => Ok(Response::Output(HELP.to_string())),
+            ".quit" | ".exit" => Ok(Response::Quit),
+            ".tables" => {
+                let names: Vec<String> = self
+                    .system_catalog
+                    .list_relations()
+                    .into_iter()
+                    .map(|(_, name)| name)
+                    .collect();
+                Ok(Response::Output(names.join("\n")))
+            }
+            ".schema" => self.schema(args),
+            "insert" => self.insert(args),
+            _ if command.eq_ignore_ascii_case("CREATE") => self.create_table(line),
+            _ if command.eq_ignore_ascii_case("EXPLAIN") => {
+                let plan = parse_select(args, &self.system_catalog)?;
+                Ok(Response::Output(
+                    explain_plan(plan.as_ref()).trim_end().to_string(),
+                ))
+            }
+            _ => Err(ReplError::UnknownCommand(command.to_string())),
+        }
+    }
+
+    /// Describe each column of a relation on its own line, with its data type and constraints.
+    fn schema(&self, args: &str) -> Result<Response, ReplError> {
+        if args.is_empty() {
+            return Err(ReplError::Usage(".schema <relation>"));
+        }
+        let relation = self
+            .system_catalog
+            .get_relation(args)
+            .ok_or_else(|| ReplError::RelationNotFound(args.to_string()))?;
+
+        let columns: Vec<String> = relation
+            .get_schema()
+            .get_attributes()
+            .iter()
+            .map(|attr| {
+                let mut column = format!("{} {}", attr.get_name(), attr.get_data_type());
+                if attr.is_primary() {
+                    column.push_str(" PRIMARY KEY");
+                }
+                if attr.is_serial() {
+                    column.push_str(" SERIAL");
+                }
+                if !attr.is_nullable() {
+                    column.push_str(" NOT NULL");
+                }
+                column
+            })
+            .collect();
+        Ok(Response::Output(columns.join("\n")))
+    }
+
+    /// Create a relation from a CREATE TABLE statement.
+    fn create_table(&self, sql: &str) -> Result<Response, ReplError> {
+        let (name, schema) = parse_create_table(sql)?;
+        self.system_catalog
+            .create_relation(&name, Arc::new(schema))?;
+        Ok(Response::Output(format!("Created relation \"{}\".", name)))
+    }
+
+    /// Insert a record parsed from a JSON object into a relation.
+    fn insert(&self, args: &str) -> Result<Response, ReplError> {
+        let (name, json) = match args.find(char::is_whitespace) {
+            Some(idx) => (&args[..idx], args[idx..].trim_start()),
+            None => return Err(ReplError::Usage("insert <relation> <json>")),
+        };
+        let relation = self
+            .system_catalog
+            .get_relation(name)
+            .ok_or_else(|| ReplError::RelationNotFound(name.to_string()))?;
+
+        let record = Record::from_json(json, relation.get_schema())?;
+        let rid = relation.insert(record, &Transaction::new(SYSTEM_TXN_ID))?;
+        Ok(Response::Output(format!(
+            "Inserted record at page {}, slot {}.",
+            rid.page_id, rid.slot_index
+        )))
+    }
+}
+
+/// Custom errors to be used by the REPL.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ReplError {
+    /// Error to be thrown when a command isn't recognized. Contains the command.
+    UnknownCommand(String),
+
+    /// Error to be thrown when a command is missing arguments. Contains the usage of the
+    /// command.
+    Usage(&'static str),
+
+    /// Error to be thrown when a command names a relation that doesn't exist. Contains the
+    /// name.
+    RelationNotFound(String),
+
+    /// Error to be thrown when a record can't be created from the given values.
+    InvalidRecord(RecordErr),
+
+    /// Error to be thrown when a record can't be inserted into a relation.
+    InsertFailed(HeapError),
+
+    /// Error to be thrown when a statement can't be parsed.
+    InvalidStatement(ParseError),
+
+    /// Error to be thrown when a relation can't be created.
+    CreateFailed(CatalogError),
+}
+
+impl std::fmt::Display for ReplError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplError::UnknownCommand(command) => write!(
+                f,
+                "unknown command \"{}\". Enter .help for usage hints.",
+                command
+            ),
+            ReplError::Usage(usage) => write!(f, "usage: {}", usage),
+            ReplError::RelationNotFound(name) => write!(f, "relation \"{}\" not found", name),
+            ReplError::InvalidRecord(e) => write!(f, "invalid record ({:?})", e),
+            ReplError::InsertFailed(e) => write!(f, "insertion failed ({:?})", e),
+            ReplError::InvalidStatement(e) => write!(f, "invalid statement ({:?})", e),
+            ReplError::CreateFailed(e) => write!(f, "relation creation failed ({:?})", e),
+        }
+    }
+}
+
+impl From<RecordErr> for ReplError {
+    fn from(e: RecordErr) -> Self {
+        ReplError::InvalidRecord(e)
+    }
+}
+
+impl From<HeapError> for ReplError {
+    fn from(e: HeapError) -> Self {
+        ReplError::InsertFailed(e)
+    }
+}
+
+impl From<ParseError> for ReplError {
+    fn from(e: ParseError) -> Self {
+        ReplError::InvalidStatement(e)
+    }
+}
+
+impl From<CatalogError> for ReplError {
+    fn from(e: CatalogError) -> Self {
+        ReplError::CreateFailed(e)
+    }
+}
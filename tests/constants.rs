@@ -4,8 +4,60 @@
  */
 
 use jin::constants::{BufferFrameIdT, RelationIdT, CATALOG_ROOT_ID};
+use std::fs;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Constants used for testing
 pub const TEST_DB_FILENAME: &str = "test_db.jin";
+pub const TEST_LOG_FILENAME: &str = "test_log.jin";
 pub const TEST_BUFFER_SIZE: BufferFrameIdT = 64;
 pub const FIRST_RELATION_PAGE_ID: RelationIdT = CATALOG_ROOT_ID + 1;
+
+/// Return a filename prefix that is unique to the calling test, so that tests running in
+/// parallel never share a database (or log) file.
+fn unique_prefix() -> String {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    format!("{}_{}", process::id(), id)
+}
+
+/// A guard for a unique database file and its log file, which removes both files when dropped.
+/// The log file is only created by tests that enable logging.
+pub struct TestDb {
+    filename: String,
+    log_filename: String,
+}
+
+impl TestDb {
+    pub fn new() -> Self {
+        let prefix = unique_prefix();
+        Self {
+            filename: format!("{}_{}", prefix, TEST_DB_FILENAME),
+            log_filename: format!("{}_{}", prefix, TEST_LOG_FILENAME),
+        }
+    }
+
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Only used by tests that enable logging.
+    #[allow(dead_code)]
+    pub fn log_filename(&self) -> &str {
+        &self.log_filename
+    }
+}
+
+impl Default for TestDb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.filename);
+        let _ = fs::remove_file(&self.log_filename);
+    }
+}
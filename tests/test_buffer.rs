@@ -4,9 +4,12 @@
  */
 
 use jin::buffer::replacement::ReplacerAlgorithm;
-use jin::buffer::BufferManager;
+use jin::buffer::{BufferError, BufferManager, DeletePolicy};
+use jin::constants::{PageIdT, PAGE_SIZE};
 use jin::disk::DiskManager;
 use jin::page::RelationPage;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::sync::{mpsc, Arc, Barrier};
 use std::thread;
 
@@ -15,7 +18,7 @@ mod constants;
 fn setup() -> Arc<BufferManager> {
     Arc::new(BufferManager::new(
         constants::TEST_BUFFER_SIZE,
-        DiskManager::new(constants::TEST_DB_FILENAME),
+        DiskManager::in_memory(),
         ReplacerAlgorithm::Slow,
     ))
 }
@@ -91,7 +94,8 @@ fn test_delete_buffer_page() {
         // Acquire a latch, perform some work, and unpin the new page.
         let frame = frame_arc.write().unwrap();
         // <-- Perform some workload here in practice.
-        manager_1.unpin_w(frame);
+        drop(frame);
+        drop(frame_arc);
 
         // Notify second thread to try to delete the newly created page again (should pass).
         tx.send(()).unwrap();
@@ -101,16 +105,120 @@ fn test_delete_buffer_page() {
     let handle_2 = thread::spawn(move || {
         // Receive notification from first thread to delete newly created page (should fail).
         let _ = rx.recv().unwrap();
-        let first_attempt = manager_2.delete_page(constants::FIRST_RELATION_PAGE_ID);
+        let first_attempt =
+            manager_2.delete_page(constants::FIRST_RELATION_PAGE_ID, DeletePolicy::Discard);
         assert!(first_attempt.is_err());
         barrier_2.wait();
 
         // Receive notification from first thread to delete page again (should pass).
         let _ = rx.recv().unwrap();
-        let second_attempt = manager_2.delete_page(constants::FIRST_RELATION_PAGE_ID);
+        let second_attempt =
+            manager_2.delete_page(constants::FIRST_RELATION_PAGE_ID, DeletePolicy::Discard);
         assert!(second_attempt.is_ok());
     });
 
     handle_1.join().unwrap();
     handle_2.join().unwrap();
 }
+
+#[test]
+fn test_create_page_reuses_deleted_page_id() {
+    let manager = setup();
+
+    // Create a page, unpin it, and delete it (freeing the page on disk).
+    let frame_arc = manager.create_page().unwrap();
+    let frame = frame_arc.write().unwrap();
+    let page_id = RelationPage::get_id(frame.get_page().unwrap());
+    drop(frame);
+    drop(frame_arc);
+    manager.delete_page(page_id, DeletePolicy::Discard).unwrap();
+
+    // Assert that the next created page recycles the freed page ID.
+    let frame_arc = manager.create_page().unwrap();
+    let frame = frame_arc.read().unwrap();
+    assert_eq!(RelationPage::get_id(frame.get_page().unwrap()), page_id);
+
+    // Assert that subsequent pages are allocated from the high-water mark again.
+    let frame_arc = manager.create_page().unwrap();
+    let frame = frame_arc.read().unwrap();
+    assert_eq!(RelationPage::get_id(frame.get_page().unwrap()), page_id + 1);
+}
+
+#[test]
+fn test_create_page_in_full_buffer_does_not_allocate() {
+    let manager = setup();
+
+    // Pin every frame in the buffer with a newly created page.
+    let mut frames = Vec::new();
+    for _ in 0..constants::TEST_BUFFER_SIZE {
+        frames.push(manager.create_page().unwrap());
+    }
+    let last_id = {
+        let frame = frames.last().unwrap().read().unwrap();
+        RelationPage::get_id(frame.get_page().unwrap())
+    };
+
+    // Assert that creating a page fails without allocating a page on disk.
+    assert!(matches!(
+        manager.create_page(),
+        Err(BufferError::NoBufFrame)
+    ));
+    assert!(matches!(
+        manager.fetch_page(last_id + 1),
+        Err(BufferError::PageDiskDNE)
+    ));
+
+    // Free up a frame, and assert that the next created page receives the next page ID.
+    drop(frames.pop());
+    let frame_arc = manager.create_page().unwrap();
+    let frame = frame_arc.read().unwrap();
+    assert_eq!(RelationPage::get_id(frame.get_page().unwrap()), last_id + 1);
+}
+
+#[test]
+fn test_delete_page_with_flush_policy() {
+    let db = constants::TestDb::new();
+    let filename = db.filename();
+    let manager = BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(filename),
+        ReplacerAlgorithm::Slow,
+    );
+
+    // Create a page for each policy, modify them, and unpin them without flushing.
+    let mut page_ids = Vec::new();
+    for _ in 0..2 {
+        let frame_arc = manager.create_page().unwrap();
+        let mut frame = frame_arc.write().unwrap();
+        let page = frame.get_mut_page().unwrap();
+        RelationPage::init(page);
+        RelationPage::set_next_page_id(page, 123);
+        page_ids.push(RelationPage::get_id(page));
+    }
+    let (discarded, flushed) = (page_ids[0], page_ids[1]);
+
+    manager
+        .delete_page(discarded, DeletePolicy::Discard)
+        .unwrap();
+    manager
+        .delete_page(flushed, DeletePolicy::FlushThenDelete)
+        .unwrap();
+
+    // Assert that only the page deleted with the flush policy was written to disk.
+    let read_disk_page = |page_id: PageIdT| {
+        let mut page = [0; PAGE_SIZE as usize];
+        let mut file = File::open(filename).unwrap();
+        file.seek(SeekFrom::Start((page_id * PAGE_SIZE) as u64))
+            .unwrap();
+        file.read_exact(&mut page).unwrap();
+        page
+    };
+    assert_eq!(
+        RelationPage::get_next_page_id(&read_disk_page(discarded)),
+        None
+    );
+    assert_eq!(
+        RelationPage::get_next_page_id(&read_disk_page(flushed)),
+        Some(123)
+    );
+}
@@ -5,14 +5,17 @@
 
 use jin::buffer::replacement::ReplacerAlgorithm;
 use jin::buffer::BufferManager;
-use jin::catalog::SystemCatalog;
+use jin::catalog::{CatalogError, SystemCatalog};
+use jin::concurrency::transaction::Transaction;
+use jin::constants::{PAGE_SIZE, SYSTEM_TXN_ID};
 use jin::disk::DiskManager;
-use jin::relation::record::{Record, RecordId};
-use jin::relation::types::{DataType, InnerValue};
-use jin::relation::Attribute;
+use jin::relation::record::{Record, RecordErr, RecordId};
+use jin::relation::types::{DataType, InnerValue, Value};
 use jin::relation::Schema;
+use jin::relation::{export_csv, import_csv, Attribute, ImportError};
 
 use jin::relation::heap::HeapError;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::thread;
 
@@ -22,12 +25,14 @@ struct TestContext {
     schema_1: Arc<Schema>,
     schema_2: Arc<Schema>,
     system_catalog: Arc<SystemCatalog>,
+    _db: constants::TestDb,
 }
 
 fn setup() -> TestContext {
+    let db = constants::TestDb::new();
     let buffer_manager = BufferManager::new(
         constants::TEST_BUFFER_SIZE,
-        DiskManager::new(constants::TEST_DB_FILENAME),
+        DiskManager::new(db.filename()),
         ReplacerAlgorithm::Slow,
     );
 
@@ -46,6 +51,7 @@ fn setup() -> TestContext {
         system_catalog: Arc::new(SystemCatalog::new(Arc::new(buffer_manager))),
         schema_1,
         schema_2,
+        _db: db,
     }
 }
 
@@ -66,6 +72,371 @@ fn test_create_relation() {
     assert_eq!(relation.get_id(), 1);
 }
 
+#[test]
+fn test_create_duplicate_relation() {
+    let ctx = setup();
+
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+
+    // Assert that a second relation with the same name is rejected, even with another schema.
+    assert_eq!(
+        ctx.system_catalog
+            .create_relation("foo", ctx.schema_2.clone())
+            .err(),
+        Some(CatalogError::RelationExists)
+    );
+
+    // Assert that the original relation is unaffected and no duplicate entry was recorded.
+    let fetched = ctx.system_catalog.get_relation("foo").unwrap();
+    assert_eq!(fetched.get_id(), relation.get_id());
+    assert_eq!(fetched.get_schema(), ctx.schema_1);
+    assert_eq!(
+        ctx.system_catalog.list_relations(),
+        vec![(relation.get_id(), "foo".to_string())]
+    );
+}
+
+#[test]
+fn test_create_relations_across_dictionary_pages() {
+    let db = constants::TestDb::new();
+    let filename = db.filename();
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager.clone());
+    let schema = Arc::new(Schema::new(vec![Attribute::new(
+        "foo",
+        DataType::Int,
+        false,
+        false,
+        false,
+    )]));
+
+    // Create more relations than fit in a single dictionary page.
+    let num_relations = PAGE_SIZE / 40 + 10;
+    let names: Vec<String> = (0..num_relations)
+        .map(|i| format!("relation_{}", i))
+        .collect();
+    for name in names.iter() {
+        system_catalog
+            .create_relation(name, schema.clone())
+            .unwrap();
+    }
+
+    // Assert that every relation is resolvable by name, and listed in order of creation.
+    for (id, name) in names.iter().enumerate() {
+        let relation = system_catalog.get_relation(name).unwrap();
+        assert_eq!(relation.get_id(), id as u32);
+    }
+    let listed: Vec<String> = system_catalog
+        .list_relations()
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect();
+    assert_eq!(listed, names);
+    assert_eq!(
+        system_catalog
+            .create_relation("relation_0", schema.clone())
+            .err(),
+        Some(CatalogError::RelationExists)
+    );
+
+    // Assert that every relation is read back from the dictionary pages on disk.
+    buffer_manager.flush_all_pages().unwrap();
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::open(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager);
+    for (id, name) in names.iter().enumerate() {
+        let relation = system_catalog.get_relation(name).unwrap();
+        assert_eq!(relation.get_id(), id as u32);
+    }
+}
+
+#[test]
+fn test_get_or_create_relation() {
+    let ctx = setup();
+
+    // Assert that a relation that doesn't exist is created.
+    let relation = ctx
+        .system_catalog
+        .get_or_create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+    assert_eq!(relation.get_name(), "foo");
+    assert_eq!(ctx.system_catalog.list_relations().len(), 1);
+
+    // Assert that an existing relation is returned, given an equal schema.
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("foo", DataType::Int, true, true, true),
+        Attribute::new("bar", DataType::Boolean, false, false, true),
+        Attribute::new("baz", DataType::Varchar, false, false, true),
+    ]));
+    let existing = ctx
+        .system_catalog
+        .get_or_create_relation("foo", schema)
+        .unwrap();
+    assert_eq!(existing.get_id(), relation.get_id());
+    assert_eq!(ctx.system_catalog.list_relations().len(), 1);
+
+    // Assert that an existing relation with a different schema is rejected.
+    assert!(matches!(
+        ctx.system_catalog
+            .get_or_create_relation("foo", ctx.schema_2.clone()),
+        Err(CatalogError::SchemaMismatch)
+    ));
+    assert_eq!(ctx.system_catalog.list_relations().len(), 1);
+}
+
+#[test]
+fn test_list_relations() {
+    let ctx = setup();
+    assert!(ctx.system_catalog.list_relations().is_empty());
+
+    let mut ids = Vec::new();
+    for (name, schema) in [
+        ("relation_1", &ctx.schema_1),
+        ("relation_2", &ctx.schema_2),
+        ("relation_3", &ctx.schema_1),
+    ]
+    .iter()
+    {
+        let relation = ctx
+            .system_catalog
+            .create_relation(name, (*schema).clone())
+            .unwrap();
+        ids.push(relation.get_id());
+    }
+
+    // Assert that every relation is listed in order of creation.
+    assert_eq!(
+        ctx.system_catalog.list_relations(),
+        vec![
+            (ids[0], "relation_1".to_string()),
+            (ids[1], "relation_2".to_string()),
+            (ids[2], "relation_3".to_string()),
+        ]
+    );
+
+    // Assert that a relation name which doesn't fit in the dictionary is rejected.
+    let result = ctx
+        .system_catalog
+        .create_relation(&"x".repeat(33), ctx.schema_1.clone());
+    assert_eq!(result.err(), Some(CatalogError::NameTooLong));
+    assert_eq!(ctx.system_catalog.list_relations().len(), 3);
+}
+
+#[test]
+fn test_reload_catalog() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let db = constants::TestDb::new();
+    let filename = db.filename();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, true, false),
+        Attribute::new("price", DataType::Decimal, false, false, true).with_scale(2),
+        Attribute::new("name", DataType::Varchar, false, false, true),
+        Attribute::new("status", DataType::Varchar, false, false, false)
+            .with_default(Box::new("new".to_string())),
+    ]));
+
+    // Create a relation with a record, and flush every page to disk.
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager.clone());
+    let relation = system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+    let record = Record::new(
+        vec![
+            Some(Box::new(7_i32)),
+            None,
+            Some(Box::new("bar".to_string())),
+            None,
+        ],
+        schema.clone(),
+    )
+    .unwrap();
+    let rid = relation.insert(record, &txn).unwrap();
+    buffer_manager.flush_all_pages().unwrap();
+
+    // Construct a fresh system catalog over the same database file.
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::open(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager);
+
+    // Assert that the relation and its schema were read back.
+    let reloaded = system_catalog.get_relation("foo").unwrap();
+    assert_eq!(reloaded.get_id(), relation.get_id());
+    let reloaded_schema = reloaded.get_schema();
+    assert_eq!(reloaded_schema.attr_len(), 4);
+    for (attr, expected) in reloaded_schema
+        .get_attributes()
+        .iter()
+        .zip(schema.get_attributes().iter())
+    {
+        assert_eq!(attr.get_name(), expected.get_name());
+        assert_eq!(attr.get_data_type(), expected.get_data_type());
+        assert_eq!(attr.is_primary(), expected.is_primary());
+        assert_eq!(attr.is_serial(), expected.is_serial());
+        assert_eq!(attr.is_nullable(), expected.is_nullable());
+        assert_eq!(attr.get_scale(), expected.get_scale());
+        assert_eq!(attr.get_default(), expected.get_default());
+    }
+
+    // Assert that the record can be read and interpreted with the reloaded schema.
+    let record = reloaded.read(rid).unwrap();
+    let value = record
+        .get_value(2, reloaded_schema.clone())
+        .unwrap()
+        .unwrap();
+    assert_eq!(value.get_inner(), InnerValue::Varchar("bar".to_string()));
+
+    // Assert that records created with the reloaded schema still receive the default value.
+    let record = Record::new(
+        vec![Some(Box::new(8_i32)), None, None, None],
+        reloaded_schema.clone(),
+    )
+    .unwrap();
+    let rid = reloaded.insert(record, &txn).unwrap();
+    let record = reloaded.read(rid).unwrap();
+    let value = record.get_value(3, reloaded_schema).unwrap().unwrap();
+    assert_eq!(value.get_inner(), InnerValue::Varchar("new".to_string()));
+
+    // Assert that relations created after reloading don't reuse relation IDs.
+    let created = system_catalog
+        .create_relation("baz", schema.clone())
+        .unwrap();
+    assert!(created.get_id() > relation.get_id());
+}
+
+#[test]
+fn test_reload_catalog_serial() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let db = constants::TestDb::new();
+    let filename = db.filename();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, true, false),
+        Attribute::new("name", DataType::Varchar, false, false, true),
+    ]));
+    let new_record = |name: &str| {
+        Record::new(vec![None, Some(Box::new(name.to_string()))], schema.clone()).unwrap()
+    };
+
+    // Insert records with serial IDs 0 to 2, flag the last one for deletion, and flush every
+    // page to disk.
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager.clone());
+    let relation = system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+    let mut rids = Vec::new();
+    for name in ["a", "b", "c"].iter() {
+        rids.push(relation.insert(new_record(name), &txn).unwrap());
+    }
+    relation.flag_delete(rids[2], &txn).unwrap();
+    buffer_manager.flush_all_pages().unwrap();
+
+    // Construct a fresh system catalog over the same database file.
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::open(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager);
+    let reloaded = system_catalog.get_relation("foo").unwrap();
+
+    // Assert that serial values continue after the largest existing value, including the value
+    // of the record whose deletion is pending.
+    let rid = reloaded.insert(new_record("d"), &txn).unwrap();
+    let record = reloaded.read(rid).unwrap();
+    let value = record.get_value(0, schema.clone()).unwrap().unwrap();
+    assert_eq!(value.get_inner(), InnerValue::Int(3));
+}
+
+#[test]
+fn test_reload_catalog_checks() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let db = constants::TestDb::new();
+    let filename = db.filename();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, false),
+        Attribute::new("age", DataType::Int, false, false, true).with_check(
+            "age_positive",
+            |value| match value.get_inner() {
+                InnerValue::Int(age) => age > 0,
+                _ => false,
+            },
+        ),
+    ]));
+    let new_record = |id: i32, age: i32| {
+        let mut record = Record::new(vec![Some(Box::new(id)), None], schema.clone()).unwrap();
+        record
+            .set_value(1, Some(Box::new(age)), schema.clone())
+            .unwrap();
+        record
+    };
+
+    // Create a relation with a check, and flush every page to disk.
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager.clone());
+    let relation = system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+    relation.insert(new_record(1, 30), &txn).unwrap();
+    buffer_manager.flush_all_pages().unwrap();
+
+    // Construct a fresh system catalog over the same database file.
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::open(filename),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager);
+
+    // Assert that the check is read back, but rejects values until its validator is registered.
+    let reloaded = system_catalog.get_relation("foo").unwrap();
+    let reloaded_schema = reloaded.get_schema();
+    let attr = &reloaded_schema.get_attributes()[1];
+    assert_eq!(attr.get_check_names(), vec!["age_positive"]);
+    assert!(matches!(
+        reloaded.insert(new_record(2, 30), &txn),
+        Err(HeapError::InvalidRecord(RecordErr::CheckUnregistered(name))) if name == "age_positive"
+    ));
+
+    // Assert that the relation can be reopened with its original schema, which registers the
+    // validator of the check.
+    let reopened = system_catalog
+        .get_or_create_relation("foo", schema.clone())
+        .unwrap();
+    assert_eq!(reopened.get_id(), reloaded.get_id());
+    assert!(!reloaded.get_schema().get_attributes()[1].has_unregistered_checks());
+    reloaded.insert(new_record(2, 30), &txn).unwrap();
+    assert!(matches!(
+        reloaded.insert(new_record(3, -1), &txn),
+        Err(HeapError::InvalidRecord(RecordErr::CheckViolation(_)))
+    ));
+}
+
 #[test]
 fn test_get_relation() {
     let ctx = setup();
@@ -104,6 +475,7 @@ fn test_get_relation() {
 
 #[test]
 fn test_insert_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
     let ctx = setup();
 
     // Create new relation.
@@ -126,13 +498,147 @@ fn test_insert_record() {
     assert!(record.get_id().is_none());
 
     // Assert that the record can be inserted into the relation.
-    let record_id = relation.insert(record).unwrap();
+    let record_id = relation.insert(record, &txn).unwrap();
     assert_eq!(record_id.page_id, constants::FIRST_RELATION_PAGE_ID);
     assert_eq!(record_id.slot_index, 0);
 }
 
+#[test]
+fn test_insert_record_with_default() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, false),
+        Attribute::new("status", DataType::Varchar, false, false, false)
+            .with_default(Box::new("new".to_string())),
+        Attribute::new("count", DataType::Int, false, false, true).with_default(Box::new(0)),
+        Attribute::new("note", DataType::Varchar, false, false, true),
+    ]));
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+
+    // Insert a record omitting the columns with defaults, and assert that the defaults are
+    // stored.
+    let record = Record::new(vec![Some(Box::new(1)), None, None, None], schema.clone()).unwrap();
+    let rid = relation.insert(record, &txn).unwrap();
+    let record = relation.read(rid).unwrap();
+    assert_eq!(
+        record
+            .get_value(1, schema.clone())
+            .unwrap()
+            .unwrap()
+            .get_inner(),
+        InnerValue::Varchar("new".to_string())
+    );
+    assert_eq!(
+        record
+            .get_value(2, schema.clone())
+            .unwrap()
+            .unwrap()
+            .get_inner(),
+        InnerValue::Int(0)
+    );
+    assert!(record.get_value(3, schema.clone()).unwrap().is_none());
+
+    // Assert that specified values take precedence over defaults.
+    let record = Record::new(
+        vec![
+            Some(Box::new(2)),
+            Some(Box::new("done".to_string())),
+            Some(Box::new(5)),
+            None,
+        ],
+        schema.clone(),
+    )
+    .unwrap();
+    let rid = relation.insert(record, &txn).unwrap();
+    let record = relation.read(rid).unwrap();
+    assert_eq!(
+        record
+            .get_value(1, schema.clone())
+            .unwrap()
+            .unwrap()
+            .get_inner(),
+        InnerValue::Varchar("done".to_string())
+    );
+    assert_eq!(
+        record
+            .get_value(2, schema.clone())
+            .unwrap()
+            .unwrap()
+            .get_inner(),
+        InnerValue::Int(5)
+    );
+
+    // Assert that omitting a non-nullable column without a default still fails.
+    assert!(matches!(
+        Record::new(vec![None, None, None, None], schema.clone()),
+        Err(RecordErr::NotNullable)
+    ));
+
+    // Assert that a default with the wrong data type is rejected.
+    let schema = Arc::new(Schema::new(vec![Attribute::new(
+        "id",
+        DataType::Int,
+        false,
+        false,
+        false,
+    )
+    .with_default(Box::new(true))]));
+    assert!(matches!(
+        Record::new(vec![None], schema),
+        Err(RecordErr::ValSchemaMismatch)
+    ));
+}
+
+#[test]
+fn test_insert_record_with_check() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, false),
+        Attribute::new("age", DataType::Int, false, false, true).with_check(
+            "age_positive",
+            |value| match value.get_inner() {
+                InnerValue::Int(age) => age > 0,
+                _ => false,
+            },
+        ),
+    ]));
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+
+    // Assert that values satisfying the check, and null values, are accepted.
+    let record = Record::new(vec![Some(Box::new(1)), Some(Box::new(30))], schema.clone()).unwrap();
+    relation.insert(record, &txn).unwrap();
+    let record = Record::new(vec![Some(Box::new(2)), None], schema.clone()).unwrap();
+    relation.insert(record, &txn).unwrap();
+
+    // Assert that a negative value is rejected, naming the failed check.
+    assert!(matches!(
+        Record::new(vec![Some(Box::new(3)), Some(Box::new(-1))], schema.clone()),
+        Err(RecordErr::CheckViolation(name)) if name == "age_positive"
+    ));
+
+    // Assert that a value set after construction is checked upon insertion.
+    let mut record =
+        Record::new(vec![Some(Box::new(3)), Some(Box::new(30))], schema.clone()).unwrap();
+    record
+        .set_value(1, Some(Box::new(-1)), schema.clone())
+        .unwrap();
+    assert!(matches!(
+        relation.insert(record, &txn),
+        Err(HeapError::InvalidRecord(RecordErr::CheckViolation(_)))
+    ));
+}
+
 #[test]
 fn test_insert_many_records() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
     let ctx = setup();
 
     // Create new relation.
@@ -141,10 +647,11 @@ fn test_insert_many_records() {
         .create_relation("foo", ctx.schema_1.clone())
         .unwrap();
 
-    // Create a record for the newly created relation.
+    // Create a record for the newly created relation. The serial column is left null so that
+    // each insertion is assigned a unique value.
     let record = Record::new(
         vec![
-            Some(Box::new(0)),
+            None,
             Some(Box::new(true)),
             Some(Box::new(
                 "abcdefghijklmnopqrstuvwxyz \
@@ -171,8 +678,156 @@ fn test_insert_many_records() {
 
     // Assert that several records can be inserted into the relation.
     for _ in 0..20 {
-        assert!(relation.insert(record.clone()).is_ok());
+        assert!(relation.insert(record.clone(), &txn).is_ok());
+    }
+}
+
+#[test]
+fn test_relation_counts() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+    assert_eq!(relation.approx_row_count(), 0);
+    assert_eq!(relation.page_count(), 1);
+
+    // Insert records that are large enough to span several pages.
+    let record = Record::new(
+        vec![None, Some(Box::new(true)), Some(Box::new("x".repeat(1000)))],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+    let mut rids = Vec::new();
+    for _ in 0..37 {
+        rids.push(relation.insert(record.clone(), &txn).unwrap());
     }
+    assert_eq!(relation.approx_row_count(), 37);
+    assert!(relation.page_count() > 1);
+
+    // Assert that records flagged for deletion are not counted.
+    relation.flag_delete(rids[0], &txn).unwrap();
+    relation.flag_delete(rids[1], &txn).unwrap();
+    relation.commit_delete(rids[1], &txn).unwrap();
+    assert_eq!(relation.approx_row_count(), 35);
+}
+
+#[test]
+fn test_bulk_insert_records() {
+    let ctx = setup();
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+
+    let heap_1 = ctx
+        .system_catalog
+        .create_relation("relation_1", ctx.schema_1.clone())
+        .unwrap()
+        .get_heap();
+    let heap_2 = ctx
+        .system_catalog
+        .create_relation("relation_2", ctx.schema_1.clone())
+        .unwrap()
+        .get_heap();
+
+    // Create records spanning several pages, including a record stored in overflow pages.
+    let records: Vec<Record> = (0..1000)
+        .map(|i| {
+            let len = if i == 500 { PAGE_SIZE as usize * 2 } else { 10 };
+            Record::new(
+                vec![
+                    Some(Box::new(i)),
+                    Some(Box::new(i % 2 == 0)),
+                    Some(Box::new("a".repeat(len))),
+                ],
+                ctx.schema_1.clone(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    // Insert the records in bulk into one heap, and one at a time into the other.
+    let rids_1 = heap_1.insert_many(records.clone(), &txn).unwrap();
+    let rids_2: Vec<RecordId> = records
+        .iter()
+        .map(|record| heap_2.insert(record.clone(), &txn).unwrap())
+        .collect();
+
+    // Assert that both heaps contain the same records in input order.
+    assert_eq!(rids_1.len(), 1000);
+    assert!(heap_1.get_num_pages().unwrap() > 1);
+    assert_eq!(heap_1.get_num_pages(), heap_2.get_num_pages());
+    for ((rid_1, rid_2), record) in rids_1.iter().zip(rids_2.iter()).zip(records.iter()) {
+        assert_eq!(rid_1.slot_index, rid_2.slot_index);
+        let record_1 = heap_1.read(*rid_1).unwrap();
+        assert_eq!(record_1.as_bytes(), record.as_bytes());
+        assert_eq!(record_1.as_bytes(), heap_2.read(*rid_2).unwrap().as_bytes());
+    }
+
+    // Assert that allocated records are rejected before anything is inserted.
+    let allocated = heap_1.read(rids_1[0]).unwrap();
+    let result = heap_1.insert_many(vec![records[0].clone(), allocated], &txn);
+    assert_eq!(result.unwrap_err(), HeapError::RecordAlreadyAlloc);
+    assert_eq!(heap_1.get_num_records().unwrap(), 1000);
+}
+
+#[test]
+fn test_insert_records_into_heap_in_parallel() {
+    let ctx = setup();
+    let heap = ctx
+        .system_catalog
+        .create_relation("relation_1", ctx.schema_1.clone())
+        .unwrap()
+        .get_heap();
+    let reference = ctx
+        .system_catalog
+        .create_relation("relation_2", ctx.schema_1.clone())
+        .unwrap()
+        .get_heap();
+
+    let num_threads = 8;
+    let num_inserts_per_thread = 250;
+    let record = Record::new(
+        vec![
+            Some(Box::new(1)),
+            Some(Box::new(true)),
+            Some(Box::new("a".repeat(100))),
+        ],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+
+    // Insert records into the heap directly from several threads, so that insertions race to
+    // fill pages and append new pages.
+    let mut handles = Vec::with_capacity(num_threads);
+    for _ in 0..num_threads {
+        let heap = heap.clone();
+        let record = record.clone();
+        handles.push(thread::spawn(move || {
+            let txn = Transaction::new(SYSTEM_TXN_ID);
+            (0..num_inserts_per_thread)
+                .map(|_| heap.insert(record.clone(), &txn).unwrap())
+                .collect::<Vec<RecordId>>()
+        }));
+    }
+    let mut rids: Vec<RecordId> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect();
+
+    // Assert that every record is reachable from the root page exactly once.
+    let total = num_threads * num_inserts_per_thread;
+    assert_eq!(heap.scan().count(), total);
+    rids.sort_by_key(|rid| (rid.page_id, rid.slot_index));
+    rids.dedup();
+    assert_eq!(rids.len(), total);
+
+    // Assert that pages are filled before new pages are appended, by comparing against a heap
+    // that is filled sequentially.
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    reference.insert_many(vec![record; total], &txn).unwrap();
+    let num_pages = reference.get_num_pages().unwrap();
+    assert!(num_pages > 1);
+    assert!(heap.get_num_pages().unwrap() <= num_pages + num_threads as u32);
 }
 
 #[test]
@@ -193,7 +848,7 @@ fn test_insert_many_records_in_parallel() {
     // Create records for each newly created relation.
     let record_1 = Record::new(
         vec![
-            Some(Box::new(0)),
+            None,
             Some(Box::new(true)),
             Some(Box::new("Hello, World!".to_string())),
         ],
@@ -201,11 +856,7 @@ fn test_insert_many_records_in_parallel() {
     )
     .unwrap();
 
-    let record_2 = Record::new(
-        vec![Some(Box::new(123456789_i32)), Some(Box::new(false))],
-        ctx.schema_2.clone(),
-    )
-    .unwrap();
+    let record_2 = Record::new(vec![None, Some(Box::new(false))], ctx.schema_2.clone()).unwrap();
 
     let num_threads = 20;
     let num_inserts_per_thread = 100;
@@ -216,8 +867,9 @@ fn test_insert_many_records_in_parallel() {
         let relation = relation_1.clone();
         let record = record_1.clone();
         handles.push(thread::spawn(move || {
+            let txn = Transaction::new(SYSTEM_TXN_ID);
             for _ in 0..num_inserts_per_thread {
-                relation.insert(record.clone()).unwrap();
+                relation.insert(record.clone(), &txn).unwrap();
             }
         }));
     }
@@ -225,8 +877,9 @@ fn test_insert_many_records_in_parallel() {
         let relation = relation_2.clone();
         let record = record_2.clone();
         handles.push(thread::spawn(move || {
+            let txn = Transaction::new(SYSTEM_TXN_ID);
             for _ in 0..num_inserts_per_thread {
-                relation.insert(record.clone()).unwrap();
+                relation.insert(record.clone(), &txn).unwrap();
             }
         }));
     }
@@ -236,8 +889,256 @@ fn test_insert_many_records_in_parallel() {
     }
 }
 
+#[test]
+fn test_insert_serial_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+
+    // Create new relation.
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+
+    // Insert two records with the serial column left null.
+    let mut rids = Vec::new();
+    for _ in 0..2 {
+        let record =
+            Record::new(vec![None, Some(Box::new(true)), None], ctx.schema_1.clone()).unwrap();
+        rids.push(relation.insert(record, &txn).unwrap());
+    }
+
+    // Assert that the records were assigned consecutive serial values.
+    for (i, rid) in rids.iter().enumerate() {
+        let value = relation
+            .read(*rid)
+            .unwrap()
+            .get_value(0, ctx.schema_1.clone())
+            .unwrap()
+            .unwrap()
+            .get_inner();
+        assert_eq!(value, InnerValue::Int(i as i32));
+    }
+
+    // Assert that a serial value less than the counter is rejected.
+    let record = Record::new(
+        vec![Some(Box::new(1_i32)), Some(Box::new(true)), None],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        relation.insert(record, &txn).unwrap_err(),
+        HeapError::InvalidRecord(RecordErr::SerialConflict)
+    );
+
+    // Assert that a larger serial value is accepted, and advances the counter.
+    let record = Record::new(
+        vec![Some(Box::new(10_i32)), Some(Box::new(true)), None],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+    assert!(relation.insert(record, &txn).is_ok());
+
+    let record = Record::new(vec![None, Some(Box::new(true)), None], ctx.schema_1.clone()).unwrap();
+    let rid = relation.insert(record, &txn).unwrap();
+    let value = relation
+        .read(rid)
+        .unwrap()
+        .get_value(0, ctx.schema_1.clone())
+        .unwrap()
+        .unwrap()
+        .get_inner();
+    assert_eq!(value, InnerValue::Int(11));
+}
+
+#[test]
+fn test_insert_duplicate_primary_key() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+
+    // Create a relation with a non-serial primary key.
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, true),
+        Attribute::new("name", DataType::Varchar, false, false, true),
+    ]));
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+
+    let create = |id: i32, name: &str| {
+        Record::new(
+            vec![Some(Box::new(id)), Some(Box::new(name.to_string()))],
+            schema.clone(),
+        )
+        .unwrap()
+    };
+    assert!(relation.insert(create(1, "Alice"), &txn).is_ok());
+    let rid_2 = relation.insert(create(2, "Bob"), &txn).unwrap();
+
+    // Assert that a duplicate primary key is rejected.
+    assert_eq!(
+        relation.insert(create(1, "Carol"), &txn).unwrap_err(),
+        HeapError::DuplicatePrimaryKey
+    );
+
+    // Assert that a primary key can be reused once the existing record is deleted.
+    let rid = relation.insert(create(3, "Dave"), &txn).unwrap();
+    relation.flag_delete(rid, &txn).unwrap();
+    relation.commit_delete(rid, &txn).unwrap();
+    assert!(relation.insert(create(3, "Erin"), &txn).is_ok());
+
+    // Assert that a primary key can't be reused while the deletion of the existing record is
+    // pending, since the deletion may be rolled back.
+    relation.flag_delete(rid_2, &txn).unwrap();
+    assert_eq!(
+        relation.insert(create(2, "Frank"), &txn).unwrap_err(),
+        HeapError::DuplicatePrimaryKey
+    );
+    relation.rollback_delete(rid_2, &txn).unwrap();
+    assert_eq!(relation.count(), 3);
+}
+
+#[test]
+fn test_update_primary_key() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+
+    // Create a relation with a nullable, non-serial primary key and a non-nullable serial
+    // column.
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, true),
+        Attribute::new("seq", DataType::Int, false, true, false),
+    ]));
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+
+    let create = |id: Option<i32>, seq: Option<i32>| {
+        let id: Option<Box<dyn Value>> = match id {
+            Some(id) => Some(Box::new(id)),
+            None => None,
+        };
+        let seq: Option<Box<dyn Value>> = match seq {
+            Some(seq) => Some(Box::new(seq)),
+            None => None,
+        };
+        Record::new(vec![id, seq], schema.clone()).unwrap()
+    };
+    let rid_1 = relation.insert(create(Some(1), None), &txn).unwrap();
+    relation.insert(create(Some(2), None), &txn).unwrap();
+
+    // Assert that a record can be updated without changing its primary key.
+    let rid_1 = relation
+        .update(create(Some(1), Some(10)), rid_1, &txn)
+        .unwrap();
+
+    // Assert that a primary key can't be updated to a null or duplicate value.
+    assert_eq!(
+        relation
+            .update(create(None, Some(11)), rid_1, &txn)
+            .unwrap_err(),
+        HeapError::PrimaryKeyNull
+    );
+    assert_eq!(
+        relation
+            .update(create(Some(2), Some(11)), rid_1, &txn)
+            .unwrap_err(),
+        HeapError::DuplicatePrimaryKey
+    );
+
+    // Assert that a non-nullable column can't be updated to null.
+    assert_eq!(
+        relation
+            .update(create(Some(1), None), rid_1, &txn)
+            .unwrap_err(),
+        HeapError::InvalidRecord(RecordErr::NotNullable)
+    );
+
+    // Assert that the rejected updates left the record unchanged.
+    let record = relation.read(rid_1).unwrap();
+    let value = record.get_value(1, schema.clone()).unwrap().unwrap();
+    assert_eq!(value.get_inner(), InnerValue::Int(10));
+}
+
+#[test]
+fn test_insert_null_primary_key() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+
+    // Create a relation with a nullable, non-serial primary key.
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, true),
+        Attribute::new("name", DataType::Varchar, false, false, true),
+    ]));
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+
+    // Assert that a null primary key is rejected.
+    let record = Record::new(
+        vec![None, Some(Box::new("Alice".to_string()))],
+        schema.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        relation.insert(record, &txn).unwrap_err(),
+        HeapError::PrimaryKeyNull
+    );
+}
+
 #[test]
 fn test_read_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+
+    // Create a relation and insert a record.
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+    let record = Record::new(
+        vec![Some(Box::new(54321_i32)), Some(Box::new(false)), None],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+    let rid = relation.insert(record, &txn).unwrap();
+
+    // Assert that read values are correct.
+    let dne = RecordId {
+        page_id: rid.page_id,
+        slot_index: rid.slot_index + 1,
+    };
+
+    let result = relation.read(rid);
+    assert!(result.is_ok());
+    assert!(relation.read(dne).is_err());
+
+    let record = result.unwrap();
+
+    let value = record
+        .get_value(0, ctx.schema_1.clone())
+        .unwrap()
+        .unwrap()
+        .get_inner();
+    assert_eq!(value, InnerValue::Int(54321));
+
+    let value = record
+        .get_value(1, ctx.schema_1.clone())
+        .unwrap()
+        .unwrap()
+        .get_inner();
+    assert_eq!(value, InnerValue::Boolean(false));
+
+    let value = record.get_value(2, ctx.schema_1.clone()).unwrap();
+    assert!(value.is_none());
+}
+
+#[test]
+fn test_read_record_value_by_name() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
     let ctx = setup();
 
     // Create a relation and insert a record.
@@ -246,44 +1147,43 @@ fn test_read_record() {
         .create_relation("foo", ctx.schema_1.clone())
         .unwrap();
     let record = Record::new(
-        vec![Some(Box::new(54321_i32)), Some(Box::new(false)), None],
+        vec![
+            Some(Box::new(12345_i32)),
+            None,
+            Some(Box::new("Hello, World!".to_string())),
+        ],
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let rid = relation.insert(record).unwrap();
-
-    // Assert that read values are correct.
-    let dne = RecordId {
-        page_id: rid.page_id,
-        slot_index: rid.slot_index + 1,
-    };
-
-    let result = relation.read(rid);
-    assert!(result.is_ok());
-    assert!(relation.read(dne).is_err());
-
-    let record = result.unwrap();
+    let rid = relation.insert(record, &txn).unwrap();
+    let record = relation.read(rid).unwrap();
 
-    let value = record
-        .get_value(0, ctx.schema_1.clone())
+    // Assert that values read by name match values read by index.
+    let by_name = record
+        .get_value_by_name("baz", ctx.schema_1.clone())
         .unwrap()
         .unwrap()
         .get_inner();
-    assert_eq!(value, InnerValue::Int(54321));
-
-    let value = record
-        .get_value(1, ctx.schema_1.clone())
+    let by_index = record
+        .get_value(2, ctx.schema_1.clone())
         .unwrap()
         .unwrap()
         .get_inner();
-    assert_eq!(value, InnerValue::Boolean(false));
+    assert_eq!(by_name, by_index);
+    assert_eq!(by_name, InnerValue::Varchar("Hello, World!".to_string()));
 
-    let value = record.get_value(2, ctx.schema_1.clone()).unwrap();
+    let value = record
+        .get_value_by_name("bar", ctx.schema_1.clone())
+        .unwrap();
     assert!(value.is_none());
+
+    let result = record.get_value_by_name("qux", ctx.schema_1.clone());
+    assert_eq!(result.unwrap_err(), RecordErr::ColumnNotFound);
 }
 
 #[test]
 fn test_update_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
     let ctx = setup();
 
     // Create a relation and insert records.
@@ -300,7 +1200,7 @@ fn test_update_record() {
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let record_id = relation.insert(record).unwrap();
+    let record_id = relation.insert(record, &txn).unwrap();
 
     let other_record = Record::new(
         vec![
@@ -311,7 +1211,7 @@ fn test_update_record() {
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let other_id = relation.insert(other_record).unwrap();
+    let other_id = relation.insert(other_record, &txn).unwrap();
 
     // Update the first record to a larger record.
     let update = Record::new(
@@ -323,7 +1223,7 @@ fn test_update_record() {
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let result = relation.update(update, record_id);
+    let result = relation.update(update, record_id, &txn);
     assert!(result.is_ok());
 
     // Assert that the record is correctly updated.
@@ -361,7 +1261,7 @@ fn test_update_record() {
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let result = relation.update(update, record_id);
+    let result = relation.update(update, record_id, &txn);
     assert!(result.is_ok());
 
     // Assert that the record is correctly updated.
@@ -414,6 +1314,7 @@ fn test_update_record() {
 
 #[test]
 fn test_delete_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
     let ctx = setup();
 
     // Create a relation and insert records.
@@ -430,23 +1331,23 @@ fn test_delete_record() {
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let record_id = relation.insert(record).unwrap();
+    let record_id = relation.insert(record, &txn).unwrap();
     let other_record = Record::new(
         vec![
-            Some(Box::new(-12345)),
+            Some(Box::new(98765)),
             Some(Box::new(true)),
             Some(Box::new("Lorem Ipsum".to_string())),
         ],
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let other_id = relation.insert(other_record).unwrap();
+    let other_id = relation.insert(other_record, &txn).unwrap();
 
     // Flag and delete the first record.
-    let result = relation.flag_delete(record_id);
+    let result = relation.flag_delete(record_id, &txn);
     assert!(result.is_ok());
 
-    let result = relation.commit_delete(record_id);
+    let result = relation.commit_delete(record_id, &txn);
     assert!(result.is_ok());
 
     // Assert that the second record can still be accessed.
@@ -458,7 +1359,7 @@ fn test_delete_record() {
         .unwrap()
         .unwrap()
         .get_inner();
-    assert_eq!(value, InnerValue::Int(-12345));
+    assert_eq!(value, InnerValue::Int(98765));
 
     let value = record
         .get_value(1, ctx.schema_1.clone())
@@ -475,9 +1376,131 @@ fn test_delete_record() {
     assert_eq!(value, InnerValue::Varchar("Lorem Ipsum".to_string()));
 }
 
+#[test]
+fn test_insert_reuses_space_freed_by_delete() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, false, false, false),
+        Attribute::new("padding", DataType::Varchar, false, false, false),
+    ]));
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+    let new_record = |id: i32| {
+        Record::new(
+            vec![Some(Box::new(id)), Some(Box::new("x".repeat(500)))],
+            schema.clone(),
+        )
+        .unwrap()
+    };
+
+    // Insert records until the heap spans several pages, with space left in the last page.
+    let mut rids = Vec::new();
+    for id in 0..50 {
+        rids.push(relation.insert(new_record(id), &txn).unwrap());
+    }
+    let first_page_id = rids[0].page_id;
+    assert!(relation.page_count() >= 3);
+    let page_count = relation.page_count();
+
+    // Delete a few records from the first page.
+    for rid in rids.iter().take(3) {
+        relation.flag_delete(*rid, &txn).unwrap();
+        relation.commit_delete(*rid, &txn).unwrap();
+    }
+
+    // Assert that subsequent insertions reuse the first page instead of the last page, until
+    // the freed space runs out.
+    for id in 50..53 {
+        let rid = relation.insert(new_record(id), &txn).unwrap();
+        assert_eq!(rid.page_id, first_page_id);
+    }
+    let rid = relation.insert(new_record(53), &txn).unwrap();
+    assert_ne!(rid.page_id, first_page_id);
+    assert_eq!(relation.page_count(), page_count);
+    assert_eq!(relation.count(), 51);
+}
+
+#[test]
+fn test_vacuum_relation() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, false, false, false),
+        Attribute::new("padding", DataType::Varchar, false, false, false),
+    ]));
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", schema.clone())
+        .unwrap();
+    let index = ctx
+        .system_catalog
+        .create_index("foo_id", "foo", &[0])
+        .unwrap();
+    let lookup = |id: i32| {
+        let key = Record::new(vec![Some(Box::new(id))], index.get_meta().get_schema()).unwrap();
+        index.get(&key, &txn).unwrap()
+    };
+    let id_of = |record: &Record| match record.get_value(0, schema.clone()).unwrap() {
+        Some(value) => value.get_inner(),
+        None => panic!("Record has a null ID"),
+    };
+
+    // Insert records until the heap spans several pages, then delete the first half of them.
+    for id in 0..100 {
+        let record = Record::new(
+            vec![Some(Box::new(id)), Some(Box::new("x".repeat(500)))],
+            schema.clone(),
+        )
+        .unwrap();
+        relation.insert(record, &txn).unwrap();
+    }
+    let page_count = relation.page_count();
+    let is_first_half = |record: &Record| id_of(record) < InnerValue::Int(50);
+    assert_eq!(relation.delete_where(is_first_half, &txn).unwrap(), 50);
+
+    // Assert that the emptied pages (other than the root page) are freed, and that the
+    // remaining records are intact.
+    let ids: HashMap<RecordId, InnerValue> = relation
+        .scan()
+        .map(|record| (record.get_id().unwrap(), id_of(&record)))
+        .collect();
+    let stats = relation.vacuum().unwrap();
+    assert!(stats.pages_freed > 0);
+    assert_eq!(relation.page_count(), page_count - stats.pages_freed);
+    assert_eq!(relation.count(), 50);
+
+    // Assert that moved records are read from their new IDs, and that the index is updated.
+    assert!(stats.records_moved() > 0);
+    for (old_rid, new_rid) in stats.moved.iter() {
+        assert_eq!(id_of(&relation.read(*new_rid).unwrap()), ids[old_rid]);
+    }
+    for record in relation.scan() {
+        match id_of(&record) {
+            InnerValue::Int(id) => assert_eq!(lookup(id), vec![record.get_id().unwrap()]),
+            _ => panic!("Record has a non-integer ID"),
+        }
+    }
+    for id in 0..50 {
+        assert!(lookup(id).is_empty());
+    }
+
+    // Assert that freed space is reused by subsequent insertions.
+    let record = Record::new(
+        vec![Some(Box::new(100)), Some(Box::new("x".repeat(500)))],
+        schema.clone(),
+    )
+    .unwrap();
+    relation.insert(record, &txn).unwrap();
+    assert_eq!(relation.page_count(), page_count - stats.pages_freed);
+}
+
 #[ignore]
 #[test]
 fn test_rollback_delete_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
     let ctx = setup();
 
     // Create a relation, insert a record, then delete the record.
@@ -494,17 +1517,18 @@ fn test_rollback_delete_record() {
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let record_id = relation.insert(record).unwrap();
-    relation.flag_delete(record_id).unwrap();
-    relation.commit_delete(record_id).unwrap();
+    let record_id = relation.insert(record, &txn).unwrap();
+    relation.flag_delete(record_id, &txn).unwrap();
+    relation.commit_delete(record_id, &txn).unwrap();
 
-    let result = relation.rollback_delete(record_id);
+    let result = relation.rollback_delete(record_id, &txn);
     assert!(result.is_ok());
     assert!(relation.read(record_id).is_ok());
 }
 
 #[test]
 fn test_flag_delete_then_read_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
     let ctx = setup();
 
     let relation = ctx
@@ -520,18 +1544,611 @@ fn test_flag_delete_then_read_record() {
         ctx.schema_1.clone(),
     )
     .unwrap();
-    let record_id = relation.insert(record).unwrap(); // Create a relation and insert a record.
+    let record_id = relation.insert(record, &txn).unwrap(); // Create a relation and insert a record.
 
     // Flag the existing record, then attempt to read it.
-    relation.flag_delete(record_id).unwrap();
+    relation.flag_delete(record_id, &txn).unwrap();
     assert_eq!(
         relation.read(record_id).unwrap_err(),
         HeapError::RecordDeleted
     );
 }
 
-#[ignore]
 #[test]
 fn test_create_index() {
-    assert!(false)
+    let ctx = setup();
+    let txn = Transaction::new(0);
+
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+    let insert = |i: i32| {
+        let record = Record::new(
+            vec![
+                Some(Box::new(i)),
+                Some(Box::new(i % 2 == 0)),
+                Some(Box::new(format!("name_{}", i % 5))),
+            ],
+            ctx.schema_1.clone(),
+        )
+        .unwrap();
+        relation.insert(record, &txn).unwrap()
+    };
+    let mut rids: Vec<RecordId> = (0..300).map(insert).collect();
+
+    // Create indexes on the populated relation, which should be back-filled.
+    let id_index = ctx
+        .system_catalog
+        .create_index("foo_id", "foo", &[0])
+        .unwrap();
+    let name_index = ctx
+        .system_catalog
+        .create_index("foo_name", "foo", &[2])
+        .unwrap();
+    assert!(ctx.system_catalog.get_index("foo_id").is_some());
+
+    // Records inserted afterwards should be added to the indexes.
+    rids.extend((300..400).map(insert));
+
+    for (i, rid) in rids.iter().enumerate() {
+        let key = Record::new(
+            vec![Some(Box::new(i as i32))],
+            id_index.get_meta().get_schema(),
+        )
+        .unwrap();
+        assert_eq!(id_index.get(&key, &txn).unwrap(), vec![*rid]);
+    }
+    for n in 0..5 {
+        let key = Record::new(
+            vec![Some(Box::new(format!("name_{}", n)))],
+            name_index.get_meta().get_schema(),
+        )
+        .unwrap();
+        let expected: Vec<RecordId> = rids.iter().copied().skip(n).step_by(5).collect();
+        let mut actual = name_index.get(&key, &txn).unwrap();
+        actual.sort_by_key(|rid| (rid.page_id, rid.slot_index));
+        assert_eq!(actual, expected);
+    }
+
+    // Index names share a namespace with relation names.
+    for name in ["foo", "foo_id"] {
+        assert_eq!(
+            ctx.system_catalog.create_index(name, "foo", &[1]).err(),
+            Some(CatalogError::NameExists)
+        );
+    }
+    assert_eq!(
+        ctx.system_catalog
+            .create_relation("foo_name", ctx.schema_2.clone())
+            .err(),
+        Some(CatalogError::NameExists)
+    );
+    assert_eq!(
+        ctx.system_catalog.create_index("bar_id", "bar", &[0]).err(),
+        Some(CatalogError::RelationDNE)
+    );
+    assert_eq!(
+        ctx.system_catalog
+            .create_index("foo_bad", "foo", &[3])
+            .err(),
+        Some(CatalogError::InvalidKeyColumn)
+    );
+    assert_eq!(
+        ctx.system_catalog.list_relations(),
+        vec![(0, "foo".to_string())]
+    );
+}
+
+#[test]
+fn test_index_maintenance() {
+    let ctx = setup();
+    let txn = Transaction::new(0);
+
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+    let index = ctx
+        .system_catalog
+        .create_index("foo_name", "foo", &[2])
+        .unwrap();
+    let make_record = |id: i32, name: &str| {
+        Record::new(
+            vec![
+                Some(Box::new(id)),
+                Some(Box::new(true)),
+                Some(Box::new(name.to_string())),
+            ],
+            ctx.schema_1.clone(),
+        )
+        .unwrap()
+    };
+    let lookup = |name: &str| {
+        let key = Record::new(
+            vec![Some(Box::new(name.to_string()))],
+            index.get_meta().get_schema(),
+        )
+        .unwrap();
+        index.get(&key, &txn).unwrap()
+    };
+
+    let rid_1 = relation.insert(make_record(1, "alice"), &txn).unwrap();
+    let rid_2 = relation.insert(make_record(2, "bob"), &txn).unwrap();
+    assert_eq!(lookup("alice"), vec![rid_1]);
+    assert_eq!(lookup("bob"), vec![rid_2]);
+
+    // Change the key column of a record in place.
+    let new_rid = relation
+        .update(make_record(1, "carol"), rid_1, &txn)
+        .unwrap();
+    assert_eq!(new_rid, rid_1);
+    assert!(lookup("alice").is_empty());
+    assert_eq!(lookup("carol"), vec![rid_1]);
+
+    // Update a non-key column, which should leave the entry as-is.
+    let new_rid = relation
+        .update(make_record(10, "carol"), rid_1, &txn)
+        .unwrap();
+    assert_eq!(lookup("carol"), vec![new_rid]);
+
+    // Grow a record so that it is reallocated, which should move its entry to the new record ID.
+    let long_name = "d".repeat(PAGE_SIZE as usize);
+    let moved_rid = relation
+        .update(make_record(2, &long_name), rid_2, &txn)
+        .unwrap();
+    assert_ne!(moved_rid, rid_2);
+    assert!(lookup("bob").is_empty());
+    assert_eq!(lookup(&long_name), vec![moved_rid]);
+
+    // Entries remain while a deletion is pending, and are removed once it is committed.
+    relation.flag_delete(rid_1, &txn).unwrap();
+    assert_eq!(lookup("carol"), vec![rid_1]);
+    relation.commit_delete(rid_1, &txn).unwrap();
+    assert!(lookup("carol").is_empty());
+    assert_eq!(lookup(&long_name), vec![moved_rid]);
+}
+
+#[test]
+fn test_scan_relation() {
+    let ctx = setup();
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let relation = ctx
+        .system_catalog
+        .create_relation("relation", ctx.schema_1.clone())
+        .unwrap();
+    assert_eq!(relation.scan().count(), 0);
+
+    // Insert enough records to span multiple pages.
+    let mut rids = Vec::new();
+    for i in 0..100 {
+        let record = Record::new(
+            vec![
+                Some(Box::new(i)),
+                Some(Box::new(true)),
+                Some(Box::new("a".repeat(200))),
+            ],
+            ctx.schema_1.clone(),
+        )
+        .unwrap();
+        rids.push(relation.insert(record, &txn).unwrap());
+    }
+    assert!(relation.page_count() > 1);
+
+    // Assert that every record is yielded exactly once.
+    let mut values: Vec<InnerValue> = relation
+        .scan()
+        .map(|record| {
+            record
+                .get_value(0, ctx.schema_1.clone())
+                .unwrap()
+                .unwrap()
+                .get_inner()
+        })
+        .collect();
+    values.sort_by(|a, b| a.total_cmp(b));
+    assert_eq!(values, (0..100).map(InnerValue::Int).collect::<Vec<_>>());
+
+    // Assert that records flagged for deletion are not yielded.
+    relation.flag_delete(rids[0], &txn).unwrap();
+    assert_eq!(relation.scan().count(), 99);
+}
+
+#[test]
+fn test_count_relation() {
+    let ctx = setup();
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let relation = ctx
+        .system_catalog
+        .create_relation("relation", ctx.schema_1.clone())
+        .unwrap();
+    let make_record = || {
+        Record::new(
+            vec![None, Some(Box::new(true)), Some(Box::new("a".repeat(200)))],
+            ctx.schema_1.clone(),
+        )
+        .unwrap()
+    };
+
+    // Empty relation
+    assert_eq!(relation.count(), 0);
+    assert!(relation.is_empty());
+
+    // Relation with a single record
+    let rid = relation.insert(make_record(), &txn).unwrap();
+    assert_eq!(relation.count(), 1);
+    assert!(!relation.is_empty());
+
+    // Relation spanning multiple pages
+    for _ in 1..100 {
+        relation.insert(make_record(), &txn).unwrap();
+    }
+    assert!(relation.page_count() > 1);
+    assert_eq!(relation.count(), 100);
+    assert!(!relation.is_empty());
+
+    // Records flagged for deletion are not counted.
+    relation.flag_delete(rid, &txn).unwrap();
+    assert_eq!(relation.count(), 99);
+}
+
+#[test]
+fn test_update_overflow_record_dne() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+    let small = Record::new(
+        vec![Some(Box::new(1)), Some(Box::new(false)), None],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+    let record_id = relation.insert(small, &txn).unwrap();
+    let large = || {
+        Record::new(
+            vec![
+                Some(Box::new(2)),
+                Some(Box::new(true)),
+                Some(Box::new("a".repeat(2 * PAGE_SIZE as usize))),
+            ],
+            ctx.schema_1.clone(),
+        )
+        .unwrap()
+    };
+
+    // Assert that updating a slot which doesn't exist to an overflow record returns an error.
+    let missing = RecordId {
+        page_id: record_id.page_id,
+        slot_index: 999,
+    };
+    assert_eq!(
+        relation.update(large(), missing, &txn),
+        Err(HeapError::RecordDNE)
+    );
+
+    // Assert that updating a deleted record to an overflow record returns an error.
+    relation.flag_delete(record_id, &txn).unwrap();
+    relation.commit_delete(record_id, &txn).unwrap();
+    assert_eq!(
+        relation.update(large(), record_id, &txn),
+        Err(HeapError::RecordDeleted)
+    );
+}
+
+#[test]
+fn test_insert_overflow_record() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup();
+
+    let relation = ctx
+        .system_catalog
+        .create_relation("foo", ctx.schema_1.clone())
+        .unwrap();
+
+    // Create a record whose varchar alone spans multiple pages.
+    let large = "abcdefghijklmnopqrstuvwxyz".repeat(1_000);
+    assert!(large.len() as u32 > 2 * PAGE_SIZE);
+    let record = Record::new(
+        vec![
+            Some(Box::new(1)),
+            Some(Box::new(true)),
+            Some(Box::new(large.clone())),
+        ],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+    let small = Record::new(
+        vec![Some(Box::new(2)), Some(Box::new(false)), None],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+
+    // Assert that the large record can be inserted and read back intact.
+    let record_id = relation.insert(record, &txn).unwrap();
+    let small_id = relation.insert(small, &txn).unwrap();
+
+    let value = relation
+        .read(record_id)
+        .unwrap()
+        .get_value(2, ctx.schema_1.clone())
+        .unwrap()
+        .unwrap()
+        .get_inner();
+    assert_eq!(value, InnerValue::Varchar(large.clone()));
+
+    // Assert that the large record can be updated to another large record.
+    let larger = large.repeat(2);
+    let update = Record::new(
+        vec![
+            Some(Box::new(1)),
+            Some(Box::new(true)),
+            Some(Box::new(larger.clone())),
+        ],
+        ctx.schema_1.clone(),
+    )
+    .unwrap();
+    let record_id = relation.update(update, record_id, &txn).unwrap();
+
+    let value = relation
+        .read(record_id)
+        .unwrap()
+        .get_value(2, ctx.schema_1.clone())
+        .unwrap()
+        .unwrap()
+        .get_inner();
+    assert_eq!(value, InnerValue::Varchar(larger));
+
+    // Assert that the large record can be deleted without affecting other records.
+    relation.flag_delete(record_id, &txn).unwrap();
+    relation.commit_delete(record_id, &txn).unwrap();
+    assert!(relation.read(record_id).is_err());
+
+    let value = relation
+        .read(small_id)
+        .unwrap()
+        .get_value(0, ctx.schema_1.clone())
+        .unwrap()
+        .unwrap()
+        .get_inner();
+    assert_eq!(value, InnerValue::Int(2));
+}
+
+#[test]
+fn test_import_csv() {
+    let ctx = setup();
+    let relation = ctx
+        .system_catalog
+        .create_relation("relation", ctx.schema_1.clone())
+        .unwrap();
+
+    // Import rows with a header that lists the attributes out of order. Quoted fields may
+    // contain commas, quotes, and line breaks, and empty fields are null.
+    let csv = "baz,foo,bar\n\
+               \"Hello, World!\",1,true\n\
+               \"say \"\"hi\"\"\nbye\",2,\n\
+               ,3,false\n";
+    let count = import_csv(&relation, csv.as_bytes(), true).unwrap();
+    assert_eq!(count, 3);
+
+    // Assert that the rows are read back with the expected values.
+    let mut rows: Vec<Vec<Option<InnerValue>>> = relation
+        .scan()
+        .map(|record| {
+            (0..3)
+                .map(|idx| {
+                    record
+                        .get_value(idx, ctx.schema_1.clone())
+                        .unwrap()
+                        .map(|value| value.get_inner())
+                })
+                .collect()
+        })
+        .collect();
+    rows.sort_by(|a, b| a[0].as_ref().unwrap().total_cmp(b[0].as_ref().unwrap()));
+    assert_eq!(
+        rows,
+        vec![
+            vec![
+                Some(InnerValue::Int(1)),
+                Some(InnerValue::Boolean(true)),
+                Some(InnerValue::Varchar("Hello, World!".to_string())),
+            ],
+            vec![
+                Some(InnerValue::Int(2)),
+                None,
+                Some(InnerValue::Varchar("say \"hi\"\nbye".to_string())),
+            ],
+            vec![
+                Some(InnerValue::Int(3)),
+                Some(InnerValue::Boolean(false)),
+                None
+            ],
+        ]
+    );
+
+    // Import rows without a header, in the order of the schema.
+    let count = import_csv(&relation, "4,true,foo\r\n5,false,bar".as_bytes(), false).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(relation.count(), 5);
+
+    // Assert that invalid input is rejected.
+    assert!(matches!(
+        import_csv(&relation, "6,yes,foo\n".as_bytes(), false),
+        Err(ImportError::InvalidValue(1, name)) if name == "bar"
+    ));
+    assert!(matches!(
+        import_csv(&relation, "foo,bar\n6,true,baz\n".as_bytes(), true),
+        Err(ImportError::FieldCount(2))
+    ));
+    assert!(matches!(
+        import_csv(&relation, "foo,qux\n".as_bytes(), true),
+        Err(ImportError::InvalidHeader(name)) if name == "qux"
+    ));
+    assert!(matches!(
+        import_csv(&relation, "6,true,\"foo\n".as_bytes(), false),
+        Err(ImportError::MalformedRow(1))
+    ));
+    assert_eq!(relation.count(), 5);
+}
+
+#[test]
+fn test_export_csv() {
+    let ctx = setup();
+    let relation = ctx
+        .system_catalog
+        .create_relation("relation", ctx.schema_1.clone())
+        .unwrap();
+
+    let rows = [
+        "1,true,plain",
+        "2,,\"Hello, World!\"",
+        "3,false,\"say \"\"hi\"\"\nbye\"",
+        "4,true,\"\"",
+        "5,false,",
+    ];
+    let csv = format!("foo,bar,baz\n{}\n", rows.join("\n"));
+    assert_eq!(import_csv(&relation, csv.as_bytes(), true).unwrap(), 5);
+
+    // Assert that the exported rows match the imported rows, modulo ordering.
+    let mut output = Vec::new();
+    assert_eq!(export_csv(&relation, &mut output, true).unwrap(), 5);
+    let output = String::from_utf8(output).unwrap();
+    let (header, body) = output.split_at(output.find('\n').unwrap() + 1);
+    assert_eq!(header, "foo,bar,baz\n");
+
+    // Compare sorted lines rather than rows, since a quoted field contains a line break.
+    let mut exported: Vec<&str> = body.trim_end().split("\n").collect();
+    exported.sort_unstable();
+    let mut expected: Vec<&str> = csv.trim_end().split("\n").skip(1).collect();
+    expected.sort_unstable();
+    assert_eq!(exported, expected);
+
+    // Assert that the export can be imported again without a header.
+    let copy = ctx
+        .system_catalog
+        .create_relation("copy", ctx.schema_1.clone())
+        .unwrap();
+    let mut output = Vec::new();
+    export_csv(&relation, &mut output, false).unwrap();
+    assert_eq!(import_csv(&copy, output.as_slice(), false).unwrap(), 5);
+    assert_eq!(copy.count(), 5);
+}
+
+#[test]
+fn test_update_where() {
+    let ctx = setup();
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let relation = ctx
+        .system_catalog
+        .create_relation("relation", ctx.schema_2.clone())
+        .unwrap();
+    for i in 0..100 {
+        let record = Record::new(
+            vec![Some(Box::new(i)), Some(Box::new(i % 2 == 0))],
+            ctx.schema_2.clone(),
+        )
+        .unwrap();
+        relation.insert(record, &txn).unwrap();
+    }
+
+    // Increment the ID of every record with a true flag, by 1000 so that the IDs stay unique.
+    let schema = ctx.schema_2.clone();
+    let flag = |record: &Record| {
+        let value = record.get_value(1, schema.clone()).unwrap().unwrap();
+        value.get_inner() == InnerValue::Boolean(true)
+    };
+    let increment = |record: &Record| {
+        let mut record = record.clone();
+        let id = match record
+            .get_value(0, schema.clone())
+            .unwrap()
+            .unwrap()
+            .get_inner()
+        {
+            InnerValue::Int(id) => id,
+            _ => panic!("Expected an integer ID"),
+        };
+        record
+            .set_value(0, Some(Box::new(id + 1000)), schema.clone())
+            .unwrap();
+        record
+    };
+    assert_eq!(relation.update_where(flag, increment, &txn).unwrap(), 50);
+
+    // Assert that each matching record was updated exactly once.
+    let mut ids: Vec<InnerValue> = relation
+        .scan()
+        .map(|record| {
+            record
+                .get_value(0, ctx.schema_2.clone())
+                .unwrap()
+                .unwrap()
+                .get_inner()
+        })
+        .collect();
+    ids.sort_by(|a, b| a.total_cmp(b));
+    let mut expected: Vec<InnerValue> = (0..100)
+        .map(|i| InnerValue::Int(if i % 2 == 0 { i + 1000 } else { i }))
+        .collect();
+    expected.sort_by(|a, b| a.total_cmp(b));
+    assert_eq!(ids, expected);
+
+    // Assert that no record is updated when nothing matches.
+    let none = |_: &Record| false;
+    assert_eq!(relation.update_where(none, increment, &txn).unwrap(), 0);
+}
+
+#[test]
+fn test_delete_where() {
+    let ctx = setup();
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let relation = ctx
+        .system_catalog
+        .create_relation("relation", ctx.schema_1.clone())
+        .unwrap();
+    let names = ["foo", "bar", "baz"];
+    for i in 0..90 {
+        let record = Record::new(
+            vec![
+                Some(Box::new(i)),
+                None,
+                Some(Box::new(names[i as usize % 3].to_string())),
+            ],
+            ctx.schema_1.clone(),
+        )
+        .unwrap();
+        relation.insert(record, &txn).unwrap();
+    }
+
+    // Delete every record with the name "bar".
+    let schema = ctx.schema_1.clone();
+    let is_bar = |record: &Record| {
+        let value = record.get_value(2, schema.clone()).unwrap().unwrap();
+        value.get_inner() == InnerValue::Varchar("bar".to_string())
+    };
+    assert_eq!(relation.delete_where(is_bar, &txn).unwrap(), 30);
+
+    // Assert that only the other records survive.
+    let mut ids = Vec::new();
+    for record in relation.scan() {
+        let value = record.get_value(2, ctx.schema_1.clone()).unwrap().unwrap();
+        assert_ne!(value.get_inner(), InnerValue::Varchar("bar".to_string()));
+        ids.push(
+            record
+                .get_value(0, ctx.schema_1.clone())
+                .unwrap()
+                .unwrap()
+                .get_inner(),
+        );
+    }
+    ids.sort_by(|a, b| a.total_cmp(b));
+    let expected: Vec<InnerValue> = (0..90)
+        .filter(|i| i % 3 != 1)
+        .map(InnerValue::Int)
+        .collect();
+    assert_eq!(ids, expected);
+    assert_eq!(relation.delete_where(is_bar, &txn).unwrap(), 0);
 }
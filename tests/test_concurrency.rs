@@ -0,0 +1,390 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use jin::buffer::replacement::ReplacerAlgorithm;
+use jin::buffer::BufferManager;
+use jin::catalog::SystemCatalog;
+use jin::concurrency::lock_manager::{LockError, LockManager};
+use jin::concurrency::transaction::{Transaction, TransactionState};
+use jin::concurrency::transaction_manager::{TransactionError, TransactionManager};
+use jin::constants::SYSTEM_TXN_ID;
+use jin::disk::DiskManager;
+use jin::relation::heap::HeapError;
+use jin::relation::record::{Record, RecordId};
+use jin::relation::types::{DataType, InnerValue};
+use jin::relation::{Attribute, Relation, Schema};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+#[allow(dead_code)]
+mod constants;
+
+struct TestContext {
+    relation: Arc<Relation>,
+    _db: constants::TestDb,
+}
+
+fn setup() -> TestContext {
+    let db = constants::TestDb::new();
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(db.filename()),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = SystemCatalog::new(buffer_manager);
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, false, false, false),
+        Attribute::new("name", DataType::Varchar, false, false, false),
+    ]));
+    let relation = system_catalog.create_relation("foo", schema).unwrap();
+
+    TestContext { relation, _db: db }
+}
+
+fn make_record(ctx: &TestContext, id: i32, name: &str) -> Record {
+    Record::new(
+        vec![Some(Box::new(id)), Some(Box::new(name.to_string()))],
+        ctx.relation.get_schema(),
+    )
+    .unwrap()
+}
+
+fn rid(slot_index: u32) -> RecordId {
+    RecordId {
+        page_id: 1,
+        slot_index,
+    }
+}
+
+#[test]
+fn test_begin_unique_ids() {
+    let manager = Arc::new(TransactionManager::new(Arc::new(LockManager::new())));
+
+    // IDs of transactions begun by a single thread should increase.
+    let first = manager.begin();
+    let second = manager.begin();
+    assert!(first.get_id() < second.get_id());
+
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        let manager = manager.clone();
+        handles.push(thread::spawn(move || {
+            (0..100)
+                .map(|_| manager.begin().get_id())
+                .collect::<Vec<_>>()
+        }));
+    }
+
+    let mut ids = HashSet::new();
+    for handle in handles {
+        for id in handle.join().unwrap() {
+            assert!(ids.insert(id));
+        }
+    }
+    assert_eq!(ids.len(), 400);
+}
+
+#[test]
+fn test_transaction_states() {
+    let manager = TransactionManager::new(Arc::new(LockManager::new()));
+
+    let txn = manager.begin();
+    assert_eq!(txn.get_state(), TransactionState::Growing);
+    manager.commit(&txn).unwrap();
+    assert_eq!(txn.get_state(), TransactionState::Committed);
+    assert_eq!(manager.commit(&txn), Err(TransactionError::InvalidState));
+    assert_eq!(manager.abort(&txn), Err(TransactionError::InvalidState));
+    assert_eq!(txn.get_state(), TransactionState::Committed);
+
+    let txn = manager.begin();
+    manager.abort(&txn).unwrap();
+    assert_eq!(txn.get_state(), TransactionState::Aborted);
+    assert_eq!(manager.commit(&txn), Err(TransactionError::InvalidState));
+    assert_eq!(txn.get_state(), TransactionState::Aborted);
+}
+
+#[test]
+fn test_commit_and_abort_write_set() {
+    let ctx = setup();
+    let manager = TransactionManager::new(Arc::new(LockManager::new()));
+
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let deleted = ctx
+        .relation
+        .insert(make_record(&ctx, 1, "alice"), &txn)
+        .unwrap();
+    let updated = ctx
+        .relation
+        .insert(make_record(&ctx, 2, "bob"), &txn)
+        .unwrap();
+    let committed = ctx
+        .relation
+        .insert(make_record(&ctx, 3, "carol"), &txn)
+        .unwrap();
+
+    // Modify records on behalf of a transaction, then abort it.
+    let txn = manager.begin();
+    let inserted = ctx
+        .relation
+        .insert(make_record(&ctx, 4, "dave"), &txn)
+        .unwrap();
+    ctx.relation.flag_delete(deleted, &txn).unwrap();
+    let updated = ctx
+        .relation
+        .update(make_record(&ctx, 2, "robert"), updated, &txn)
+        .unwrap();
+    manager.abort(&txn).unwrap();
+
+    // Every modification should be undone.
+    assert_eq!(
+        ctx.relation.read(inserted).unwrap_err(),
+        HeapError::RecordDeleted
+    );
+    let schema = ctx.relation.get_schema();
+    assert!(ctx
+        .relation
+        .read(deleted)
+        .unwrap()
+        .equals(&make_record(&ctx, 1, "alice"), schema.clone()));
+    assert!(ctx
+        .relation
+        .read(updated)
+        .unwrap()
+        .equals(&make_record(&ctx, 2, "bob"), schema.clone()));
+    assert_eq!(ctx.relation.approx_row_count(), 3);
+
+    // Deletions should be committed when the transaction commits.
+    let txn = manager.begin();
+    ctx.relation.flag_delete(committed, &txn).unwrap();
+    manager.commit(&txn).unwrap();
+    assert_eq!(
+        ctx.relation.rollback_delete(committed, &txn).unwrap_err(),
+        HeapError::RecordDeleted
+    );
+    assert_eq!(ctx.relation.approx_row_count(), 2);
+}
+
+#[test]
+fn test_abort_bulk_modifications() {
+    let ctx = setup();
+    let manager = TransactionManager::new(Arc::new(LockManager::new()));
+
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    for (id, name) in [(1, "alice"), (2, "bob"), (3, "carol")].iter() {
+        ctx.relation
+            .insert(make_record(&ctx, *id, name), &txn)
+            .unwrap();
+    }
+
+    // Update and delete records in bulk on behalf of a transaction, then abort it.
+    let schema = ctx.relation.get_schema();
+    let id_of = |record: &Record| match record.get_value(0, schema.clone()).unwrap() {
+        Some(value) => match value.get_inner() {
+            InnerValue::Int(id) => id,
+            _ => panic!("expected an integer ID"),
+        },
+        None => panic!("expected a non-null ID"),
+    };
+    let txn = manager.begin();
+    let updated = ctx
+        .relation
+        .update_where(
+            |_| true,
+            |record| make_record(&ctx, id_of(record), "dave"),
+            &txn,
+        )
+        .unwrap();
+    assert_eq!(updated, 3);
+    let deleted = ctx.relation.delete_where(|_| true, &txn).unwrap();
+    assert_eq!(deleted, 3);
+    assert_eq!(ctx.relation.scan().count(), 0);
+    manager.abort(&txn).unwrap();
+
+    // Every record should be restored to its value before the transaction.
+    let mut records: Vec<Record> = ctx.relation.scan().collect();
+    records.sort_by_key(|record| id_of(record));
+    assert_eq!(records.len(), 3);
+    for (record, (id, name)) in records
+        .iter()
+        .zip([(1, "alice"), (2, "bob"), (3, "carol")].iter())
+    {
+        assert!(record.equals(&make_record(&ctx, *id, name), schema.clone()));
+    }
+
+    // Deletions made in bulk should be committed when the transaction commits.
+    let txn = manager.begin();
+    ctx.relation.delete_where(|_| true, &txn).unwrap();
+    manager.commit(&txn).unwrap();
+    assert_eq!(ctx.relation.scan().count(), 0);
+    assert_eq!(ctx.relation.approx_row_count(), 0);
+}
+
+#[test]
+fn test_lock_modes() {
+    let lock_manager = Arc::new(LockManager::new());
+    let manager = TransactionManager::new(lock_manager.clone());
+    let txn_1 = manager.begin();
+    let txn_2 = manager.begin();
+
+    // Shared locks are compatible with each other.
+    lock_manager.lock_shared(&txn_1, rid(0)).unwrap();
+    lock_manager.lock_shared(&txn_2, rid(0)).unwrap();
+    lock_manager.lock_shared(&txn_2, rid(1)).unwrap();
+
+    // A shared lock can be upgraded once no other transaction holds one.
+    assert!(lock_manager.unlock(&txn_2, rid(0)));
+    assert!(!lock_manager.unlock(&txn_2, rid(0)));
+    assert_eq!(txn_2.get_state(), TransactionState::Shrinking);
+    lock_manager.lock_exclusive(&txn_1, rid(0)).unwrap();
+    assert!(txn_1.is_locked(rid(0)));
+
+    // A shrinking transaction can't acquire any more locks under two-phase locking.
+    assert_eq!(
+        lock_manager.lock_shared(&txn_2, rid(2)),
+        Err(LockError::LockOnShrinking)
+    );
+    assert_eq!(txn_2.get_state(), TransactionState::Aborted);
+    manager.abort(&txn_2).unwrap();
+    assert!(!txn_2.is_locked(rid(1)));
+
+    // Locks are released when the transaction commits.
+    manager.commit(&txn_1).unwrap();
+    assert!(!txn_1.is_locked(rid(0)));
+    assert_eq!(
+        lock_manager.lock_shared(&txn_1, rid(0)),
+        Err(LockError::TransactionFinished)
+    );
+    let txn_3 = manager.begin();
+    lock_manager.lock_exclusive(&txn_3, rid(0)).unwrap();
+}
+
+#[test]
+fn test_exclusive_lock_contention() {
+    let lock_manager = Arc::new(LockManager::new());
+    let manager = Arc::new(TransactionManager::new(lock_manager.clone()));
+    let holders = Arc::new(AtomicU32::new(0));
+    let barrier = Arc::new(Barrier::new(2));
+
+    let mut handles = Vec::new();
+    for _ in 0..2 {
+        let lock_manager = lock_manager.clone();
+        let manager = manager.clone();
+        let holders = holders.clone();
+        let barrier = barrier.clone();
+        handles.push(thread::spawn(move || {
+            barrier.wait();
+            for _ in 0..20 {
+                let txn = manager.begin();
+                lock_manager.lock_exclusive(&txn, rid(0)).unwrap();
+
+                // No other transaction should hold the lock at the same time.
+                assert_eq!(holders.fetch_add(1, Ordering::SeqCst), 0);
+                thread::sleep(Duration::from_millis(1));
+                assert_eq!(holders.fetch_sub(1, Ordering::SeqCst), 1);
+
+                manager.commit(&txn).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // A waiting transaction should proceed once the holder releases the lock.
+    let txn_1 = manager.begin();
+    let txn_2 = manager.begin();
+    lock_manager.lock_exclusive(&txn_1, rid(0)).unwrap();
+    let acquired = Arc::new(AtomicU32::new(0));
+    let handle = {
+        let lock_manager = lock_manager.clone();
+        let acquired = acquired.clone();
+        thread::spawn(move || {
+            lock_manager.lock_shared(&txn_2, rid(0)).unwrap();
+            acquired.store(1, Ordering::SeqCst);
+        })
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(acquired.load(Ordering::SeqCst), 0);
+    manager.commit(&txn_1).unwrap();
+    handle.join().unwrap();
+    assert_eq!(acquired.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_deadlock_detection() {
+    let lock_manager = Arc::new(LockManager::new());
+    let manager = Arc::new(TransactionManager::new(lock_manager.clone()));
+    let barrier = Arc::new(Barrier::new(2));
+
+    // Each transaction locks one record, then requests the record locked by the other.
+    let txn_1 = manager.begin();
+    let txn_2 = manager.begin();
+    let mut handles = Vec::new();
+    for (txn, first, second) in [(txn_1, rid(0), rid(1)), (txn_2, rid(1), rid(0))] {
+        let lock_manager = lock_manager.clone();
+        let manager = manager.clone();
+        let barrier = barrier.clone();
+        handles.push(thread::spawn(move || {
+            lock_manager.lock_exclusive(&txn, first).unwrap();
+            barrier.wait();
+            let result = lock_manager.lock_exclusive(&txn, second);
+            match result {
+                Ok(()) => manager.commit(&txn).unwrap(),
+                Err(_) => manager.abort(&txn).unwrap(),
+            }
+            (txn.get_id(), result, txn.get_state())
+        }));
+    }
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // Exactly one transaction should be aborted, which is the youngest.
+    let aborted: Vec<_> = results
+        .iter()
+        .filter(|(_, result, _)| result.is_err())
+        .collect();
+    assert_eq!(aborted.len(), 1);
+    let (id, result, state) = aborted[0];
+    assert_eq!(*id, results[1].0);
+    assert_eq!(*result, Err(LockError::Deadlock));
+    assert_eq!(*state, TransactionState::Aborted);
+    assert_eq!(results[0].2, TransactionState::Committed);
+}
+
+#[test]
+fn test_lock_timeout() {
+    let lock_manager = Arc::new(LockManager::new());
+    let manager = TransactionManager::new(lock_manager.clone());
+
+    let holder = manager.begin();
+    lock_manager.lock_exclusive(&holder, rid(0)).unwrap();
+
+    // Requests for the locked record should time out in another thread.
+    let handle = {
+        let lock_manager = lock_manager.clone();
+        let txn_1 = manager.begin();
+        let txn_2 = manager.begin();
+        thread::spawn(move || {
+            let result =
+                lock_manager.lock_exclusive_timeout(&txn_1, rid(0), Duration::from_millis(20));
+            assert_eq!(result, Err(LockError::Timeout));
+            assert_eq!(txn_1.get_state(), TransactionState::Aborted);
+
+            let result =
+                lock_manager.lock_shared_timeout(&txn_2, rid(0), Duration::from_millis(20));
+            assert_eq!(result, Err(LockError::Timeout));
+        })
+    };
+    handle.join().unwrap();
+
+    // Once the holder commits, the lock should be granted within the timeout.
+    manager.commit(&holder).unwrap();
+    let txn = manager.begin();
+    lock_manager
+        .lock_exclusive_timeout(&txn, rid(0), Duration::from_millis(20))
+        .unwrap();
+}
@@ -4,7 +4,7 @@
  */
 
 use jin::constants::{CATALOG_ROOT_ID, PAGE_SIZE};
-use jin::disk::{open_write_file, DiskManager};
+use jin::disk::{open_write_file, DiskError, DiskManager};
 use std::convert::TryInto;
 use std::fs::{self, File};
 use std::io::{Read, Seek, SeekFrom, Write};
@@ -38,7 +38,7 @@ fn test_disk_allocation() {
     assert_eq!(manager.is_allocated(CATALOG_ROOT_ID), true);
     assert_eq!(manager.is_allocated(CATALOG_ROOT_ID + 1), false);
 
-    let page_id = manager.allocate_page();
+    let page_id = manager.allocate_page().unwrap();
     assert_eq!(page_id, CATALOG_ROOT_ID + 1);
     assert_eq!(manager.is_allocated(CATALOG_ROOT_ID + 1), true);
 }
@@ -49,8 +49,8 @@ fn test_disk_write() {
 
     // Write expected data to disk with disk manager.
     let expected = [123; PAGE_SIZE as usize];
-    let page_id = ctx.disk_manager.allocate_page();
-    ctx.disk_manager.write_page(page_id, &expected);
+    let page_id = ctx.disk_manager.allocate_page().unwrap();
+    ctx.disk_manager.write_page(page_id, &expected).unwrap();
 
     // Manually read page data from disk.
     let mut actual = [0; PAGE_SIZE as usize];
@@ -71,8 +71,8 @@ fn test_disk_read() {
     let ctx = setup(2);
 
     // Manually write page data to disk.
-    let mut file = open_write_file(&ctx.filename);
-    let page_id = ctx.disk_manager.allocate_page();
+    let mut file = open_write_file(&ctx.filename).unwrap();
+    let page_id = ctx.disk_manager.allocate_page().unwrap();
     file.seek(SeekFrom::Start((page_id * PAGE_SIZE) as u64))
         .unwrap();
     for i in 0..=255 {
@@ -82,7 +82,7 @@ fn test_disk_read() {
 
     // Read page data from disk with disk manager.
     let mut data = [0; PAGE_SIZE as usize];
-    ctx.disk_manager.read_page(page_id, &mut data);
+    ctx.disk_manager.read_page(page_id, &mut data).unwrap();
 
     // Assert that actual data matches expected data.
     for i in 0..=255 {
@@ -91,17 +91,17 @@ fn test_disk_read() {
 }
 
 #[test]
-#[should_panic]
 fn test_unallocated_read() {
     let ctx = setup(3);
-    ctx.disk_manager.read_page(2, &mut [0; PAGE_SIZE as usize]);
+    let result = ctx.disk_manager.read_page(2, &mut [0; PAGE_SIZE as usize]);
+    assert!(matches!(result, Err(DiskError::PageNotAllocated)));
 }
 
 #[test]
-#[should_panic]
 fn test_unallocated_write() {
     let ctx = setup(4);
-    ctx.disk_manager.write_page(2, &[0; PAGE_SIZE as usize]);
+    let result = ctx.disk_manager.write_page(2, &[0; PAGE_SIZE as usize]);
+    assert!(matches!(result, Err(DiskError::PageNotAllocated)));
 }
 
 #[test]
@@ -111,9 +111,9 @@ fn test_concurrent_read_access() {
     let num_threads = 10;
 
     // Write data to a page on disk.
-    let page_id = ctx.disk_manager.allocate_page();
+    let page_id = ctx.disk_manager.allocate_page().unwrap();
     let expected = [213; PAGE_SIZE as usize];
-    ctx.disk_manager.write_page(page_id, &expected);
+    ctx.disk_manager.write_page(page_id, &expected).unwrap();
 
     // Spin up multiple threads, and make each thread independently read the same page into
     // memory. Assert that each thread obtains the correct data.
@@ -121,7 +121,7 @@ fn test_concurrent_read_access() {
         let ctx_c = ctx.clone();
         thread::spawn(move || {
             let mut actual = [0; PAGE_SIZE as usize];
-            ctx_c.disk_manager.read_page(page_id, &mut actual);
+            ctx_c.disk_manager.read_page(page_id, &mut actual).unwrap();
 
             for i in 0..PAGE_SIZE as usize {
                 assert_eq!(actual[i], expected[i]);
@@ -144,12 +144,13 @@ fn test_concurrent_write_access() {
     for _ in 0..num_threads {
         let ctx_c = ctx.clone();
         handles.push(thread::spawn(move || {
-            let page_id = ctx_c.disk_manager.allocate_page();
+            let page_id = ctx_c.disk_manager.allocate_page().unwrap();
 
             // Write the page's ID to each byte of the newly allocated page.
             ctx_c
                 .disk_manager
-                .write_page(page_id, &[page_id.try_into().unwrap(); PAGE_SIZE as usize]);
+                .write_page(page_id, &[page_id.try_into().unwrap(); PAGE_SIZE as usize])
+                .unwrap();
         }));
     }
 
@@ -174,7 +175,7 @@ fn test_concurrent_write_access() {
             bar.wait(); // Sync all threads
 
             // Assert that each byte of the page is the page's ID.
-            ctx_c.disk_manager.read_page(i, &mut data);
+            ctx_c.disk_manager.read_page(i, &mut data).unwrap();
 
             for j in 0..PAGE_SIZE as usize {
                 assert_eq!(data[j], i as u8);
@@ -186,3 +187,112 @@ fn test_concurrent_write_access() {
         handle.join().unwrap();
     }
 }
+
+#[test]
+fn test_disk_deallocation() {
+    let ctx = setup(7);
+    let manager = &ctx.disk_manager;
+
+    let first = manager.allocate_page().unwrap();
+    let second = manager.allocate_page().unwrap();
+    manager.deallocate_page(first);
+    assert!(!manager.is_allocated(first));
+    assert!(manager.is_allocated(second));
+
+    // Assert that the freed page ID is recycled before a new ID is handed out.
+    assert_eq!(manager.allocate_page().unwrap(), first);
+    assert!(manager.is_allocated(first));
+    assert_eq!(manager.allocate_page().unwrap(), second + 1);
+}
+
+#[test]
+fn test_disk_reopen() {
+    let ctx = setup(8);
+
+    // Write a page with the original disk manager.
+    let expected = [42; PAGE_SIZE as usize];
+    let page_id = ctx.disk_manager.allocate_page().unwrap();
+    ctx.disk_manager.write_page(page_id, &expected).unwrap();
+
+    // Assert that a disk manager for the existing file can read the page back, and allocates
+    // pages after the existing pages.
+    let manager = DiskManager::open(&ctx.filename);
+    assert!(manager.is_allocated(page_id));
+    let mut actual = [0; PAGE_SIZE as usize];
+    manager.read_page(page_id, &mut actual).unwrap();
+    assert_eq!(actual, expected);
+    assert!(manager.allocate_page().unwrap() > page_id);
+}
+
+#[test]
+fn test_write_to_unwritable_path() {
+    let ctx = setup(9);
+    let page_id = ctx.disk_manager.allocate_page().unwrap();
+
+    // Replace the database file with a directory, which cannot be opened for writing regardless
+    // of the permissions of the current user.
+    fs::remove_file(&ctx.filename).unwrap();
+    fs::create_dir(&ctx.filename).unwrap();
+
+    // Assert that writing and allocating pages return an error instead of panicking.
+    let result = ctx
+        .disk_manager
+        .write_page(page_id, &[0; PAGE_SIZE as usize]);
+    assert!(matches!(result, Err(DiskError::Io(_))));
+    assert!(matches!(
+        ctx.disk_manager.allocate_page(),
+        Err(DiskError::Io(_))
+    ));
+
+    // Restore the database file so that the test context can clean up after itself.
+    fs::remove_dir(&ctx.filename).unwrap();
+    File::create(&ctx.filename).unwrap();
+}
+
+#[test]
+fn test_allocated_page_ids() {
+    let ctx = setup(10);
+    let manager = &ctx.disk_manager;
+
+    let mut page_ids = Vec::new();
+    for _ in 0..5 {
+        page_ids.push(manager.allocate_page().unwrap());
+    }
+    manager.deallocate_page(page_ids[2]);
+
+    // Assert that the catalog root and every page except the freed page are returned in order.
+    let mut expected = vec![CATALOG_ROOT_ID];
+    expected.extend(page_ids.iter().filter(|&&page_id| page_id != page_ids[2]));
+    assert_eq!(manager.allocated_page_ids(), expected);
+}
+
+#[test]
+fn test_in_memory_storage() {
+    let manager = DiskManager::in_memory();
+    assert!(manager.is_allocated(CATALOG_ROOT_ID));
+
+    // Assert that pages can be allocated, written, and read back.
+    let first = manager.allocate_page().unwrap();
+    let second = manager.allocate_page().unwrap();
+    assert_eq!(second, first + 1);
+    manager
+        .write_page(second, &[7; PAGE_SIZE as usize])
+        .unwrap();
+    let mut data = [0; PAGE_SIZE as usize];
+    manager.read_page(second, &mut data).unwrap();
+    assert_eq!(data, [7; PAGE_SIZE as usize]);
+
+    // Assert that a freed page is recycled and zeroed.
+    manager.deallocate_page(second);
+    assert!(matches!(
+        manager.read_page(second, &mut data),
+        Err(DiskError::PageNotAllocated)
+    ));
+    assert_eq!(manager.allocate_page().unwrap(), second);
+    manager.read_page(second, &mut data).unwrap();
+    assert_eq!(data, [0; PAGE_SIZE as usize]);
+    assert_eq!(
+        manager.allocated_page_ids(),
+        vec![CATALOG_ROOT_ID, first, second]
+    );
+}
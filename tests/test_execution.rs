@@ -3,10 +3,49 @@
  * Please refer to github.com/shoyo/jindb for more information about this project and its license.
  */
 
+use jin::buffer::replacement::ReplacerAlgorithm;
+use jin::buffer::BufferManager;
+use jin::catalog::SystemCatalog;
+use jin::concurrency::transaction::Transaction;
+use jin::constants::SYSTEM_TXN_ID;
+use jin::disk::DiskManager;
+use jin::executor::exec_aggr::AggregationExecutor;
+use jin::executor::exec_delete::DeleteExecutor;
+use jin::executor::exec_distinct::DistinctExecutor;
+use jin::executor::exec_filter::FilterExecutor;
+use jin::executor::exec_hash_join::HashJoinExecutor;
+use jin::executor::exec_index_scan::IndexScanExecutor;
+use jin::executor::exec_insert::InsertExecutor;
+use jin::executor::exec_limit::LimitExecutor;
+use jin::executor::exec_nested_index_join::NestedIndexJoinExecutor;
+use jin::executor::exec_projection::ProjectionExecutor;
+use jin::executor::exec_seq_scan::SeqScanExecutor;
+use jin::executor::exec_sort::SortExecutor;
+use jin::executor::exec_update::UpdateExecutor;
+use jin::executor::{count_schema, BaseExecutor, ExecutorContext, ExecutorError};
+use jin::index::btree_index::BTreeIndex;
+use jin::index::{Index, IndexError, IndexMeta};
+use jin::plan::aggr::{AggFn, AggregationPlanNode};
+use jin::plan::delete::DeletePlanNode;
+use jin::plan::distinct::DistinctPlanNode;
+use jin::plan::filter::FilterPlanNode;
+use jin::plan::hash_join::HashJoinPlanNode;
+use jin::plan::index_scan::IndexScanPlanNode;
 use jin::plan::insert::InsertPlanNode;
+use jin::plan::limit::LimitPlanNode;
+use jin::plan::nested_index_join::NestedIndexJoinPlanNode;
+use jin::plan::projection::ProjectionPlanNode;
+use jin::plan::seq_scan::SeqScanPlanNode;
+use jin::plan::sort::SortPlanNode;
+use jin::plan::update::UpdatePlanNode;
 use jin::plan::QueryPlanNode;
-use jin::relation::Schema;
-use std::sync::Arc;
+use jin::relation::heap::HeapError;
+use jin::relation::record::{Record, RecordId};
+use jin::relation::types::{DataType, InnerValue, Value, ValueError};
+use jin::relation::{Attribute, Relation, Schema};
+use std::sync::{Arc, Mutex};
+
+mod constants;
 
 /// Tests for query execution.
 /// A query plan is a tree structure constructed out of plan nodes. During execution, the query
@@ -29,3 +68,1126 @@ fn test_execute_query_plan() {
     let _root = setup();
     assert!(false);
 }
+
+struct TestContext {
+    exec_ctx: Arc<ExecutorContext>,
+    schema: Arc<Schema>,
+    _db: constants::TestDb,
+}
+
+/// Return a query context with an empty system catalog.
+fn setup_context() -> TestContext {
+    let db = constants::TestDb::new();
+    let buffer_manager = Arc::new(BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(db.filename()),
+        ReplacerAlgorithm::Slow,
+    ));
+    let system_catalog = Arc::new(SystemCatalog::new(buffer_manager.clone()));
+
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, false, false, false),
+        Attribute::new("padding", DataType::Varchar, false, false, false),
+    ]));
+
+    TestContext {
+        exec_ctx: Arc::new(ExecutorContext::new(
+            system_catalog,
+            buffer_manager,
+            Transaction::new(0),
+        )),
+        schema,
+        _db: db,
+    }
+}
+
+/// Return a record with the test schema and the specified ID.
+fn new_record(ctx: &TestContext, id: i32) -> Record {
+    Record::new(
+        vec![Some(Box::new(id)), Some(Box::new("x".repeat(500)))],
+        ctx.schema.clone(),
+    )
+    .unwrap()
+}
+
+/// Create a relation with the test schema and insert records with IDs 0 to n-1. Each record is
+/// padded so that the records span several pages.
+fn create_relation(ctx: &TestContext, name: &str, n: i32) -> Arc<Relation> {
+    create_relation_with_ids(ctx, name, &(0..n).collect::<Vec<i32>>())
+}
+
+/// Create a relation with the test schema and insert records with the specified IDs.
+fn create_relation_with_ids(ctx: &TestContext, name: &str, ids: &[i32]) -> Arc<Relation> {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let relation = ctx
+        .exec_ctx
+        .get_system_catalog()
+        .create_relation(name, ctx.schema.clone())
+        .unwrap();
+    for id in ids {
+        relation.insert(new_record(ctx, *id), &txn).unwrap();
+    }
+    relation
+}
+
+/// Return the ID of a record with the test schema.
+fn get_id(record: &Record, schema: Arc<Schema>) -> i32 {
+    match record.get_value(0, schema).unwrap().unwrap().get_inner() {
+        InnerValue::Int(id) => id,
+        _ => panic!("expected an int"),
+    }
+}
+
+#[test]
+fn test_seq_scan() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 50);
+
+    let node = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    let mut executor = SeqScanExecutor::new(ctx.exec_ctx.clone(), node);
+
+    // Assert that the scan starts from the root page of the heap.
+    let first = executor.next().unwrap();
+    let first = first.lock().unwrap();
+    assert_eq!(
+        first.get_id().unwrap().page_id,
+        constants::FIRST_RELATION_PAGE_ID
+    );
+
+    // Assert that every record is returned exactly once.
+    let mut ids = vec![get_id(&first, ctx.schema.clone())];
+    while let Some(record) = executor.next() {
+        ids.push(get_id(&record.lock().unwrap(), ctx.schema.clone()));
+    }
+    ids.sort_unstable();
+    assert_eq!(ids, (0..50).collect::<Vec<i32>>());
+    assert!(executor.next().is_none());
+}
+
+#[test]
+fn test_seq_scan_error() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 50);
+    let node = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    let mut executor = SeqScanExecutor::new(ctx.exec_ctx.clone(), node);
+    assert!(executor.next().is_some());
+
+    // Pin every buffer frame, so that the remaining pages of the heap can't be read.
+    let buffer_manager = ctx.exec_ctx.get_buffer_manager();
+    let mut frames = Vec::new();
+    for _ in 0..constants::TEST_BUFFER_SIZE {
+        frames.push(buffer_manager.create_page().unwrap());
+    }
+
+    // Assert that the scan stops with an error after the records of the root page.
+    let mut count = 1;
+    while executor.next().is_some() {
+        count += 1;
+    }
+    assert!(count < 50);
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Heap(HeapError::BufMgrNoBufFrame))
+    );
+    assert!(executor.next().is_none());
+
+    // Assert that the scan succeeds after the frames are unpinned and the scan is reset.
+    drop(frames);
+    executor.reset();
+    let mut count = 0;
+    while executor.next().is_some() {
+        count += 1;
+    }
+    assert_eq!(count, 50);
+    assert_eq!(executor.take_error(), None);
+}
+
+/// An executor which outputs the first records of its child, and then fails with a corrupted
+/// page as if the remaining pages of a scan couldn't be read.
+struct FailingExecutor {
+    child: Box<dyn BaseExecutor>,
+    remaining: usize,
+    failed: bool,
+}
+
+impl FailingExecutor {
+    fn new(child: Box<dyn BaseExecutor>, remaining: usize) -> Self {
+        Self {
+            child,
+            remaining,
+            failed: false,
+        }
+    }
+}
+
+impl BaseExecutor for FailingExecutor {
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        if self.remaining == 0 {
+            self.failed = true;
+            return None;
+        }
+        self.remaining -= 1;
+        self.child.next()
+    }
+
+    fn take_error(&mut self) -> Option<ExecutorError> {
+        match self.failed {
+            true => Some(ExecutorError::Heap(HeapError::PageCorrupted)),
+            false => None,
+        }
+    }
+}
+
+#[test]
+fn test_child_error() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 10);
+    let failing_scan = || {
+        let node = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+        let scan = SeqScanExecutor::new(ctx.exec_ctx.clone(), node);
+        Box::new(FailingExecutor::new(Box::new(scan), 3))
+    };
+    let corrupted = Some(ExecutorError::Heap(HeapError::PageCorrupted));
+
+    // Assert that the error of a child is passed through by a filter.
+    let schema = ctx.schema.clone();
+    let filter = FilterPlanNode::new(Box::new(move |_| true), schema);
+    let mut executor = FilterExecutor::new(filter, failing_scan());
+    while executor.next().is_some() {}
+    assert_eq!(executor.take_error(), corrupted);
+
+    // Assert that an aggregation over a failed child outputs nothing, rather than a partial
+    // count.
+    let node =
+        AggregationPlanNode::new(ctx.schema.clone(), Vec::new(), vec![(AggFn::CountStar, 0)])
+            .unwrap();
+    let mut executor = AggregationExecutor::new(node, failing_scan());
+    assert!(executor.next().is_none());
+    assert_eq!(executor.take_error(), corrupted);
+
+    // Assert that a delete over a failed child returns the error, rather than a partial count.
+    let node = DeletePlanNode::new(relation.get_id(), count_schema());
+    let mut executor = DeleteExecutor::new(ctx.exec_ctx.clone(), node, failing_scan());
+    assert_eq!(
+        executor.execute(),
+        Err(ExecutorError::Heap(HeapError::PageCorrupted))
+    );
+}
+
+/// Return an executor for a scan of the relation, filtered to records whose ID is even.
+fn even_scan(ctx: &TestContext, relation: &Relation) -> Box<dyn BaseExecutor> {
+    let scan = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    let schema = ctx.schema.clone();
+    let filter = FilterPlanNode::new(
+        Box::new(move |record| get_id(record, schema.clone()) % 2 == 0),
+        ctx.schema.clone(),
+    );
+    Box::new(FilterExecutor::new(
+        filter,
+        Box::new(SeqScanExecutor::new(ctx.exec_ctx.clone(), scan)),
+    ))
+}
+
+#[test]
+fn test_seq_scan_reset() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 40);
+    let node = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    let mut executor = SeqScanExecutor::new(ctx.exec_ctx.clone(), node);
+
+    let mut ids = Vec::new();
+    while let Some(record) = executor.next() {
+        ids.push(get_id(&record.lock().unwrap(), ctx.schema.clone()));
+    }
+    assert_eq!(ids, (0..40).collect::<Vec<i32>>());
+
+    // Assert that the full sequence repeats after resetting an exhausted scan.
+    executor.reset();
+    let mut repeated = Vec::new();
+    while let Some(record) = executor.next() {
+        repeated.push(get_id(&record.lock().unwrap(), ctx.schema.clone()));
+    }
+    assert_eq!(repeated, ids);
+
+    // Assert that resetting in the middle of a scan also restarts from the first record.
+    executor.reset();
+    executor.next().unwrap();
+    executor.next().unwrap();
+    executor.reset();
+    let record = executor.next().unwrap();
+    assert_eq!(get_id(&record.lock().unwrap(), ctx.schema.clone()), 0);
+}
+
+#[test]
+fn test_filter() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 20);
+
+    // Filter a scan of the relation down to the records with an even ID.
+    let mut executor = even_scan(&ctx, &relation);
+
+    let mut count = 0;
+    while let Some(record) = executor.next() {
+        assert_eq!(get_id(&record.lock().unwrap(), ctx.schema.clone()) % 2, 0);
+        count += 1;
+    }
+    assert_eq!(count, 10);
+}
+
+/// Return the IDs of every record in the relation, in ascending order.
+fn scan_ids(ctx: &TestContext, relation: &Relation) -> Vec<i32> {
+    let node = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    let mut executor = SeqScanExecutor::new(ctx.exec_ctx.clone(), node);
+
+    let mut ids = Vec::new();
+    while let Some(record) = executor.next() {
+        ids.push(get_id(&record.lock().unwrap(), ctx.schema.clone()));
+    }
+    ids.sort_unstable();
+    ids
+}
+
+#[test]
+fn test_insert() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 0);
+
+    // Insert three literal records through the executor.
+    let records = vec![
+        new_record(&ctx, 7),
+        new_record(&ctx, 8),
+        new_record(&ctx, 9),
+    ];
+    let node = InsertPlanNode::new(relation.get_id(), count_schema()).with_records(records);
+    let mut executor = InsertExecutor::new(ctx.exec_ctx.clone(), node, None);
+
+    // Assert that the executor outputs the number of inserted rows exactly once.
+    let result = executor.next().unwrap();
+    let count = result.lock().unwrap().get_value(0, count_schema()).unwrap();
+    assert_eq!(count.unwrap().get_inner(), InnerValue::BigInt(3));
+    assert!(executor.next().is_none());
+
+    // Assert that the records are readable through the relation.
+    assert_eq!(scan_ids(&ctx, &relation), vec![7, 8, 9]);
+}
+
+#[test]
+fn test_insert_from_same_relation() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 20);
+
+    // Assert that inserting the records of a scan of the same relation terminates and copies
+    // each record exactly once.
+    let node = InsertPlanNode::new(relation.get_id(), count_schema());
+    let scan = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    let scan = SeqScanExecutor::new(ctx.exec_ctx.clone(), scan);
+    let mut executor = InsertExecutor::new(ctx.exec_ctx.clone(), node, Some(Box::new(scan)));
+    assert_eq!(executor.execute(), Ok(20));
+    let mut ids = scan_ids(&ctx, &relation);
+    ids.sort_unstable();
+    let expected: Vec<i32> = (0..20).flat_map(|id| vec![id, id]).collect();
+    assert_eq!(ids, expected);
+}
+
+/// Create a relation whose ID column is a primary key, and return it along with its schema.
+fn create_keyed_relation(ctx: &TestContext, name: &str) -> (Arc<Relation>, Arc<Schema>) {
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, false),
+        Attribute::new("padding", DataType::Varchar, false, false, false),
+    ]));
+    let relation = ctx
+        .exec_ctx
+        .get_system_catalog()
+        .create_relation(name, schema.clone())
+        .unwrap();
+    (relation, schema)
+}
+
+/// Return a record with the specified ID for a relation created by `create_keyed_relation()`.
+fn keyed_record(schema: &Arc<Schema>, id: i32) -> Record {
+    Record::new(
+        vec![Some(Box::new(id)), Some(Box::new("x".to_string()))],
+        schema.clone(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_insert_error() {
+    let ctx = setup_context();
+    let (relation, schema) = create_keyed_relation(&ctx, "foo");
+    let record = |id: i32| keyed_record(&schema, id);
+
+    // Assert that a duplicate primary key stops the insert with an error, rather than a panic.
+    let records = vec![record(1), record(2), record(1)];
+    let node = InsertPlanNode::new(relation.get_id(), count_schema()).with_records(records);
+    let mut executor = InsertExecutor::new(ctx.exec_ctx.clone(), node, None);
+    assert!(executor.next().is_none());
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Heap(HeapError::DuplicatePrimaryKey))
+    );
+}
+
+#[test]
+fn test_delete() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 10);
+
+    // Delete every record with an even ID.
+    let node = DeletePlanNode::new(relation.get_id(), count_schema());
+    let mut executor = DeleteExecutor::new(ctx.exec_ctx.clone(), node, even_scan(&ctx, &relation));
+    assert_eq!(executor.execute(), Ok(5));
+
+    // Assert that only records with an odd ID remain.
+    assert_eq!(scan_ids(&ctx, &relation), vec![1, 3, 5, 7, 9]);
+}
+
+/// An executor which outputs a fixed list of records.
+struct RecordsExecutor {
+    records: Vec<Record>,
+}
+
+impl BaseExecutor for RecordsExecutor {
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        match self.records.is_empty() {
+            true => None,
+            false => Some(Arc::new(Mutex::new(self.records.remove(0)))),
+        }
+    }
+}
+
+#[test]
+fn test_delete_already_deleted() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 10);
+
+    // Collect the records with an even ID, and repeat them so that each is deleted twice.
+    let mut records = Vec::new();
+    let mut scan = even_scan(&ctx, &relation);
+    while let Some(record) = scan.next() {
+        records.push(record.lock().unwrap().clone());
+    }
+    records.extend(records.clone());
+
+    // Assert that records which have already been deleted are skipped.
+    let node = DeletePlanNode::new(relation.get_id(), count_schema());
+    let child = Box::new(RecordsExecutor { records });
+    let mut executor = DeleteExecutor::new(ctx.exec_ctx.clone(), node, child);
+    assert_eq!(executor.execute(), Ok(5));
+    assert_eq!(scan_ids(&ctx, &relation), vec![1, 3, 5, 7, 9]);
+}
+
+#[test]
+fn test_delete_error() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 5);
+
+    // Assert that deleting a record ID which doesn't exist stops the delete with an error,
+    // rather than a panic.
+    let mut record = new_record(&ctx, 0);
+    record.allocate(relation.get_heap().get_root_id(), 999);
+    let node = DeletePlanNode::new(relation.get_id(), count_schema());
+    let child = Box::new(RecordsExecutor {
+        records: vec![record],
+    });
+    let mut executor = DeleteExecutor::new(ctx.exec_ctx.clone(), node, child);
+    assert!(executor.next().is_none());
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Heap(HeapError::RecordDNE))
+    );
+    assert_eq!(scan_ids(&ctx, &relation), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_update_error() {
+    let ctx = setup_context();
+    let (relation, schema) = create_keyed_relation(&ctx, "foo");
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    relation.insert(keyed_record(&schema, 1), &txn).unwrap();
+    let rid = relation.insert(keyed_record(&schema, 2), &txn).unwrap();
+
+    // Assert that an update to a duplicate primary key stops the update with an error, rather
+    // than a panic.
+    let mut record = keyed_record(&schema, 1);
+    record.allocate(rid.page_id, rid.slot_index);
+    let node = UpdatePlanNode::new(relation.get_id(), count_schema());
+    let child = Box::new(RecordsExecutor {
+        records: vec![record],
+    });
+    let mut executor = UpdateExecutor::new(ctx.exec_ctx.clone(), node, child);
+    assert!(executor.next().is_none());
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Heap(HeapError::DuplicatePrimaryKey))
+    );
+}
+
+#[test]
+fn test_update() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 15);
+
+    // Collect the IDs of the first two records.
+    let mut scan = SeqScanExecutor::new(
+        ctx.exec_ctx.clone(),
+        SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone()),
+    );
+    let rid_1 = scan.next().unwrap().lock().unwrap().get_id().unwrap();
+    let rid_2 = scan.next().unwrap().lock().unwrap().get_id().unwrap();
+
+    // Update the first record with a smaller record, and the second record with a record that
+    // is too large to fit in the remaining space of its page.
+    let new_record = |id: i32, padding: &str, rid: RecordId| {
+        let mut record = Record::new(
+            vec![Some(Box::new(id)), Some(Box::new(padding.to_string()))],
+            ctx.schema.clone(),
+        )
+        .unwrap();
+        record.allocate(rid.page_id, rid.slot_index);
+        record
+    };
+    let records = vec![
+        new_record(100, "small", rid_1),
+        new_record(200, &"y".repeat(2000), rid_2),
+    ];
+
+    let node = UpdatePlanNode::new(relation.get_id(), count_schema());
+    let child = Box::new(RecordsExecutor { records });
+    let mut executor = UpdateExecutor::new(ctx.exec_ctx.clone(), node, child);
+    assert_eq!(executor.execute(), Ok(2));
+
+    // Assert that the smaller record was updated in place and the larger record was
+    // reallocated.
+    let updated_ids = executor.get_updated_ids();
+    assert_eq!(updated_ids[0], rid_1);
+    assert_ne!(updated_ids[1], rid_2);
+
+    // Assert that the new values are readable.
+    let record = relation.read(updated_ids[0]).unwrap();
+    assert_eq!(get_id(&record, ctx.schema.clone()), 100);
+    let padding = record.get_value(1, ctx.schema.clone()).unwrap().unwrap();
+    assert_eq!(
+        padding.get_inner(),
+        InnerValue::Varchar("small".to_string())
+    );
+
+    let record = relation.read(updated_ids[1]).unwrap();
+    assert_eq!(get_id(&record, ctx.schema.clone()), 200);
+    let padding = record.get_value(1, ctx.schema.clone()).unwrap().unwrap();
+    assert_eq!(padding.get_inner(), InnerValue::Varchar("y".repeat(2000)));
+
+    // Assert that the relation still contains the same number of records.
+    assert_eq!(scan_ids(&ctx, &relation).len(), 15);
+}
+
+/// An executor which outputs the records of its child with 1000 added to their ID and 500 bytes
+/// appended to their padding, keeping their record IDs.
+struct GrowExecutor {
+    child: Box<dyn BaseExecutor>,
+    schema: Arc<Schema>,
+}
+
+impl BaseExecutor for GrowExecutor {
+    fn next(&mut self) -> Option<Arc<Mutex<Record>>> {
+        let record = self.child.next()?;
+        let record = record.lock().unwrap();
+        let padding = match record.get_value(1, self.schema.clone()).unwrap() {
+            Some(value) => match value.get_inner() {
+                InnerValue::Varchar(padding) => padding,
+                _ => panic!("expected a varchar"),
+            },
+            None => panic!("expected a value"),
+        };
+        let mut grown = Record::new(
+            vec![
+                Some(Box::new(get_id(&record, self.schema.clone()) + 1000)),
+                Some(Box::new(padding + &"y".repeat(500))),
+            ],
+            self.schema.clone(),
+        )
+        .unwrap();
+        let rid = record.get_id().unwrap();
+        grown.allocate(rid.page_id, rid.slot_index);
+        Some(Arc::new(Mutex::new(grown)))
+    }
+}
+
+#[test]
+fn test_update_from_seq_scan() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 30);
+
+    // Grow every record of a scan of the relation, so that records are moved to pages that the
+    // scan hasn't reached yet.
+    let scan = SeqScanExecutor::new(
+        ctx.exec_ctx.clone(),
+        SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone()),
+    );
+    let child = Box::new(GrowExecutor {
+        child: Box::new(scan),
+        schema: ctx.schema.clone(),
+    });
+    let node = UpdatePlanNode::new(relation.get_id(), count_schema());
+    let mut executor = UpdateExecutor::new(ctx.exec_ctx.clone(), node, child);
+    assert_eq!(executor.execute(), Ok(30));
+
+    // Assert that every record was updated exactly once.
+    assert_eq!(
+        scan_ids(&ctx, &relation),
+        (1000..1030).collect::<Vec<i32>>()
+    );
+    for record in relation.scan() {
+        let padding = record.get_value(1, ctx.schema.clone()).unwrap().unwrap();
+        assert_eq!(
+            padding.get_inner(),
+            InnerValue::Varchar("x".repeat(500) + &"y".repeat(500))
+        );
+    }
+}
+
+/// Return the IDs of every record outputted by a limit over a scan of the relation.
+fn limit_ids(ctx: &TestContext, relation: &Relation, limit: usize, offset: usize) -> Vec<i32> {
+    let scan = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    let node = LimitPlanNode::new(limit, offset, ctx.schema.clone());
+    let mut executor = LimitExecutor::new(
+        node,
+        Box::new(SeqScanExecutor::new(ctx.exec_ctx.clone(), scan)),
+    );
+
+    let mut ids = Vec::new();
+    while let Some(record) = executor.next() {
+        ids.push(get_id(&record.lock().unwrap(), ctx.schema.clone()));
+    }
+    ids
+}
+
+#[test]
+fn test_limit() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 20);
+
+    assert_eq!(limit_ids(&ctx, &relation, 5, 0), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_limit_offset() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 20);
+
+    // Assert that the 4th to 8th records are outputted.
+    assert_eq!(limit_ids(&ctx, &relation, 5, 3), vec![3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn test_limit_exceeds_records() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 20);
+
+    assert_eq!(
+        limit_ids(&ctx, &relation, 50, 0),
+        (0..20).collect::<Vec<i32>>()
+    );
+    assert!(limit_ids(&ctx, &relation, 5, 20).is_empty());
+}
+
+/// Return an executor for a scan of the relation.
+fn scan(ctx: &TestContext, relation: &Relation) -> Box<dyn BaseExecutor> {
+    let node = SeqScanPlanNode::new(relation.get_id(), ctx.schema.clone());
+    Box::new(SeqScanExecutor::new(ctx.exec_ctx.clone(), node))
+}
+
+#[test]
+fn test_hash_join() {
+    let ctx = setup_context();
+    let left = create_relation_with_ids(&ctx, "left", &[0, 1, 1, 2, 2, 2]);
+    let right = create_relation_with_ids(&ctx, "right", &[1, 2, 2, 3]);
+
+    // Join the relations on their IDs.
+    let node = HashJoinPlanNode::new(ctx.schema.clone(), vec![0], ctx.schema.clone(), vec![0]);
+    let out_schema = node.get_output_schema();
+    assert_eq!(out_schema.attr_len(), 4);
+    let mut executor = HashJoinExecutor::new(node, scan(&ctx, &left), scan(&ctx, &right));
+
+    // Assert that every matching pair is outputted, with the left ID in the first column and
+    // the right ID in the third column.
+    let mut pairs = Vec::new();
+    while let Some(record) = executor.next() {
+        let record = record.lock().unwrap();
+        let id = |idx| match record.get_value(idx, out_schema.clone()).unwrap() {
+            Some(value) => value.get_inner(),
+            None => panic!("expected a value"),
+        };
+        pairs.push((id(0), id(2)));
+    }
+    let count = |id| {
+        pairs
+            .iter()
+            .filter(|pair| **pair == (InnerValue::Int(id), InnerValue::Int(id)))
+            .count()
+    };
+    assert_eq!(pairs.len(), 8);
+    assert_eq!(count(1), 2);
+    assert_eq!(count(2), 6);
+}
+
+/// Return a schema of employees and records of employees in several departments, where some
+/// salaries are null.
+fn employee_records() -> (Arc<Schema>, Vec<Record>) {
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("dept", DataType::Varchar, false, false, false),
+        Attribute::new("salary", DataType::Int, false, false, true),
+    ]));
+    let employees = [
+        ("a", Some(10)),
+        ("b", Some(20)),
+        ("a", Some(5)),
+        ("b", None),
+        ("c", None),
+        ("a", None),
+    ];
+    let records = employees
+        .iter()
+        .map(|(dept, salary)| {
+            let salary = salary.map(|salary: i32| Box::new(salary) as _);
+            Record::new(
+                vec![Some(Box::new(dept.to_string())), salary],
+                schema.clone(),
+            )
+            .unwrap()
+        })
+        .collect();
+    (schema, records)
+}
+
+/// Return the values of every record outputted by the executor.
+fn collect_values(
+    executor: &mut dyn BaseExecutor,
+    schema: Arc<Schema>,
+) -> Vec<Vec<Option<InnerValue>>> {
+    let mut rows = Vec::new();
+    while let Some(record) = executor.next() {
+        let record = record.lock().unwrap();
+        let row = (0..schema.attr_len())
+            .map(|idx| {
+                let value = record.get_value(idx, schema.clone()).unwrap();
+                value.map(|value| value.get_inner())
+            })
+            .collect();
+        rows.push(row);
+    }
+    rows
+}
+
+#[test]
+fn test_aggregate_global_count() {
+    let (schema, records) = employee_records();
+    let aggregates = vec![
+        (AggFn::CountStar, 1),
+        (AggFn::Count, 1),
+        (AggFn::Avg, 1),
+        (AggFn::Max, 1),
+    ];
+    let node = AggregationPlanNode::new(schema, Vec::new(), aggregates).unwrap();
+    let out_schema = node.get_output_schema();
+    let mut executor = AggregationExecutor::new(node, Box::new(RecordsExecutor { records }));
+
+    // Assert that a single record is outputted, where nulls are only counted by COUNT(*).
+    assert_eq!(
+        collect_values(&mut executor, out_schema),
+        vec![vec![
+            Some(InnerValue::BigInt(6)),
+            Some(InnerValue::BigInt(3)),
+            Some(InnerValue::Real(35.0 / 3.0)),
+            Some(InnerValue::Int(20)),
+        ]]
+    );
+}
+
+#[test]
+fn test_aggregate_grouped_sum() {
+    let (schema, records) = employee_records();
+    let node = AggregationPlanNode::new(schema, vec![0], vec![(AggFn::Sum, 1)]).unwrap();
+    let out_schema = node.get_output_schema();
+    assert_eq!(out_schema.get_attributes()[1].get_name(), "sum(salary)");
+    let mut executor = AggregationExecutor::new(node, Box::new(RecordsExecutor { records }));
+
+    // Assert that a record is outputted for each department in order of appearance, where the
+    // sum of a department without any salaries is null.
+    let dept = |dept: &str| Some(InnerValue::Varchar(dept.to_string()));
+    assert_eq!(
+        collect_values(&mut executor, out_schema),
+        vec![
+            vec![dept("a"), Some(InnerValue::BigInt(15))],
+            vec![dept("b"), Some(InnerValue::BigInt(20))],
+            vec![dept("c"), None],
+        ]
+    );
+}
+
+#[test]
+fn test_aggregate_errors() {
+    // Assert that aggregates which require a numeric column are rejected for other columns.
+    let (schema, _) = employee_records();
+    for agg in [AggFn::Sum, AggFn::Avg].iter() {
+        assert!(matches!(
+            AggregationPlanNode::new(schema.clone(), Vec::new(), vec![(*agg, 0)]),
+            Err(ValueError::NonNumeric)
+        ));
+    }
+
+    // Assert that a sum which overflows stops the aggregation with an error.
+    let schema = Arc::new(Schema::new(vec![Attribute::new(
+        "amount",
+        DataType::BigInt,
+        false,
+        false,
+        false,
+    )]));
+    let records = vec![i64::MAX, 1]
+        .into_iter()
+        .map(|amount| Record::new(vec![Some(Box::new(amount))], schema.clone()).unwrap())
+        .collect();
+    let node = AggregationPlanNode::new(schema, Vec::new(), vec![(AggFn::Sum, 0)]).unwrap();
+    let mut executor = AggregationExecutor::new(node, Box::new(RecordsExecutor { records }));
+    assert!(executor.next().is_none());
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Value(ValueError::Overflow))
+    );
+}
+
+/// Return records with an ID and a nullable name, which are not in order of either column.
+fn unsorted_records() -> (Arc<Schema>, Vec<Record>) {
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, false, false, false),
+        Attribute::new("name", DataType::Varchar, false, false, true),
+    ]));
+    let people = [
+        (7, Some("grace")),
+        (-2, Some("bob")),
+        (5, None),
+        (0, Some("alice")),
+        (9, Some("judy")),
+        (3, Some("carol")),
+        (-8, Some("frank")),
+        (1, Some("erin")),
+        (4, None),
+        (2, Some("dave")),
+    ];
+    let records = people
+        .iter()
+        .map(|(id, name)| {
+            let name = name.map(|name: &str| Box::new(name.to_string()) as _);
+            Record::new(vec![Some(Box::new(*id)), name], schema.clone()).unwrap()
+        })
+        .collect();
+    (schema, records)
+}
+
+#[test]
+fn test_sort() {
+    // Sort by ID in ascending order.
+    let (schema, records) = unsorted_records();
+    let node = SortPlanNode::new(vec![(0, true)], schema.clone());
+    let mut executor = SortExecutor::new(node, Box::new(RecordsExecutor { records }));
+
+    let ids: Vec<Option<InnerValue>> = collect_values(&mut executor, schema)
+        .into_iter()
+        .map(|row| row[0].clone())
+        .collect();
+    let expected: Vec<Option<InnerValue>> = [-8, -2, 0, 1, 2, 3, 4, 5, 7, 9]
+        .iter()
+        .map(|id| Some(InnerValue::Int(*id)))
+        .collect();
+    assert_eq!(ids, expected);
+
+    // Sort by name in descending order. Nulls are ordered first, so they are outputted last.
+    let (schema, records) = unsorted_records();
+    let node = SortPlanNode::new(vec![(1, false)], schema.clone());
+    let mut executor = SortExecutor::new(node, Box::new(RecordsExecutor { records }));
+
+    let names: Vec<Option<InnerValue>> = collect_values(&mut executor, schema)
+        .into_iter()
+        .map(|row| row[1].clone())
+        .collect();
+    let name = |name: &str| Some(InnerValue::Varchar(name.to_string()));
+    let expected = vec![
+        name("judy"),
+        name("grace"),
+        name("frank"),
+        name("erin"),
+        name("dave"),
+        name("carol"),
+        name("bob"),
+        name("alice"),
+        None,
+        None,
+    ];
+    assert_eq!(names, expected);
+}
+
+#[test]
+fn test_distinct() {
+    let (schema, records) = employee_records();
+
+    // Add duplicates of four records, including records with null values.
+    let mut records = records;
+    for idx in [0, 1, 3, 5].iter() {
+        records.push(records[*idx].clone());
+    }
+    assert_eq!(records.len(), 10);
+
+    let node = DistinctPlanNode::new(schema.clone());
+    let mut executor = DistinctExecutor::new(node, Box::new(RecordsExecutor { records }));
+
+    // Assert that only the first occurrence of each record is outputted. Records that only
+    // differ by a null value are distinct.
+    let dept = |dept: &str| Some(InnerValue::Varchar(dept.to_string()));
+    let salary = |salary: i32| Some(InnerValue::Int(salary));
+    assert_eq!(
+        collect_values(&mut executor, schema),
+        vec![
+            vec![dept("a"), salary(10)],
+            vec![dept("b"), salary(20)],
+            vec![dept("a"), salary(5)],
+            vec![dept("b"), None],
+            vec![dept("c"), None],
+            vec![dept("a"), None],
+        ]
+    );
+}
+
+#[test]
+fn test_projection() {
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let ctx = setup_context();
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, false, false, false),
+        Attribute::new("name", DataType::Varchar, false, false, false),
+        Attribute::new("age", DataType::SmallInt, false, false, false),
+        Attribute::new("active", DataType::Boolean, false, false, false),
+    ]));
+    let relation = ctx
+        .exec_ctx
+        .get_system_catalog()
+        .create_relation("people", schema.clone())
+        .unwrap();
+    let people = [(1_i32, "alice", 30_i16, true), (2, "bob", 25, false)];
+    for (id, name, age, active) in people.iter() {
+        let values: Vec<Option<Box<dyn Value>>> = vec![
+            Some(Box::new(*id)),
+            Some(Box::new(name.to_string())),
+            Some(Box::new(*age)),
+            Some(Box::new(*active)),
+        ];
+        relation
+            .insert(Record::new(values, schema.clone()).unwrap(), &txn)
+            .unwrap();
+    }
+
+    // Project the age and name columns, in that order.
+    let out_schema = Arc::new(Schema::new(vec![
+        Attribute::new("age", DataType::SmallInt, false, false, false),
+        Attribute::new("name", DataType::Varchar, false, false, false),
+    ]));
+    let scan_node = SeqScanPlanNode::new(relation.get_id(), schema.clone());
+    let scan = SeqScanExecutor::new(ctx.exec_ctx.clone(), scan_node);
+    let node = ProjectionPlanNode::new(schema, vec![2, 1], out_schema);
+    let out_schema = node.get_output_schema();
+    let mut executor = ProjectionExecutor::new(node, Box::new(scan));
+
+    let names: Vec<&str> = out_schema
+        .get_attributes()
+        .iter()
+        .map(|attr| attr.get_name())
+        .collect();
+    assert_eq!(names, vec!["age", "name"]);
+
+    let name = |name: &str| Some(InnerValue::Varchar(name.to_string()));
+    assert_eq!(
+        collect_values(&mut executor, out_schema),
+        vec![
+            vec![Some(InnerValue::SmallInt(30)), name("alice")],
+            vec![Some(InnerValue::SmallInt(25)), name("bob")],
+        ]
+    );
+}
+
+/// Return the key schema of an index over the ID column of the test schema.
+fn id_key_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![Attribute::new(
+        "id",
+        DataType::Int,
+        false,
+        false,
+        false,
+    )]))
+}
+
+/// Create a B+ tree index over the ID column of a relation with the test schema, and add an
+/// entry for every record of the relation.
+fn create_id_index(ctx: &TestContext, relation: &Relation, txn: &Transaction) -> Arc<BTreeIndex> {
+    let meta = IndexMeta::new(
+        "id_idx",
+        relation.get_name(),
+        id_key_schema(),
+        vec![0],
+        ctx.exec_ctx.get_buffer_manager(),
+    );
+    let index = BTreeIndex::new(meta).unwrap();
+
+    let mut scan = scan(ctx, relation);
+    while let Some(record) = scan.next() {
+        let record = record.lock().unwrap();
+        let key = index
+            .get_meta()
+            .project_key(&record, ctx.schema.clone())
+            .unwrap();
+        index.set(&key, record.get_id().unwrap(), txn).unwrap();
+    }
+    Arc::new(index)
+}
+
+/// Return a key record for the index over the ID column.
+fn id_key(id: i32) -> Record {
+    Record::new(vec![Some(Box::new(id))], id_key_schema()).unwrap()
+}
+
+#[test]
+fn test_index_scan() {
+    let ctx = setup_context();
+    let ids: Vec<i32> = (0..100).map(|i| (i * 37) % 100).collect();
+    let relation = create_relation_with_ids(&ctx, "foo", &ids);
+    let txn = ctx.exec_ctx.get_transaction();
+    let index = create_id_index(&ctx, &relation, txn);
+
+    let index_scan = |node: IndexScanPlanNode| {
+        let mut executor = IndexScanExecutor::new(ctx.exec_ctx.clone(), node, index.clone());
+        let mut ids = Vec::new();
+        while let Some(record) = executor.next() {
+            ids.push(get_id(&record.lock().unwrap(), ctx.schema.clone()));
+        }
+        ids
+    };
+
+    // Assert that scanning for a single key returns the record with that key.
+    let node = IndexScanPlanNode::new(relation.get_id(), ctx.schema.clone()).with_key(id_key(42));
+    assert_eq!(index_scan(node), vec![42]);
+
+    // Assert that a range scan returns records in key order, and skips deleted records.
+    let rid = index.get(&id_key(12), txn).unwrap()[0];
+    relation.flag_delete(rid, txn).unwrap();
+    relation.commit_delete(rid, txn).unwrap();
+    let node = IndexScanPlanNode::new(relation.get_id(), ctx.schema.clone())
+        .with_range(Some(id_key(10)), Some(id_key(14)));
+    assert_eq!(index_scan(node), vec![10, 11, 13, 14]);
+}
+
+#[test]
+fn test_index_scan_error() {
+    let ctx = setup_context();
+    let relation = create_relation(&ctx, "foo", 50);
+    let txn = ctx.exec_ctx.get_transaction();
+    let index = create_id_index(&ctx, &relation, txn);
+    let buffer_manager = ctx.exec_ctx.get_buffer_manager();
+    let pin_all = || {
+        (0..constants::TEST_BUFFER_SIZE)
+            .map(|_| buffer_manager.create_page().unwrap())
+            .collect::<Vec<_>>()
+    };
+    let node = || {
+        IndexScanPlanNode::new(relation.get_id(), ctx.schema.clone())
+            .with_range(Some(id_key(0)), Some(id_key(40)))
+    };
+
+    // Assert that the scan stops with an error if the index can't be read.
+    let mut executor = IndexScanExecutor::new(ctx.exec_ctx.clone(), node(), index.clone());
+    let frames = pin_all();
+    assert!(executor.next().is_none());
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Index(IndexError::BufMgrNoBufFrame))
+    );
+    drop(frames);
+
+    // Assert that the scan stops with an error if a record can't be read from the heap.
+    let mut executor = IndexScanExecutor::new(ctx.exec_ctx.clone(), node(), index.clone());
+    assert!(executor.next().is_some());
+    let frames = pin_all();
+    while executor.next().is_some() {}
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Heap(HeapError::BufMgrNoBufFrame))
+    );
+    drop(frames);
+}
+
+#[test]
+fn test_nested_index_join() {
+    let ctx = setup_context();
+    let outer = create_relation_with_ids(&ctx, "outer", &[3, 5, 5, 120, 7, 0, 12]);
+    let inner = create_relation_with_ids(&ctx, "inner", &[12, 5, 0, 1, 5, 7, 2, 9, 5]);
+    let index = create_id_index(&ctx, &inner, ctx.exec_ctx.get_transaction());
+
+    let node = NestedIndexJoinPlanNode::new(
+        ctx.schema.clone(),
+        vec![0],
+        inner.get_id(),
+        ctx.schema.clone(),
+    );
+    let out_schema = node.get_output_schema();
+    let mut executor =
+        NestedIndexJoinExecutor::new(ctx.exec_ctx.clone(), node, scan(&ctx, &outer), index);
+    let mut joined = collect_values(&mut executor, out_schema.clone());
+
+    // Compute the expected output with a nested loop join over both relations.
+    let outer_records = collect_values(scan(&ctx, &outer).as_mut(), ctx.schema.clone());
+    let inner_records = collect_values(scan(&ctx, &inner).as_mut(), ctx.schema.clone());
+    let mut expected = Vec::new();
+    for outer in outer_records.iter() {
+        for inner in inner_records.iter() {
+            if outer[0] == inner[0] {
+                expected.push(outer.iter().chain(inner.iter()).cloned().collect());
+            }
+        }
+    }
+
+    // Assert that both joins output the same records, regardless of order.
+    assert_eq!(joined.len(), 9);
+    joined.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    expected.sort_by(|a: &Vec<Option<InnerValue>>, b| a.partial_cmp(b).unwrap());
+    assert_eq!(joined, expected);
+}
+
+#[test]
+fn test_nested_index_join_error() {
+    let ctx = setup_context();
+    let inner = create_relation_with_ids(&ctx, "inner", &[1, 2, 3]);
+    let index = create_id_index(&ctx, &inner, ctx.exec_ctx.get_transaction());
+    let node = NestedIndexJoinPlanNode::new(
+        ctx.schema.clone(),
+        vec![0],
+        inner.get_id(),
+        ctx.schema.clone(),
+    );
+    let outer = Box::new(RecordsExecutor {
+        records: vec![new_record(&ctx, 1), new_record(&ctx, 2)],
+    });
+    let mut executor = NestedIndexJoinExecutor::new(ctx.exec_ctx.clone(), node, outer, index);
+
+    // Pin every buffer frame, so that the index can't be probed.
+    let buffer_manager = ctx.exec_ctx.get_buffer_manager();
+    let mut frames = Vec::new();
+    for _ in 0..constants::TEST_BUFFER_SIZE {
+        frames.push(buffer_manager.create_page().unwrap());
+    }
+
+    // Assert that the join stops with an error, rather than a panic.
+    assert!(executor.next().is_none());
+    assert_eq!(
+        executor.take_error(),
+        Some(ExecutorError::Index(IndexError::BufMgrNoBufFrame))
+    );
+}
@@ -0,0 +1,381 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use jin::buffer::replacement::ReplacerAlgorithm;
+use jin::buffer::BufferManager;
+use jin::concurrency::transaction::Transaction;
+use jin::disk::DiskManager;
+use jin::index::btree_index::BTreeIndex;
+use jin::index::hash_table_index::HashTableIndex;
+use jin::index::{Index, IndexError, IndexMeta};
+use jin::relation::record::{Record, RecordId};
+use jin::relation::types::DataType;
+use jin::relation::{Attribute, Schema};
+use std::sync::{Arc, Mutex};
+
+mod constants;
+
+struct TestContext {
+    key_schema: Arc<Schema>,
+    buffer_manager: Arc<BufferManager>,
+    _db: constants::TestDb,
+}
+
+fn setup() -> TestContext {
+    let db = constants::TestDb::new();
+    let buffer_manager = BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(db.filename()),
+        ReplacerAlgorithm::Slow,
+    );
+
+    let key_schema = Arc::new(Schema::new(vec![Attribute::new(
+        "foo",
+        DataType::Int,
+        false,
+        false,
+        true,
+    )]));
+
+    TestContext {
+        key_schema,
+        buffer_manager: Arc::new(buffer_manager),
+        _db: db,
+    }
+}
+
+/// Return a key record for the test key schema.
+fn key(ctx: &TestContext, value: i32) -> Record {
+    Record::new(vec![Some(Box::new(value))], ctx.key_schema.clone()).unwrap()
+}
+
+/// Return a record ID that is unique for each value.
+fn rid(value: i32) -> RecordId {
+    RecordId {
+        page_id: (value / 100) as u32 + 1,
+        slot_index: (value % 100) as u32,
+    }
+}
+
+#[test]
+fn test_btree_get_set_delete() {
+    let ctx = setup();
+    let meta = IndexMeta::new(
+        "foo_idx",
+        "foo",
+        ctx.key_schema.clone(),
+        vec![0],
+        ctx.buffer_manager.clone(),
+    );
+    let index = BTreeIndex::new(meta).unwrap();
+    let txn = Transaction::new(0);
+    assert_eq!(index.get_root_id(), constants::FIRST_RELATION_PAGE_ID);
+
+    // Insert 1,000 keys in a scrambled order.
+    let num_keys = 1000;
+    let values: Vec<i32> = (0..num_keys).map(|i| (i * 7919) % num_keys - 500).collect();
+    for &value in values.iter() {
+        index
+            .set(&key(&ctx, value), rid(value + 500), &txn)
+            .unwrap();
+    }
+
+    // Assert that each key can be looked up.
+    for &value in values.iter() {
+        let result = index.get(&key(&ctx, value), &txn).unwrap();
+        assert_eq!(result, vec![rid(value + 500)]);
+    }
+    assert!(index.get(&key(&ctx, num_keys), &txn).unwrap().is_empty());
+
+    // Delete half of the keys.
+    for value in (-500..500).step_by(2) {
+        assert!(index
+            .delete(&key(&ctx, value), rid(value + 500), &txn)
+            .unwrap());
+    }
+    assert!(!index.delete(&key(&ctx, -500), rid(0), &txn).unwrap());
+
+    // Assert that only the remaining keys can be looked up.
+    for value in -500..500 {
+        let result = index.get(&key(&ctx, value), &txn).unwrap();
+        match value % 2 == 0 {
+            true => assert!(result.is_empty()),
+            false => assert_eq!(result, vec![rid(value + 500)]),
+        }
+    }
+}
+
+#[test]
+fn test_btree_duplicate_keys() {
+    let ctx = setup();
+    let meta = IndexMeta::new(
+        "foo_idx",
+        "foo",
+        ctx.key_schema.clone(),
+        vec![0],
+        ctx.buffer_manager.clone(),
+    );
+    let index = BTreeIndex::new(meta).unwrap();
+    let txn = Transaction::new(0);
+
+    // Insert many records with the same key, enough to span several leaves.
+    let num_rids = 2000;
+    for i in 0..num_rids {
+        index.set(&key(&ctx, i % 2), rid(i), &txn).unwrap();
+    }
+
+    // Assert that every record ID is returned for each key, in record ID order.
+    let expected: Vec<RecordId> = (0..num_rids).step_by(2).map(rid).collect();
+    assert_eq!(index.get(&key(&ctx, 0), &txn).unwrap(), expected);
+    let expected: Vec<RecordId> = (1..num_rids).step_by(2).map(rid).collect();
+    assert_eq!(index.get(&key(&ctx, 1), &txn).unwrap(), expected);
+
+    // Assert that a null key is indexed separately from other keys.
+    let null_key = Record::new(vec![None], ctx.key_schema.clone()).unwrap();
+    index.set(&null_key, rid(num_rids), &txn).unwrap();
+    assert_eq!(index.get(&null_key, &txn).unwrap(), vec![rid(num_rids)]);
+    assert_eq!(
+        index.get(&key(&ctx, 0), &txn).unwrap().len(),
+        num_rids as usize / 2
+    );
+}
+
+#[test]
+fn test_btree_unsupported_key() {
+    let ctx = setup();
+    let schema = Arc::new(Schema::new(Vec::new()));
+    let meta = IndexMeta::new(
+        "foo_idx",
+        "foo",
+        schema,
+        Vec::new(),
+        ctx.buffer_manager.clone(),
+    );
+
+    let result = BTreeIndex::new(meta);
+    assert_eq!(result.err(), Some(IndexError::UnsupportedKeyType));
+}
+
+#[test]
+fn test_hash_get_set_delete() {
+    let ctx = setup();
+    let meta = IndexMeta::new(
+        "foo_idx",
+        "foo",
+        ctx.key_schema.clone(),
+        vec![0],
+        ctx.buffer_manager.clone(),
+    );
+    let index = HashTableIndex::new(meta).unwrap();
+    let txn = Transaction::new(0);
+
+    // Insert 500 entries, where some keys are shared by several records. This is enough to
+    // overflow the initial bucket and split it.
+    let num_entries = 500;
+    let num_keys = 150;
+    for i in 0..num_entries {
+        index.set(&key(&ctx, i % num_keys), rid(i), &txn).unwrap();
+    }
+
+    // Assert that every record ID is returned for each key.
+    for value in 0..num_keys {
+        let mut result = index.get(&key(&ctx, value), &txn).unwrap();
+        result.sort_by_key(|rid| (rid.page_id, rid.slot_index));
+        let expected: Vec<RecordId> = (value..num_entries)
+            .step_by(num_keys as usize)
+            .map(rid)
+            .collect();
+        assert_eq!(result, expected);
+    }
+    assert!(index.get(&key(&ctx, num_keys), &txn).unwrap().is_empty());
+
+    // Delete a specific entry and assert that the other entries with the same key remain.
+    assert!(index.delete(&key(&ctx, 7), rid(7), &txn).unwrap());
+    assert!(!index.delete(&key(&ctx, 7), rid(7), &txn).unwrap());
+    let mut result = index.get(&key(&ctx, 7), &txn).unwrap();
+    result.sort_by_key(|rid| (rid.page_id, rid.slot_index));
+    assert_eq!(result, vec![rid(157), rid(307), rid(457)]);
+}
+
+#[test]
+fn test_btree_composite_key() {
+    let ctx = setup();
+    let table_schema = Arc::new(Schema::new(vec![
+        Attribute::new("age", DataType::Int, false, false, false),
+        Attribute::new("first_name", DataType::Varchar, false, false, true),
+        Attribute::new("last_name", DataType::Varchar, false, false, false),
+    ]));
+    let key_schema = Arc::new(Schema::new(vec![
+        Attribute::new("last_name", DataType::Varchar, false, false, false),
+        Attribute::new("first_name", DataType::Varchar, false, false, true),
+    ]));
+    let meta = IndexMeta::new(
+        "name_idx",
+        "people",
+        key_schema.clone(),
+        vec![2, 1],
+        ctx.buffer_manager.clone(),
+    )
+    .with_varchar_key_size(16);
+    let index = BTreeIndex::new(meta).unwrap();
+    let txn = Transaction::new(0);
+
+    // Insert a record ID for each person, where some people share a first or last name.
+    let people = [
+        ("Smith", Some("John")),
+        ("Doe", Some("Jane")),
+        ("Smith", Some("Jane")),
+        ("Doe", Some("John")),
+        ("Smith", None),
+        ("Smithson", Some("John")),
+        ("Smith", Some("John")),
+    ];
+    for (i, (last, first)) in people.iter().enumerate() {
+        let record = Record::new(
+            vec![
+                Some(Box::new(30 + i as i32)),
+                first.map(|first| Box::new(first.to_string()) as _),
+                Some(Box::new(last.to_string())),
+            ],
+            table_schema.clone(),
+        )
+        .unwrap();
+        let key = index
+            .get_meta()
+            .project_key(&record, table_schema.clone())
+            .unwrap();
+        index.set(&key, rid(i as i32), &txn).unwrap();
+    }
+
+    // Assert that looking up each name in order returns the record IDs of matching people.
+    let expected = vec![
+        ("Doe", Some("Jane"), vec![rid(1)]),
+        ("Doe", Some("John"), vec![rid(3)]),
+        ("Smith", None, vec![rid(4)]),
+        ("Smith", Some("Jane"), vec![rid(2)]),
+        ("Smith", Some("John"), vec![rid(0), rid(6)]),
+        ("Smithson", Some("John"), vec![rid(5)]),
+        ("Smithson", Some("Jane"), vec![]),
+    ];
+    for (last, first, rids) in expected {
+        let key = Record::new(
+            vec![
+                Some(Box::new(last.to_string())),
+                first.map(|first| Box::new(first.to_string()) as _),
+            ],
+            key_schema.clone(),
+        )
+        .unwrap();
+        assert_eq!(index.get(&key, &txn).unwrap(), rids);
+    }
+}
+
+/// An in-memory index that stores its entries in a vector.
+struct MockIndex {
+    meta: IndexMeta,
+    entries: Mutex<Vec<(Record, RecordId)>>,
+}
+
+impl Index for MockIndex {
+    fn get_meta(&self) -> &IndexMeta {
+        &self.meta
+    }
+
+    fn get(&self, key: &Record, _txn: &Transaction) -> Result<Vec<RecordId>, IndexError> {
+        let entries = self.entries.lock().unwrap();
+        Ok(entries
+            .iter()
+            .filter(|(k, _)| k.equals(key, self.meta.get_schema()))
+            .map(|(_, rid)| *rid)
+            .collect())
+    }
+
+    fn set(&self, key: &Record, rid: RecordId, _txn: &Transaction) -> Result<(), IndexError> {
+        self.entries.lock().unwrap().push((key.clone(), rid));
+        Ok(())
+    }
+
+    fn delete(&self, key: &Record, rid: RecordId, _txn: &Transaction) -> Result<bool, IndexError> {
+        let mut entries = self.entries.lock().unwrap();
+        let schema = self.meta.get_schema();
+        match entries
+            .iter()
+            .position(|(k, r)| k.equals(key, schema.clone()) && *r == rid)
+        {
+            Some(idx) => {
+                entries.remove(idx);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+#[test]
+fn test_mock_index() {
+    let ctx = setup();
+    let meta = IndexMeta::new(
+        "foo_idx",
+        "foo",
+        ctx.key_schema.clone(),
+        vec![0],
+        ctx.buffer_manager.clone(),
+    );
+    let index: Box<dyn Index> = Box::new(MockIndex {
+        meta,
+        entries: Mutex::new(Vec::new()),
+    });
+    let txn = Transaction::new(0);
+
+    index.set(&key(&ctx, 1), rid(1), &txn).unwrap();
+    index.set(&key(&ctx, 1), rid(2), &txn).unwrap();
+    index.set(&key(&ctx, 2), rid(3), &txn).unwrap();
+    assert_eq!(
+        index.get(&key(&ctx, 1), &txn).unwrap(),
+        vec![rid(1), rid(2)]
+    );
+
+    assert!(index.delete(&key(&ctx, 1), rid(1), &txn).unwrap());
+    assert!(!index.delete(&key(&ctx, 2), rid(1), &txn).unwrap());
+    assert_eq!(index.get(&key(&ctx, 1), &txn).unwrap(), vec![rid(2)]);
+}
+
+#[test]
+fn test_btree_range_scan() {
+    let ctx = setup();
+    let meta = IndexMeta::new(
+        "foo_idx",
+        "foo",
+        ctx.key_schema.clone(),
+        vec![0],
+        ctx.buffer_manager.clone(),
+    );
+    let index = BTreeIndex::new(meta).unwrap();
+    let txn = Transaction::new(0);
+
+    // Insert keys 0..100 in reverse order.
+    for value in (0..100).rev() {
+        index.set(&key(&ctx, value), rid(value), &txn).unwrap();
+    }
+
+    // Assert that a bounded scan returns exactly the keys in range, in key order.
+    let result = index
+        .range_scan(Some(&key(&ctx, 20)), Some(&key(&ctx, 30)), &txn)
+        .unwrap();
+    assert_eq!(result, (20..=30).map(rid).collect::<Vec<RecordId>>());
+
+    // Assert that open bounds scan to the ends of the index.
+    let result = index.range_scan(None, Some(&key(&ctx, 4)), &txn).unwrap();
+    assert_eq!(result, (0..=4).map(rid).collect::<Vec<RecordId>>());
+    let result = index.range_scan(Some(&key(&ctx, 95)), None, &txn).unwrap();
+    assert_eq!(result, (95..100).map(rid).collect::<Vec<RecordId>>());
+    assert_eq!(index.range_scan(None, None, &txn).unwrap().len(), 100);
+
+    // Assert that an empty range returns nothing.
+    let result = index
+        .range_scan(Some(&key(&ctx, 50)), Some(&key(&ctx, 40)), &txn)
+        .unwrap();
+    assert!(result.is_empty());
+}
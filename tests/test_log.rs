@@ -0,0 +1,377 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use jin::buffer::replacement::ReplacerAlgorithm;
+use jin::buffer::BufferManager;
+use jin::concurrency::lock_manager::LockManager;
+use jin::concurrency::transaction::Transaction;
+use jin::concurrency::transaction_manager::TransactionManager;
+use jin::constants::{LsnT, PageIdT, INVALID_LSN, PAGE_SIZE, SYSTEM_TXN_ID};
+use jin::disk::DiskManager;
+use jin::log::{LogManager, LogOperation, RecoveryError};
+use jin::page::{RawPage, RelationPage};
+use jin::relation::heap::Heap;
+use jin::relation::record::{Record, RecordId};
+use jin::relation::types::DataType;
+use jin::relation::{Attribute, Schema};
+use std::sync::Arc;
+
+#[allow(dead_code)]
+mod constants;
+
+struct TestContext {
+    db: constants::TestDb,
+    buffer_manager: Arc<BufferManager>,
+    log_manager: Arc<LogManager>,
+    schema: Arc<Schema>,
+}
+
+/// Set up a buffer manager with logging enabled. Each test uses its own database and log files,
+/// since tests run in parallel and inspect the contents of the files. The files are removed when
+/// the context is dropped.
+fn setup() -> TestContext {
+    let db = constants::TestDb::new();
+    let log_manager = Arc::new(LogManager::new(db.log_filename()));
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, false, false, false),
+        Attribute::new("name", DataType::Varchar, false, false, false),
+    ]));
+    let buffer_manager = Arc::new(
+        BufferManager::new(
+            constants::TEST_BUFFER_SIZE,
+            DiskManager::new(db.filename()),
+            ReplacerAlgorithm::Slow,
+        )
+        .with_log_manager(log_manager.clone()),
+    );
+
+    TestContext {
+        db,
+        buffer_manager,
+        log_manager,
+        schema,
+    }
+}
+
+fn make_record(ctx: &TestContext, id: i32, name: &str) -> Record {
+    Record::new(
+        vec![Some(Box::new(id)), Some(Box::new(name.to_string()))],
+        ctx.schema.clone(),
+    )
+    .unwrap()
+}
+
+/// Read the LSN of the specified page as it is stored on disk.
+fn get_disk_lsn(ctx: &TestContext, page_id: PageIdT) -> LsnT {
+    let mut page = RawPage::new(page_id);
+    DiskManager::open(ctx.db.filename())
+        .read_page(page_id, &mut page)
+        .unwrap();
+    RelationPage::get_lsn(&page)
+}
+
+fn get_page_lsn(ctx: &TestContext, rid: RecordId) -> LsnT {
+    let frame_arc = ctx.buffer_manager.fetch_page(rid.page_id).unwrap();
+    let frame = frame_arc.read().unwrap();
+    RelationPage::get_lsn(frame.get_page().unwrap())
+}
+
+#[test]
+fn test_log_heap_operations() {
+    let ctx = setup();
+    let txn = Transaction::new(1);
+    let heap = Heap::new(ctx.buffer_manager.clone()).unwrap();
+    assert!(ctx.log_manager.read_records().is_empty());
+
+    // Insert a record, and verify that a matching log record was appended.
+    let record = make_record(&ctx, 1, "foo");
+    let rid = heap.insert(record.clone(), &txn).unwrap();
+
+    let records = ctx.log_manager.read_records();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].get_lsn() > INVALID_LSN);
+    assert_eq!(records[0].get_txn_id(), txn.get_id());
+    assert_eq!(
+        records[0].get_operation(),
+        &LogOperation::Insert {
+            rid,
+            after: record.as_bytes().to_vec(),
+        }
+    );
+    assert_eq!(get_page_lsn(&ctx, rid), records[0].get_lsn());
+
+    // Modify the record, and verify that every operation is logged with a monotonic LSN.
+    let updated = make_record(&ctx, 1, "bar");
+    assert_eq!(heap.update(updated.clone(), rid, &txn).unwrap(), rid);
+    heap.flag_delete(rid, &txn).unwrap();
+    heap.rollback_delete(rid, &txn).unwrap();
+    heap.flag_delete(rid, &txn).unwrap();
+    heap.commit_delete(rid, &txn).unwrap();
+
+    let records = ctx.log_manager.read_records();
+    assert_eq!(records.len(), 6);
+    for pair in records.windows(2) {
+        assert!(pair[0].get_lsn() < pair[1].get_lsn());
+    }
+    assert_eq!(get_page_lsn(&ctx, rid), records[5].get_lsn());
+
+    let operations: Vec<LogOperation> = records[1..]
+        .iter()
+        .map(|record| record.get_operation().clone())
+        .collect();
+    assert_eq!(
+        operations,
+        vec![
+            LogOperation::Update {
+                rid,
+                before: record.as_bytes().to_vec(),
+                after: updated.as_bytes().to_vec(),
+            },
+            LogOperation::MarkDelete {
+                rid,
+                before: updated.as_bytes().to_vec(),
+            },
+            LogOperation::RollbackDelete { rid },
+            LogOperation::MarkDelete {
+                rid,
+                before: updated.as_bytes().to_vec(),
+            },
+            LogOperation::ApplyDelete {
+                rid,
+                before: updated.as_bytes().to_vec(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_flush_log_before_page() {
+    let ctx = setup();
+    let txn = Transaction::new(SYSTEM_TXN_ID);
+    let heap = Heap::new(ctx.buffer_manager.clone()).unwrap();
+
+    // Log records are buffered until a page that depends on them is written to disk.
+    let rid = heap.insert(make_record(&ctx, 1, "foo"), &txn).unwrap();
+    let lsn = get_page_lsn(&ctx, rid);
+    assert_ne!(lsn, INVALID_LSN);
+    assert_eq!(ctx.log_manager.get_flushed_lsn(), INVALID_LSN);
+
+    // Flushing the page flushes the log up to the LSN of the page first.
+    ctx.buffer_manager.flush_page(rid.page_id).unwrap();
+    assert!(ctx.log_manager.get_flushed_lsn() >= lsn);
+    assert_eq!(get_disk_lsn(&ctx, rid.page_id), lsn);
+
+    // Dirty the page again, and evict it by filling up the buffer pool with new pages.
+    heap.update(make_record(&ctx, 1, "bar"), rid, &txn).unwrap();
+    let lsn = get_page_lsn(&ctx, rid);
+    assert!(ctx.log_manager.get_flushed_lsn() < lsn);
+
+    for _ in 0..constants::TEST_BUFFER_SIZE {
+        ctx.buffer_manager.create_page().unwrap();
+    }
+    assert!(ctx.log_manager.get_flushed_lsn() >= lsn);
+    assert_eq!(get_disk_lsn(&ctx, rid.page_id), lsn);
+
+    // Flushed log records are written to the log file exactly once.
+    ctx.log_manager.flush();
+    let lsns: Vec<LsnT> = ctx
+        .log_manager
+        .read_records()
+        .iter()
+        .map(|record| record.get_lsn())
+        .collect();
+    assert_eq!(lsns.len(), 2);
+    assert!(lsns[0] < lsns[1]);
+}
+
+#[test]
+fn test_recover_uncommitted_transaction() {
+    let ctx = setup();
+    let heap = Heap::new(ctx.buffer_manager.clone()).unwrap();
+    let manager = TransactionManager::new(Arc::new(LockManager::new()))
+        .with_log_manager(ctx.log_manager.clone());
+
+    // Insert a record on behalf of a transaction that commits.
+    let winner = manager.begin();
+    let rid_1 = heap.insert(make_record(&ctx, 1, "foo"), &winner).unwrap();
+    manager.commit(&winner).unwrap();
+    assert!(ctx.log_manager.get_flushed_lsn() >= get_page_lsn(&ctx, rid_1));
+
+    // Modify records on behalf of a transaction that never commits, and write its changes to
+    // disk before the crash.
+    let loser = manager.begin();
+    let rid_2 = heap.insert(make_record(&ctx, 2, "bar"), &loser).unwrap();
+    heap.update(make_record(&ctx, 1, "baz"), rid_1, &loser)
+        .unwrap();
+    ctx.buffer_manager.flush_page(rid_1.page_id).unwrap();
+
+    // Insert another record on behalf of a transaction that commits, but whose changes are only
+    // in the log at the time of the crash.
+    let winner = manager.begin();
+    let rid_3 = heap.insert(make_record(&ctx, 3, "qux"), &winner).unwrap();
+    manager.commit(&winner).unwrap();
+
+    // Crash by abandoning the buffer pool without flushing it, and recover from the log.
+    let root_id = heap.get_root_id();
+    drop(heap);
+    drop(manager);
+
+    let recover = || {
+        let log_manager = Arc::new(LogManager::open(ctx.db.log_filename()));
+        let buffer_manager = Arc::new(
+            BufferManager::new(
+                constants::TEST_BUFFER_SIZE,
+                DiskManager::open(ctx.db.filename()),
+                ReplacerAlgorithm::Slow,
+            )
+            .with_log_manager(log_manager.clone()),
+        );
+        let losers = log_manager.recover(&buffer_manager).unwrap();
+        buffer_manager.flush_all_pages().unwrap();
+        (log_manager, Heap::open(buffer_manager, root_id), losers)
+    };
+    let (log_manager, heap, losers) = recover();
+    assert_eq!(losers, vec![loser.get_id()]);
+
+    // The changes of the loser should be undone, and the changes of the winners should remain.
+    assert!(heap.read(rid_2).is_err());
+    assert_eq!(
+        heap.read(rid_1).unwrap().as_bytes(),
+        make_record(&ctx, 1, "foo").as_bytes()
+    );
+    assert_eq!(
+        heap.read(rid_3).unwrap().as_bytes(),
+        make_record(&ctx, 3, "qux").as_bytes()
+    );
+    assert_eq!(heap.get_num_records().unwrap(), 2);
+
+    // Each undo should be logged as a compensation log record, followed by an abort.
+    let records = log_manager.read_records();
+    let (last, clrs) = records.split_last().unwrap();
+    assert_eq!(last.get_txn_id(), loser.get_id());
+    assert_eq!(last.get_operation(), &LogOperation::Abort);
+    let undone: Vec<Option<LsnT>> = clrs[clrs.len() - 2..]
+        .iter()
+        .map(|record| record.get_undone_lsn())
+        .collect();
+    assert!(undone.iter().all(|lsn| lsn.is_some()));
+    assert!(undone[0] > undone[1]);
+
+    // Recovering again should have no effect, since the loser has aborted.
+    drop(heap);
+    drop(log_manager);
+    let (_, heap, losers) = recover();
+    assert!(losers.is_empty());
+    assert!(heap.read(rid_2).is_err());
+    assert_eq!(heap.get_num_records().unwrap(), 2);
+}
+
+#[test]
+fn test_recover_from_checkpoint() {
+    let ctx = setup();
+    let heap = Heap::new(ctx.buffer_manager.clone()).unwrap();
+    let manager = TransactionManager::new(Arc::new(LockManager::new()))
+        .with_log_manager(ctx.log_manager.clone());
+    assert_eq!(ctx.log_manager.get_checkpoint_lsn(), INVALID_LSN);
+
+    // Insert records on behalf of a transaction that commits, and a transaction that is still
+    // active at the time of the checkpoint.
+    let winner = manager.begin();
+    let rid_1 = heap.insert(make_record(&ctx, 1, "foo"), &winner).unwrap();
+    manager.commit(&winner).unwrap();
+    let loser = manager.begin();
+    let rid_2 = heap.insert(make_record(&ctx, 2, "bar"), &loser).unwrap();
+
+    // Take a checkpoint, which flushes the modified page and lists the active transaction.
+    let lsn = ctx.log_manager.checkpoint(&ctx.buffer_manager).unwrap();
+    assert_eq!(ctx.log_manager.get_checkpoint_lsn(), lsn);
+    assert_eq!(ctx.log_manager.get_flushed_lsn(), lsn);
+    assert_eq!(get_disk_lsn(&ctx, rid_1.page_id), get_page_lsn(&ctx, rid_1));
+
+    let records = ctx.log_manager.read_records();
+    let checkpoint = records.last().unwrap();
+    assert_eq!(checkpoint.get_lsn(), lsn);
+    assert_eq!(checkpoint.get_txn_id(), SYSTEM_TXN_ID);
+    assert_eq!(
+        checkpoint.get_operation(),
+        &LogOperation::Checkpoint {
+            redo_lsn: records[records.len() - 2].get_lsn() + 1,
+            active_txns: vec![loser.get_id()],
+            dirty_pages: vec![],
+        }
+    );
+
+    // Insert another record on behalf of a transaction that commits after the checkpoint.
+    let winner = manager.begin();
+    let rid_3 = heap.insert(make_record(&ctx, 3, "baz"), &winner).unwrap();
+    manager.commit(&winner).unwrap();
+
+    // Crash by abandoning the buffer pool without flushing it, and recover from the log.
+    let log_manager = Arc::new(LogManager::open(ctx.db.log_filename()));
+    let buffer_manager = Arc::new(
+        BufferManager::new(
+            constants::TEST_BUFFER_SIZE,
+            DiskManager::open(ctx.db.filename()),
+            ReplacerAlgorithm::Slow,
+        )
+        .with_log_manager(log_manager.clone()),
+    );
+
+    // Recovery should start from the checkpoint, whose active transactions include the loser even
+    // though the loser didn't log any operation after the checkpoint.
+    assert_eq!(log_manager.get_checkpoint_lsn(), lsn);
+    assert!(records[0].get_lsn() < lsn);
+    let losers = log_manager.recover(&buffer_manager).unwrap();
+    assert_eq!(losers, vec![loser.get_id()]);
+
+    let heap = Heap::open(buffer_manager, heap.get_root_id());
+    assert!(heap.read(rid_1).is_ok());
+    assert!(heap.read(rid_2).is_err());
+    assert_eq!(
+        heap.read(rid_3).unwrap().as_bytes(),
+        make_record(&ctx, 3, "baz").as_bytes()
+    );
+}
+
+#[test]
+fn test_recover_rejects_freed_overflow_pages() {
+    let ctx = setup();
+    let heap = Heap::new(ctx.buffer_manager.clone()).unwrap();
+    let manager = TransactionManager::new(Arc::new(LockManager::new()))
+        .with_log_manager(ctx.log_manager.clone());
+
+    // Insert a record stored in overflow pages on behalf of a transaction that commits.
+    let name = "a".repeat(PAGE_SIZE as usize * 2);
+    let winner = manager.begin();
+    let rid = heap.insert(make_record(&ctx, 1, &name), &winner).unwrap();
+    manager.commit(&winner).unwrap();
+
+    // Delete the record on behalf of a transaction that never commits, which frees its overflow
+    // pages.
+    let loser = manager.begin();
+    heap.flag_delete(rid, &loser).unwrap();
+    heap.commit_delete(rid, &loser).unwrap();
+    let records = ctx.log_manager.read_records();
+    assert_eq!(
+        records.last().unwrap().get_operation(),
+        &LogOperation::FreeOverflow { rid }
+    );
+    ctx.log_manager.flush();
+
+    // Recovery should refuse to restore the stub of the record, since its pages were freed.
+    drop(heap);
+    drop(manager);
+    let log_manager = Arc::new(LogManager::open(ctx.db.log_filename()));
+    let buffer_manager = BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::open(ctx.db.filename()),
+        ReplacerAlgorithm::Slow,
+    )
+    .with_log_manager(log_manager.clone());
+    match log_manager.recover(&buffer_manager) {
+        Err(RecoveryError::OverflowFreed(freed)) => assert_eq!(freed, rid),
+        result => panic!("expected OverflowFreed, got {:?}", result),
+    }
+}
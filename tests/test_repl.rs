@@ -0,0 +1,187 @@
+/*
+ * Copyright (c) 2020 - 2021.  Shoyo Inokuchi.
+ * Please refer to github.com/shoyo/jindb for more information about this project and its license.
+ */
+
+use jin::buffer::replacement::ReplacerAlgorithm;
+use jin::buffer::BufferManager;
+use jin::catalog::{CatalogError, SystemCatalog};
+use jin::disk::DiskManager;
+use jin::plan::parser::ParseError;
+use jin::relation::record::RecordErr;
+use jin::relation::types::{DataType, InnerValue};
+use jin::relation::{Attribute, Schema};
+use jin::repl::{Repl, ReplError, Response};
+use std::sync::Arc;
+
+mod constants;
+
+struct TestContext {
+    system_catalog: Arc<SystemCatalog>,
+    repl: Repl,
+    _db: constants::TestDb,
+}
+
+fn setup() -> TestContext {
+    let db = constants::TestDb::new();
+    let buffer_manager = BufferManager::new(
+        constants::TEST_BUFFER_SIZE,
+        DiskManager::new(db.filename()),
+        ReplacerAlgorithm::Slow,
+    );
+    let system_catalog = Arc::new(SystemCatalog::new(Arc::new(buffer_manager)));
+
+    let schema = Arc::new(Schema::new(vec![
+        Attribute::new("id", DataType::Int, true, false, false),
+        Attribute::new("name", DataType::Varchar, false, false, true),
+    ]));
+    system_catalog.create_relation("users", schema).unwrap();
+
+    TestContext {
+        repl: Repl::new(system_catalog.clone()),
+        system_catalog,
+        _db: db,
+    }
+}
+
+#[test]
+fn test_dispatch_commands() {
+    let ctx = setup();
+
+    assert!(matches!(
+        ctx.repl.dispatch(".help"),
+        Ok(Response::Output(text)) if text.contains(".schema <relation>")
+    ));
+    assert_eq!(
+        ctx.repl.dispatch(".tables"),
+        Ok(Response::Output("users".to_string()))
+    );
+    assert_eq!(
+        ctx.repl.dispatch(".schema users"),
+        Ok(Response::Output(
+            "id INT PRIMARY KEY NOT NULL\nname VARCHAR".to_string()
+        ))
+    );
+    assert_eq!(ctx.repl.dispatch("  "), Ok(Response::Output(String::new())));
+    assert_eq!(ctx.repl.dispatch(".quit"), Ok(Response::Quit));
+
+    // Assert that an inserted record is stored in the relation.
+    assert!(ctx
+        .repl
+        .dispatch(r#"insert users {"id": 1, "name": "Alice"}"#)
+        .is_ok());
+    let relation = ctx.system_catalog.get_relation("users").unwrap();
+    let schema = relation.get_schema();
+    let records: Vec<_> = relation.scan().collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0]
+            .get_value(1, schema)
+            .unwrap()
+            .unwrap()
+            .get_inner(),
+        InnerValue::Varchar("Alice".to_string())
+    );
+}
+
+#[test]
+fn test_dispatch_errors() {
+    let ctx = setup();
+
+    assert_eq!(
+        ctx.repl.dispatch(".foo"),
+        Err(ReplError::UnknownCommand(".foo".to_string()))
+    );
+    assert_eq!(
+        ctx.repl.dispatch(".schema"),
+        Err(ReplError::Usage(".schema <relation>"))
+    );
+    assert_eq!(
+        ctx.repl.dispatch(".schema missing"),
+        Err(ReplError::RelationNotFound("missing".to_string()))
+    );
+    assert_eq!(
+        ctx.repl.dispatch("insert users"),
+        Err(ReplError::Usage("insert <relation> <json>"))
+    );
+    assert_eq!(
+        ctx.repl.dispatch(r#"insert users {"id": "one"}"#),
+        Err(ReplError::InvalidRecord(RecordErr::ValSchemaMismatch))
+    );
+}
+
+#[test]
+fn test_dispatch_create_table() {
+    let ctx = setup();
+
+    assert_eq!(
+        ctx.repl
+            .dispatch("CREATE TABLE items (id INT PRIMARY KEY, label VARCHAR(16) NOT NULL);"),
+        Ok(Response::Output("Created relation \"items\".".to_string()))
+    );
+    assert_eq!(
+        ctx.repl.dispatch(".tables"),
+        Ok(Response::Output("users\nitems".to_string()))
+    );
+    assert_eq!(
+        ctx.repl.dispatch(".schema items"),
+        Ok(Response::Output(
+            "id INT PRIMARY KEY NOT NULL\nlabel VARCHAR NOT NULL".to_string()
+        ))
+    );
+
+    assert_eq!(
+        ctx.repl.dispatch("create table users (id INT)"),
+        Err(ReplError::CreateFailed(CatalogError::RelationExists))
+    );
+    assert_eq!(
+        ctx.repl.dispatch("create table foo"),
+        Err(ReplError::InvalidStatement(ParseError::UnexpectedEnd))
+    );
+}
+
+#[test]
+fn test_dispatch_explain() {
+    let ctx = setup();
+
+    assert_eq!(
+        ctx.repl
+            .dispatch("EXPLAIN SELECT name FROM users WHERE id > 3"),
+        Ok(Response::Output(
+            "Projection (name VARCHAR)\n  \
+             Filter (id INT, name VARCHAR)\n    \
+             SeqScan (id INT, name VARCHAR)"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        ctx.repl.dispatch("explain SELECT * FROM missing"),
+        Err(ReplError::InvalidStatement(ParseError::RelationNotFound(
+            "missing".to_string()
+        )))
+    );
+}
+
+#[test]
+fn test_run() {
+    let ctx = setup();
+
+    let input = ".tables\n.bar\ninsert users {\"id\": 7}\n.quit\n.tables\n";
+    let mut output = Vec::new();
+    ctx.repl.run(input.as_bytes(), &mut output).unwrap();
+
+    // Assert that each command's output follows a prompt, and that commands after .quit are
+    // not executed.
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(
+        output,
+        format!(
+            "Enter .help for usage hints.\n\
+             jin> users\n\
+             jin> Error: unknown command \".bar\". Enter .help for usage hints.\n\
+             jin> Inserted record at page {}, slot 0.\n\
+             jin> ",
+            constants::FIRST_RELATION_PAGE_ID
+        )
+    );
+}